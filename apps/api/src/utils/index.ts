@@ -1,2 +1,3 @@
 export * from './solana.js';
+export * from './rewardAccounts.js';
 export * from './crypto.js';
\ No newline at end of file
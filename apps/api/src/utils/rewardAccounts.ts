@@ -0,0 +1,287 @@
+import { createHash } from 'crypto';
+import { Commitment, Connection, PublicKey } from '@solana/web3.js';
+import { config } from '../config/index.js';
+import logger from '../config/logger.js';
+import { connection } from './solana.js';
+
+// getMultipleAccounts accepts at most 100 keys per request
+const MAX_ACCOUNTS_PER_REQUEST = 100;
+const CADENCE_HISTORY_LEN = 4;
+
+const USER_ACCOUNT_DISCRIMINATOR = createHash('sha256')
+  .update('account:UserAccount')
+  .digest()
+  .subarray(0, 8);
+
+/**
+ * Decoded on-chain `UserAccount`.
+ * u64/i64 fields are kept as bigint to avoid precision loss.
+ */
+export interface UserAccountState {
+  authority: PublicKey;
+  pool: PublicKey;
+  totalEarned: bigint;
+  totalFeesPaid: bigint;
+  totalClaims: bigint;
+  lastClaimTimestamp: bigint;
+  registrationTimestamp: bigint;
+  isActive: boolean;
+  claimManager: PublicKey | null;
+  dormancyWarnedAt: bigint;
+  regionCode: number;
+  regionAttestedAt: bigint;
+  totalWithheld: bigint;
+  currentStreak: number;
+  longestStreak: number;
+  streakInsured: boolean;
+  recentClaimIntervals: bigint[];
+  cadenceSamples: number;
+  multiplierBps: number;
+  multiplierExpiresAt: bigint;
+  auctionBoostBps: number;
+  auctionBoostStartsAt: bigint;
+  auctionBoostEndsAt: bigint;
+  nftBoostMint: PublicKey | null;
+  nftBoostBps: number;
+  referrer: PublicKey | null;
+  referralCount: number;
+  referralEarned: bigint;
+  referralPaid: bigint;
+  nextEligibleAt: bigint;
+  bannedAt: bigint;
+  coSigner: PublicKey | null;
+  soloClaimLimit: bigint;
+  termsVersion: number;
+  termsAcceptedAt: bigint;
+  pausedAt: bigint;
+  pausedSeconds: bigint;
+  totalPausedSeconds: bigint;
+  claimDelegate: PublicKey | null;
+  accrualDust: bigint;
+  accruedUnclaimed: bigint;
+  poolPauseCheckpoint: bigint;
+  lotteryCommitSlot: bigint;
+  lotteryWins: number;
+  lotteryBonusEarned: bigint;
+  badgesMinted: number;
+  activityPoints: bigint;
+  totalActivityPoints: bigint;
+  claimsTodayDay: bigint;
+  claimsToday: number;
+  bump: number;
+  version: number;
+}
+
+export interface UserAccountResult {
+  address: PublicKey;
+  /** null when the account does not exist or failed to decode (see `error`) */
+  account: UserAccountState | null;
+  error?: string;
+}
+
+export interface FetchUserAccountsOptions {
+  /** Keys per getMultipleAccounts request (max 100) */
+  batchSize?: number;
+  /** Requests in flight at once */
+  concurrency?: number;
+  commitment?: Commitment;
+}
+
+class BorshReader {
+  private offset = 0;
+
+  constructor(private readonly data: Buffer) {}
+
+  u8(): number {
+    const value = this.data.readUInt8(this.offset);
+    this.offset += 1;
+    return value;
+  }
+
+  u16(): number {
+    const value = this.data.readUInt16LE(this.offset);
+    this.offset += 2;
+    return value;
+  }
+
+  u32(): number {
+    const value = this.data.readUInt32LE(this.offset);
+    this.offset += 4;
+    return value;
+  }
+
+  u64(): bigint {
+    const value = this.data.readBigUInt64LE(this.offset);
+    this.offset += 8;
+    return value;
+  }
+
+  i64(): bigint {
+    const value = this.data.readBigInt64LE(this.offset);
+    this.offset += 8;
+    return value;
+  }
+
+  bool(): boolean {
+    return this.u8() !== 0;
+  }
+
+  pubkey(): PublicKey {
+    const value = new PublicKey(
+      this.data.subarray(this.offset, this.offset + 32)
+    );
+    this.offset += 32;
+    return value;
+  }
+
+  optionPubkey(): PublicKey | null {
+    return this.bool() ? this.pubkey() : null;
+  }
+}
+
+export function deriveUserAccountAddress(
+  authority: PublicKey,
+  programId: PublicKey = new PublicKey(config.solana.programId as string)
+): PublicKey {
+  const [address] = PublicKey.findProgramAddressSync(
+    [Buffer.from('user_account'), authority.toBuffer()],
+    programId
+  );
+  return address;
+}
+
+export function decodeUserAccount(data: Buffer): UserAccountState {
+  const discriminator = data.subarray(0, 8);
+  if (data.length < 8 || !discriminator.equals(USER_ACCOUNT_DISCRIMINATOR)) {
+    throw new Error('Account is not a UserAccount');
+  }
+
+  const reader = new BorshReader(data.subarray(8));
+  return {
+    authority: reader.pubkey(),
+    pool: reader.pubkey(),
+    totalEarned: reader.u64(),
+    totalFeesPaid: reader.u64(),
+    totalClaims: reader.u64(),
+    lastClaimTimestamp: reader.i64(),
+    registrationTimestamp: reader.i64(),
+    isActive: reader.bool(),
+    claimManager: reader.optionPubkey(),
+    dormancyWarnedAt: reader.i64(),
+    regionCode: reader.u16(),
+    regionAttestedAt: reader.i64(),
+    totalWithheld: reader.u64(),
+    currentStreak: reader.u32(),
+    longestStreak: reader.u32(),
+    streakInsured: reader.bool(),
+    recentClaimIntervals: Array.from({ length: CADENCE_HISTORY_LEN }, () =>
+      reader.i64()
+    ),
+    cadenceSamples: reader.u8(),
+    multiplierBps: reader.u16(),
+    multiplierExpiresAt: reader.i64(),
+    auctionBoostBps: reader.u16(),
+    auctionBoostStartsAt: reader.i64(),
+    auctionBoostEndsAt: reader.i64(),
+    nftBoostMint: reader.optionPubkey(),
+    nftBoostBps: reader.u16(),
+    referrer: reader.optionPubkey(),
+    referralCount: reader.u32(),
+    referralEarned: reader.u64(),
+    referralPaid: reader.u64(),
+    nextEligibleAt: reader.i64(),
+    bannedAt: reader.i64(),
+    coSigner: reader.optionPubkey(),
+    soloClaimLimit: reader.u64(),
+    termsVersion: reader.u32(),
+    termsAcceptedAt: reader.i64(),
+    pausedAt: reader.i64(),
+    pausedSeconds: reader.i64(),
+    totalPausedSeconds: reader.i64(),
+    claimDelegate: reader.optionPubkey(),
+    accrualDust: reader.u64(),
+    accruedUnclaimed: reader.u64(),
+    poolPauseCheckpoint: reader.i64(),
+    lotteryCommitSlot: reader.u64(),
+    lotteryWins: reader.u32(),
+    lotteryBonusEarned: reader.u64(),
+    badgesMinted: reader.u8(),
+    activityPoints: reader.u64(),
+    totalActivityPoints: reader.u64(),
+    claimsTodayDay: reader.u64(),
+    claimsToday: reader.u16(),
+    bump: reader.u8(),
+    version: reader.u8(),
+  };
+}
+
+/**
+ * Fetch and decode many user accounts with batched getMultipleAccounts calls.
+ * Results are returned in the order of `addresses`; a failed batch marks its
+ * accounts with an error instead of failing the whole fetch.
+ */
+export async function fetchUserAccounts(
+  addresses: PublicKey[],
+  options: FetchUserAccountsOptions = {},
+  rpc: Connection = connection
+): Promise<UserAccountResult[]> {
+  const batchSize = Math.min(
+    Math.max(options.batchSize ?? MAX_ACCOUNTS_PER_REQUEST, 1),
+    MAX_ACCOUNTS_PER_REQUEST
+  );
+  const concurrency = Math.max(options.concurrency ?? 4, 1);
+
+  const batches: PublicKey[][] = [];
+  for (let i = 0; i < addresses.length; i += batchSize) {
+    batches.push(addresses.slice(i, i + batchSize));
+  }
+
+  const results: UserAccountResult[][] = new Array(batches.length);
+  let nextBatch = 0;
+
+  const worker = async () => {
+    while (nextBatch < batches.length) {
+      const index = nextBatch++;
+      const batch = batches[index];
+
+      try {
+        const infos = await rpc.getMultipleAccountsInfo(
+          batch,
+          options.commitment
+        );
+        results[index] = batch.map((address, i) => {
+          const info = infos[i];
+          if (!info) {
+            return { address, account: null, error: 'Account not found' };
+          }
+          try {
+            return { address, account: decodeUserAccount(info.data) };
+          } catch (error) {
+            return {
+              address,
+              account: null,
+              error: error instanceof Error ? error.message : 'Unknown error',
+            };
+          }
+        });
+      } catch (error) {
+        const message =
+          error instanceof Error ? error.message : 'Unknown error';
+        logger.error('Failed to fetch user account batch', {
+          error: message,
+          batchIndex: index,
+          batchSize: batch.length,
+        });
+        results[index] = batch.map(address => ({
+          address,
+          account: null,
+          error: message,
+        }));
+      }
+    }
+  };
+
+  const workers = Math.min(concurrency, batches.length);
+  await Promise.all(Array.from({ length: workers }, worker));
+  return results.flat();
+}
@@ -0,0 +1,560 @@
+//! Operator CLI for reward pools. Amounts are in base units of the pool's mint.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anchor_spl::token_2022::spl_token_2022::{
+    extension::StateWithExtensions, state::Mint as MintState,
+};
+use anyhow::{anyhow, ensure, Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use reward_system_client::{
+    fetch_pool, fetch_pool_users, find_pool_address,
+    instructions::{self, PoolConfigUpdate},
+    reward_system::math,
+    DistributionMode, PoolKeys,
+};
+use solana_client::{nonce_utils, rpc_client::RpcClient};
+#[cfg(feature = "ledger")]
+use solana_remote_wallet::{
+    locator::Locator, remote_keypair::generate_remote_keypair, remote_wallet::maybe_wallet_manager,
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::Instruction,
+    message::Message,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Signature, Signer},
+    transaction::{uses_durable_nonce, Transaction},
+};
+#[cfg(feature = "ledger")]
+use {solana_sdk::derivation_path::DerivationPath, uriparse::URIReference};
+
+#[derive(Parser)]
+#[command(
+    name = "reward-cli",
+    version,
+    about = "Operate timestamped token reward pools"
+)]
+struct Cli {
+    /// RPC endpoint
+    #[arg(
+        long,
+        short = 'u',
+        env = "SOLANA_URL",
+        default_value = "http://localhost:8899"
+    )]
+    url: String,
+
+    /// Keypair file of the pool authority, which also pays fees, or a hardware wallet such as
+    /// `usb://ledger` (`usb://ledger?key=1` for another account)
+    #[arg(
+        long,
+        short = 'k',
+        env = "SOLANA_KEYPAIR",
+        default_value = "~/.config/solana/id.json"
+    )]
+    keypair: String,
+
+    /// Pool authority, for read-only commands on another operator's pool; defaults to the
+    /// keypair's
+    #[arg(long, global = true)]
+    authority: Option<Pubkey>,
+
+    /// Print the transaction unsigned, as base64, instead of sending it, so it can be signed
+    /// on an offline machine with `sign` and sent later with `submit`. No keypair is read;
+    /// `--authority` names the signer, which also pays fees
+    #[arg(long, global = true, requires = "authority")]
+    export_unsigned: bool,
+
+    /// Durable nonce account whose stored blockhash the transaction uses, so it doesn't
+    /// expire while it is being signed offline
+    #[arg(long, global = true)]
+    nonce: Option<Pubkey>,
+
+    /// Authority of the nonce account; defaults to the fee payer
+    #[arg(long, global = true, requires = "nonce")]
+    nonce_authority: Option<Pubkey>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create a pool owned by the keypair, with its vault
+    InitPool {
+        #[arg(long)]
+        mint: Pubkey,
+        #[arg(long)]
+        rate_per_hour: u64,
+        #[arg(long)]
+        min_claim_interval_secs: u64,
+        #[arg(long)]
+        max_daily_reward: u64,
+        /// Hours of accrual a claim can cover; 0 = unbounded
+        #[arg(long, default_value_t = 0)]
+        max_accrual_hours: u64,
+        #[arg(long, value_enum, default_value_t = Mode::Vault)]
+        mode: Mode,
+    },
+    /// Transfer tokens from the keypair's token account into the pool vault
+    Fund { amount: u64 },
+    /// Change rate, caps and limits; omitted options are left unchanged
+    UpdateConfig(ConfigArgs),
+    /// Pause (or with --resume, resume) claims, registrations or accrual
+    Pause {
+        #[arg(value_enum)]
+        what: PauseTarget,
+        #[arg(long)]
+        resume: bool,
+    },
+    /// List the pool's registered users
+    ListUsers,
+    /// Vault balance against projected liabilities
+    Runway,
+    /// Announce, execute or cancel an emergency withdrawal
+    EmergencyWithdraw {
+        #[command(subcommand)]
+        step: WithdrawStep,
+    },
+    /// Sign a transaction exported with --export-unsigned using the keypair, without contacting
+    /// the network, and print it as base64
+    Sign { transaction: String },
+    /// Send a transaction signed with `sign`
+    Submit { transaction: String },
+}
+
+#[derive(Args)]
+struct ConfigArgs {
+    #[arg(long)]
+    rate_per_hour: Option<u64>,
+    #[arg(long)]
+    min_claim_interval_secs: Option<u64>,
+    #[arg(long)]
+    max_daily_reward: Option<u64>,
+    #[arg(long)]
+    max_accrual_hours: Option<u64>,
+    #[arg(long)]
+    max_lifetime_reward_per_user: Option<u64>,
+    #[arg(long)]
+    max_total_distribution: Option<u64>,
+    #[arg(long)]
+    max_claims_per_day: Option<u16>,
+}
+
+#[derive(Subcommand)]
+enum WithdrawStep {
+    /// Start the timelock for a withdrawal of `amount` to `destination`
+    Announce {
+        amount: u64,
+        /// Token account to receive the funds; defaults to the keypair's
+        #[arg(long)]
+        destination: Option<Pubkey>,
+    },
+    /// Execute the announced withdrawal once its delay has passed
+    Execute {
+        amount: u64,
+        #[arg(long)]
+        destination: Option<Pubkey>,
+    },
+    /// Cancel the announced withdrawal
+    Cancel,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Mode {
+    Vault,
+    MintOnClaim,
+    NativeSol,
+}
+
+impl From<Mode> for DistributionMode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Vault => DistributionMode::Vault,
+            Mode::MintOnClaim => DistributionMode::MintOnClaim,
+            Mode::NativeSol => DistributionMode::NativeSol,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum PauseTarget {
+    Claims,
+    Registrations,
+    Accrual,
+}
+
+struct Operator {
+    rpc: RpcClient,
+    /// `None` when exporting unsigned transactions
+    signer: Option<Box<dyn Signer>>,
+    authority: Pubkey,
+    nonce: Option<Pubkey>,
+    nonce_authority: Option<Pubkey>,
+}
+
+impl Operator {
+    /// Fee payer, and owner of the token accounts the commands default to
+    fn payer(&self) -> Pubkey {
+        self.signer
+            .as_ref()
+            .map_or(self.authority, |signer| signer.pubkey())
+    }
+
+    /// Transaction for `instructions`, on a recent blockhash or, with `--nonce`, on the nonce
+    /// account's blockhash after an instruction advancing it
+    fn unsigned_transaction(&self, instructions: &[Instruction]) -> Result<Transaction> {
+        let payer = self.payer();
+        let message = match self.nonce {
+            Some(nonce) => {
+                let account = nonce_utils::get_account_with_commitment(
+                    &self.rpc,
+                    &nonce,
+                    CommitmentConfig::confirmed(),
+                )
+                .context("fetching nonce account")?;
+                let data = nonce_utils::data_from_account(&account)?;
+                let nonce_authority = self.nonce_authority.unwrap_or(payer);
+                ensure!(
+                    data.authority == nonce_authority,
+                    "nonce account {nonce} is controlled by {}, not {nonce_authority}",
+                    data.authority
+                );
+                let mut message = Message::new_with_nonce(
+                    instructions.to_vec(),
+                    Some(&payer),
+                    &nonce,
+                    &nonce_authority,
+                );
+                message.recent_blockhash = data.blockhash();
+                message
+            }
+            None => Message::new_with_blockhash(
+                instructions,
+                Some(&payer),
+                &self.rpc.get_latest_blockhash()?,
+            ),
+        };
+        Ok(Transaction::new_unsigned(message))
+    }
+
+    /// Sign and send `instructions`, printing `summary` with the signature, or with
+    /// `--export-unsigned` print the unsigned transaction instead
+    fn execute(&self, instructions: &[Instruction], summary: &str) -> Result<()> {
+        let mut tx = self.unsigned_transaction(instructions)?;
+        let Some(signer) = &self.signer else {
+            println!("{}", encode_transaction(&tx)?);
+            eprintln!("Unsigned: {summary} once signed and submitted");
+            if self.nonce.is_none() {
+                eprintln!("The blockhash expires in about a minute; use --nonce to sign later");
+            }
+            return Ok(());
+        };
+        let blockhash = tx.message.recent_blockhash;
+        tx.try_sign(&[signer.as_ref()], blockhash)?;
+        let signature = self.rpc.send_and_confirm_transaction(&tx)?;
+        println!("{summary}: {signature}");
+        Ok(())
+    }
+
+    /// Pool keys, with the token program and decimals read from the mint
+    fn pool_keys(&self, mint: &Pubkey) -> Result<(PoolKeys, u8)> {
+        let account = self.rpc.get_account(mint).context("fetching mint")?;
+        let decimals = StateWithExtensions::<MintState>::unpack(&account.data)?
+            .base
+            .decimals;
+        Ok((
+            PoolKeys::new(self.authority, *mint, account.owner),
+            decimals,
+        ))
+    }
+
+    fn pool(&self) -> Result<(Pubkey, reward_system_client::RewardPool)> {
+        let address = find_pool_address(&self.authority).0;
+        Ok((address, fetch_pool(&self.rpc, &address)?))
+    }
+}
+
+fn encode_transaction(tx: &Transaction) -> Result<String> {
+    Ok(BASE64.encode(bincode::serialize(tx)?))
+}
+
+fn decode_transaction(encoded: &str) -> Result<Transaction> {
+    let bytes = BASE64
+        .decode(encoded.trim())
+        .context("transaction is not base64")?;
+    bincode::deserialize(&bytes).context("decoding transaction")
+}
+
+/// Keys whose signatures `tx` still lacks
+fn missing_signers(tx: &Transaction) -> Vec<Pubkey> {
+    tx.message
+        .account_keys
+        .iter()
+        .zip(&tx.signatures)
+        .filter(|(_, signature)| **signature == Signature::default())
+        .map(|(key, _)| *key)
+        .collect()
+}
+
+/// Add the keypair's signature to an exported transaction, after showing what it does
+fn sign_offline(keypair: &str, encoded: &str) -> Result<()> {
+    let signer = load_signer(keypair)?;
+    let mut tx = decode_transaction(encoded)?;
+    let keys = &tx.message.account_keys;
+    eprintln!("Fee payer: {}", keys[0]);
+    match uses_durable_nonce(&tx) {
+        Some(advance) => eprintln!("Nonce account: {}", keys[advance.accounts[0] as usize]),
+        None => eprintln!("Blockhash: {}", tx.message.recent_blockhash),
+    }
+    for instruction in &tx.message.instructions {
+        eprintln!(
+            "Instruction: program {}, {} accounts, {} bytes of data",
+            keys[instruction.program_id_index as usize],
+            instruction.accounts.len(),
+            instruction.data.len()
+        );
+    }
+
+    let blockhash = tx.message.recent_blockhash;
+    tx.try_partial_sign(&[signer.as_ref()], blockhash)?;
+    println!("{}", encode_transaction(&tx)?);
+    let missing = missing_signers(&tx);
+    if !missing.is_empty() {
+        eprintln!("Still needs signatures from: {missing:?}");
+    }
+    Ok(())
+}
+
+fn submit(url: String, encoded: &str) -> Result<()> {
+    let tx = decode_transaction(encoded)?;
+    let missing = missing_signers(&tx);
+    ensure!(missing.is_empty(), "missing signatures from {missing:?}");
+    tx.verify().context("transaction signatures don't verify")?;
+    let rpc = RpcClient::new_with_commitment(url, CommitmentConfig::confirmed());
+    let signature = rpc.send_and_confirm_transaction(&tx)?;
+    println!("Submitted: {signature}");
+    Ok(())
+}
+
+/// Signer for `path`: a `usb://` hardware wallet locator, or a keypair file
+fn load_signer(path: &str) -> Result<Box<dyn Signer>> {
+    if !path.starts_with("usb://") {
+        let keypair = read_keypair_file(expand_tilde(path))
+            .map_err(|err| anyhow!("reading keypair {path}: {err}"))?;
+        return Ok(Box::new(keypair));
+    }
+
+    load_hardware_signer(path)
+}
+
+/// Signer for a `usb://` locator, confirmed on the device
+#[cfg(feature = "ledger")]
+fn load_hardware_signer(path: &str) -> Result<Box<dyn Signer>> {
+    let uri = URIReference::try_from(path).with_context(|| format!("parsing {path}"))?;
+    let locator = Locator::new_from_uri(&uri)?;
+    let derivation_path = DerivationPath::from_uri_key_query(&uri)?.unwrap_or_default();
+    let wallet_manager = maybe_wallet_manager()?.context("no hardware wallet connected")?;
+    // Ask for the derived address to be confirmed on the device before anything is signed
+    let keypair =
+        generate_remote_keypair(locator, derivation_path, &wallet_manager, true, "authority")?;
+    Ok(Box::new(keypair))
+}
+
+#[cfg(not(feature = "ledger"))]
+fn load_hardware_signer(path: &str) -> Result<Box<dyn Signer>> {
+    Err(anyhow!(
+        "{path}: hardware wallets need reward-cli built with `--features ledger`"
+    ))
+}
+
+fn expand_tilde(path: &str) -> String {
+    match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{home}/{rest}"),
+        _ => path.to_string(),
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64)
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    match &cli.command {
+        Command::Sign { transaction } => return sign_offline(&cli.keypair, transaction),
+        Command::Submit { transaction } => return submit(cli.url, transaction),
+        _ => {}
+    }
+    let signer = if cli.export_unsigned {
+        None
+    } else {
+        Some(load_signer(&cli.keypair)?)
+    };
+    let authority = match (cli.authority, &signer) {
+        (Some(authority), _) => authority,
+        (None, Some(signer)) => signer.pubkey(),
+        (None, None) => unreachable!("--export-unsigned requires --authority"),
+    };
+    let op = Operator {
+        rpc: RpcClient::new_with_commitment(cli.url, CommitmentConfig::confirmed()),
+        signer,
+        authority,
+        nonce: cli.nonce,
+        nonce_authority: cli.nonce_authority,
+    };
+
+    match cli.command {
+        Command::InitPool {
+            mint,
+            rate_per_hour,
+            min_claim_interval_secs,
+            max_daily_reward,
+            max_accrual_hours,
+            mode,
+        } => {
+            let (keys, _) = op.pool_keys(&mint)?;
+            op.execute(
+                &[instructions::initialize_pool(
+                    &keys,
+                    rate_per_hour,
+                    min_claim_interval_secs,
+                    max_daily_reward,
+                    max_accrual_hours,
+                    mode.into(),
+                )],
+                &format!("Pool {} created", keys.pool()),
+            )?;
+        }
+        Command::Fund { amount } => {
+            let (_, pool) = op.pool()?;
+            let (keys, decimals) = op.pool_keys(&pool.mint)?;
+            let source = keys.token_account(&op.payer());
+            op.execute(
+                &[instructions::fund_vault(
+                    &keys,
+                    &source,
+                    &op.payer(),
+                    amount,
+                    decimals,
+                )?],
+                &format!("Funded vault {} with {amount}", keys.vault()),
+            )?;
+        }
+        Command::UpdateConfig(args) => {
+            let update = PoolConfigUpdate {
+                reward_rate_per_hour: args.rate_per_hour,
+                min_claim_interval_secs: args.min_claim_interval_secs,
+                max_daily_reward: args.max_daily_reward,
+                max_accrual_hours: args.max_accrual_hours,
+                max_lifetime_reward_per_user: args.max_lifetime_reward_per_user,
+                max_total_distribution: args.max_total_distribution,
+                max_claims_per_day: args.max_claims_per_day,
+                ..Default::default()
+            };
+            op.execute(
+                &[instructions::update_pool_config(&op.authority, update)],
+                "Config updated",
+            )?;
+        }
+        Command::Pause { what, resume } => {
+            let paused = Some(!resume);
+            let update = match what {
+                PauseTarget::Claims => PoolConfigUpdate {
+                    claims_paused: paused,
+                    ..Default::default()
+                },
+                PauseTarget::Registrations => PoolConfigUpdate {
+                    registrations_paused: paused,
+                    ..Default::default()
+                },
+                PauseTarget::Accrual => PoolConfigUpdate {
+                    accrual_paused: paused,
+                    ..Default::default()
+                },
+            };
+            op.execute(
+                &[instructions::update_pool_config(&op.authority, update)],
+                if resume { "Resumed" } else { "Paused" },
+            )?;
+        }
+        Command::ListUsers => {
+            let (address, _) = op.pool()?;
+            let mut users = fetch_pool_users(&op.rpc, &address)?;
+            users.sort_by_key(|(_, user)| std::cmp::Reverse(user.total_earned));
+
+            println!(
+                "{:<44} {:>20} {:>8} {:>12} {:>6}",
+                "wallet", "total earned", "claims", "last claim", "active"
+            );
+            for (_, user) in &users {
+                println!(
+                    "{:<44} {:>20} {:>8} {:>12} {:>6}",
+                    user.authority,
+                    user.total_earned,
+                    user.total_claims,
+                    user.last_claim_timestamp,
+                    user.is_active
+                );
+            }
+            println!("{} users", users.len());
+        }
+        Command::Runway => {
+            let (_, pool) = op.pool()?;
+            let (keys, _) = op.pool_keys(&pool.mint)?;
+            let balance: u64 = op
+                .rpc
+                .get_token_account_balance(&keys.vault())?
+                .amount
+                .parse()?;
+            let now = now();
+            let reserve = math::reserved_liabilities(&pool, now).map_err(|err| anyhow!("{err}"))?;
+            let rate = math::rate_per_hour_at(&pool, now).map_err(|err| anyhow!("{err}"))?;
+            let burn_per_hour = pool.participant_count.saturating_mul(rate);
+
+            println!("Vault balance:        {balance}");
+            println!("Reserved liabilities: {reserve}");
+            if balance >= reserve {
+                println!("Excess:               {}", balance - reserve);
+            } else {
+                println!("Shortfall:            {}", reserve - balance);
+            }
+            println!("Participants:         {}", pool.participant_count);
+            match balance.checked_div(burn_per_hour) {
+                Some(hours) => println!("Runway at full claims: {hours} hours"),
+                None => println!("Runway at full claims: unlimited (nothing accruing)"),
+            }
+        }
+        Command::EmergencyWithdraw { step } => {
+            let (_, pool) = op.pool()?;
+            let (keys, _) = op.pool_keys(&pool.mint)?;
+            let default_destination = keys.token_account(&op.payer());
+            let instruction = match step {
+                WithdrawStep::Announce {
+                    amount,
+                    destination,
+                } => instructions::announce_emergency_withdraw(
+                    &op.authority,
+                    &destination.unwrap_or(default_destination),
+                    amount,
+                ),
+                WithdrawStep::Execute {
+                    amount,
+                    destination,
+                } => instructions::emergency_withdraw(
+                    &keys,
+                    &destination.unwrap_or(default_destination),
+                    amount,
+                ),
+                WithdrawStep::Cancel => instructions::cancel_emergency_withdraw(&op.authority),
+            };
+            op.execute(&[instruction], "Done")?;
+        }
+        Command::Sign { .. } | Command::Submit { .. } => unreachable!("handled before connecting"),
+    }
+    Ok(())
+}
@@ -0,0 +1,71 @@
+-- Schema written by reward-indexer. It runs unchanged on SQLite and Postgres.
+--
+-- Addresses and signatures are base58 TEXT. Slots and timestamps are BIGINT, and
+-- timestamps are unix seconds taken from the on-chain clock. Token amounts and caps are
+-- u64 on chain, stored as BIGINT. A value above the BIGINT maximum is clamped to it; in
+-- practice only an "uncapped" sentinel gets that large.
+
+-- One row per event the program emitted in a successful transaction.
+CREATE TABLE IF NOT EXISTS events (
+    signature TEXT NOT NULL,
+    -- Position among the program's events in the transaction
+    idx INTEGER NOT NULL,
+    slot BIGINT NOT NULL,
+    -- Event struct name, e.g. 'ExcessWithdrawn'. Unknown discriminators are stored as
+    -- 'unknown'.
+    name TEXT NOT NULL,
+    -- Base64 Borsh payload with the 8-byte discriminator stripped
+    data TEXT NOT NULL,
+    PRIMARY KEY (signature, idx)
+);
+
+-- One row per user account, written the first time the indexer sees the account.
+CREATE TABLE IF NOT EXISTS registrations (
+    user_account TEXT PRIMARY KEY,
+    wallet TEXT NOT NULL,
+    pool TEXT NOT NULL,
+    registered_at BIGINT NOT NULL,
+    slot BIGINT NOT NULL
+);
+
+-- Claims, derived from increases in a user account's claim counter. A single row can
+-- cover several claims if they landed between two account updates.
+CREATE TABLE IF NOT EXISTS claims (
+    user_account TEXT NOT NULL,
+    -- The user's total_claims after this claim
+    claim_number BIGINT NOT NULL,
+    wallet TEXT NOT NULL,
+    pool TEXT NOT NULL,
+    -- Increase in total_earned: the amount paid to the user
+    amount BIGINT NOT NULL,
+    -- Increase in total_fees_paid
+    fees BIGINT NOT NULL,
+    claimed_at BIGINT NOT NULL,
+    slot BIGINT NOT NULL,
+    PRIMARY KEY (user_account, claim_number)
+);
+
+-- Pool configuration, written when the pool is first seen and again whenever one of
+-- these columns changes.
+CREATE TABLE IF NOT EXISTS config_changes (
+    pool TEXT NOT NULL,
+    slot BIGINT NOT NULL,
+    authority TEXT NOT NULL,
+    reward_rate_per_hour BIGINT NOT NULL,
+    -- 'Floor', 'Ceil' or 'HalfEven'
+    rounding TEXT NOT NULL,
+    -- Named min_claim_interval_hours, and in hours, before the program moved to seconds.
+    -- Older databases need
+    -- ALTER TABLE config_changes RENAME COLUMN min_claim_interval_hours TO min_claim_interval_secs
+    min_claim_interval_secs BIGINT NOT NULL,
+    max_daily_reward BIGINT NOT NULL,
+    max_accrual_hours BIGINT NOT NULL,
+    max_claims_per_day INTEGER NOT NULL,
+    claims_paused BOOLEAN NOT NULL,
+    registrations_paused BOOLEAN NOT NULL,
+    accrual_paused BOOLEAN NOT NULL,
+    PRIMARY KEY (pool, slot)
+);
+
+CREATE INDEX IF NOT EXISTS claims_by_pool ON claims (pool, claimed_at);
+CREATE INDEX IF NOT EXISTS events_by_name ON events (name, slot);
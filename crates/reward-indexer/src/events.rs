@@ -0,0 +1,160 @@
+//! Program events recovered from transaction logs
+
+use anchor_lang::{prelude::Pubkey, AnchorDeserialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reward_system::ID;
+
+macro_rules! event_names {
+    ($($event:ident),* $(,)?) => {
+        const EVENT_NAMES: &[([u8; 8], &str)] = &[
+            $((<reward_system::$event as Discriminator>::DISCRIMINATOR, stringify!($event)),)*
+        ];
+    };
+}
+
+event_names!(
+    MatchingContribution,
+    ReferralBonus,
+    LotteryWon,
+    BadgeMinted,
+    ActivityRecorded,
+    BoostGranted,
+    BoostPurchased,
+    ExcessWithdrawn,
+    EmergencyWithdrawAnnounced,
+    EmergencyWithdrawCancelled,
+    PendingConfigApplied,
+    EpochRolled,
+    AccrualSnapshotCommitted,
+    YieldRebalanced,
+    BudgetDrawn,
+    CrankClaimed,
+    RewardsBurned,
+    ClaimRetryHint,
+    DenylistBlocked,
+    DormancyWarning,
+);
+
+/// An event emitted by the program, still Borsh-encoded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawEvent {
+    pub name: &'static str,
+    /// Payload after the discriminator
+    pub data: Vec<u8>,
+}
+
+impl RawEvent {
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let (discriminator, data) = (bytes.get(..8)?, bytes.get(8..)?);
+        let name = EVENT_NAMES
+            .iter()
+            .find(|(known, _)| known == discriminator)
+            .map_or("unknown", |(_, name)| name);
+        Some(Self {
+            name,
+            data: data.to_vec(),
+        })
+    }
+
+    /// Deserialize into the event type, if the name matches it
+    pub fn parse<T: Discriminator + AnchorDeserialize>(&self) -> Option<T> {
+        let (_, name) = EVENT_NAMES
+            .iter()
+            .find(|(discriminator, _)| *discriminator == T::DISCRIMINATOR)?;
+        (*name == self.name).then(|| T::try_from_slice(&self.data).ok())?
+    }
+}
+
+/// Events logged by the program itself in a transaction's logs, in order. `Program data:`
+/// lines written while another program is executing, including programs the reward
+/// program calls, are skipped.
+pub fn events_in_logs(logs: &[String]) -> Vec<RawEvent> {
+    let program = ID.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        if let Some(data) = line.strip_prefix("Program data: ") {
+            if stack.last() != Some(&program.as_str()) {
+                continue;
+            }
+            // sol_log_data writes one base64 chunk per field; emit! logs a single field
+            let decoded = data
+                .split(' ')
+                .next()
+                .and_then(|chunk| STANDARD.decode(chunk).ok());
+            events.extend(decoded.as_deref().and_then(RawEvent::decode));
+        } else if let Some(rest) = line.strip_prefix("Program ") {
+            let mut words = rest.split(' ');
+            let (Some(id), Some(verb)) = (words.next(), words.next()) else {
+                continue;
+            };
+            if id.parse::<Pubkey>().is_err() {
+                continue;
+            }
+            match verb {
+                "invoke" => stack.push(id),
+                "success" | "failed:" => {
+                    stack.pop();
+                }
+                _ => {}
+            }
+        }
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AnchorSerialize;
+    use reward_system::ExcessWithdrawn;
+
+    fn data_line(event: &impl AnchorSerialize, discriminator: [u8; 8]) -> String {
+        let mut bytes = discriminator.to_vec();
+        event.serialize(&mut bytes).unwrap();
+        format!("Program data: {}", STANDARD.encode(bytes))
+    }
+
+    #[test]
+    fn only_the_programs_own_events_are_decoded() {
+        let program = ID.to_string();
+        let other = Pubkey::new_unique().to_string();
+        let event = ExcessWithdrawn {
+            pool: Pubkey::new_unique(),
+            destination: Pubkey::new_unique(),
+            amount: 42,
+            reserve: 7,
+        };
+        let line = data_line(&event, ExcessWithdrawn::DISCRIMINATOR);
+        let logs = vec![
+            format!("Program {program} invoke [1]"),
+            format!("Program {other} invoke [2]"),
+            line.clone(),
+            format!("Program {other} success"),
+            line,
+            format!("Program {program} consumed 5000 of 200000 compute units"),
+            format!("Program {program} success"),
+        ];
+
+        let events = events_in_logs(&logs);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].name, "ExcessWithdrawn");
+        let parsed: ExcessWithdrawn = events[0].parse().unwrap();
+        assert_eq!((parsed.amount, parsed.reserve), (42, 7));
+    }
+
+    #[test]
+    fn unknown_discriminators_are_kept() {
+        let program = ID.to_string();
+        let logs = vec![
+            format!("Program {program} invoke [1]"),
+            format!("Program data: {}", STANDARD.encode([9u8; 12])),
+            format!("Program {program} success"),
+        ];
+
+        let events = events_in_logs(&logs);
+        assert_eq!(events[0].name, "unknown");
+        assert_eq!(events[0].data, [9u8; 4]);
+    }
+}
@@ -0,0 +1,58 @@
+//! Indexer for the reward system program. It follows the program's transaction logs and
+//! account updates, decodes events and derives claims, registrations and config changes,
+//! and writes them to a [`Sink`] using the tables in `schema.sql`.
+//!
+//! [`websocket::run`] feeds an [`Indexer`] from a validator's websocket API. Deployments
+//! with a Geyser stream can drive the same [`Indexer::transaction_logs`] and
+//! [`Indexer::account_updated`] methods from their own subscription.
+
+pub mod events;
+pub mod records;
+pub mod sink;
+pub mod websocket;
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::Result;
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+pub use events::{events_in_logs, RawEvent};
+pub use records::{Record, Tracker};
+pub use sink::Sink;
+
+use records::EventRecord;
+
+pub struct Indexer<S> {
+    sink: S,
+    tracker: Tracker,
+}
+
+impl<S: Sink> Indexer<S> {
+    pub fn new(sink: S) -> Self {
+        Self {
+            sink,
+            tracker: Tracker::default(),
+        }
+    }
+
+    /// Record the events in the logs of a successful transaction
+    pub fn transaction_logs(&mut self, signature: &str, slot: u64, logs: &[String]) -> Result<()> {
+        for (idx, event) in events_in_logs(logs).into_iter().enumerate() {
+            self.sink.write(&Record::Event(EventRecord {
+                signature: signature.to_string(),
+                idx: idx as u32,
+                slot,
+                name: event.name,
+                data: STANDARD.encode(&event.data),
+            }))?;
+        }
+        Ok(())
+    }
+
+    /// Record what changed in a program account
+    pub fn account_updated(&mut self, address: &Pubkey, slot: u64, data: &[u8]) -> Result<()> {
+        for record in self.tracker.account_updated(address, slot, data) {
+            self.sink.write(&record)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use anyhow::Result;
+use clap::Parser;
+use reward_indexer::{sink, websocket, Indexer};
+
+#[derive(Parser)]
+#[command(
+    name = "reward-indexer",
+    version,
+    about = "Index reward system events into SQL"
+)]
+struct Cli {
+    /// Validator websocket endpoint
+    #[arg(long, env = "SOLANA_WS_URL", default_value = "ws://localhost:8900")]
+    ws_url: String,
+
+    /// SQLite database path, or a postgres:// connection string
+    #[arg(long, env = "DATABASE_URL", default_value = "reward-index.db")]
+    database: String,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut indexer = Indexer::new(sink::open(&cli.database)?);
+    websocket::run(&cli.ws_url, &mut indexer)
+}
@@ -0,0 +1,236 @@
+//! Rows written to a sink, and the tracker that derives them from account updates
+
+use std::collections::HashMap;
+
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, Discriminator};
+use reward_system::{RewardPool, UserAccount};
+
+/// A row for one of the tables in `schema.sql`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Record {
+    Event(EventRecord),
+    Registration(RegistrationRecord),
+    Claim(ClaimRecord),
+    ConfigChange(ConfigRecord),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EventRecord {
+    pub signature: String,
+    pub idx: u32,
+    pub slot: u64,
+    pub name: &'static str,
+    /// Base64 Borsh payload
+    pub data: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegistrationRecord {
+    pub user_account: Pubkey,
+    pub wallet: Pubkey,
+    pub pool: Pubkey,
+    pub registered_at: i64,
+    pub slot: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ClaimRecord {
+    pub user_account: Pubkey,
+    pub claim_number: u64,
+    pub wallet: Pubkey,
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub fees: u64,
+    pub claimed_at: i64,
+    pub slot: u64,
+}
+
+/// The configuration columns of a pool tracked in `config_changes`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PoolConfig {
+    pub authority: Pubkey,
+    pub reward_rate_per_hour: u64,
+    pub rounding: String,
+    pub min_claim_interval_secs: u64,
+    pub max_daily_reward: u64,
+    pub max_accrual_hours: u64,
+    pub max_claims_per_day: u16,
+    pub claims_paused: bool,
+    pub registrations_paused: bool,
+    pub accrual_paused: bool,
+}
+
+impl From<&RewardPool> for PoolConfig {
+    fn from(pool: &RewardPool) -> Self {
+        Self {
+            authority: pool.authority,
+            reward_rate_per_hour: pool.reward_rate_per_hour,
+            rounding: format!("{:?}", pool.rounding),
+            min_claim_interval_secs: pool.min_claim_interval_secs,
+            max_daily_reward: pool.max_daily_reward,
+            max_accrual_hours: pool.max_accrual_hours,
+            max_claims_per_day: pool.max_claims_per_day,
+            claims_paused: pool.claims_paused,
+            registrations_paused: pool.registrations_paused,
+            accrual_paused: pool.accrual_paused_at != 0,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigRecord {
+    pub pool: Pubkey,
+    pub slot: u64,
+    pub config: PoolConfig,
+}
+
+/// Counters of a user account as of its last update
+struct UserSnapshot {
+    total_claims: u64,
+    total_earned: u64,
+    total_fees_paid: u64,
+}
+
+/// Turns a stream of program account updates into registration, claim and config rows by
+/// comparing each account with its previous state. The first update seen for a user
+/// account records its registration and only sets the baseline for claims, so claims made
+/// while the indexer was not running are not recorded.
+#[derive(Default)]
+pub struct Tracker {
+    users: HashMap<Pubkey, UserSnapshot>,
+    pools: HashMap<Pubkey, PoolConfig>,
+}
+
+impl Tracker {
+    /// Rows implied by `address` now holding `data`. Accounts of other types, and accounts
+    /// on an older layout that no longer deserialize, yield nothing.
+    pub fn account_updated(&mut self, address: &Pubkey, slot: u64, data: &[u8]) -> Vec<Record> {
+        match data.get(..8) {
+            Some(discriminator) if discriminator == UserAccount::DISCRIMINATOR => {
+                UserAccount::try_deserialize(&mut &data[..])
+                    .map(|user| self.user_updated(address, slot, &user))
+                    .unwrap_or_default()
+            }
+            Some(discriminator) if discriminator == RewardPool::DISCRIMINATOR => {
+                RewardPool::try_deserialize(&mut &data[..])
+                    .map(|pool| self.pool_updated(address, slot, &pool))
+                    .unwrap_or_default()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn user_updated(&mut self, address: &Pubkey, slot: u64, user: &UserAccount) -> Vec<Record> {
+        let snapshot = UserSnapshot {
+            total_claims: user.total_claims,
+            total_earned: user.total_earned,
+            total_fees_paid: user.total_fees_paid,
+        };
+        let Some(previous) = self.users.insert(*address, snapshot) else {
+            return vec![Record::Registration(RegistrationRecord {
+                user_account: *address,
+                wallet: user.authority,
+                pool: user.pool,
+                registered_at: user.registration_timestamp,
+                slot,
+            })];
+        };
+        if user.total_claims <= previous.total_claims {
+            return Vec::new();
+        }
+        vec![Record::Claim(ClaimRecord {
+            user_account: *address,
+            claim_number: user.total_claims,
+            wallet: user.authority,
+            pool: user.pool,
+            amount: user.total_earned.saturating_sub(previous.total_earned),
+            fees: user
+                .total_fees_paid
+                .saturating_sub(previous.total_fees_paid),
+            claimed_at: user.last_claim_timestamp,
+            slot,
+        })]
+    }
+
+    fn pool_updated(&mut self, address: &Pubkey, slot: u64, pool: &RewardPool) -> Vec<Record> {
+        let config = PoolConfig::from(pool);
+        if self.pools.get(address) == Some(&config) {
+            return Vec::new();
+        }
+        self.pools.insert(*address, config.clone());
+        vec![Record::ConfigChange(ConfigRecord {
+            pool: *address,
+            slot,
+            config,
+        })]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::AccountSerialize;
+
+    fn encode(account: &impl AccountSerialize) -> Vec<u8> {
+        let mut data = Vec::new();
+        account.try_serialize(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn claims_are_derived_from_counter_increases() {
+        let mut tracker = Tracker::default();
+        let address = Pubkey::new_unique();
+        let mut user = UserAccount {
+            authority: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            registration_timestamp: 1_000,
+            ..Default::default()
+        };
+
+        let records = tracker.account_updated(&address, 10, &encode(&user));
+        assert!(matches!(records[..], [Record::Registration(_)]));
+
+        user.total_claims = 1;
+        user.total_earned = 250;
+        user.total_fees_paid = 5;
+        user.last_claim_timestamp = 90_000;
+        let records = tracker.account_updated(&address, 20, &encode(&user));
+        let [Record::Claim(claim)] = &records[..] else {
+            panic!("expected a claim, got {records:?}");
+        };
+        assert_eq!((claim.claim_number, claim.amount, claim.fees), (1, 250, 5));
+        assert_eq!((claim.claimed_at, claim.slot), (90_000, 20));
+
+        // Updates that don't claim, e.g. a streak or activity change, record nothing
+        user.activity_points = 3;
+        assert!(tracker
+            .account_updated(&address, 30, &encode(&user))
+            .is_empty());
+    }
+
+    #[test]
+    fn config_rows_are_written_on_change_only() {
+        let mut tracker = Tracker::default();
+        let address = Pubkey::new_unique();
+        let mut pool = RewardPool {
+            reward_rate_per_hour: 100,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            tracker.account_updated(&address, 1, &encode(&pool)).len(),
+            1
+        );
+        pool.total_distributed = 500;
+        assert!(tracker
+            .account_updated(&address, 2, &encode(&pool))
+            .is_empty());
+        pool.claims_paused = true;
+        let records = tracker.account_updated(&address, 3, &encode(&pool));
+        assert!(matches!(
+            &records[..],
+            [Record::ConfigChange(ConfigRecord { slot: 3, config, .. })] if config.claims_paused
+        ));
+    }
+}
@@ -0,0 +1,64 @@
+//! Storage backends. Both create the tables in `schema.sql` on open and write rows with
+//! `ON CONFLICT DO NOTHING`, so replaying the same updates after a restart is harmless.
+
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+#[cfg(feature = "postgres")]
+pub use self::postgres::PostgresSink;
+#[cfg(feature = "sqlite")]
+pub use self::sqlite::SqliteSink;
+
+use anyhow::Result;
+
+use crate::records::Record;
+
+pub const SCHEMA: &str = include_str!("../../schema.sql");
+
+// Statements use Postgres placeholders; SQLite reads `?N` for the same positions
+const INSERT_EVENT: &str = "INSERT INTO events (signature, idx, slot, name, data) \
+     VALUES ($1, $2, $3, $4, $5) ON CONFLICT DO NOTHING";
+const INSERT_REGISTRATION: &str =
+    "INSERT INTO registrations (user_account, wallet, pool, registered_at, slot) \
+     VALUES ($1, $2, $3, $4, $5) ON CONFLICT DO NOTHING";
+const INSERT_CLAIM: &str = "INSERT INTO claims \
+     (user_account, claim_number, wallet, pool, amount, fees, claimed_at, slot) \
+     VALUES ($1, $2, $3, $4, $5, $6, $7, $8) ON CONFLICT DO NOTHING";
+const INSERT_CONFIG_CHANGE: &str = "INSERT INTO config_changes \
+     (pool, slot, authority, reward_rate_per_hour, rounding, min_claim_interval_secs, \
+     max_daily_reward, max_accrual_hours, max_claims_per_day, claims_paused, \
+     registrations_paused, accrual_paused) \
+     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) ON CONFLICT DO NOTHING";
+
+/// Where indexed rows go
+pub trait Sink {
+    fn write(&mut self, record: &Record) -> Result<()>;
+}
+
+impl<S: Sink + ?Sized> Sink for Box<S> {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        (**self).write(record)
+    }
+}
+
+/// Open the sink for `url`: a `postgres://` or `postgresql://` connection string, or
+/// otherwise a SQLite database path
+pub fn open(url: &str) -> Result<Box<dyn Sink>> {
+    if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+        #[cfg(feature = "postgres")]
+        return Ok(Box::new(PostgresSink::connect(url)?));
+        #[cfg(not(feature = "postgres"))]
+        anyhow::bail!("built without the `postgres` feature");
+    }
+    #[cfg(feature = "sqlite")]
+    return Ok(Box::new(SqliteSink::open(url)?));
+    #[cfg(not(feature = "sqlite"))]
+    anyhow::bail!("built without the `sqlite` feature");
+}
+
+/// u64 columns are BIGINT; values past its range are clamped (see `schema.sql`)
+fn sql_int(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
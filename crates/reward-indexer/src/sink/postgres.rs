@@ -0,0 +1,82 @@
+use anyhow::Result;
+use postgres::{Client, NoTls};
+
+use super::{
+    sql_int, Sink, INSERT_CLAIM, INSERT_CONFIG_CHANGE, INSERT_EVENT, INSERT_REGISTRATION, SCHEMA,
+};
+use crate::records::Record;
+
+/// Postgres sink over an unencrypted connection; put TLS in front with a local proxy or
+/// socket if the database is remote
+pub struct PostgresSink {
+    client: Client,
+}
+
+impl PostgresSink {
+    pub fn connect(url: &str) -> Result<Self> {
+        let mut client = Client::connect(url, NoTls)?;
+        client.batch_execute(SCHEMA)?;
+        Ok(Self { client })
+    }
+}
+
+impl Sink for PostgresSink {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        match record {
+            Record::Event(event) => self.client.execute(
+                INSERT_EVENT,
+                &[
+                    &event.signature,
+                    &(event.idx as i32),
+                    &sql_int(event.slot),
+                    &event.name,
+                    &event.data,
+                ],
+            )?,
+            Record::Registration(registration) => self.client.execute(
+                INSERT_REGISTRATION,
+                &[
+                    &registration.user_account.to_string(),
+                    &registration.wallet.to_string(),
+                    &registration.pool.to_string(),
+                    &registration.registered_at,
+                    &sql_int(registration.slot),
+                ],
+            )?,
+            Record::Claim(claim) => self.client.execute(
+                INSERT_CLAIM,
+                &[
+                    &claim.user_account.to_string(),
+                    &sql_int(claim.claim_number),
+                    &claim.wallet.to_string(),
+                    &claim.pool.to_string(),
+                    &sql_int(claim.amount),
+                    &sql_int(claim.fees),
+                    &claim.claimed_at,
+                    &sql_int(claim.slot),
+                ],
+            )?,
+            Record::ConfigChange(change) => {
+                let config = &change.config;
+                self.client.execute(
+                    INSERT_CONFIG_CHANGE,
+                    &[
+                        &change.pool.to_string(),
+                        &sql_int(change.slot),
+                        &config.authority.to_string(),
+                        &sql_int(config.reward_rate_per_hour),
+                        &config.rounding,
+                        &sql_int(config.min_claim_interval_secs),
+                        &sql_int(config.max_daily_reward),
+                        &sql_int(config.max_accrual_hours),
+                        &i32::from(config.max_claims_per_day),
+                        &config.claims_paused,
+                        &config.registrations_paused,
+                        &config.accrual_paused,
+                    ],
+                )?
+            }
+        };
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use anyhow::Result;
+use rusqlite::{params, Connection};
+
+use super::{
+    sql_int, Sink, INSERT_CLAIM, INSERT_CONFIG_CHANGE, INSERT_EVENT, INSERT_REGISTRATION, SCHEMA,
+};
+use crate::records::Record;
+
+pub struct SqliteSink {
+    connection: Connection,
+}
+
+impl SqliteSink {
+    pub fn open(path: &str) -> Result<Self> {
+        Self::new(Connection::open(path)?)
+    }
+
+    pub fn new(connection: Connection) -> Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    fn execute(&self, sql: &str, params: impl rusqlite::Params) -> Result<()> {
+        self.connection
+            .prepare_cached(&sql.replace('$', "?"))?
+            .execute(params)?;
+        Ok(())
+    }
+}
+
+impl Sink for SqliteSink {
+    fn write(&mut self, record: &Record) -> Result<()> {
+        match record {
+            Record::Event(event) => self.execute(
+                INSERT_EVENT,
+                params![
+                    event.signature,
+                    event.idx,
+                    sql_int(event.slot),
+                    event.name,
+                    event.data
+                ],
+            ),
+            Record::Registration(registration) => self.execute(
+                INSERT_REGISTRATION,
+                params![
+                    registration.user_account.to_string(),
+                    registration.wallet.to_string(),
+                    registration.pool.to_string(),
+                    registration.registered_at,
+                    sql_int(registration.slot)
+                ],
+            ),
+            Record::Claim(claim) => self.execute(
+                INSERT_CLAIM,
+                params![
+                    claim.user_account.to_string(),
+                    sql_int(claim.claim_number),
+                    claim.wallet.to_string(),
+                    claim.pool.to_string(),
+                    sql_int(claim.amount),
+                    sql_int(claim.fees),
+                    claim.claimed_at,
+                    sql_int(claim.slot)
+                ],
+            ),
+            Record::ConfigChange(change) => {
+                let config = &change.config;
+                self.execute(
+                    INSERT_CONFIG_CHANGE,
+                    params![
+                        change.pool.to_string(),
+                        sql_int(change.slot),
+                        config.authority.to_string(),
+                        sql_int(config.reward_rate_per_hour),
+                        config.rounding,
+                        sql_int(config.min_claim_interval_secs),
+                        sql_int(config.max_daily_reward),
+                        sql_int(config.max_accrual_hours),
+                        config.max_claims_per_day,
+                        config.claims_paused,
+                        config.registrations_paused,
+                        config.accrual_paused
+                    ],
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::records::ClaimRecord;
+    use anchor_lang::prelude::Pubkey;
+
+    #[test]
+    fn replayed_rows_are_ignored() {
+        let mut sink = SqliteSink::new(Connection::open_in_memory().unwrap()).unwrap();
+        let claim = Record::Claim(ClaimRecord {
+            user_account: Pubkey::new_unique(),
+            claim_number: 1,
+            wallet: Pubkey::new_unique(),
+            pool: Pubkey::new_unique(),
+            amount: u64::MAX,
+            fees: 0,
+            claimed_at: 1_700_000_000,
+            slot: 5,
+        });
+
+        sink.write(&claim).unwrap();
+        sink.write(&claim).unwrap();
+
+        let (rows, amount): (i64, i64) = sink
+            .connection()
+            .query_row("SELECT COUNT(*), MAX(amount) FROM claims", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!((rows, amount), (1, i64::MAX));
+    }
+}
@@ -0,0 +1,108 @@
+//! Subscriptions over a validator's websocket API
+
+use std::{sync::mpsc, thread};
+
+use anchor_lang::prelude::Pubkey;
+use anyhow::{anyhow, Result};
+use reward_system::ID;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    pubsub_client::PubsubClient,
+    rpc_config::{
+        RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionLogsConfig,
+        RpcTransactionLogsFilter,
+    },
+};
+use solana_sdk::{account::Account, commitment_config::CommitmentConfig};
+
+use crate::{Indexer, Sink};
+
+enum Update {
+    Logs {
+        signature: String,
+        slot: u64,
+        logs: Vec<String>,
+    },
+    Account {
+        address: Pubkey,
+        slot: u64,
+        data: Vec<u8>,
+    },
+}
+
+/// Subscribe to the program's logs and accounts at `ws_url` (`confirmed` commitment) and
+/// index updates until either subscription ends. Updates missed while disconnected are
+/// not replayed; restart to resubscribe.
+pub fn run<S: Sink>(ws_url: &str, indexer: &mut Indexer<S>) -> Result<()> {
+    let commitment = Some(CommitmentConfig::confirmed());
+    let (_logs_subscription, logs) = PubsubClient::logs_subscribe(
+        ws_url,
+        RpcTransactionLogsFilter::Mentions(vec![ID.to_string()]),
+        RpcTransactionLogsConfig { commitment },
+    )?;
+    let (_accounts_subscription, accounts) = PubsubClient::program_subscribe(
+        ws_url,
+        &ID,
+        Some(RpcProgramAccountsConfig {
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment,
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+    )?;
+
+    let (sender, updates) = mpsc::channel();
+    let logs_sender = sender.clone();
+    thread::spawn(move || {
+        for response in logs {
+            // Failed transactions are rolled back, events included
+            if response.value.err.is_some() {
+                continue;
+            }
+            let update = Update::Logs {
+                signature: response.value.signature,
+                slot: response.context.slot,
+                logs: response.value.logs,
+            };
+            if logs_sender.send(update).is_err() {
+                break;
+            }
+        }
+    });
+    thread::spawn(move || {
+        for response in accounts {
+            let keyed = response.value;
+            let (Ok(address), Some(account)) =
+                (keyed.pubkey.parse(), keyed.account.decode::<Account>())
+            else {
+                continue;
+            };
+            let update = Update::Account {
+                address,
+                slot: response.context.slot,
+                data: account.data,
+            };
+            if sender.send(update).is_err() {
+                break;
+            }
+        }
+    });
+
+    for update in updates {
+        match update {
+            Update::Logs {
+                signature,
+                slot,
+                logs,
+            } => indexer.transaction_logs(&signature, slot, &logs)?,
+            Update::Account {
+                address,
+                slot,
+                data,
+            } => indexer.account_updated(&address, slot, &data)?,
+        }
+    }
+    Err(anyhow!("websocket subscription to {ws_url} closed"))
+}
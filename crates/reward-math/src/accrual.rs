@@ -0,0 +1,401 @@
+//! Accrual over time: integrating per-hour rates and applying the window and daily caps
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    accrual_step_secs, apply_bps, div_to_int, fixed_point::FRACTION_BITS, whole_steps,
+    RoundingMode, BPS_DENOMINATOR, Q64, SECONDS_PER_HOUR,
+};
+
+/// Flat-rate accrual parameters of a pool
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct AccrualConfig {
+    /// Reward per hour
+    pub reward_rate: Q64,
+    pub min_claim_interval_secs: u64,
+    /// Cap on a single claim's base reward
+    pub max_daily_reward: u64,
+    /// Hours a claim can accrue (0 = unbounded)
+    pub max_accrual_hours: u64,
+    pub rounding: RoundingMode,
+    /// Hourly growth of the unclaimed reward under compound accrual (0 = linear)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub compound_growth_bps: u16,
+}
+
+impl AccrualConfig {
+    /// Hours of `elapsed_hours` that accrue: at most the accrual window, extended by
+    /// `extension_hours` (e.g. a declared outage)
+    pub fn accrual_hours(&self, elapsed_hours: u64, extension_hours: u64) -> u64 {
+        if self.max_accrual_hours == 0 {
+            return elapsed_hours;
+        }
+        elapsed_hours.min(self.max_accrual_hours.saturating_add(extension_hours))
+    }
+
+    /// Seconds of `elapsed_secs` that accrue: whole accrual steps (see
+    /// [`accrual_step_secs`]), at most the accrual window
+    pub fn accrual_secs(&self, elapsed_secs: i64) -> u64 {
+        let step_secs = accrual_step_secs(self.min_claim_interval_secs);
+        let secs = whole_steps(elapsed_secs, step_secs);
+        if self.max_accrual_hours == 0 {
+            return secs;
+        }
+        let window_secs = self
+            .max_accrual_hours
+            .saturating_mul(SECONDS_PER_HOUR as u64);
+        secs.min(window_secs)
+    }
+
+    pub fn interval_met(&self, elapsed_secs: i64) -> bool {
+        u64::try_from(elapsed_secs).unwrap_or(0) >= self.min_claim_interval_secs
+    }
+
+    /// Base reward for a claim `elapsed_secs` after the last one at a constant rate: whole
+    /// accrual steps only, compounded if the config says so, limited to the accrual window
+    /// and `max_daily_reward`, then scaled by `multiplier_bps`. Bonuses and pool-specific
+    /// adjustments come on top.
+    pub fn reward(&self, elapsed_secs: i64, multiplier_bps: u16) -> Option<u64> {
+        let secs = self.accrual_secs(elapsed_secs);
+        let hours = secs / SECONDS_PER_HOUR as u64;
+        let linear = integrate_rate([(self.reward_rate, secs)], self.rounding)?;
+        let uncapped = compound_reward(linear, hours, self.compound_growth_bps, self.rounding);
+        cap_reward(
+            uncapped,
+            self.max_daily_reward,
+            multiplier_bps,
+            self.rounding,
+        )
+    }
+}
+
+/// Reward accrued over consecutive spans of `(rate per hour, seconds)`. Rate-seconds are
+/// summed exactly and the total rounds once, so splitting a span never changes the result.
+pub fn integrate_rate(
+    segments: impl IntoIterator<Item = (Q64, u64)>,
+    rounding: RoundingMode,
+) -> Option<u64> {
+    // Whole and fractional rate-seconds are summed separately so the fractional rate only
+    // rounds once, at the end
+    let (mut whole_secs, mut fraction_secs) = (0u128, 0u128);
+    for (rate, secs) in segments {
+        whole_secs = whole_secs.checked_add(rate.whole() as u128 * secs as u128)?;
+        fraction_secs = fraction_secs.checked_add(rate.fraction() as u128 * secs as u128)?;
+    }
+
+    let whole_secs = whole_secs.checked_add(fraction_secs >> FRACTION_BITS)?;
+    div_to_int(
+        whole_secs,
+        fraction_secs as u64,
+        SECONDS_PER_HOUR as u64,
+        rounding,
+    )
+}
+
+/// Hours of linear accrual that `hours` of compound accrual are worth when the unclaimed
+/// reward grows by `growth_bps` every hour: `((1 + g)^hours - 1) / g` for
+/// `g = growth_bps / 10_000`, the future value of one unit accrued each hour. Rounds down;
+/// `None` if it doesn't fit a [`Q64`].
+pub fn compound_hours(hours: u64, growth_bps: u16) -> Option<Q64> {
+    if growth_bps == 0 {
+        return Some(Q64::from_int(hours));
+    }
+    let growth = Q64::from_ratio(growth_bps.into(), BPS_DENOMINATOR, RoundingMode::Floor)?;
+    let factor = Q64(Q64::ONE.0.checked_add(growth.0)?).checked_pow(hours)?;
+
+    // Dividing by g is multiplying by 10_000 / growth_bps, split so it can't overflow early
+    let excess = factor.0 - Q64::ONE.0;
+    let growth_bps = u128::from(growth_bps);
+    let denominator = u128::from(BPS_DENOMINATOR);
+    (excess / growth_bps)
+        .checked_mul(denominator)?
+        .checked_add(excess % growth_bps * denominator / growth_bps)
+        .map(Q64)
+}
+
+/// Compound a `linear_reward` accrued evenly over `hours`: each hour's accrual also grows
+/// by `growth_bps` per hour until the claim, so the reward is scaled by
+/// `compound_hours(hours) / hours`. Rate changes inside the span are treated as spread
+/// evenly over it. Growth beyond `u64` saturates, leaving the per-claim cap to bound it.
+///
+/// The growth factor is a Q64.64 binary power rounded down at every step, so it never
+/// exceeds the exact factor. Its relative error stays below 2^-32 for spans up to a year
+/// (8,760 hours) at any rate of 1 bps or more; the final rounding adds at most one unit.
+pub fn compound_reward(
+    linear_reward: u64,
+    hours: u64,
+    growth_bps: u16,
+    rounding: RoundingMode,
+) -> u64 {
+    if growth_bps == 0 || hours <= 1 {
+        return linear_reward;
+    }
+    compound_hours(hours, growth_bps)
+        .and_then(|effective| effective.mul_div_int(linear_reward, hours, rounding))
+        .unwrap_or(u64::MAX)
+}
+
+/// Grow an unclaimed `reward` by `growth_bps` every hour for `hours`, as compound accrual
+/// grows what has accrued so far. Rounds the growth factor down like [`compound_reward`] and
+/// saturates the same way.
+pub fn grow_reward(reward: u64, hours: u64, growth_bps: u16, rounding: RoundingMode) -> u64 {
+    if growth_bps == 0 || hours == 0 {
+        return reward;
+    }
+    Q64::from_ratio(growth_bps.into(), BPS_DENOMINATOR, RoundingMode::Floor)
+        .and_then(|growth| Q64(Q64::ONE.0.checked_add(growth.0)?).checked_pow(hours))
+        .and_then(|factor| factor.mul_div_int(reward, 1, rounding))
+        .unwrap_or(u64::MAX)
+}
+
+/// Apply the per-claim cap, then the user's multiplier
+pub fn cap_reward(
+    uncapped: u64,
+    max_daily_reward: u64,
+    multiplier_bps: u16,
+    rounding: RoundingMode,
+) -> Option<u64> {
+    apply_bps(uncapped.min(max_daily_reward), multiplier_bps, rounding)
+}
+
+/// Rate that decays by `decay_bps` every `decay_interval_secs` from `start_time`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct EmissionSchedule {
+    pub start_time: i64,
+    pub start_rate: u64,
+    pub decay_interval_secs: i64,
+    pub decay_bps: u16,
+}
+
+impl EmissionSchedule {
+    /// Decay intervals completed by `at`; times before the schedule starts are in interval 0
+    pub fn epoch(&self, at: i64) -> u64 {
+        u64::try_from(at.saturating_sub(self.start_time) / self.decay_interval_secs).unwrap_or(0)
+    }
+
+    /// Per-hour rate during decay interval `epoch`
+    pub fn rate_in_epoch(&self, epoch: u64) -> Option<u64> {
+        let mut rate = self.start_rate;
+        for _ in 0..epoch {
+            if rate == 0 {
+                break;
+            }
+            rate = self.decay(rate)?;
+        }
+        Some(rate)
+    }
+
+    pub fn rate_at(&self, at: i64) -> Option<u64> {
+        self.rate_in_epoch(self.epoch(at))
+    }
+
+    /// Rate after one more decay step. Always rounds down so emission never grows.
+    pub fn decay(&self, rate: u64) -> Option<u64> {
+        apply_bps(
+            rate,
+            (BPS_DENOMINATOR as u16).saturating_sub(self.decay_bps),
+            RoundingMode::Floor,
+        )
+    }
+
+    /// Reward emitted from `from` to `end`, integrated piecewise so a span crossing a
+    /// decay boundary gets the blended amount
+    pub fn emitted(&self, from: i64, end: i64, rounding: RoundingMode) -> Option<u64> {
+        let mut epoch = self.epoch(from);
+        let mut rate = self.rate_in_epoch(epoch)?;
+        let mut cursor = from;
+        let mut rate_secs: u128 = 0;
+        while cursor < end && rate > 0 {
+            let boundary = epoch
+                .checked_add(1)
+                .and_then(|next| i64::try_from(next).ok())
+                .and_then(|next| next.checked_mul(self.decay_interval_secs))
+                .and_then(|offset| self.start_time.checked_add(offset))?;
+            let segment_end = end.min(boundary);
+            rate_secs = (rate as u128)
+                .checked_mul((segment_end - cursor) as u128)
+                .and_then(|segment| rate_secs.checked_add(segment))?;
+
+            cursor = segment_end;
+            epoch += 1;
+            rate = self.decay(rate)?;
+        }
+
+        div_to_int(rate_secs, 0, SECONDS_PER_HOUR as u64, rounding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+
+    const HOUR: i64 = SECONDS_PER_HOUR;
+
+    fn config(rate: Q64, max_daily: u64, max_accrual: u64) -> AccrualConfig {
+        AccrualConfig {
+            reward_rate: rate,
+            min_claim_interval_secs: HOUR as u64,
+            max_daily_reward: max_daily,
+            max_accrual_hours: max_accrual,
+            rounding: RoundingMode::Floor,
+            compound_growth_bps: 0,
+        }
+    }
+
+    /// Exact compound reward, floored: `linear * ((1 + g)^h - 1) / (g * h)` in integers
+    fn exact_compound(linear: u64, hours: u32, growth_bps: u16) -> u64 {
+        let scale = BPS_DENOMINATOR as u128;
+        let grown = (scale + growth_bps as u128).pow(hours);
+        let numerator = linear as u128 * (grown - scale.pow(hours));
+        let denominator = growth_bps as u128 * scale.pow(hours - 1) * hours as u128;
+        (numerator / denominator) as u64
+    }
+
+    /// Compound reward in floating point, `expm1` and `ln_1p` keeping small rates accurate
+    fn float_compound(linear: u64, hours: u64, growth_bps: u16) -> f64 {
+        let growth = growth_bps as f64 / BPS_DENOMINATOR as f64;
+        let effective_hours = (hours as f64 * growth.ln_1p()).exp_m1() / growth;
+        linear as f64 * effective_hours / hours as f64
+    }
+
+    #[test]
+    fn only_whole_hours_inside_the_window_accrue() {
+        let config = config(Q64::from_int(10), 1_000, 24);
+
+        assert_eq!(config.reward(5 * HOUR + 59 * 60, 10_000), Some(50));
+        assert_eq!(config.reward(72 * HOUR, 10_000), Some(240));
+        assert_eq!(config.reward(-HOUR, 10_000), Some(0));
+    }
+
+    #[test]
+    fn sub_hour_intervals_accrue_in_whole_intervals() {
+        let config = AccrualConfig {
+            min_claim_interval_secs: 15 * 60,
+            ..config(Q64::from_int(60), 1_000, 24)
+        };
+
+        assert!(!config.interval_met(14 * 60));
+        assert!(config.interval_met(15 * 60));
+        assert_eq!(config.reward(15 * 60, 10_000), Some(15));
+        assert_eq!(config.reward(44 * 60, 10_000), Some(30));
+        assert_eq!(config.reward(90 * 60, 10_000), Some(90));
+    }
+
+    #[test]
+    fn daily_cap_applies_before_the_multiplier() {
+        let config = config(Q64::from_int(100), 1_000, 0);
+
+        assert_eq!(config.reward(24 * HOUR, 15_000), Some(1_500));
+    }
+
+    #[test]
+    fn split_spans_integrate_like_one() {
+        let rate = Q64::from_ratio(1, 3, RoundingMode::Floor).unwrap();
+        let whole = integrate_rate([(rate, 9 * HOUR as u64)], RoundingMode::Floor);
+        let split = integrate_rate(
+            [(rate, 4 * HOUR as u64), (rate, 5 * HOUR as u64)],
+            RoundingMode::Floor,
+        );
+
+        // A third floored to Q64 is just under 1/3, so nine hours come to 2.99..
+        assert_eq!(whole, Some(2));
+        assert_eq!(split, whole);
+    }
+
+    #[test]
+    fn compound_matches_the_exact_formula() {
+        // Two hours at 50%: the first hour's 100 grows to 150 by the second
+        assert_eq!(compound_reward(200, 2, 5_000, RoundingMode::Floor), 250);
+        assert_eq!(compound_reward(200, 2, 0, RoundingMode::Floor), 200);
+        assert_eq!(compound_reward(200, 1, 5_000, RoundingMode::Floor), 200);
+
+        for hours in 1..=7 {
+            for growth_bps in [1, 7, 250, 3_333, 10_000] {
+                for linear in [1, 999, 123_456, 1_000_000] {
+                    let fixed = compound_reward(linear, hours, growth_bps, RoundingMode::Floor);
+                    let exact = exact_compound(linear, hours as u32, growth_bps);
+                    assert!(
+                        fixed <= exact && exact - fixed <= 1,
+                        "{hours}h {growth_bps}bps"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn compound_error_stays_bounded_over_a_year() {
+        for hours in [24, 168, 1_000, 8_760] {
+            for growth_bps in [1, 3, 10, 25] {
+                let linear = 1_000_000 * hours;
+                let fixed = compound_reward(linear, hours, growth_bps, RoundingMode::Floor);
+                let reference = float_compound(linear, hours, growth_bps);
+                let error = (fixed as f64 - reference).abs();
+                assert!(
+                    error <= reference * 2f64.powi(-32) + 1.0,
+                    "{hours}h {growth_bps}bps: {fixed} vs {reference}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn grown_rewards_follow_the_hourly_factor() {
+        // 1,000 growing 50% an hour for two hours, and 1% an hour for a day
+        assert_eq!(grow_reward(1_000, 2, 5_000, RoundingMode::Floor), 2_250);
+        assert_eq!(grow_reward(1_000, 24, 100, RoundingMode::Floor), 1_269);
+        assert_eq!(grow_reward(1_000, 0, 5_000, RoundingMode::Floor), 1_000);
+        assert_eq!(grow_reward(1_000, 2, 0, RoundingMode::Floor), 1_000);
+        assert_eq!(
+            grow_reward(1_000, 100_000, 10_000, RoundingMode::Floor),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn compound_growth_saturates_into_the_cap() {
+        assert_eq!(
+            compound_reward(1_000, 100_000, 10_000, RoundingMode::Floor),
+            u64::MAX
+        );
+        let config = AccrualConfig {
+            compound_growth_bps: 10_000,
+            ..config(Q64::from_int(1_000), 50_000, 0)
+        };
+        assert_eq!(config.reward(100_000 * HOUR, 10_000), Some(50_000));
+    }
+
+    #[test]
+    fn emission_blends_across_decay_boundaries() {
+        let schedule = EmissionSchedule {
+            start_time: 0,
+            start_rate: 100,
+            decay_interval_secs: 10 * HOUR,
+            decay_bps: 5_000,
+        };
+
+        assert_eq!(schedule.rate_at(25 * HOUR), Some(25));
+        // 5h at 100 and 5h at 50
+        assert_eq!(
+            schedule.emitted(5 * HOUR, 15 * HOUR, RoundingMode::Floor),
+            Some(750)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn config_round_trips_through_json() {
+        let config = config(Q64::from_parts(2, 1 << 63), 5_000, 48);
+        let json = serde_json::to_string(&config).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<AccrualConfig>(&json).unwrap(),
+            config
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Q64.64 fixed-point arithmetic for sub-unit reward rates.
+//!
+//! A [`Q64`] stores `value * 2^64` in a `u128`: the high 64 bits are whole units and the
+//! low 64 bits the fraction, so a rate like half a base unit per hour is exact. Results
+//! only become integers at the end of a calculation, rounded as the caller asks.
+
+use core::cmp::Ordering;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+pub const FRACTION_BITS: u32 = 64;
+
+/// How a result that falls between two integers is resolved. Applied to an amount the
+/// pool pays out, `Floor` favors the pool and `Ceil` the recipient; applied to a
+/// deduction the favor is reversed. `HalfEven` is unbiased over many claims.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum RoundingMode {
+    /// Toward zero
+    #[default]
+    Floor,
+    /// Away from zero
+    Ceil,
+    /// To the nearest integer, ties to the even one (banker's rounding)
+    HalfEven,
+}
+
+/// Whether `quotient + remainder / denominator` rounds up to `quotient + 1`
+fn rounds_up(quotient: u128, remainder: u128, denominator: u128, rounding: RoundingMode) -> bool {
+    match rounding {
+        RoundingMode::Floor => false,
+        RoundingMode::Ceil => remainder > 0,
+        RoundingMode::HalfEven => match remainder.cmp(&(denominator - remainder)) {
+            Ordering::Less => false,
+            Ordering::Greater => true,
+            Ordering::Equal => quotient % 2 == 1,
+        },
+    }
+}
+
+/// `(whole + fraction / 2^64) / denominator`, rounded to an integer
+pub fn div_to_int(
+    whole: u128,
+    fraction: u64,
+    denominator: u64,
+    rounding: RoundingMode,
+) -> Option<u64> {
+    if denominator == 0 {
+        return None;
+    }
+    let denominator = denominator as u128;
+    let quotient = whole / denominator;
+    let remainder = ((whole % denominator) << FRACTION_BITS) | fraction as u128;
+    let up = rounds_up(quotient, remainder, denominator << FRACTION_BITS, rounding);
+
+    quotient
+        .checked_add(u128::from(up))
+        .and_then(|rounded| u64::try_from(rounded).ok())
+}
+
+/// `a * b / denominator` without intermediate overflow, rounded to an integer
+pub fn mul_div(a: u64, b: u64, denominator: u64, rounding: RoundingMode) -> Option<u64> {
+    div_to_int(a as u128 * b as u128, 0, denominator, rounding)
+}
+
+/// Unsigned Q64.64 fixed-point number
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Q64(pub u128);
+
+impl Q64 {
+    pub const ZERO: Self = Self(0);
+    pub const ONE: Self = Self(1 << FRACTION_BITS);
+
+    pub fn from_int(value: u64) -> Self {
+        Self((value as u128) << FRACTION_BITS)
+    }
+
+    pub fn from_parts(whole: u64, fraction: u64) -> Self {
+        Self((whole as u128) << FRACTION_BITS | fraction as u128)
+    }
+
+    /// `numerator / denominator`, rounded to the nearest representable value
+    pub fn from_ratio(numerator: u64, denominator: u64, rounding: RoundingMode) -> Option<Self> {
+        if denominator == 0 {
+            return None;
+        }
+        let scaled = (numerator as u128) << FRACTION_BITS;
+        let denominator = denominator as u128;
+        let quotient = scaled / denominator;
+        let up = rounds_up(quotient, scaled % denominator, denominator, rounding);
+        Some(Self(quotient + u128::from(up)))
+    }
+
+    pub fn whole(self) -> u64 {
+        (self.0 >> FRACTION_BITS) as u64
+    }
+
+    pub fn fraction(self) -> u64 {
+        self.0 as u64
+    }
+
+    /// `self * other`, rounded down; `None` if the product doesn't fit
+    pub fn checked_mul(self, other: Self) -> Option<Self> {
+        let (a_whole, a_fraction) = (self.whole() as u128, self.fraction() as u128);
+        let (b_whole, b_fraction) = (other.whole() as u128, other.fraction() as u128);
+        let whole = a_whole * b_whole;
+        if whole > u64::MAX as u128 {
+            return None;
+        }
+        (whole << FRACTION_BITS)
+            .checked_add(a_whole * b_fraction)?
+            .checked_add(a_fraction * b_whole)?
+            .checked_add((a_fraction * b_fraction) >> FRACTION_BITS)
+            .map(Self)
+    }
+
+    /// `self^exponent` by binary exponentiation, rounding every product down, so the result
+    /// never exceeds the exact power; `None` if it doesn't fit
+    pub fn checked_pow(self, mut exponent: u64) -> Option<Self> {
+        let (mut base, mut result) = (self, Self::ONE);
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.checked_mul(base)?;
+            }
+            exponent >>= 1;
+            if exponent > 0 {
+                base = base.checked_mul(base)?;
+            }
+        }
+        Some(result)
+    }
+
+    /// `self * value / denominator`, computed exactly and rounded to an integer
+    pub fn mul_div_int(self, value: u64, denominator: u64, rounding: RoundingMode) -> Option<u64> {
+        let fraction_product = self.fraction() as u128 * value as u128;
+        let whole = (self.whole() as u128 * value as u128)
+            .checked_add(fraction_product >> FRACTION_BITS)?;
+        div_to_int(whole, fraction_product as u64, denominator, rounding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_a_unit_per_hour_accrues_exactly() {
+        let half = Q64::from_ratio(1, 2, RoundingMode::Floor).unwrap();
+        assert_eq!(half, Q64::from_parts(0, 1 << 63));
+
+        assert_eq!(half.mul_div_int(3, 1, RoundingMode::Floor), Some(1));
+        assert_eq!(half.mul_div_int(3, 1, RoundingMode::Ceil), Some(2));
+        // 1.5 hours at 0.5/hour, computed in seconds
+        assert_eq!(
+            half.mul_div_int(5_400, 3_600, RoundingMode::HalfEven),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn powers_round_down_and_stop_at_overflow() {
+        let one_and_a_half = Q64::from_parts(1, 1 << 63);
+        // 1.5^4 = 5.0625 is exact in binary
+        assert_eq!(
+            one_and_a_half.checked_pow(4),
+            Some(Q64::from_parts(5, 1 << 60))
+        );
+        assert_eq!(one_and_a_half.checked_pow(0), Some(Q64::ONE));
+        assert_eq!(
+            Q64::from_int(2).checked_pow(63),
+            Some(Q64::from_int(1 << 63))
+        );
+        assert_eq!(Q64::from_int(2).checked_pow(64), None);
+
+        // A third squared loses its last bit to the floor
+        let third = Q64::from_ratio(1, 3, RoundingMode::Floor).unwrap();
+        let ninth = Q64::from_ratio(1, 9, RoundingMode::Floor).unwrap();
+        let squared = third.checked_mul(third).unwrap();
+        assert!(squared <= ninth && ninth.0 - squared.0 <= 1);
+    }
+
+    #[test]
+    fn rounding_modes_resolve_remainders() {
+        assert_eq!(mul_div(7, 1, 2, RoundingMode::Floor), Some(3));
+        assert_eq!(mul_div(7, 1, 2, RoundingMode::Ceil), Some(4));
+        assert_eq!(mul_div(7, 1, 2, RoundingMode::HalfEven), Some(4));
+        assert_eq!(mul_div(5, 1, 2, RoundingMode::HalfEven), Some(2));
+        assert_eq!(mul_div(u64::MAX, 2, 1, RoundingMode::Floor), None);
+        assert_eq!(mul_div(1, 1, 0, RoundingMode::Floor), None);
+    }
+}
@@ -0,0 +1,62 @@
+//! Reward accrual arithmetic with no Solana dependencies, shared by the on-chain program
+//! and off-chain services so both compute exactly the same amounts.
+//!
+//! The crate is `no_std` and allocation-free. Functions return `None` where the program
+//! would fail with an arithmetic overflow. Enable the `serde` feature to serialize the
+//! config types.
+
+#![no_std]
+
+pub mod accrual;
+pub mod fixed_point;
+
+pub use accrual::{AccrualConfig, EmissionSchedule};
+pub use fixed_point::{div_to_int, mul_div, RoundingMode, Q64};
+
+pub const SECONDS_PER_HOUR: i64 = 3600;
+pub const SECONDS_PER_DAY: i64 = 86_400;
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Whole hours in a span of seconds; negative spans count as none
+pub fn whole_hours(secs: i64) -> u64 {
+    u64::try_from(secs / SECONDS_PER_HOUR).unwrap_or(0)
+}
+
+/// Granularity accrual is counted in under a minimum claim interval: whole hours, or
+/// whole intervals where claims may come more often than hourly
+pub fn accrual_step_secs(min_claim_interval_secs: u64) -> u64 {
+    if min_claim_interval_secs > 0 && min_claim_interval_secs < SECONDS_PER_HOUR as u64 {
+        min_claim_interval_secs
+    } else {
+        SECONDS_PER_HOUR as u64
+    }
+}
+
+/// Seconds of a span in whole `step_secs` steps; negative spans count as none, and a zero
+/// step leaves the span whole
+pub fn whole_steps(secs: i64, step_secs: u64) -> u64 {
+    let secs = u64::try_from(secs).unwrap_or(0);
+    secs - secs.checked_rem(step_secs).unwrap_or(0)
+}
+
+/// UTC day containing unix time `timestamp`, as days since the unix epoch
+pub fn day_index(timestamp: i64) -> u64 {
+    timestamp.max(0) as u64 / SECONDS_PER_DAY as u64
+}
+
+/// `amount * bps / 10_000`, rounded as `rounding` says
+pub fn apply_bps(amount: u64, bps: u16, rounding: RoundingMode) -> Option<u64> {
+    mul_div(amount, bps.into(), BPS_DENOMINATOR, rounding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn whole_steps_round_down_and_tolerate_a_zero_step() {
+        assert_eq!(whole_steps(44 * 60, 15 * 60), 30 * 60);
+        assert_eq!(whole_steps(-SECONDS_PER_HOUR, 15 * 60), 0);
+        assert_eq!(whole_steps(44 * 60, 0), 44 * 60);
+    }
+}
@@ -0,0 +1,1544 @@
+//! Typed builders for the program's instructions. Builders that take optional accounts
+//! have an `_accounts` counterpart returning the account set, so callers can fill in the
+//! accounts their pool needs before building the instruction themselves.
+
+use anchor_lang::{
+    prelude::Pubkey,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        program_error::ProgramError,
+        sysvar::{rent, slot_hashes},
+    },
+    system_program, InstructionData, ToAccountMetas,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address_with_program_id},
+    memo,
+    metadata::mpl_token_metadata,
+    token::spl_token,
+    token_2022::spl_token_2022,
+};
+pub use reward_system::PoolConfigUpdate;
+use reward_system::{
+    accounts, instruction, BoostTier, DistributionMode, MilestoneKind, NftStakingConfig,
+    RegistrationFee, VestingSchedule, ID, MAX_BOOST_TIERS,
+};
+
+use crate::pda::{
+    find_accrual_snapshot_address, find_badge_milestone_address, find_badge_mint_address,
+    find_boost_granter_address, find_budget_delegation_address, find_claim_session_address,
+    find_denylist_address, find_epoch_address, find_master_edition_address, find_metadata_address,
+    find_pool_member_address, find_recovery_address, find_region_policy_address,
+    find_rent_sponsor_address, find_sol_vault_address, find_stake_position_address,
+    find_stake_vault_address, find_staked_nft_address, find_user_address,
+    find_vesting_position_address, find_wallet_rotation_address, PoolKeys,
+};
+
+/// Instruction calling the program with `accounts` and `data`
+pub fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// `ix`, built for the wallet `user`'s registration account, pointed at its `join_pool`
+/// membership in `pool` instead; the per-user instructions take either
+pub fn for_membership(mut ix: Instruction, pool: &Pubkey, user: &Pubkey) -> Instruction {
+    let registration = find_user_address(user).0;
+    let membership = find_pool_member_address(pool, user).0;
+    for meta in &mut ix.accounts {
+        if meta.pubkey == registration {
+            meta.pubkey = membership;
+        }
+    }
+    ix
+}
+
+/// `initialize_pool` creating the pool PDA of `keys.authority` and its vault
+pub fn initialize_pool(
+    keys: &PoolKeys,
+    reward_rate_per_hour: u64,
+    min_claim_interval_secs: u64,
+    max_daily_reward: u64,
+    max_accrual_hours: u64,
+    distribution_mode: DistributionMode,
+) -> Instruction {
+    build(
+        accounts::InitializePool {
+            authority: keys.authority,
+            reward_pool: keys.pool(),
+            mint: keys.mint,
+            vault: keys.vault(),
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::InitializePool {
+            reward_rate_per_hour,
+            min_claim_interval_secs,
+            max_daily_reward,
+            max_accrual_hours,
+            distribution_mode,
+        },
+    )
+}
+
+/// `migrate_pool` upgrading the pool owned by `authority` to the current layout
+pub fn migrate_pool(authority: &Pubkey) -> Instruction {
+    build(
+        accounts::MigratePool {
+            reward_pool: crate::find_pool_address(authority).0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::MigratePool {},
+    )
+}
+
+/// `migrate_user` upgrading the user account of the wallet `user` to the current layout,
+/// with `payer` covering any extra rent
+pub fn migrate_user(payer: &Pubkey, user: &Pubkey) -> Instruction {
+    build(
+        accounts::MigrateUser {
+            user_account: find_user_address(user).0,
+            user: *user,
+            payer: *payer,
+            system_program: system_program::ID,
+        },
+        instruction::MigrateUser {},
+    )
+}
+
+/// Accounts for `register_user` by the wallet `user`, paying no registration fee
+pub fn register_user_accounts(user: &Pubkey, pool: &Pubkey) -> accounts::RegisterUser {
+    accounts::RegisterUser {
+        authority: *user,
+        user_account: find_user_address(user).0,
+        reward_pool: *pool,
+        denylist_entry: find_denylist_address(pool, user).0,
+        membership: find_pool_member_address(pool, user).0,
+        sol_vault: None,
+        fee_token_account: None,
+        vault: None,
+        mint: None,
+        token_program: None,
+        system_program: system_program::ID,
+    }
+}
+
+/// Fill in the accounts `register_user` needs to pay the pool's `fee`; token fees come from
+/// the user's associated token account
+pub fn pay_registration_fee(
+    accounts: &mut accounts::RegisterUser,
+    keys: &PoolKeys,
+    fee: RegistrationFee,
+) {
+    match fee {
+        RegistrationFee::Lamports(_) => {
+            accounts.sol_vault = Some(find_sol_vault_address(&keys.pool()).0);
+        }
+        RegistrationFee::Tokens(_) => {
+            accounts.fee_token_account = Some(keys.token_account(&accounts.authority));
+            accounts.vault = Some(keys.vault());
+            accounts.mint = Some(keys.mint);
+            accounts.token_program = Some(keys.token_program);
+        }
+    }
+}
+
+/// `register_user` for the wallet `user` on a pool without a registration fee; `proof` is
+/// only checked on allowlisted pools
+pub fn register_user(
+    user: &Pubkey,
+    pool: &Pubkey,
+    proof: Vec<[u8; 32]>,
+    terms_version: u32,
+) -> Instruction {
+    build(
+        register_user_accounts(user, pool),
+        instruction::RegisterUser {
+            proof,
+            terms_version,
+        },
+    )
+}
+
+/// `join_pool` for the wallet `user` on a pool without a registration fee
+pub fn join_pool(
+    user: &Pubkey,
+    pool: &Pubkey,
+    proof: Vec<[u8; 32]>,
+    terms_version: u32,
+) -> Instruction {
+    build(
+        accounts::JoinPool {
+            authority: *user,
+            user_account: find_pool_member_address(pool, user).0,
+            reward_pool: *pool,
+            denylist_entry: find_denylist_address(pool, user).0,
+            registration: find_user_address(user).0,
+            sol_vault: None,
+            fee_token_account: None,
+            vault: None,
+            mint: None,
+            token_program: None,
+            system_program: system_program::ID,
+        },
+        instruction::JoinPool {
+            proof,
+            terms_version,
+        },
+    )
+}
+
+/// `register_user_with_referrer` for the wallet `user`, referred by the wallet `referrer`,
+/// on a pool without a registration fee
+pub fn register_user_with_referrer(
+    user: &Pubkey,
+    pool: &Pubkey,
+    referrer: &Pubkey,
+    proof: Vec<[u8; 32]>,
+    terms_version: u32,
+) -> Instruction {
+    build(
+        accounts::RegisterUserWithReferrer {
+            authority: *user,
+            user_account: find_user_address(user).0,
+            referrer_account: find_user_address(referrer).0,
+            reward_pool: *pool,
+            denylist_entry: find_denylist_address(pool, user).0,
+            membership: find_pool_member_address(pool, user).0,
+            sol_vault: None,
+            fee_token_account: None,
+            vault: None,
+            mint: None,
+            token_program: None,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterUserWithReferrer {
+            referrer: *referrer,
+            proof,
+            terms_version,
+        },
+    )
+}
+
+/// `create_rent_sponsor` opening campaign `campaign_id` on the pool owned by `authority`;
+/// fund it by transferring lamports to [`find_rent_sponsor_address`]
+pub fn create_rent_sponsor(
+    authority: &Pubkey,
+    campaign_id: u64,
+    budget_lamports: u64,
+) -> Instruction {
+    let pool = crate::find_pool_address(authority).0;
+    build(
+        accounts::CreateRentSponsor {
+            reward_pool: pool,
+            rent_sponsor: find_rent_sponsor_address(&pool, campaign_id).0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::CreateRentSponsor {
+            campaign_id,
+            budget_lamports,
+        },
+    )
+}
+
+/// `register_user_sponsored` for the wallet `user`, with campaign `campaign_id` paying the
+/// user account rent
+pub fn register_user_sponsored(
+    user: &Pubkey,
+    pool: &Pubkey,
+    campaign_id: u64,
+    proof: Vec<[u8; 32]>,
+    terms_version: u32,
+) -> Instruction {
+    build(
+        accounts::RegisterUserSponsored {
+            authority: *user,
+            user_account: find_user_address(user).0,
+            reward_pool: *pool,
+            rent_sponsor: find_rent_sponsor_address(pool, campaign_id).0,
+            denylist_entry: find_denylist_address(pool, user).0,
+            membership: find_pool_member_address(pool, user).0,
+            system_program: system_program::ID,
+        },
+        instruction::RegisterUserSponsored {
+            campaign_id,
+            proof,
+            terms_version,
+        },
+    )
+}
+
+/// `create_token_account_sponsored` for the registered wallet `user`, to send right before
+/// their first `claim_rewards` so campaign `campaign_id` pays the token account rent
+pub fn create_token_account_sponsored(
+    keys: &PoolKeys,
+    user: &Pubkey,
+    campaign_id: u64,
+) -> Instruction {
+    let pool = keys.pool();
+    build(
+        accounts::CreateTokenAccountSponsored {
+            authority: *user,
+            user_account: find_user_address(user).0,
+            reward_pool: pool,
+            rent_sponsor: find_rent_sponsor_address(&pool, campaign_id).0,
+            user_token_account: keys.token_account(user),
+            mint: keys.mint,
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CreateTokenAccountSponsored { campaign_id },
+    )
+}
+
+/// Accounts for `owner` claiming into their associated token account, with every optional
+/// account left out. `region_code` is the user's attested region (0 if none).
+pub fn claim_rewards_accounts(
+    keys: &PoolKeys,
+    owner: &Pubkey,
+    region_code: u16,
+) -> accounts::ClaimRewards {
+    let pool = keys.pool();
+    accounts::ClaimRewards {
+        authority: *owner,
+        owner: *owner,
+        user_account: find_user_address(owner).0,
+        reward_pool: pool,
+        denylist_entry: find_denylist_address(&pool, owner).0,
+        region_policy: find_region_policy_address(&pool, region_code).0,
+        vault: keys.vault(),
+        user_token_account: keys.token_account(owner),
+        mint: keys.mint,
+        destination: None,
+        captcha_verifier: None,
+        co_signer: None,
+        vesting_position: None,
+        claim_session: None,
+        claim_receipt: None,
+        matching_sponsor: None,
+        matching_vault: None,
+        matching_destination: None,
+        nft_token_account: None,
+        mint_stats: None,
+        epoch: None,
+        daily_stats: None,
+        referrer_account: None,
+        price_feed: None,
+        slot_hashes: None,
+        leaderboard: None,
+        memo_program: None,
+        token_program: keys.token_program,
+        associated_token_program: associated_token::ID,
+        system_program: system_program::ID,
+    }
+}
+
+/// `claim_rewards` by `owner` on a pool that needs none of the optional accounts
+pub fn claim_rewards(
+    keys: &PoolKeys,
+    owner: &Pubkey,
+    region_code: u16,
+    min_expected_amount: u64,
+    amount: Option<u64>,
+) -> Instruction {
+    build(
+        claim_rewards_accounts(keys, owner, region_code),
+        instruction::ClaimRewards {
+            min_expected_amount,
+            amount,
+        },
+    )
+}
+
+/// Accounts for `owner` claiming lamports from a native SOL pool, with every optional
+/// account left out. `region_code` is the user's attested region (0 if none).
+pub fn claim_native_rewards_accounts(
+    keys: &PoolKeys,
+    owner: &Pubkey,
+    region_code: u16,
+) -> accounts::ClaimNativeRewards {
+    let pool = keys.pool();
+    accounts::ClaimNativeRewards {
+        authority: *owner,
+        user_account: find_user_address(owner).0,
+        reward_pool: pool,
+        denylist_entry: find_denylist_address(&pool, owner).0,
+        region_policy: find_region_policy_address(&pool, region_code).0,
+        sol_vault: find_sol_vault_address(&pool).0,
+        nft_token_account: None,
+        mint_stats: None,
+        epoch: None,
+        daily_stats: None,
+        claim_receipt: None,
+        referrer_account: None,
+        co_signer: None,
+        slot_hashes: None,
+        system_program: system_program::ID,
+    }
+}
+
+/// `claim_native_rewards` by `owner` on a native SOL pool that needs none of the optional
+/// accounts
+pub fn claim_native_rewards(
+    keys: &PoolKeys,
+    owner: &Pubkey,
+    region_code: u16,
+    min_expected_amount: u64,
+) -> Instruction {
+    build(
+        claim_native_rewards_accounts(keys, owner, region_code),
+        instruction::ClaimNativeRewards {
+            min_expected_amount,
+        },
+    )
+}
+
+/// `claim_referral_earnings` by `user`, into their associated token account
+pub fn claim_referral_earnings(keys: &PoolKeys, user: &Pubkey) -> Instruction {
+    let pool = keys.pool();
+    build(
+        accounts::ClaimReferralEarnings {
+            authority: *user,
+            user_account: find_user_address(user).0,
+            reward_pool: pool,
+            vault: keys.vault(),
+            user_token_account: keys.token_account(user),
+            mint: keys.mint,
+            denylist_entry: find_denylist_address(&pool, user).0,
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ClaimReferralEarnings {},
+    )
+}
+
+/// `claim_native_referral_earnings` by `user`, paid in lamports from the pool's SOL vault
+pub fn claim_native_referral_earnings(keys: &PoolKeys, user: &Pubkey) -> Instruction {
+    let pool = keys.pool();
+    build(
+        accounts::ClaimNativeReferralEarnings {
+            authority: *user,
+            user_account: find_user_address(user).0,
+            reward_pool: pool,
+            sol_vault: find_sol_vault_address(&pool).0,
+            denylist_entry: find_denylist_address(&pool, user).0,
+            system_program: system_program::ID,
+        },
+        instruction::ClaimNativeReferralEarnings {},
+    )
+}
+
+/// `set_user_metadata` by `user` of `pool`, who pays for (or is refunded) any change in
+/// account size
+pub fn set_user_metadata(
+    user: &Pubkey,
+    pool: &Pubkey,
+    nickname: &str,
+    metadata_uri: &str,
+) -> Instruction {
+    build(
+        accounts::SetUserMetadata {
+            authority: *user,
+            user_account: find_user_address(user).0,
+            reward_pool: *pool,
+            system_program: system_program::ID,
+        },
+        instruction::SetUserMetadata {
+            nickname: nickname.to_string(),
+            metadata_uri: metadata_uri.to_string(),
+        },
+    )
+}
+
+/// `initiate_wallet_rotation` by `user` towards `new_authority`
+pub fn initiate_wallet_rotation(user: &Pubkey, new_authority: &Pubkey) -> Instruction {
+    let user_account = find_user_address(user).0;
+    build(
+        accounts::InitiateWalletRotation {
+            authority: *user,
+            user_account,
+            rotation: find_wallet_rotation_address(&user_account).0,
+            system_program: system_program::ID,
+        },
+        instruction::InitiateWalletRotation {
+            new_authority: *new_authority,
+        },
+    )
+}
+
+/// `cancel_wallet_rotation` by `user`
+pub fn cancel_wallet_rotation(user: &Pubkey) -> Instruction {
+    let user_account = find_user_address(user).0;
+    build(
+        accounts::CancelWalletRotation {
+            authority: *user,
+            user_account,
+            rotation: find_wallet_rotation_address(&user_account).0,
+        },
+        instruction::CancelWalletRotation {},
+    )
+}
+
+/// `complete_wallet_rotation` of `old_wallet`'s account in `pool`, signed by `new_authority`
+pub fn complete_wallet_rotation(
+    pool: &Pubkey,
+    old_wallet: &Pubkey,
+    new_authority: &Pubkey,
+) -> Instruction {
+    let old_user_account = find_user_address(old_wallet).0;
+    build(
+        accounts::CompleteWalletRotation {
+            new_authority: *new_authority,
+            old_user_account,
+            new_user_account: find_user_address(new_authority).0,
+            new_sibling_account: find_pool_member_address(pool, new_authority).0,
+            rotation: find_wallet_rotation_address(&old_user_account).0,
+            denylist_entry: find_denylist_address(pool, new_authority).0,
+            system_program: system_program::ID,
+        },
+        instruction::CompleteWalletRotation {},
+    )
+}
+
+/// `set_recovery_guardians` by `user`, letting `threshold` of `guardians` move the account
+/// after `timelock_secs`
+pub fn set_recovery_guardians(
+    user: &Pubkey,
+    guardians: Vec<Pubkey>,
+    threshold: u8,
+    timelock_secs: i64,
+) -> Instruction {
+    let user_account = find_user_address(user).0;
+    build(
+        accounts::SetRecoveryGuardians {
+            authority: *user,
+            user_account,
+            recovery: find_recovery_address(&user_account).0,
+            system_program: system_program::ID,
+        },
+        instruction::SetRecoveryGuardians {
+            guardians,
+            threshold,
+            timelock_secs,
+        },
+    )
+}
+
+/// `approve_recovery` by `guardian` of moving `wallet`'s account to `new_authority`
+pub fn approve_recovery(guardian: &Pubkey, wallet: &Pubkey, new_authority: &Pubkey) -> Instruction {
+    build(
+        accounts::ApproveRecovery {
+            guardian: *guardian,
+            recovery: find_recovery_address(&find_user_address(wallet).0).0,
+        },
+        instruction::ApproveRecovery {
+            new_authority: *new_authority,
+        },
+    )
+}
+
+/// `cancel_recovery` by `user`
+pub fn cancel_recovery(user: &Pubkey) -> Instruction {
+    let user_account = find_user_address(user).0;
+    build(
+        accounts::CancelRecovery {
+            authority: *user,
+            user_account,
+            recovery: find_recovery_address(&user_account).0,
+        },
+        instruction::CancelRecovery {},
+    )
+}
+
+/// `execute_recovery` of `old_wallet`'s account in `pool`, signed by `new_authority`
+pub fn execute_recovery(pool: &Pubkey, old_wallet: &Pubkey, new_authority: &Pubkey) -> Instruction {
+    let old_user_account = find_user_address(old_wallet).0;
+    build(
+        accounts::ExecuteRecovery {
+            new_authority: *new_authority,
+            old_user_account,
+            new_user_account: find_user_address(new_authority).0,
+            new_sibling_account: find_pool_member_address(pool, new_authority).0,
+            recovery: find_recovery_address(&old_user_account).0,
+            denylist_entry: find_denylist_address(pool, new_authority).0,
+            system_program: system_program::ID,
+        },
+        instruction::ExecuteRecovery {},
+    )
+}
+
+/// `set_claim_manager` by `user` of `pool`; `None` revokes the current manager
+pub fn set_claim_manager(user: &Pubkey, pool: &Pubkey, manager: Option<Pubkey>) -> Instruction {
+    build(
+        accounts::SetClaimManager {
+            authority: *user,
+            user_account: find_user_address(user).0,
+            reward_pool: *pool,
+        },
+        instruction::SetClaimManager { manager },
+    )
+}
+
+/// `claim_for_members` by `manager` over `members`, given as `(wallet, attested region
+/// code, referrer wallet)`, on a pool that needs none of the optional stats accounts.
+/// Each member's associated token account must exist.
+pub fn claim_for_members(
+    keys: &PoolKeys,
+    manager: &Pubkey,
+    members: &[(Pubkey, u16, Option<Pubkey>)],
+) -> Instruction {
+    let pool = keys.pool();
+    let mut ix = build(
+        accounts::ClaimForMembers {
+            manager: *manager,
+            reward_pool: pool,
+            vault: keys.vault(),
+            mint: keys.mint,
+            mint_stats: None,
+            epoch: None,
+            daily_stats: None,
+            slot_hashes: Some(slot_hashes::ID),
+            token_program: keys.token_program,
+        },
+        instruction::ClaimForMembers {},
+    );
+    for (wallet, region_code, referrer) in members {
+        let referrer = match referrer {
+            Some(referrer) => AccountMeta::new(find_user_address(referrer).0, false),
+            None => AccountMeta::new_readonly(ID, false),
+        };
+        ix.accounts.extend([
+            AccountMeta::new(find_user_address(wallet).0, false),
+            AccountMeta::new(keys.token_account(wallet), false),
+            AccountMeta::new_readonly(find_denylist_address(&pool, wallet).0, false),
+            AccountMeta::new_readonly(find_region_policy_address(&pool, *region_code).0, false),
+            referrer,
+        ]);
+    }
+    ix
+}
+
+/// Accounts for `settle_users` by `cranker`, with none of the optional stats accounts
+pub fn settle_users_accounts(keys: &PoolKeys, cranker: &Pubkey) -> accounts::SettleUsers {
+    accounts::SettleUsers {
+        cranker: *cranker,
+        reward_pool: keys.pool(),
+        vault: keys.vault(),
+        cranker_token_account: keys.token_account(cranker),
+        mint: keys.mint,
+        mint_stats: None,
+        epoch: None,
+        daily_stats: None,
+        slot_hashes: Some(slot_hashes::ID),
+        token_program: keys.token_program,
+    }
+}
+
+/// `settle_users` by `cranker` over `users`, given as `(wallet, attested region code,
+/// referrer wallet)`. Tips go to the cranker's associated token account, which must exist.
+pub fn settle_users(
+    keys: &PoolKeys,
+    cranker: &Pubkey,
+    users: &[(Pubkey, u16, Option<Pubkey>)],
+) -> Instruction {
+    settle_users_with(keys, settle_users_accounts(keys, cranker), users)
+}
+
+/// `settle_users` over `users` as for [`settle_users`], with the given `accounts`
+pub fn settle_users_with(
+    keys: &PoolKeys,
+    accounts: accounts::SettleUsers,
+    users: &[(Pubkey, u16, Option<Pubkey>)],
+) -> Instruction {
+    let pool = keys.pool();
+    let mut ix = build(accounts, instruction::SettleUsers {});
+    for (wallet, region_code, referrer) in users {
+        let referrer = match referrer {
+            Some(referrer) => AccountMeta::new(find_user_address(referrer).0, false),
+            None => AccountMeta::new_readonly(ID, false),
+        };
+        ix.accounts.extend([
+            AccountMeta::new(find_user_address(wallet).0, false),
+            AccountMeta::new(keys.token_account(wallet), false),
+            AccountMeta::new_readonly(find_denylist_address(&pool, wallet).0, false),
+            AccountMeta::new_readonly(find_region_policy_address(&pool, *region_code).0, false),
+            referrer,
+        ]);
+    }
+    ix
+}
+
+/// `claim_all` by `owner` from each pool in `memberships`, given with the owner's user
+/// account in it
+pub fn claim_all(owner: &Pubkey, memberships: &[(PoolKeys, Pubkey)]) -> Instruction {
+    let mut ix = build(
+        accounts::ClaimAll {
+            authority: *owner,
+            token_program: spl_token::ID,
+            slot_hashes: Some(slot_hashes::ID),
+        },
+        instruction::ClaimAll {},
+    );
+    for (keys, user_account) in memberships {
+        ix.accounts.extend([
+            AccountMeta::new(keys.pool(), false),
+            AccountMeta::new(*user_account, false),
+            AccountMeta::new(keys.vault(), false),
+            AccountMeta::new(keys.token_account(owner), false),
+        ]);
+    }
+    ix
+}
+
+/// `crank_claim` by `cranker` for `owner`, tipping the cranker's associated token account.
+/// `referrer` is the owner's referrer wallet, if any.
+pub fn crank_claim(
+    keys: &PoolKeys,
+    cranker: &Pubkey,
+    owner: &Pubkey,
+    region_code: u16,
+    referrer: Option<&Pubkey>,
+) -> Instruction {
+    let pool = keys.pool();
+    build(
+        accounts::CrankClaim {
+            cranker: *cranker,
+            user_account: find_user_address(owner).0,
+            owner: *owner,
+            reward_pool: pool,
+            denylist_entry: find_denylist_address(&pool, owner).0,
+            region_policy: find_region_policy_address(&pool, region_code).0,
+            vault: keys.vault(),
+            user_token_account: keys.token_account(owner),
+            cranker_token_account: keys.token_account(cranker),
+            mint: keys.mint,
+            mint_stats: None,
+            epoch: None,
+            daily_stats: None,
+            referrer_account: referrer.map(|referrer| find_user_address(referrer).0),
+            slot_hashes: Some(slot_hashes::ID),
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::CrankClaim {},
+    )
+}
+
+/// `purchase_boost` by `user`, paying from their associated token account. Pass the
+/// pool's `boost_proceeds_destination`, which is `None` on pools that burn purchases.
+pub fn purchase_boost(
+    keys: &PoolKeys,
+    user: &Pubkey,
+    proceeds_destination: Option<Pubkey>,
+    duration_hours: u64,
+    tier: u8,
+) -> Instruction {
+    build(
+        accounts::PurchaseBoost {
+            authority: *user,
+            user_account: find_user_address(user).0,
+            reward_pool: keys.pool(),
+            user_token_account: keys.token_account(user),
+            proceeds_destination,
+            mint: keys.mint,
+            token_program: keys.token_program,
+        },
+        instruction::PurchaseBoost {
+            duration_hours,
+            tier,
+        },
+    )
+}
+
+/// Read-only `get_claim_quote` for `user_account`, meant to be simulated
+pub fn get_claim_quote(user_account: &Pubkey, pool: &Pubkey) -> Instruction {
+    build(
+        accounts::GetClaimQuote {
+            user_account: *user_account,
+            reward_pool: *pool,
+        },
+        instruction::GetClaimQuote {},
+    )
+}
+
+fn update_pool_config_accounts(authority: &Pubkey) -> accounts::UpdatePoolConfig {
+    accounts::UpdatePoolConfig {
+        reward_pool: crate::find_pool_address(authority).0,
+        authority: *authority,
+    }
+}
+
+/// `update_pool_config` on the pool owned by `authority`
+pub fn update_pool_config(authority: &Pubkey, update: PoolConfigUpdate) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::UpdatePoolConfig { update },
+    )
+}
+
+fn ban_user_accounts(authority: &Pubkey, wallet: &Pubkey) -> accounts::BanUser {
+    accounts::BanUser {
+        reward_pool: crate::find_pool_address(authority).0,
+        user_account: find_user_address(wallet).0,
+        authority: *authority,
+    }
+}
+
+/// `ban_user` of `wallet`'s account in the pool owned by `authority`
+pub fn ban_user(authority: &Pubkey, wallet: &Pubkey) -> Instruction {
+    build(
+        ban_user_accounts(authority, wallet),
+        instruction::BanUser {},
+    )
+}
+
+/// `unban_user` of `wallet`'s account in the pool owned by `authority`
+pub fn unban_user(authority: &Pubkey, wallet: &Pubkey) -> Instruction {
+    build(
+        ban_user_accounts(authority, wallet),
+        instruction::UnbanUser {},
+    )
+}
+
+/// `create_budget_delegation` of `allowance` to `delegate_program`, drawn by
+/// `delegate_authority`; `guardian` co-signs on safe-mode pools
+pub fn create_budget_delegation(
+    authority: &Pubkey,
+    delegate_program: &Pubkey,
+    delegate_authority: &Pubkey,
+    allowance: u64,
+    guardian: Option<Pubkey>,
+) -> Instruction {
+    let pool = crate::find_pool_address(authority).0;
+    build(
+        accounts::CreateBudgetDelegation {
+            reward_pool: pool,
+            delegation: find_budget_delegation_address(&pool, delegate_program).0,
+            authority: *authority,
+            guardian,
+            system_program: system_program::ID,
+        },
+        instruction::CreateBudgetDelegation {
+            delegate_program: *delegate_program,
+            delegate_authority: *delegate_authority,
+            allowance,
+        },
+    )
+}
+
+/// `update_budget_delegation` of the delegation to `delegate_program`; `None` leaves a field
+/// as it is and `guardian` co-signs allowance raises on safe-mode pools
+pub fn update_budget_delegation(
+    authority: &Pubkey,
+    delegate_program: &Pubkey,
+    allowance: Option<u64>,
+    is_active: Option<bool>,
+    guardian: Option<Pubkey>,
+) -> Instruction {
+    let pool = crate::find_pool_address(authority).0;
+    build(
+        accounts::UpdateBudgetDelegation {
+            reward_pool: pool,
+            delegation: find_budget_delegation_address(&pool, delegate_program).0,
+            authority: *authority,
+            guardian,
+        },
+        instruction::UpdateBudgetDelegation {
+            allowance,
+            is_active,
+        },
+    )
+}
+
+/// `draw_delegated_budget` of `amount` from the delegation to `delegate_program`
+pub fn draw_delegated_budget(
+    keys: &PoolKeys,
+    delegate_authority: &Pubkey,
+    delegate_program: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let pool = keys.pool();
+    build(
+        accounts::DrawDelegatedBudget {
+            delegate_authority: *delegate_authority,
+            reward_pool: pool,
+            delegation: find_budget_delegation_address(&pool, delegate_program).0,
+            vault: keys.vault(),
+            destination: *destination,
+            mint: keys.mint,
+            token_program: keys.token_program,
+        },
+        instruction::DrawDelegatedBudget { amount },
+    )
+}
+
+/// `announce_emergency_withdraw` of `amount` to `destination`
+pub fn announce_emergency_withdraw(
+    authority: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        accounts::AnnounceEmergencyWithdraw {
+            reward_pool: crate::find_pool_address(authority).0,
+            destination: *destination,
+            authority: *authority,
+        },
+        instruction::AnnounceEmergencyWithdraw { amount },
+    )
+}
+
+/// `set_sponsorship_daily_limit` on the pool owned by `authority`
+pub fn set_sponsorship_daily_limit(authority: &Pubkey, max_sponsored_per_day: u32) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetSponsorshipDailyLimit {
+            max_sponsored_per_day,
+        },
+    )
+}
+
+/// `set_registration_fee` on the pool owned by `authority`
+pub fn set_registration_fee(authority: &Pubkey, fee: Option<RegistrationFee>) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetRegistrationFee { fee },
+    )
+}
+
+/// `set_boost_tiers` on the pool owned by `authority`; `proceeds_destination` of `None`
+/// burns purchases
+pub fn set_boost_tiers(
+    authority: &Pubkey,
+    tiers: [BoostTier; MAX_BOOST_TIERS],
+    proceeds_destination: Option<Pubkey>,
+) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetBoostTiers {
+            tiers,
+            proceeds_destination,
+        },
+    )
+}
+
+/// `set_receipt_policy` on the pool owned by `authority`; 0 turns receipts off
+pub fn set_receipt_policy(authority: &Pubkey, expiry_secs: i64) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetReceiptPolicy { expiry_secs },
+    )
+}
+
+/// `set_snapshot_policy` on the pool owned by `authority`
+pub fn set_snapshot_policy(
+    authority: &Pubkey,
+    snapshot_cranker: Option<Pubkey>,
+    snapshot_interval_secs: i64,
+) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetSnapshotPolicy {
+            snapshot_cranker,
+            snapshot_interval_secs,
+        },
+    )
+}
+
+/// `commit_accrual_snapshot` by `cranker` as snapshot `index`, which must be the pool's
+/// current `snapshot_count`
+pub fn commit_accrual_snapshot(
+    pool: &Pubkey,
+    cranker: &Pubkey,
+    index: u64,
+    merkle_root: [u8; 32],
+    as_of: i64,
+    user_count: u64,
+    total_accrued: u64,
+) -> Instruction {
+    build(
+        accounts::CommitAccrualSnapshot {
+            cranker: *cranker,
+            reward_pool: *pool,
+            snapshot: find_accrual_snapshot_address(pool, index).0,
+            system_program: system_program::ID,
+        },
+        instruction::CommitAccrualSnapshot {
+            merkle_root,
+            as_of,
+            user_count,
+            total_accrued,
+        },
+    )
+}
+
+/// `verify_snapshot_entry` of `wallet` having `accrued` in snapshot `index`
+pub fn verify_snapshot_entry(
+    pool: &Pubkey,
+    index: u64,
+    wallet: &Pubkey,
+    accrued: u64,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    build(
+        accounts::VerifySnapshotEntry {
+            reward_pool: *pool,
+            snapshot: find_accrual_snapshot_address(pool, index).0,
+        },
+        instruction::VerifySnapshotEntry {
+            _index: index,
+            wallet: *wallet,
+            accrued,
+            proof,
+        },
+    )
+}
+
+/// `set_nft_staking` on the pool owned by `authority`; `None` turns NFT staking off
+pub fn set_nft_staking(authority: &Pubkey, config: Option<NftStakingConfig>) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetNftStaking { config },
+    )
+}
+
+/// `stake_nft` by `owner` of `nft_mint` (a `nft_token_program` mint), escrowed from the
+/// owner's associated token account
+pub fn stake_nft(
+    keys: &PoolKeys,
+    owner: &Pubkey,
+    nft_mint: &Pubkey,
+    nft_token_program: &Pubkey,
+) -> Instruction {
+    let pool = keys.pool();
+    let staked_nft = find_staked_nft_address(&pool, nft_mint).0;
+    build(
+        accounts::StakeNft {
+            owner: *owner,
+            reward_pool: pool,
+            staked_nft,
+            escrow: get_associated_token_address_with_program_id(
+                &staked_nft,
+                nft_mint,
+                nft_token_program,
+            ),
+            owner_nft_account: get_associated_token_address_with_program_id(
+                owner,
+                nft_mint,
+                nft_token_program,
+            ),
+            nft_mint: *nft_mint,
+            nft_metadata: find_metadata_address(nft_mint).0,
+            denylist_entry: find_denylist_address(&pool, owner).0,
+            nft_token_program: *nft_token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::StakeNft {},
+    )
+}
+
+/// `unstake_nft` by `owner`, returning `nft_mint` to the owner's associated token account
+/// and paying the accrued rewards to their account for the pool mint
+pub fn unstake_nft(
+    keys: &PoolKeys,
+    owner: &Pubkey,
+    nft_mint: &Pubkey,
+    nft_token_program: &Pubkey,
+) -> Instruction {
+    let pool = keys.pool();
+    let staked_nft = find_staked_nft_address(&pool, nft_mint).0;
+    build(
+        accounts::UnstakeNft {
+            owner: *owner,
+            reward_pool: pool,
+            staked_nft,
+            escrow: get_associated_token_address_with_program_id(
+                &staked_nft,
+                nft_mint,
+                nft_token_program,
+            ),
+            owner_nft_account: get_associated_token_address_with_program_id(
+                owner,
+                nft_mint,
+                nft_token_program,
+            ),
+            nft_mint: *nft_mint,
+            vault: keys.vault(),
+            owner_reward_account: keys.token_account(owner),
+            mint: keys.mint,
+            token_program: keys.token_program,
+            nft_token_program: *nft_token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::UnstakeNft {},
+    )
+}
+
+/// `enable_staking` of the pool's own reward mint, paying stakers `reward_rate_per_hour`
+pub fn enable_staking(keys: &PoolKeys, reward_rate_per_hour: u64) -> Instruction {
+    let pool = keys.pool();
+    build(
+        accounts::EnableStaking {
+            reward_pool: pool,
+            stake_vault: find_stake_vault_address(&pool).0,
+            stake_mint: keys.mint,
+            authority: keys.authority,
+            token_program: keys.token_program,
+            system_program: system_program::ID,
+        },
+        instruction::EnableStaking {
+            reward_rate_per_hour,
+        },
+    )
+}
+
+/// `claim_and_compound` by `owner` into their stake position, on a pool that needs none
+/// of the optional accounts. `region_code` is the user's attested region (0 if none) and
+/// `referrer` the owner's referrer wallet, if any.
+pub fn claim_and_compound(
+    keys: &PoolKeys,
+    owner: &Pubkey,
+    region_code: u16,
+    referrer: Option<&Pubkey>,
+) -> Instruction {
+    let pool = keys.pool();
+    build(
+        accounts::ClaimAndCompound {
+            owner: *owner,
+            user_account: find_user_address(owner).0,
+            reward_pool: pool,
+            stake_position: find_stake_position_address(&pool, owner).0,
+            stake_vault: find_stake_vault_address(&pool).0,
+            vault: keys.vault(),
+            mint: keys.mint,
+            denylist_entry: find_denylist_address(&pool, owner).0,
+            region_policy: find_region_policy_address(&pool, region_code).0,
+            nft_token_account: None,
+            co_signer: None,
+            mint_stats: None,
+            epoch: None,
+            daily_stats: None,
+            referrer_account: referrer.map(|referrer| find_user_address(referrer).0),
+            slot_hashes: Some(slot_hashes::ID),
+            token_program: keys.token_program,
+            system_program: system_program::ID,
+        },
+        instruction::ClaimAndCompound {},
+    )
+}
+
+/// `set_max_claim_per_tx` on the pool owned by `authority`; 0 removes the cap
+pub fn set_max_claim_per_tx(authority: &Pubkey, max_claim_per_tx: u64) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetMaxClaimPerTx { max_claim_per_tx },
+    )
+}
+
+/// `claim_session_part` by `owner`, paying the next capped part of their open claim
+/// session into their associated token account
+pub fn claim_session_part(keys: &PoolKeys, owner: &Pubkey) -> Instruction {
+    let user_account = find_user_address(owner).0;
+    build(
+        accounts::ClaimSessionPart {
+            authority: *owner,
+            user_account,
+            reward_pool: keys.pool(),
+            claim_session: find_claim_session_address(&user_account).0,
+            vault: keys.vault(),
+            user_token_account: keys.token_account(owner),
+            mint: keys.mint,
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ClaimSessionPart {},
+    )
+}
+
+/// `set_badge_milestone` `index` on the pool owned by `authority`
+pub fn set_badge_milestone(
+    authority: &Pubkey,
+    index: u8,
+    kind: MilestoneKind,
+    threshold: u64,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Instruction {
+    let pool = crate::find_pool_address(authority).0;
+    build(
+        accounts::SetBadgeMilestone {
+            reward_pool: pool,
+            milestone: find_badge_milestone_address(&pool, index).0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::SetBadgeMilestone {
+            index,
+            kind,
+            threshold,
+            name,
+            symbol,
+            uri,
+        },
+    )
+}
+
+/// `mint_milestone_badge` by the registered wallet `user` for milestone `index`, into
+/// their associated token account for the badge mint
+pub fn mint_milestone_badge(pool: &Pubkey, user: &Pubkey, index: u8) -> Instruction {
+    let user_account = find_user_address(user).0;
+    let badge_mint = find_badge_mint_address(&user_account, index).0;
+    build(
+        accounts::MintMilestoneBadge {
+            authority: *user,
+            user_account,
+            reward_pool: *pool,
+            milestone: find_badge_milestone_address(pool, index).0,
+            badge_mint,
+            badge_token_account: get_associated_token_address_with_program_id(
+                user,
+                &badge_mint,
+                &spl_token::ID,
+            ),
+            badge_metadata: find_metadata_address(&badge_mint).0,
+            badge_edition: find_master_edition_address(&badge_mint).0,
+            token_program: spl_token::ID,
+            metadata_program: mpl_token_metadata::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+            rent: rent::ID,
+        },
+        instruction::MintMilestoneBadge {},
+    )
+}
+
+/// `set_vesting_schedule` on the pool owned by `authority`; `None` pays claims out
+/// directly again
+pub fn set_vesting_schedule(authority: &Pubkey, schedule: Option<VestingSchedule>) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetVestingSchedule { schedule },
+    )
+}
+
+/// `release_vested` by `owner` of the position opened by its claim number
+/// `claim_index`
+pub fn release_vested(keys: &PoolKeys, owner: &Pubkey, claim_index: u64) -> Instruction {
+    let user_account = find_user_address(owner).0;
+    build(
+        accounts::ReleaseVested {
+            authority: *owner,
+            user_account,
+            reward_pool: keys.pool(),
+            vesting_position: find_vesting_position_address(&user_account, claim_index).0,
+            vault: keys.vault(),
+            user_token_account: keys.token_account(owner),
+            mint: keys.mint,
+            token_program: keys.token_program,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        },
+        instruction::ReleaseVested {},
+    )
+}
+
+/// `set_user_multiplier` on `user` of the pool owned by `authority`
+pub fn set_user_multiplier(
+    authority: &Pubkey,
+    user: &Pubkey,
+    multiplier_bps: u16,
+    expires_at: Option<i64>,
+) -> Instruction {
+    build(
+        accounts::SetUserMultiplier {
+            reward_pool: crate::find_pool_address(authority).0,
+            user_account: find_user_address(user).0,
+            authority: *authority,
+        },
+        instruction::SetUserMultiplier {
+            multiplier_bps,
+            expires_at,
+        },
+    )
+}
+
+/// `set_boost_granters` on the pool owned by `authority`
+pub fn set_boost_granters(
+    authority: &Pubkey,
+    programs: Vec<Pubkey>,
+    max_granted_boost_bps: u16,
+) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetBoostGranters {
+            programs,
+            max_granted_boost_bps,
+        },
+    )
+}
+
+/// `grant_boost` to `user` of `pool`, signed by `granter_program`'s granter PDA. Only
+/// valid as a CPI from that program, which must sign with the PDA.
+pub fn grant_boost(
+    pool: &Pubkey,
+    user: &Pubkey,
+    granter_program: &Pubkey,
+    multiplier_bps: u16,
+    duration_secs: i64,
+) -> Instruction {
+    build(
+        accounts::GrantBoost {
+            granter: find_boost_granter_address(granter_program).0,
+            reward_pool: *pool,
+            user_account: find_user_address(user).0,
+        },
+        instruction::GrantBoost {
+            granter_program: *granter_program,
+            multiplier_bps,
+            duration_secs,
+        },
+    )
+}
+
+/// `start_epochs` on the pool owned by `authority`, creating epoch 0
+pub fn start_epochs(authority: &Pubkey, duration_secs: i64, budget: u64) -> Instruction {
+    let pool = crate::find_pool_address(authority).0;
+    build(
+        accounts::StartEpochs {
+            reward_pool: pool,
+            epoch: find_epoch_address(&pool, 0).0,
+            authority: *authority,
+            system_program: system_program::ID,
+        },
+        instruction::StartEpochs {
+            duration_secs,
+            budget,
+        },
+    )
+}
+
+/// `set_epoch_config` on the pool owned by `authority`, applying from the next epoch
+pub fn set_epoch_config(authority: &Pubkey, duration_secs: i64, budget: u64) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetEpochConfig {
+            duration_secs,
+            budget,
+        },
+    )
+}
+
+/// `roll_epoch` by `cranker`, opening epoch `next_index` after the pool's current one
+pub fn roll_epoch(pool: &Pubkey, cranker: &Pubkey, next_index: u64) -> Instruction {
+    build(
+        accounts::RollEpoch {
+            cranker: *cranker,
+            reward_pool: *pool,
+            current_epoch: find_epoch_address(pool, next_index.saturating_sub(1)).0,
+            next_epoch: find_epoch_address(pool, next_index).0,
+            system_program: system_program::ID,
+        },
+        instruction::RollEpoch { next_index },
+    )
+}
+
+/// `set_referral_bps` on the pool owned by `authority`
+pub fn set_referral_bps(authority: &Pubkey, referral_bps: u16) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetReferralBps { referral_bps },
+    )
+}
+
+/// `set_lottery` on the pool owned by `authority`
+pub fn set_lottery(authority: &Pubkey, chance_bps: u16, multiplier_bps: u16) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetLottery {
+            chance_bps,
+            multiplier_bps,
+        },
+    )
+}
+
+/// `commit_lottery_draw` by `user` for their next claim in `pool`
+pub fn commit_lottery_draw(user: &Pubkey, pool: &Pubkey) -> Instruction {
+    build(
+        accounts::CommitLotteryDraw {
+            authority: *user,
+            user_account: find_user_address(user).0,
+            reward_pool: *pool,
+        },
+        instruction::CommitLotteryDraw {},
+    )
+}
+
+/// `set_burn_bps` on the pool owned by `authority`
+pub fn set_burn_bps(authority: &Pubkey, burn_bps: u16) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetBurnBps { burn_bps },
+    )
+}
+
+/// `set_compound_growth` on the pool owned by `authority`
+pub fn set_compound_growth(authority: &Pubkey, growth_bps: u16) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetCompoundGrowth { growth_bps },
+    )
+}
+
+/// `set_crank_tip_bps` on the pool owned by `authority`
+pub fn set_crank_tip_bps(authority: &Pubkey, crank_tip_bps: u16) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetCrankTipBps { crank_tip_bps },
+    )
+}
+
+/// `set_transfer_memos` on the pool owned by `authority`. Claims on a pool with memos
+/// enabled need `memo_program` set in [`claim_rewards_accounts`].
+pub fn set_transfer_memos(authority: &Pubkey, enabled: bool) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetTransferMemos { enabled },
+    )
+}
+
+/// `cancel_emergency_withdraw` on the pool owned by `authority`
+pub fn cancel_emergency_withdraw(authority: &Pubkey) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::CancelEmergencyWithdraw {},
+    )
+}
+
+/// `set_emergency_withdraw_delay` on the pool owned by `authority`
+pub fn set_emergency_withdraw_delay(authority: &Pubkey, delay_secs: i64) -> Instruction {
+    build(
+        update_pool_config_accounts(authority),
+        instruction::SetEmergencyWithdrawDelay { delay_secs },
+    )
+}
+
+/// `emergency_withdraw` executing an announced withdrawal to the token account
+/// `destination`
+pub fn emergency_withdraw(keys: &PoolKeys, destination: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::EmergencyWithdraw {
+            reward_pool: keys.pool(),
+            vault: keys.vault(),
+            destination: *destination,
+            authority: keys.authority,
+            mint: keys.mint,
+            token_program: keys.token_program,
+            // Always attached, so the withdrawal works whether or not the pool writes memos
+            memo_program: Some(memo::ID),
+        },
+        instruction::EmergencyWithdraw { amount },
+    )
+}
+
+/// `withdraw_excess` of `amount` above the pool's reserved liabilities
+pub fn withdraw_excess(keys: &PoolKeys, destination: &Pubkey, amount: u64) -> Instruction {
+    build(
+        accounts::WithdrawExcess {
+            reward_pool: keys.pool(),
+            vault: keys.vault(),
+            destination: *destination,
+            authority: keys.authority,
+            mint: keys.mint,
+            token_program: keys.token_program,
+        },
+        instruction::WithdrawExcess { amount },
+    )
+}
+
+/// Token transfer of `amount` from `source` (owned by `funder`) into the pool vault; fails
+/// when the pool keys name a token program other than SPL Token or Token-2022
+pub fn fund_vault(
+    keys: &PoolKeys,
+    source: &Pubkey,
+    funder: &Pubkey,
+    amount: u64,
+    decimals: u8,
+) -> Result<Instruction, ProgramError> {
+    spl_token_2022::instruction::transfer_checked(
+        &keys.token_program,
+        source,
+        &keys.mint,
+        &keys.vault(),
+        funder,
+        &[],
+        amount,
+        decimals,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn claim_is_signed_by_the_owner_only() {
+        let keys = PoolKeys::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            anchor_spl::token::ID,
+        );
+        let owner = Pubkey::new_unique();
+        let ix = claim_rewards(&keys, &owner, 0, 1, None);
+
+        let signers: Vec<_> = ix.accounts.iter().filter(|meta| meta.is_signer).collect();
+        assert_eq!(signers.len(), 1);
+        assert_eq!(signers[0].pubkey, owner);
+        assert!(ix
+            .accounts
+            .iter()
+            .any(|meta| meta.pubkey == find_user_address(&owner).0 && meta.is_writable));
+    }
+
+    #[test]
+    fn config_update_leaves_unset_fields_alone() {
+        let authority = Pubkey::new_unique();
+        let ix = update_pool_config(
+            &authority,
+            PoolConfigUpdate {
+                claims_paused: Some(true),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(ix.accounts[1].pubkey, authority);
+        // Discriminator, then four unset options (one byte each) before `claims_paused`
+        assert_eq!(ix.data[8..14], [0, 0, 0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn funding_rejects_an_unknown_token_program() {
+        let keys = PoolKeys::new(
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let funder = Pubkey::new_unique();
+        let result = fund_vault(&keys, &keys.token_account(&funder), &funder, 100, 6);
+
+        assert_eq!(result, Err(ProgramError::IncorrectProgramId));
+    }
+}
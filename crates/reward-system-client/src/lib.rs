@@ -0,0 +1,15 @@
+//! Rust client for the reward system program: PDA derivation, typed instruction builders,
+//! account fetching and read-only simulations over `solana-client`.
+
+pub mod instructions;
+pub mod pda;
+pub mod rpc;
+
+pub use pda::{find_pool_address, find_user_address, PoolKeys};
+pub use reward_system::{
+    self, BoostTier, ClaimQuote, DistributionMode, RegistrationFee, RewardPool, UserAccount,
+    ID as PROGRAM_ID,
+};
+pub use rpc::{
+    fetch_pool, fetch_pool_users, fetch_user, fetch_users, get_pending_rewards, ClientError,
+};
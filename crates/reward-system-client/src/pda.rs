@@ -0,0 +1,191 @@
+use anchor_lang::prelude::Pubkey;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    metadata::mpl_token_metadata::accounts::{MasterEdition, Metadata},
+};
+use reward_system::{RewardPool, ID};
+
+/// Pool PDA owned by `authority`
+pub fn find_pool_address(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"reward_pool", authority.as_ref()], &ID)
+}
+
+/// User account PDA of the wallet `authority`
+pub fn find_user_address(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"user_account", authority.as_ref()], &ID)
+}
+
+/// `join_pool` membership of the wallet `authority` in `pool`
+pub fn find_pool_member_address(pool: &Pubkey, authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"user_account", pool.as_ref(), authority.as_ref()], &ID)
+}
+
+/// Lamport vault of a pool, which pays native SOL rewards and collects lamport
+/// registration fees
+pub fn find_sol_vault_address(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"sol_vault", pool.as_ref()], &ID)
+}
+
+/// Denylist entry for `wallet`; registration and claims require it not to exist
+pub fn find_denylist_address(pool: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"denylist", pool.as_ref(), wallet.as_ref()], &ID)
+}
+
+/// Rent sponsorship campaign `campaign_id` of a pool
+pub fn find_rent_sponsor_address(pool: &Pubkey, campaign_id: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"rent_sponsor", pool.as_ref(), &campaign_id.to_le_bytes()],
+        &ID,
+    )
+}
+
+/// Receipt of `user`'s claim number `claim_index` (counted from 0) on a pool
+pub fn find_claim_receipt_address(pool: &Pubkey, user: &Pubkey, claim_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"claim_receipt",
+            pool.as_ref(),
+            user.as_ref(),
+            &claim_index.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+/// Vesting position opened by claim number `claim_index` (counted from 0) of the
+/// user account at `user_account`
+pub fn find_vesting_position_address(user_account: &Pubkey, claim_index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"vesting",
+            user_account.as_ref(),
+            &claim_index.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+/// Epoch `index` of a pool that runs in epochs
+pub fn find_epoch_address(pool: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"epoch", pool.as_ref(), &index.to_le_bytes()], &ID)
+}
+
+/// Guardian recovery config of the user account at `user_account`
+pub fn find_recovery_address(user_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"recovery", user_account.as_ref()], &ID)
+}
+
+/// Open claim session of the user account at `user_account`
+pub fn find_claim_session_address(user_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"claim_session", user_account.as_ref()], &ID)
+}
+
+/// Accrual snapshot `index` of a pool
+pub fn find_accrual_snapshot_address(pool: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"accrual_snapshot", pool.as_ref(), &index.to_le_bytes()],
+        &ID,
+    )
+}
+
+/// Stake record of `nft_mint` in a pool, which also owns the NFT's escrow account
+pub fn find_staked_nft_address(pool: &Pubkey, nft_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"staked_nft", pool.as_ref(), nft_mint.as_ref()], &ID)
+}
+
+/// Token account of a pool holding its stakers' tokens
+pub fn find_stake_vault_address(pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"stake_vault", pool.as_ref()], &ID)
+}
+
+/// Stake position of the wallet `owner` in a pool
+pub fn find_stake_position_address(pool: &Pubkey, owner: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"stake_position", pool.as_ref(), owner.as_ref()], &ID)
+}
+
+/// Metaplex metadata account of `mint`
+pub fn find_metadata_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Metadata::find_pda(mint)
+}
+
+/// Master edition account of `mint`
+pub fn find_master_edition_address(mint: &Pubkey) -> (Pubkey, u8) {
+    MasterEdition::find_pda(mint)
+}
+
+/// Badge milestone `index` of a pool
+pub fn find_badge_milestone_address(pool: &Pubkey, index: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"badge_milestone", pool.as_ref(), &[index]], &ID)
+}
+
+/// Badge mint of milestone `index` for the user account at `user_account`
+pub fn find_badge_mint_address(user_account: &Pubkey, index: u8) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"badge", user_account.as_ref(), &[index]], &ID)
+}
+
+/// Pending wallet rotation of the user account at `user_account`
+pub fn find_wallet_rotation_address(user_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"wallet_rotation", user_account.as_ref()], &ID)
+}
+
+/// Policy PDA for users attested to `region_code`
+pub fn find_region_policy_address(pool: &Pubkey, region_code: u16) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[b"region_policy", pool.as_ref(), &region_code.to_le_bytes()],
+        &ID,
+    )
+}
+
+/// Budget delegation of a pool to `delegate_program`
+pub fn find_budget_delegation_address(pool: &Pubkey, delegate_program: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            b"budget_delegation",
+            pool.as_ref(),
+            delegate_program.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// Signer PDA a whitelisted `granter_program` signs `grant_boost` CPIs with
+pub fn find_boost_granter_address(granter_program: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"boost_granter"], granter_program)
+}
+
+/// Addresses that identify a pool and its token accounts
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolKeys {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub token_program: Pubkey,
+}
+
+impl PoolKeys {
+    pub fn new(authority: Pubkey, mint: Pubkey, token_program: Pubkey) -> Self {
+        Self {
+            authority,
+            mint,
+            token_program,
+        }
+    }
+
+    /// Keys of a fetched pool; the token program is the owner of its mint
+    pub fn from_pool(pool: &RewardPool, token_program: Pubkey) -> Self {
+        Self::new(pool.authority, pool.mint, token_program)
+    }
+
+    pub fn pool(&self) -> Pubkey {
+        find_pool_address(&self.authority).0
+    }
+
+    /// The pool's vault: its associated token account for the mint
+    pub fn vault(&self) -> Pubkey {
+        get_associated_token_address_with_program_id(&self.pool(), &self.mint, &self.token_program)
+    }
+
+    /// Associated token account of `owner` for the pool's mint
+    pub fn token_account(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &self.mint, &self.token_program)
+    }
+}
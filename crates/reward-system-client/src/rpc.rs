@@ -0,0 +1,135 @@
+//! Account fetching and read-only simulations against an RPC node
+
+use anchor_lang::{prelude::Pubkey, AccountDeserialize, AnchorDeserialize, Discriminator};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use reward_system::{ClaimQuote, RewardPool, UserAccount, ID};
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSimulateTransactionConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_sdk::{account::Account, message::Message, transaction::Transaction};
+
+use crate::{find_user_address, instructions};
+
+// getMultipleAccounts accepts at most 100 keys per request
+const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+/// Offset of `UserAccount::pool`: discriminator, then `authority`
+const USER_POOL_OFFSET: usize = 8 + 32;
+
+#[derive(Debug, thiserror::Error)]
+pub enum ClientError {
+    #[error(transparent)]
+    Rpc(Box<solana_client::client_error::ClientError>),
+    #[error("account {0} not found")]
+    AccountNotFound(Pubkey),
+    #[error("failed to decode account {0}: {1}")]
+    Decode(Pubkey, anchor_lang::error::Error),
+    #[error("simulation failed: {0}")]
+    Simulation(String),
+}
+
+// Boxed so every `Result` of this module stays small
+impl From<solana_client::client_error::ClientError> for ClientError {
+    fn from(err: solana_client::client_error::ClientError) -> Self {
+        Self::Rpc(Box::new(err))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, ClientError>;
+
+fn decode<T: AccountDeserialize>(address: &Pubkey, account: &Account) -> Result<T> {
+    T::try_deserialize(&mut account.data.as_slice())
+        .map_err(|err| ClientError::Decode(*address, err))
+}
+
+/// Fetch and decode any program account
+pub fn fetch_account<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    let account = rpc
+        .get_account_with_commitment(address, rpc.commitment())?
+        .value
+        .ok_or(ClientError::AccountNotFound(*address))?;
+    decode(address, &account)
+}
+
+pub fn fetch_pool(rpc: &RpcClient, pool: &Pubkey) -> Result<RewardPool> {
+    fetch_account(rpc, pool)
+}
+
+pub fn fetch_user(rpc: &RpcClient, user_account: &Pubkey) -> Result<UserAccount> {
+    fetch_account(rpc, user_account)
+}
+
+/// Fetch many user accounts in batches, in the order of `addresses`; missing accounts are
+/// `None`
+pub fn fetch_users(rpc: &RpcClient, addresses: &[Pubkey]) -> Result<Vec<Option<UserAccount>>> {
+    let mut users = Vec::with_capacity(addresses.len());
+    for batch in addresses.chunks(MAX_ACCOUNTS_PER_REQUEST) {
+        for (address, account) in batch.iter().zip(rpc.get_multiple_accounts(batch)?) {
+            users.push(
+                account
+                    .map(|account| decode(address, &account))
+                    .transpose()?,
+            );
+        }
+    }
+    Ok(users)
+}
+
+/// Every user account registered with `pool`, by address
+pub fn fetch_pool_users(rpc: &RpcClient, pool: &Pubkey) -> Result<Vec<(Pubkey, UserAccount)>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                0,
+                UserAccount::DISCRIMINATOR.to_vec(),
+            )),
+            RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                USER_POOL_OFFSET,
+                pool.to_bytes().to_vec(),
+            )),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            commitment: Some(rpc.commitment()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    rpc.get_program_accounts_with_config(&ID, config)?
+        .into_iter()
+        .map(|(address, account)| Ok((address, decode(&address, &account)?)))
+        .collect()
+}
+
+/// What `user` would receive by claiming from `pool` now, simulated through
+/// `get_claim_quote` with `fee_payer` (any funded account) paying for the simulation
+pub fn get_pending_rewards(
+    rpc: &RpcClient,
+    user: &Pubkey,
+    pool: &Pubkey,
+    fee_payer: &Pubkey,
+) -> Result<ClaimQuote> {
+    let ix = instructions::get_claim_quote(&find_user_address(user).0, pool);
+    let tx = Transaction::new_unsigned(Message::new(&[ix], Some(fee_payer)));
+    let config = RpcSimulateTransactionConfig {
+        sig_verify: false,
+        replace_recent_blockhash: true,
+        commitment: Some(rpc.commitment()),
+        ..Default::default()
+    };
+
+    let result = rpc.simulate_transaction_with_config(&tx, config)?.value;
+    if let Some(err) = result.err {
+        let logs = result.logs.unwrap_or_default().join("\n");
+        return Err(ClientError::Simulation(format!("{err}\n{logs}")));
+    }
+    let (data, _) = result
+        .return_data
+        .ok_or_else(|| ClientError::Simulation("no return data".to_string()))?
+        .data;
+    let bytes = STANDARD
+        .decode(data)
+        .map_err(|err| ClientError::Simulation(err.to_string()))?;
+    ClaimQuote::try_from_slice(&bytes).map_err(|err| ClientError::Simulation(err.to_string()))
+}
@@ -0,0 +1,469 @@
+//! `solana-program-test` harness for the reward system program: a pool over a fresh SPL
+//! mint, clock warping, vault funding and account assertions.
+//!
+//! The program runs natively by default. Set `SBF_OUT_DIR` to the directory holding
+//! `reward_system.so` (e.g. `target/deploy` after `anchor build`) to run the compiled
+//! program instead.
+
+use anchor_lang::{
+    prelude::{AccountInfo, Pubkey},
+    solana_program::{entrypoint::ProgramResult, program_pack::Pack},
+    AccountDeserialize,
+};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address_with_program_id},
+    token::spl_token,
+};
+use reward_system_client::{
+    find_user_address, instructions, pda::find_sol_vault_address, reward_system::ErrorCode,
+    DistributionMode, PoolKeys, RewardPool, UserAccount, PROGRAM_ID,
+};
+use solana_banks_interface::TransactionMetadata;
+use solana_program_test::{processor, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    clock::Clock,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    signature::{Keypair, Signer},
+    system_instruction, system_program,
+    transaction::{Transaction, TransactionError},
+};
+
+pub const HOUR: i64 = 3_600;
+pub const MINT_DECIMALS: u8 = 6;
+/// Lamports given to each test user for fees and rent
+const USER_LAMPORTS: u64 = 1_000_000_000;
+
+// Anchor's entrypoint ties the account slice and infos to one lifetime, which
+// `processor!` can't express; leaking the copied slice satisfies it
+fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    data: &[u8],
+) -> ProgramResult {
+    let accounts = Box::leak(accounts.to_vec().into_boxed_slice());
+    reward_system_client::reward_system::entry(program_id, accounts, data)
+}
+
+/// The program, plus the SPL programs `ProgramTest` loads by default
+pub fn program_test() -> ProgramTest {
+    ProgramTest::new("reward_system", PROGRAM_ID, processor!(process_instruction))
+}
+
+/// Pool parameters for [`TestPool::start`]
+#[derive(Clone, Copy, Debug)]
+pub struct PoolParams {
+    pub reward_rate_per_hour: u64,
+    pub min_claim_interval_secs: u64,
+    pub max_daily_reward: u64,
+    pub max_accrual_hours: u64,
+}
+
+impl Default for PoolParams {
+    fn default() -> Self {
+        Self {
+            reward_rate_per_hour: 100,
+            min_claim_interval_secs: 24 * HOUR as u64,
+            max_daily_reward: 10_000,
+            max_accrual_hours: 48,
+        }
+    }
+}
+
+/// A running test validator with a Vault pool over a fresh SPL Token mint. The pool
+/// authority is also the mint authority, so it can fund the vault by minting.
+pub struct TestPool {
+    pub context: ProgramTestContext,
+    pub authority: Keypair,
+    pub keys: PoolKeys,
+}
+
+impl TestPool {
+    pub async fn start(params: PoolParams) -> Self {
+        Self::start_with(program_test(), params).await
+    }
+
+    /// Start from a customized [`program_test`], e.g. one preferring the compiled program
+    pub async fn start_with(program_test: ProgramTest, params: PoolParams) -> Self {
+        let context = program_test.start_with_context().await;
+        let authority = Keypair::new();
+        let keys = PoolKeys::new(authority.pubkey(), Pubkey::default(), spl_token::ID);
+        let mut pool = Self {
+            context,
+            authority: authority.insecure_clone(),
+            keys,
+        };
+        pool.keys = pool.create_vault_pool(&authority, params).await;
+        pool
+    }
+
+    /// Another Vault pool in the same validator, with its own authority and fresh mint.
+    /// Fund it with [`Self::fund_vault_of`].
+    pub async fn start_another(&mut self, params: PoolParams) -> (Keypair, PoolKeys) {
+        let authority = Keypair::new();
+        let keys = self.create_vault_pool(&authority, params).await;
+        (authority, keys)
+    }
+
+    async fn create_vault_pool(&mut self, authority: &Keypair, params: PoolParams) -> PoolKeys {
+        let mint = Keypair::new();
+        let keys = PoolKeys::new(authority.pubkey(), mint.pubkey(), spl_token::ID);
+        let payer = self.context.payer.pubkey();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        self.process(
+            &[
+                system_instruction::transfer(&payer, &authority.pubkey(), USER_LAMPORTS),
+                system_instruction::create_account(
+                    &payer,
+                    &mint.pubkey(),
+                    rent.minimum_balance(spl_token::state::Mint::LEN),
+                    spl_token::state::Mint::LEN as u64,
+                    &spl_token::ID,
+                ),
+                spl_token::instruction::initialize_mint2(
+                    &spl_token::ID,
+                    &mint.pubkey(),
+                    &authority.pubkey(),
+                    None,
+                    MINT_DECIMALS,
+                )
+                .unwrap(),
+            ],
+            &[&mint],
+        )
+        .await
+        .unwrap();
+
+        self.initialize(authority, &keys, params, DistributionMode::Vault)
+            .await;
+        keys
+    }
+
+    /// A native SOL pool, paying lamports out of its SOL vault; fund it with
+    /// [`Self::fund_sol_vault`]
+    pub async fn start_native(params: PoolParams) -> Self {
+        let context = program_test().start_with_context().await;
+        let authority = Keypair::new();
+        let keys = PoolKeys::new(
+            authority.pubkey(),
+            spl_token::native_mint::ID,
+            spl_token::ID,
+        );
+
+        let payer = context.payer.pubkey();
+        let mut pool = Self {
+            context,
+            authority,
+            keys,
+        };
+        pool.process(
+            &[system_instruction::transfer(
+                &payer,
+                &pool.authority.pubkey(),
+                USER_LAMPORTS,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+
+        let authority = pool.authority.insecure_clone();
+        let keys = pool.keys;
+        pool.initialize(&authority, &keys, params, DistributionMode::NativeSol)
+            .await;
+        pool
+    }
+
+    async fn initialize(
+        &mut self,
+        authority: &Keypair,
+        keys: &PoolKeys,
+        params: PoolParams,
+        mode: DistributionMode,
+    ) {
+        self.process(
+            &[instructions::initialize_pool(
+                keys,
+                params.reward_rate_per_hour,
+                params.min_claim_interval_secs,
+                params.max_daily_reward,
+                params.max_accrual_hours,
+                mode,
+            )],
+            &[authority],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// Send `instructions` paid by the context payer and also signed by `signers`, on a
+    /// fresh blockhash so repeating an identical transaction is not deduplicated
+    pub async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let tx = self.transaction(instructions, signers).await?;
+        self.context.banks_client.process_transaction(tx).await
+    }
+
+    async fn transaction(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Transaction, BanksClientError> {
+        let blockhash = self.context.get_new_latest_blockhash().await?;
+        let mut all_signers = vec![&self.context.payer];
+        all_signers.extend_from_slice(signers);
+        Ok(Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.context.payer.pubkey()),
+            &all_signers,
+            blockhash,
+        ))
+    }
+
+    /// Compute units used by a transaction of `instructions`, which must succeed. Only
+    /// meaningful when running the compiled program; the native build is not metered.
+    pub async fn compute_units(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> u64 {
+        self.process_with_metadata(instructions, signers)
+            .await
+            .compute_units_consumed
+    }
+
+    /// Log messages of a transaction of `instructions`, which must succeed
+    pub async fn logs(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Vec<String> {
+        self.process_with_metadata(instructions, signers)
+            .await
+            .log_messages
+    }
+
+    async fn process_with_metadata(
+        &mut self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> TransactionMetadata {
+        let tx = self.transaction(instructions, signers).await.unwrap();
+        let outcome = self
+            .context
+            .banks_client
+            .process_transaction_with_metadata(tx)
+            .await
+            .unwrap();
+        let metadata = outcome.metadata.expect("transaction metadata");
+        if let Err(err) = outcome.result {
+            panic!(
+                "transaction failed: {err}\n{}",
+                metadata.log_messages.join("\n")
+            );
+        }
+        metadata
+    }
+
+    pub async fn clock(&mut self) -> Clock {
+        self.context.banks_client.get_sysvar().await.unwrap()
+    }
+
+    pub async fn now(&mut self) -> i64 {
+        self.clock().await.unix_timestamp
+    }
+
+    /// Move to the next slot with the clock `secs` later than it is now
+    pub async fn warp_secs(&mut self, secs: i64) {
+        let mut clock = self.clock().await;
+        self.context.warp_to_slot(clock.slot + 1).unwrap();
+        clock.slot += 1;
+        clock.unix_timestamp += secs;
+        self.context.set_sysvar(&clock);
+    }
+
+    pub async fn warp_hours(&mut self, hours: i64) {
+        self.warp_secs(hours * HOUR).await;
+    }
+
+    /// Mint `amount` straight into the pool vault
+    pub async fn fund_vault(&mut self, amount: u64) {
+        let authority = self.authority.insecure_clone();
+        let keys = self.keys;
+        self.fund_vault_of(&authority, &keys, amount).await;
+    }
+
+    /// Mint `amount` into the vault of a pool from [`Self::start_another`]
+    pub async fn fund_vault_of(&mut self, authority: &Keypair, keys: &PoolKeys, amount: u64) {
+        let ix = spl_token::instruction::mint_to(
+            &spl_token::ID,
+            &keys.mint,
+            &keys.vault(),
+            &authority.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+        self.process(&[ix], &[authority]).await.unwrap();
+    }
+
+    /// Create `wallet`'s associated token account for the mint of `keys`
+    pub async fn create_token_account(&mut self, wallet: &Pubkey, keys: &PoolKeys) {
+        self.create_associated_account(wallet, &keys.mint, &keys.token_program)
+            .await;
+    }
+
+    /// Create `wallet`'s associated token account for any `mint`
+    pub async fn create_associated_account(
+        &mut self,
+        wallet: &Pubkey,
+        mint: &Pubkey,
+        token_program: &Pubkey,
+    ) {
+        let payer = self.context.payer.pubkey();
+        let ix = Instruction {
+            program_id: associated_token::ID,
+            accounts: vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new(
+                    get_associated_token_address_with_program_id(wallet, mint, token_program),
+                    false,
+                ),
+                AccountMeta::new_readonly(*wallet, false),
+                AccountMeta::new_readonly(*mint, false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(*token_program, false),
+            ],
+            data: Vec::new(),
+        };
+        self.process(&[ix], &[]).await.unwrap();
+    }
+
+    /// Make `lamports` more spendable from a native pool's SOL vault, covering the vault's
+    /// rent floor on the first deposit
+    pub async fn fund_sol_vault(&mut self, lamports: u64) {
+        let sol_vault = find_sol_vault_address(&self.keys.pool()).0;
+        let floor = match self.sol_balance(&sol_vault).await {
+            0 => self
+                .context
+                .banks_client
+                .get_rent()
+                .await
+                .unwrap()
+                .minimum_balance(0),
+            _ => 0,
+        };
+        let payer = self.context.payer.pubkey();
+        self.process(
+            &[system_instruction::transfer(
+                &payer,
+                &sol_vault,
+                lamports + floor,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+    }
+
+    /// A new wallet with enough SOL for fees and rent
+    pub async fn new_user(&mut self) -> Keypair {
+        let user = Keypair::new();
+        let payer = self.context.payer.pubkey();
+        self.process(
+            &[system_instruction::transfer(
+                &payer,
+                &user.pubkey(),
+                USER_LAMPORTS,
+            )],
+            &[],
+        )
+        .await
+        .unwrap();
+        user
+    }
+
+    pub async fn register(&mut self, user: &Keypair) -> Result<(), BanksClientError> {
+        let ix = instructions::register_user(&user.pubkey(), &self.keys.pool(), Vec::new(), 0);
+        self.process(&[ix], &[user]).await
+    }
+
+    /// Claim everything owed to `user`, into their associated token account
+    pub async fn claim(&mut self, user: &Keypair) -> Result<(), BanksClientError> {
+        let ix = instructions::claim_rewards(&self.keys, &user.pubkey(), 0, 0, None);
+        self.process(&[ix], &[user]).await
+    }
+
+    pub async fn pool(&mut self) -> RewardPool {
+        self.account(&self.keys.pool()).await
+    }
+
+    pub async fn user(&mut self, wallet: &Pubkey) -> UserAccount {
+        self.account(&find_user_address(wallet).0).await
+    }
+
+    pub async fn account<T: AccountDeserialize>(&mut self, address: &Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(*address)
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("account {address} does not exist"));
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    /// Balance of a token account, 0 if it doesn't exist yet
+    pub async fn token_balance(&mut self, token_account: &Pubkey) -> u64 {
+        match self
+            .context
+            .banks_client
+            .get_account(*token_account)
+            .await
+            .unwrap()
+        {
+            Some(account) => {
+                spl_token::state::Account::unpack(&account.data)
+                    .unwrap()
+                    .amount
+            }
+            None => 0,
+        }
+    }
+
+    /// Lamports held by `address`
+    pub async fn sol_balance(&mut self, address: &Pubkey) -> u64 {
+        self.context
+            .banks_client
+            .get_balance(*address)
+            .await
+            .unwrap()
+    }
+
+    pub async fn vault_balance(&mut self) -> u64 {
+        self.token_balance(&self.keys.vault()).await
+    }
+
+    /// Balance of `wallet`'s associated token account for the pool's mint
+    pub async fn wallet_balance(&mut self, wallet: &Pubkey) -> u64 {
+        self.token_balance(&self.keys.token_account(wallet)).await
+    }
+}
+
+/// Assert that a transaction failed with the program error `expected`
+pub fn assert_program_error(result: Result<(), BanksClientError>, expected: ErrorCode) {
+    let code = u32::from(expected);
+    let err = match result {
+        Ok(()) => panic!("expected {expected:?}, but the transaction succeeded"),
+        Err(BanksClientError::TransactionError(err))
+        | Err(BanksClientError::SimulationError { err, .. }) => err,
+        Err(other) => panic!("expected {expected:?}, got {other:?}"),
+    };
+    assert_eq!(
+        err,
+        TransactionError::InstructionError(0, InstructionError::Custom(code)),
+        "expected {expected:?} ({code})"
+    );
+}
@@ -0,0 +1,112 @@
+use anchor_lang::solana_program::keccak;
+use reward_system_client::{
+    instructions,
+    pda::find_accrual_snapshot_address,
+    reward_system::{merkle, AccrualSnapshot, ErrorCode},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool, HOUR};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+
+/// Root of the two-leaf tree over `a` and `b`, hashed as a sorted pair
+fn root_of(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    keccak::hashv(&[&low, &high]).to_bytes()
+}
+
+#[tokio::test]
+async fn committed_snapshots_verify_their_entries() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let authority = pool.authority.insecure_clone();
+    let cranker = pool.new_user().await;
+    pool.process(
+        &[instructions::set_snapshot_policy(
+            &authority.pubkey(),
+            Some(cranker.pubkey()),
+            24 * HOUR,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let (alice, bob) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let alice_leaf = merkle::airdrop_leaf(&alice, 2_400);
+    let bob_leaf = merkle::airdrop_leaf(&bob, 1_200);
+    let root = root_of(alice_leaf, bob_leaf);
+    let pool_address = pool.keys.pool();
+    let as_of = pool.now().await;
+    pool.process(
+        &[instructions::commit_accrual_snapshot(
+            &pool_address,
+            &cranker.pubkey(),
+            0,
+            root,
+            as_of,
+            2,
+            3_600,
+        )],
+        &[&cranker],
+    )
+    .await
+    .unwrap();
+    let snapshot: AccrualSnapshot = pool
+        .account(&find_accrual_snapshot_address(&pool_address, 0).0)
+        .await;
+    assert_eq!(snapshot.merkle_root, root);
+    assert_eq!(snapshot.total_accrued, 3_600);
+    assert_eq!(pool.pool().await.snapshot_count, 1);
+
+    let verify =
+        instructions::verify_snapshot_entry(&pool_address, 0, &alice, 2_400, vec![bob_leaf]);
+    pool.process(&[verify], &[]).await.unwrap();
+    let inflated =
+        instructions::verify_snapshot_entry(&pool_address, 0, &alice, 2_401, vec![bob_leaf]);
+    let result = pool.process(&[inflated], &[]).await;
+    assert_program_error(result, ErrorCode::InvalidSnapshotProof);
+}
+
+#[tokio::test]
+async fn only_the_cranker_commits_and_only_once_per_interval() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let authority = pool.authority.insecure_clone();
+    let cranker = pool.new_user().await;
+    let stranger = pool.new_user().await;
+    pool.process(
+        &[instructions::set_snapshot_policy(
+            &authority.pubkey(),
+            Some(cranker.pubkey()),
+            24 * HOUR,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let pool_address = pool.keys.pool();
+    let root = merkle::airdrop_leaf(&Pubkey::new_unique(), 1);
+
+    let as_of = pool.now().await;
+    let commit = |signer: Pubkey, index, as_of| {
+        instructions::commit_accrual_snapshot(&pool_address, &signer, index, root, as_of, 1, 1)
+    };
+    let result = pool
+        .process(&[commit(stranger.pubkey(), 0, as_of)], &[&stranger])
+        .await;
+    assert_program_error(result, ErrorCode::NotSnapshotCranker);
+    pool.process(&[commit(cranker.pubkey(), 0, as_of)], &[&cranker])
+        .await
+        .unwrap();
+
+    pool.warp_hours(12).await;
+    let as_of = pool.now().await;
+    let result = pool
+        .process(&[commit(cranker.pubkey(), 1, as_of)], &[&cranker])
+        .await;
+    assert_program_error(result, ErrorCode::SnapshotTooSoon);
+
+    pool.warp_hours(12).await;
+    let as_of = pool.now().await;
+    pool.process(&[commit(cranker.pubkey(), 1, as_of)], &[&cranker])
+        .await
+        .unwrap();
+    assert_eq!(pool.pool().await.snapshot_count, 2);
+}
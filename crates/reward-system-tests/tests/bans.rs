@@ -0,0 +1,47 @@
+use reward_system_client::{instructions, reward_system::ErrorCode};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn a_ban_forfeits_carried_over_rewards() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+
+    // Take 1,000 of the day's 2,400 and leave 1,400 carried over
+    pool.warp_hours(24).await;
+    let partial = instructions::claim_rewards(&pool.keys, &user.pubkey(), 0, 0, Some(1_000));
+    pool.process(&[partial], &[&user]).await.unwrap();
+    assert_eq!(pool.user(&user.pubkey()).await.accrued_unclaimed, 1_400);
+
+    pool.warp_hours(12).await;
+    pool.process(
+        &[instructions::ban_user(&authority.pubkey(), &user.pubkey())],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let banned = pool.user(&user.pubkey()).await;
+    assert_eq!(banned.accrued_unclaimed, 0);
+    assert_eq!(banned.activity_points, 0);
+    let result = pool.claim(&user).await;
+    assert_program_error(result, ErrorCode::UserBanned);
+
+    pool.warp_hours(24).await;
+    pool.process(
+        &[instructions::unban_user(
+            &authority.pubkey(),
+            &user.pubkey(),
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    // Only time after the unban pays
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 1_000 + 2_400);
+}
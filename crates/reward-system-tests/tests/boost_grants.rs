@@ -0,0 +1,172 @@
+use anchor_lang::{
+    prelude::AccountInfo,
+    solana_program::{entrypoint::ProgramResult, program::invoke_signed},
+};
+use reward_system_client::{
+    instructions, pda::find_boost_granter_address, reward_system::ErrorCode, PROGRAM_ID,
+};
+use reward_system_tests::{assert_program_error, program_test, PoolParams, TestPool, HOUR};
+use solana_program_test::processor;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+const GRANTER_PROGRAM: Pubkey = Pubkey::new_from_array([7; 32]);
+
+/// Stand-in granter: forwards its instruction data to `grant_boost`, signing with its
+/// granter PDA
+fn process_granter(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let (granter, bump) = find_boost_granter_address(program_id);
+    let grant = Instruction {
+        program_id: PROGRAM_ID,
+        accounts: accounts[1..]
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: *account.key,
+                is_signer: *account.key == granter,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data: data.to_vec(),
+    };
+    invoke_signed(&grant, accounts, &[&[b"boost_granter", &[bump]]])
+}
+
+async fn start() -> TestPool {
+    let mut program_test = program_test();
+    program_test.add_program(
+        "boost_granter",
+        GRANTER_PROGRAM,
+        processor!(process_granter),
+    );
+    let mut pool = TestPool::start_with(program_test, PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    pool
+}
+
+/// `grant_boost` to `user` as a CPI from the stand-in granter
+fn grant_via_granter(pool: &TestPool, user: &Keypair, multiplier_bps: u16) -> Instruction {
+    let grant = instructions::grant_boost(
+        &pool.keys.pool(),
+        &user.pubkey(),
+        &GRANTER_PROGRAM,
+        multiplier_bps,
+        48 * HOUR,
+    );
+    let mut accounts = vec![AccountMeta::new_readonly(PROGRAM_ID, false)];
+    accounts.extend(grant.accounts.into_iter().map(|meta| AccountMeta {
+        is_signer: false,
+        ..meta
+    }));
+    Instruction {
+        program_id: GRANTER_PROGRAM,
+        accounts,
+        data: grant.data,
+    }
+}
+
+#[tokio::test]
+async fn granted_boosts_leave_the_authority_multiplier_in_place() {
+    let mut pool = start().await;
+    let authority = pool.authority.insecure_clone();
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.process(
+        &[
+            instructions::set_boost_granters(&authority.pubkey(), vec![GRANTER_PROGRAM], 30_000),
+            instructions::set_user_multiplier(&authority.pubkey(), &user.pubkey(), 15_000, None),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let grant = grant_via_granter(&pool, &user, 20_000);
+    pool.process(&[grant], &[]).await.unwrap();
+    let granted = pool.user(&user.pubkey()).await;
+    assert_eq!(granted.granted_boost_bps, 20_000);
+    assert_eq!(
+        granted.granted_boost_expires_at,
+        pool.now().await + 48 * HOUR
+    );
+    assert_eq!(granted.multiplier_bps, 15_000);
+
+    // The higher grant applies while it runs, then the authority's multiplier again
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 4_800);
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 4_800 + 3_600);
+}
+
+#[tokio::test]
+async fn grant_boost_requires_a_whitelisted_granter_signature() {
+    let mut pool = start().await;
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+
+    let grant = grant_via_granter(&pool, &user, 20_000);
+    let result = pool.process(&[grant], &[]).await;
+    assert_program_error(result, ErrorCode::UnauthorizedBoostGranter);
+
+    // Signing with any key other than the granter program's PDA is rejected outright
+    let authority = pool.authority.insecure_clone();
+    let set = instructions::set_boost_granters(&authority.pubkey(), vec![GRANTER_PROGRAM], 30_000);
+    pool.process(&[set], &[&authority]).await.unwrap();
+    let impostor = Keypair::new();
+    let mut direct = instructions::grant_boost(
+        &pool.keys.pool(),
+        &user.pubkey(),
+        &GRANTER_PROGRAM,
+        20_000,
+        48 * HOUR,
+    );
+    direct.accounts[0] = AccountMeta::new_readonly(impostor.pubkey(), true);
+    assert!(pool.process(&[direct], &[&impostor]).await.is_err());
+    assert_eq!(pool.user(&user.pubkey()).await.granted_boost_bps, 0);
+}
+
+#[tokio::test]
+async fn authority_multipliers_are_bounded_and_expire_in_the_future() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let authority = pool.authority.insecure_clone();
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    let now = pool.now().await;
+
+    for (multiplier_bps, expires_at) in [
+        (50_001, None),
+        (9_999, None),
+        (15_000, Some(now)),
+        (0, Some(now + HOUR)),
+    ] {
+        let result = pool
+            .process(
+                &[instructions::set_user_multiplier(
+                    &authority.pubkey(),
+                    &user.pubkey(),
+                    multiplier_bps,
+                    expires_at,
+                )],
+                &[&authority],
+            )
+            .await;
+        assert_program_error(result, ErrorCode::InvalidUserMultiplier);
+    }
+
+    pool.process(
+        &[instructions::set_user_multiplier(
+            &authority.pubkey(),
+            &user.pubkey(),
+            50_000,
+            Some(now + HOUR),
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    assert_eq!(pool.user(&user.pubkey()).await.multiplier_bps, 50_000);
+}
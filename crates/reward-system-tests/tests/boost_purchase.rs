@@ -0,0 +1,109 @@
+use anchor_spl::token::spl_token;
+use reward_system_client::{instructions, reward_system::ErrorCode, BoostTier};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool, HOUR};
+use solana_sdk::signature::Signer;
+
+const DOUBLE: BoostTier = BoostTier {
+    bonus_bps: 10_000,
+    price_per_hour: 10,
+};
+
+#[tokio::test]
+async fn burned_boost_doubles_the_next_claim() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_boost_tiers(
+            &authority.pubkey(),
+            [
+                DOUBLE,
+                BoostTier::default(),
+                BoostTier::default(),
+                BoostTier::default(),
+            ],
+            None,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+
+    let purchase = instructions::purchase_boost(&pool.keys, &user.pubkey(), None, 24, 0);
+    pool.process(&[purchase], &[&user]).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_400 - 240);
+    assert_eq!(pool.pool().await.total_boost_sales, 240);
+    let mint = pool
+        .context
+        .banks_client
+        .get_packed_account_data::<spl_token::state::Mint>(pool.keys.mint)
+        .await
+        .unwrap();
+    assert_eq!(mint.supply, 1_000_000 - 240);
+
+    // The whole next accrual period falls inside the boost
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+    assert_eq!(
+        pool.wallet_balance(&user.pubkey()).await,
+        2_400 - 240 + 2 * 2_400
+    );
+
+    let unpriced = instructions::purchase_boost(&pool.keys, &user.pubkey(), None, 24, 1);
+    let result = pool.process(&[unpriced], &[&user]).await;
+    assert_program_error(result, ErrorCode::BoostTierUnavailable);
+}
+
+#[tokio::test]
+async fn treasury_boosts_extend_only_within_their_tier() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    let triple = BoostTier {
+        bonus_bps: 20_000,
+        price_per_hour: 20,
+    };
+    // Proceeds go back into the vault
+    let treasury = pool.keys.vault();
+    pool.process(
+        &[instructions::set_boost_tiers(
+            &authority.pubkey(),
+            [DOUBLE, triple, BoostTier::default(), BoostTier::default()],
+            Some(treasury),
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+
+    let unpaid = instructions::purchase_boost(&pool.keys, &user.pubkey(), None, 12, 0);
+    let result = pool.process(&[unpaid], &[&user]).await;
+    assert_program_error(result, ErrorCode::BoostTierUnavailable);
+
+    let purchase = instructions::purchase_boost(&pool.keys, &user.pubkey(), Some(treasury), 12, 0);
+    pool.process(std::slice::from_ref(&purchase), &[&user])
+        .await
+        .unwrap();
+    pool.process(&[purchase], &[&user]).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_400 - 2 * 120);
+    assert_eq!(pool.vault_balance().await, 1_000_000 - 2_400 + 2 * 120);
+    let boosted = pool.user(&user.pubkey()).await;
+    assert_eq!(
+        boosted.purchased_boost_ends_at - boosted.purchased_boost_starts_at,
+        24 * HOUR
+    );
+
+    let upgrade = instructions::purchase_boost(&pool.keys, &user.pubkey(), Some(treasury), 12, 1);
+    let result = pool.process(&[upgrade], &[&user]).await;
+    assert_program_error(result, ErrorCode::PurchasedBoostActive);
+}
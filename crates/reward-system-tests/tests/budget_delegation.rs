@@ -0,0 +1,116 @@
+use reward_system_client::{instructions, reward_system::ErrorCode};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+#[tokio::test]
+async fn delegated_draws_wait_for_the_delay_and_leave_the_reserve() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(20_000).await;
+    let authority = pool.authority.insecure_clone();
+
+    // The user's claim opens the token account draws go to
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+    let destination = pool.keys.token_account(&user.pubkey());
+
+    let delegate_program = Pubkey::new_unique();
+    let delegate = Keypair::new();
+    let create = instructions::create_budget_delegation(
+        &authority.pubkey(),
+        &delegate_program,
+        &delegate.pubkey(),
+        100_000,
+        None,
+    );
+    pool.process(&[create], &[&authority]).await.unwrap();
+
+    let keys = pool.keys;
+    let draw = |amount| {
+        instructions::draw_delegated_budget(
+            &keys,
+            &delegate.pubkey(),
+            &delegate_program,
+            &destination,
+            amount,
+        )
+    };
+    let early = draw(1_000);
+    let result = pool.process(&[early], &[&delegate]).await;
+    assert_program_error(result, ErrorCode::DelegationAllowanceExceeded);
+
+    // After the emergency withdrawal delay, draws can take the vault down to the 4,800
+    // reserved for the one participant's accrual window
+    pool.warp_hours(24).await;
+    let (too_much, excess) = (draw(12_801), draw(12_800));
+    let result = pool.process(&[too_much], &[&delegate]).await;
+    assert_program_error(result, ErrorCode::ExceedsExcessFunds);
+    pool.process(&[excess], &[&delegate]).await.unwrap();
+    assert_eq!(pool.vault_balance().await, 4_800);
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_400 + 12_800);
+}
+
+#[tokio::test]
+async fn paused_delegations_cannot_draw_and_allowances_stay_above_the_drawn_amount() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(20_000).await;
+    let authority = pool.authority.insecure_clone();
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+    let destination = pool.keys.token_account(&user.pubkey());
+
+    let delegate_program = Pubkey::new_unique();
+    let delegate = Keypair::new();
+    let create = instructions::create_budget_delegation(
+        &authority.pubkey(),
+        &delegate_program,
+        &delegate.pubkey(),
+        100_000,
+        None,
+    );
+    pool.process(&[create], &[&authority]).await.unwrap();
+    pool.warp_hours(24).await;
+    let keys = pool.keys;
+    let draw = |amount| {
+        instructions::draw_delegated_budget(
+            &keys,
+            &delegate.pubkey(),
+            &delegate_program,
+            &destination,
+            amount,
+        )
+    };
+    pool.process(&[draw(1_000)], &[&delegate]).await.unwrap();
+
+    let update = |allowance, is_active| {
+        instructions::update_budget_delegation(
+            &authority.pubkey(),
+            &delegate_program,
+            allowance,
+            is_active,
+            None,
+        )
+    };
+    let pause = update(None, Some(false));
+    pool.process(&[pause], &[&authority]).await.unwrap();
+    let result = pool.process(&[draw(1_000)], &[&delegate]).await;
+    assert_program_error(result, ErrorCode::DelegationNotActive);
+
+    let below_drawn = update(Some(999), Some(true));
+    let result = pool.process(&[below_drawn], &[&authority]).await;
+    assert_program_error(result, ErrorCode::InvalidDelegationAllowance);
+
+    // Lowering the allowance applies at once and caps further draws
+    let lower = update(Some(1_500), Some(true));
+    pool.process(&[lower], &[&authority]).await.unwrap();
+    let result = pool.process(&[draw(501)], &[&delegate]).await;
+    assert_program_error(result, ErrorCode::DelegationAllowanceExceeded);
+    pool.process(&[draw(500)], &[&delegate]).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_400 + 1_500);
+}
@@ -0,0 +1,382 @@
+use anchor_lang::{system_program, AccountDeserialize, AccountSerialize};
+use reward_system_client::{
+    instructions::{self, PoolConfigUpdate},
+    pda::{find_denylist_address, find_pool_member_address, find_user_address},
+    reward_system::{accounts, instruction, ErrorCode, UserAccount},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+};
+
+#[tokio::test]
+async fn claim_all_pays_every_pool_and_reports_the_ones_that_fail() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let (other_authority, other_keys) = pool.start_another(PoolParams::default()).await;
+    pool.fund_vault_of(&other_authority, &other_keys, 1_000_000)
+        .await;
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.process(
+        &[instructions::join_pool(
+            &user.pubkey(),
+            &other_keys.pool(),
+            Vec::new(),
+            0,
+        )],
+        &[&user],
+    )
+    .await
+    .unwrap();
+    let keys = pool.keys;
+    pool.create_token_account(&user.pubkey(), &keys).await;
+    pool.create_token_account(&user.pubkey(), &other_keys).await;
+
+    let membership = find_pool_member_address(&other_keys.pool(), &user.pubkey()).0;
+    let memberships = [
+        (pool.keys, find_user_address(&user.pubkey()).0),
+        (other_keys, membership),
+    ];
+    let claim_all = instructions::claim_all(&user.pubkey(), &memberships);
+
+    pool.warp_hours(24).await;
+    pool.process(std::slice::from_ref(&claim_all), &[&user])
+        .await
+        .unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_400);
+    assert_eq!(
+        pool.token_balance(&other_keys.token_account(&user.pubkey()))
+            .await,
+        2_400
+    );
+    let member: UserAccount = pool.account(&membership).await;
+    assert_eq!(member.total_earned, 2_400);
+    assert_eq!(member.total_claims, 1);
+
+    // A paused pool fails on its own while the other still pays
+    pool.process(
+        &[instructions::update_pool_config(
+            &other_authority.pubkey(),
+            PoolConfigUpdate {
+                claims_paused: Some(true),
+                ..Default::default()
+            },
+        )],
+        &[&other_authority],
+    )
+    .await
+    .unwrap();
+    pool.warp_hours(24).await;
+    let logs = pool.logs(&[claim_all], &[&user]).await;
+    assert!(logs.iter().any(|log| log
+        .contains(&format!("Claim from pool {} failed", other_keys.pool()))
+        && log.contains("ClaimsPaused")));
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("claimed from 1 of 2 pools")));
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 4_800);
+    assert_eq!(
+        pool.token_balance(&other_keys.token_account(&user.pubkey()))
+            .await,
+        2_400
+    );
+}
+
+#[tokio::test]
+async fn claim_all_reports_accounts_of_another_pool_as_that_pool_failing() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let (other_authority, other_keys) = pool.start_another(PoolParams::default()).await;
+    pool.fund_vault_of(&other_authority, &other_keys, 1_000_000)
+        .await;
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    let keys = pool.keys;
+    pool.create_token_account(&user.pubkey(), &keys).await;
+    pool.create_token_account(&user.pubkey(), &other_keys).await;
+    pool.warp_hours(24).await;
+
+    // The registration account belongs to the first pool, so claiming it under the other
+    // fails only that group
+    let registration = find_user_address(&user.pubkey()).0;
+    let claim_all = instructions::claim_all(
+        &user.pubkey(),
+        &[(other_keys, registration), (pool.keys, registration)],
+    );
+    let logs = pool.logs(&[claim_all], &[&user]).await;
+    assert!(logs.iter().any(|log| log
+        .contains(&format!("Claim from pool {} failed", other_keys.pool()))
+        && log.contains("UserPoolMismatch")));
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_400);
+
+    let mut claim_all = instructions::claim_all(&user.pubkey(), &[(pool.keys, registration)]);
+    claim_all.accounts.pop();
+    let result = pool.process(&[claim_all], &[&user]).await;
+    assert_program_error(result, ErrorCode::InvalidClaimAllAccounts);
+}
+
+#[tokio::test]
+async fn claim_all_reports_a_native_pool_without_failing_the_others() {
+    let mut pool = TestPool::start_native(PoolParams::default()).await;
+    pool.fund_sol_vault(1_000_000).await;
+    let (other_authority, other_keys) = pool.start_another(PoolParams::default()).await;
+    pool.fund_vault_of(&other_authority, &other_keys, 1_000_000)
+        .await;
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.process(
+        &[instructions::join_pool(
+            &user.pubkey(),
+            &other_keys.pool(),
+            Vec::new(),
+            0,
+        )],
+        &[&user],
+    )
+    .await
+    .unwrap();
+    pool.create_token_account(&user.pubkey(), &other_keys).await;
+    pool.warp_hours(24).await;
+
+    let membership = find_pool_member_address(&other_keys.pool(), &user.pubkey()).0;
+    let claim_all = instructions::claim_all(
+        &user.pubkey(),
+        &[
+            (pool.keys, find_user_address(&user.pubkey()).0),
+            (other_keys, membership),
+        ],
+    );
+    let logs = pool.logs(&[claim_all], &[&user]).await;
+    assert!(logs.iter().any(|log| log
+        .contains(&format!("Claim from pool {} failed", pool.keys.pool()))
+        && log.contains("WrongDistributionMode")));
+    assert_eq!(
+        pool.token_balance(&other_keys.token_account(&user.pubkey()))
+            .await,
+        2_400
+    );
+}
+
+#[tokio::test]
+async fn claim_all_reveals_lottery_draws() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_lottery(
+            &authority.pubkey(),
+            10_000,
+            20_000,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    let keys = pool.keys;
+    pool.create_token_account(&user.pubkey(), &keys).await;
+    let commit = instructions::commit_lottery_draw(&user.pubkey(), &pool.keys.pool());
+    pool.process(&[commit], &[&user]).await.unwrap();
+
+    pool.warp_hours(24).await;
+    let address = find_user_address(&user.pubkey()).0;
+    let claim_all = instructions::claim_all(&user.pubkey(), &[(pool.keys, address)]);
+    pool.process(&[claim_all], &[&user]).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 4_800);
+    let user_account = pool.user(&user.pubkey()).await;
+    assert_eq!(user_account.lottery_wins, 1);
+    assert_eq!(user_account.lottery_commit_slot, 0);
+}
+
+#[tokio::test]
+async fn claim_all_leaves_claims_needing_more_accounts_to_direct_claims() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    let keys = pool.keys;
+    pool.create_token_account(&user.pubkey(), &keys).await;
+    let address = find_user_address(&user.pubkey()).0;
+    let claim_all = instructions::claim_all(&user.pubkey(), &[(pool.keys, address)]);
+
+    // claim_all can't see the wallet's denylist entry, so any entry holds up the pool
+    let someone = Keypair::new().pubkey();
+    let deny = instructions::build(
+        accounts::AddToDenylist {
+            reward_pool: pool.keys.pool(),
+            denylist_entry: find_denylist_address(&pool.keys.pool(), &someone).0,
+            authority: authority.pubkey(),
+            system_program: system_program::ID,
+        },
+        instruction::AddToDenylist {
+            address: someone,
+            reason_code: 1,
+        },
+    );
+    pool.process(&[deny], &[&authority]).await.unwrap();
+    assert_eq!(pool.pool().await.denylist_len, 1);
+    pool.warp_hours(24).await;
+    let logs = pool.logs(std::slice::from_ref(&claim_all), &[&user]).await;
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("DenylistRequiresDirectClaim")));
+    let allow = instructions::build(
+        accounts::RemoveFromDenylist {
+            reward_pool: pool.keys.pool(),
+            denylist_entry: find_denylist_address(&pool.keys.pool(), &someone).0,
+            authority: authority.pubkey(),
+        },
+        instruction::RemoveFromDenylist {},
+    );
+    pool.process(&[allow], &[&authority]).await.unwrap();
+    pool.process(std::slice::from_ref(&claim_all), &[&user])
+        .await
+        .unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_400);
+
+    // Nor the boost NFT's token account, which a direct claim re-checks
+    let mut account = pool
+        .context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut boosted = UserAccount::try_deserialize(&mut account.data.as_slice()).unwrap();
+    boosted.nft_boost_mint = Some(Pubkey::new_unique());
+    boosted.nft_boost_bps = 5_000;
+    let mut data = Vec::new();
+    boosted.try_serialize(&mut data).unwrap();
+    account.data[..data.len()].copy_from_slice(&data);
+    pool.context.set_account(&address, &account.into());
+    pool.warp_hours(24).await;
+    let logs = pool.logs(&[claim_all], &[&user]).await;
+    assert!(logs
+        .iter()
+        .any(|log| log.contains("NftBoostRequiresDirectClaim")));
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_400);
+}
+
+#[tokio::test]
+async fn pools_claim_all_always_refuses_cannot_be_joined() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let (other_authority, other_keys) = pool.start_another(PoolParams::default()).await;
+    pool.process(
+        &[instructions::set_burn_bps(&other_authority.pubkey(), 100)],
+        &[&other_authority],
+    )
+    .await
+    .unwrap();
+
+    let user = pool.new_user().await;
+    let join = instructions::join_pool(&user.pubkey(), &other_keys.pool(), Vec::new(), 0);
+    let result = pool.process(&[join], &[&user]).await;
+    assert_program_error(result, ErrorCode::BurnRequiresDirectClaim);
+}
+
+#[tokio::test]
+async fn a_wallet_has_one_account_per_pool() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let (_, other_keys) = pool.start_another(PoolParams::default()).await;
+
+    // A registered wallet can't also join its own pool
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    let join = instructions::join_pool(&user.pubkey(), &pool.keys.pool(), Vec::new(), 0);
+    let result = pool.process(&[join], &[&user]).await;
+    assert_program_error(result, ErrorCode::AlreadyInPool);
+
+    // Nor can a member register in the pool it joined
+    let member = pool.new_user().await;
+    let join = instructions::join_pool(&member.pubkey(), &other_keys.pool(), Vec::new(), 0);
+    pool.process(&[join], &[&member]).await.unwrap();
+    let register = instructions::register_user(&member.pubkey(), &other_keys.pool(), Vec::new(), 0);
+    let result = pool.process(&[register], &[&member]).await;
+    assert_program_error(result, ErrorCode::AlreadyInPool);
+}
+
+#[tokio::test]
+async fn per_user_instructions_take_a_membership() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let (other_authority, other_keys) = pool.start_another(PoolParams::default()).await;
+    pool.fund_vault_of(&other_authority, &other_keys, 1_000_000)
+        .await;
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    let join = instructions::join_pool(&user.pubkey(), &other_keys.pool(), Vec::new(), 0);
+    pool.process(&[join], &[&user]).await.unwrap();
+    pool.create_token_account(&user.pubkey(), &other_keys).await;
+    let membership = find_pool_member_address(&other_keys.pool(), &user.pubkey()).0;
+    let claim_all = instructions::claim_all(&user.pubkey(), &[(other_keys, membership)]);
+
+    // New terms hold up the membership's claims until it accepts them
+    let set_terms = instructions::build(
+        accounts::UpdatePoolConfig {
+            reward_pool: other_keys.pool(),
+            authority: other_authority.pubkey(),
+        },
+        instruction::SetTerms {
+            terms_hash: [7; 32],
+        },
+    );
+    pool.process(&[set_terms], &[&other_authority])
+        .await
+        .unwrap();
+    pool.warp_hours(24).await;
+    let logs = pool.logs(std::slice::from_ref(&claim_all), &[&user]).await;
+    assert!(logs.iter().any(|log| log.contains("TermsNotAccepted")));
+    let acknowledge = instructions::build(
+        accounts::AcknowledgeTerms {
+            authority: user.pubkey(),
+            user_account: find_user_address(&user.pubkey()).0,
+            reward_pool: other_keys.pool(),
+        },
+        instruction::AcknowledgeTerms { terms_version: 1 },
+    );
+    let acknowledge = instructions::for_membership(acknowledge, &other_keys.pool(), &user.pubkey());
+    pool.process(&[acknowledge], &[&user]).await.unwrap();
+    let claim = instructions::claim_rewards(&other_keys, &user.pubkey(), 0, 0, None);
+    let claim = instructions::for_membership(claim, &other_keys.pool(), &user.pubkey());
+    pool.process(&[claim], &[&user]).await.unwrap();
+    assert_eq!(
+        pool.token_balance(&other_keys.token_account(&user.pubkey()))
+            .await,
+        2_400
+    );
+
+    // So do batches, once the membership names a manager
+    let manager = pool.new_user().await;
+    let approve =
+        instructions::set_claim_manager(&user.pubkey(), &other_keys.pool(), Some(manager.pubkey()));
+    let approve = instructions::for_membership(approve, &other_keys.pool(), &user.pubkey());
+    pool.process(&[approve], &[&user]).await.unwrap();
+    pool.warp_hours(24).await;
+    let batch = instructions::claim_for_members(
+        &other_keys,
+        &manager.pubkey(),
+        &[(user.pubkey(), 0, None)],
+    );
+    let batch = instructions::for_membership(batch, &other_keys.pool(), &user.pubkey());
+    pool.process(&[batch], &[&manager]).await.unwrap();
+    assert_eq!(
+        pool.token_balance(&other_keys.token_account(&user.pubkey()))
+            .await,
+        4_800
+    );
+
+    // A banned membership stops claiming
+    let ban = instructions::ban_user(&other_authority.pubkey(), &user.pubkey());
+    let ban = instructions::for_membership(ban, &other_keys.pool(), &user.pubkey());
+    pool.process(&[ban], &[&other_authority]).await.unwrap();
+    let member: UserAccount = pool.account(&membership).await;
+    assert_ne!(member.banned_at, 0);
+    pool.warp_hours(24).await;
+    let logs = pool.logs(&[claim_all], &[&user]).await;
+    assert!(logs.iter().any(|log| log.contains("UserBanned")));
+}
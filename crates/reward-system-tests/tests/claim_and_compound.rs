@@ -0,0 +1,86 @@
+use reward_system_client::{
+    instructions,
+    pda::{find_stake_position_address, find_stake_vault_address},
+    reward_system::{ErrorCode, StakePosition},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn compounded_claims_reveal_the_committed_lottery_draw() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[
+            instructions::enable_staking(&pool.keys, 0),
+            instructions::set_lottery(&authority.pubkey(), 10_000, 20_000),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    let commit = instructions::commit_lottery_draw(&user.pubkey(), &pool.keys.pool());
+    pool.process(&[commit], &[&user]).await.unwrap();
+
+    // A certain win doubles the day's 2,400, all of it staked
+    pool.warp_hours(24).await;
+    let compound = instructions::claim_and_compound(&pool.keys, &user.pubkey(), 0, None);
+    pool.process(&[compound], &[&user]).await.unwrap();
+    let pool_key = pool.keys.pool();
+    let position: StakePosition = pool
+        .account(&find_stake_position_address(&pool_key, &user.pubkey()).0)
+        .await;
+    assert_eq!(position.amount, 4_800);
+    assert_eq!(
+        pool.token_balance(&find_stake_vault_address(&pool_key).0)
+            .await,
+        4_800
+    );
+
+    let compounded = pool.user(&user.pubkey()).await;
+    assert_eq!(compounded.lottery_commit_slot, 0);
+    assert_eq!(compounded.lottery_wins, 1);
+}
+
+#[tokio::test]
+async fn compounded_claims_credit_the_referrer() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[
+            instructions::enable_staking(&pool.keys, 0),
+            instructions::set_referral_bps(&authority.pubkey(), 1_000),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let referrer = pool.new_user().await;
+    pool.register(&referrer).await.unwrap();
+    let user = pool.new_user().await;
+    let register = instructions::register_user_with_referrer(
+        &user.pubkey(),
+        &pool.keys.pool(),
+        &referrer.pubkey(),
+        Vec::new(),
+        0,
+    );
+    pool.process(&[register], &[&user]).await.unwrap();
+
+    pool.warp_hours(24).await;
+    let without_referrer = instructions::claim_and_compound(&pool.keys, &user.pubkey(), 0, None);
+    let result = pool.process(&[without_referrer], &[&user]).await;
+    assert_program_error(result, ErrorCode::ReferrerAccountRequired);
+
+    let compound =
+        instructions::claim_and_compound(&pool.keys, &user.pubkey(), 0, Some(&referrer.pubkey()));
+    pool.process(&[compound], &[&user]).await.unwrap();
+    assert_eq!(pool.user(&referrer.pubkey()).await.referral_earned, 240);
+    assert_eq!(pool.pool().await.total_referral_owed, 240);
+}
@@ -0,0 +1,101 @@
+use anchor_spl::token::spl_token;
+use reward_system_client::{instructions, reward_system::ErrorCode};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn claims_burn_their_share_from_the_vault() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+
+    let result = pool
+        .process(
+            &[instructions::set_burn_bps(&authority.pubkey(), 10_001)],
+            &[&authority],
+        )
+        .await;
+    assert_program_error(result, ErrorCode::InvalidBasisPoints);
+    pool.process(
+        &[instructions::set_burn_bps(&authority.pubkey(), 2_500)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 1_800);
+    let state = pool.pool().await;
+    assert_eq!(state.total_burned, 600);
+    assert_eq!(state.total_distributed, 1_800);
+    assert_eq!(pool.vault_balance().await, 1_000_000 - 2_400);
+    let mint = pool
+        .context
+        .banks_client
+        .get_packed_account_data::<spl_token::state::Mint>(pool.keys.mint)
+        .await
+        .unwrap();
+    assert_eq!(mint.supply, 1_000_000 - 600);
+}
+
+#[tokio::test]
+async fn a_full_burn_pays_nothing_but_spends_the_accrual() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_burn_bps(&authority.pubkey(), 10_000)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 0);
+    let state = pool.pool().await;
+    assert_eq!(state.total_burned, 2_400);
+    assert_eq!(state.total_distributed, 0);
+    assert_eq!(pool.vault_balance().await, 1_000_000 - 2_400);
+    assert_eq!(pool.user(&user.pubkey()).await.total_claims, 1);
+    let result = pool.claim(&user).await;
+    assert_program_error(result, ErrorCode::ClaimTooSoon);
+}
+
+#[tokio::test]
+async fn crank_tips_come_out_of_the_unburned_share() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[
+            instructions::set_burn_bps(&authority.pubkey(), 2_500),
+            instructions::set_crank_tip_bps(&authority.pubkey(), 1_000),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let cranker = pool.new_user().await;
+    let user = pool.new_user().await;
+    pool.register(&cranker).await.unwrap();
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(24).await;
+    pool.claim(&cranker).await.unwrap();
+    let crank = instructions::crank_claim(&pool.keys, &cranker.pubkey(), &user.pubkey(), 0, None);
+    pool.process(&[crank], &[&cranker]).await.unwrap();
+
+    // 600 of 2,400 burns, then the cranker takes 10% of the remaining 1,800
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 1_620);
+    assert_eq!(pool.wallet_balance(&cranker.pubkey()).await, 1_800 + 180);
+    assert_eq!(pool.pool().await.total_burned, 600 + 600);
+    assert_eq!(pool.vault_balance().await, 1_000_000 - 2 * 2_400);
+}
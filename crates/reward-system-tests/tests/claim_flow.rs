@@ -0,0 +1,143 @@
+use reward_system_client::{
+    instructions,
+    reward_system::{instruction, ErrorCode},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool, HOUR};
+use solana_sdk::{signature::Signer, sysvar::slot_hashes};
+
+#[tokio::test]
+async fn register_accrue_claim() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let user = pool.new_user().await;
+
+    pool.register(&user).await.unwrap();
+    let registered_at = pool.now().await;
+    assert_eq!(pool.pool().await.participant_count, 1);
+
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_400);
+    assert_eq!(pool.vault_balance().await, 1_000_000 - 2_400);
+    let account = pool.user(&user.pubkey()).await;
+    assert_eq!(account.total_claims, 1);
+    assert_eq!(account.total_earned, 2_400);
+    assert_eq!(account.last_claim_timestamp, registered_at + 24 * 3_600);
+    assert_eq!(pool.pool().await.total_distributed, 2_400);
+}
+
+#[tokio::test]
+async fn claims_inside_the_interval_are_rejected() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+
+    pool.warp_hours(23).await;
+    let result = pool.claim(&user).await;
+    assert_program_error(result, ErrorCode::ClaimTooSoon);
+
+    pool.warp_hours(1).await;
+    pool.claim(&user).await.unwrap();
+
+    // The interval restarts from the claim
+    let result = pool.claim(&user).await;
+    assert_program_error(result, ErrorCode::ClaimTooSoon);
+    pool.warp_hours(23).await;
+    let result = pool.claim(&user).await;
+    assert_program_error(result, ErrorCode::ClaimTooSoon);
+    pool.warp_hours(1).await;
+    pool.claim(&user).await.unwrap();
+
+    assert_eq!(pool.user(&user.pubkey()).await.total_claims, 2);
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 4_800);
+}
+
+#[tokio::test]
+async fn partial_hours_do_not_accrue() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+
+    pool.warp_secs(30 * 3_600 - 1).await;
+    pool.claim(&user).await.unwrap();
+
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_900);
+}
+
+#[tokio::test]
+async fn accrual_stops_at_the_window_and_the_daily_cap() {
+    let mut pool = TestPool::start(PoolParams {
+        reward_rate_per_hour: 100,
+        min_claim_interval_secs: 24 * HOUR as u64,
+        max_daily_reward: 4_000,
+        max_accrual_hours: 30,
+    })
+    .await;
+    pool.fund_vault(1_000_000).await;
+    let windowed = pool.new_user().await;
+    pool.register(&windowed).await.unwrap();
+
+    // 72 hours away, but only 30 accrue
+    pool.warp_hours(72).await;
+    pool.claim(&windowed).await.unwrap();
+    assert_eq!(pool.wallet_balance(&windowed.pubkey()).await, 3_000);
+
+    let mut pool = TestPool::start(PoolParams {
+        max_daily_reward: 2_000,
+        ..PoolParams::default()
+    })
+    .await;
+    pool.fund_vault(1_000_000).await;
+    let capped = pool.new_user().await;
+    pool.register(&capped).await.unwrap();
+
+    pool.warp_hours(24).await;
+    pool.claim(&capped).await.unwrap();
+    assert_eq!(pool.wallet_balance(&capped.pubkey()).await, 2_000);
+}
+
+#[tokio::test]
+async fn a_pending_lottery_draw_is_revealed_against_the_whole_accrual() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_lottery(
+            &authority.pubkey(),
+            10_000,
+            20_000,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    let commit = instructions::commit_lottery_draw(&user.pubkey(), &pool.keys.pool());
+    pool.process(&[commit], &[&user]).await.unwrap();
+
+    pool.warp_hours(24).await;
+    let claim = |amount| {
+        let mut accounts = instructions::claim_rewards_accounts(&pool.keys, &user.pubkey(), 0);
+        accounts.slot_hashes = Some(slot_hashes::ID);
+        instructions::build(
+            accounts,
+            instruction::ClaimRewards {
+                min_expected_amount: 0,
+                amount,
+            },
+        )
+    };
+    // A sliver can't spend the draw, so a losing roll can't be wasted on it
+    let partial = claim(Some(1));
+    let full = claim(None);
+    let result = pool.process(&[partial], &[&user]).await;
+    assert_program_error(result, ErrorCode::LotteryDrawPending);
+
+    pool.process(&[full], &[&user]).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 4_800);
+    assert_eq!(pool.user(&user.pubkey()).await.lottery_commit_slot, 0);
+}
@@ -0,0 +1,180 @@
+use reward_system_client::{instructions, reward_system::ErrorCode};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn managers_claim_for_members_into_their_own_accounts() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let manager = pool.new_user().await;
+    let keys = pool.keys;
+    let mut members = Vec::new();
+    for _ in 0..2 {
+        let member = pool.new_user().await;
+        pool.register(&member).await.unwrap();
+        pool.process(
+            &[instructions::set_claim_manager(
+                &member.pubkey(),
+                &keys.pool(),
+                Some(manager.pubkey()),
+            )],
+            &[&member],
+        )
+        .await
+        .unwrap();
+        pool.create_token_account(&member.pubkey(), &keys).await;
+        members.push((member.pubkey(), 0, None));
+    }
+
+    pool.warp_hours(24).await;
+    pool.process(
+        &[instructions::claim_for_members(
+            &keys,
+            &manager.pubkey(),
+            &members,
+        )],
+        &[&manager],
+    )
+    .await
+    .unwrap();
+    for (member, _, _) in &members {
+        assert_eq!(pool.wallet_balance(member).await, 2_400);
+        assert_eq!(pool.user(member).await.total_claims, 1);
+    }
+    assert_eq!(pool.wallet_balance(&manager.pubkey()).await, 0);
+    assert_eq!(pool.pool().await.total_distributed, 4_800);
+}
+
+#[tokio::test]
+async fn managers_cannot_claim_for_unapproved_or_revoked_members() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let manager = pool.new_user().await;
+    let member = pool.new_user().await;
+    let keys = pool.keys;
+    pool.register(&member).await.unwrap();
+    pool.create_token_account(&member.pubkey(), &keys).await;
+    pool.warp_hours(24).await;
+
+    let claim =
+        instructions::claim_for_members(&keys, &manager.pubkey(), &[(member.pubkey(), 0, None)]);
+    let result = pool
+        .process(std::slice::from_ref(&claim), &[&manager])
+        .await;
+    assert_program_error(result, ErrorCode::NotClaimManager);
+
+    pool.process(
+        &[instructions::set_claim_manager(
+            &member.pubkey(),
+            &keys.pool(),
+            Some(manager.pubkey()),
+        )],
+        &[&member],
+    )
+    .await
+    .unwrap();
+    pool.process(
+        &[instructions::set_claim_manager(
+            &member.pubkey(),
+            &keys.pool(),
+            None,
+        )],
+        &[&member],
+    )
+    .await
+    .unwrap();
+    let result = pool.process(&[claim], &[&manager]).await;
+    assert_program_error(result, ErrorCode::NotClaimManager);
+    assert_eq!(pool.wallet_balance(&member.pubkey()).await, 0);
+}
+
+#[tokio::test]
+async fn managers_reveal_their_members_lottery_draws() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_lottery(
+            &authority.pubkey(),
+            10_000,
+            20_000,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let manager = pool.new_user().await;
+    let member = pool.new_user().await;
+    let keys = pool.keys;
+    pool.register(&member).await.unwrap();
+    pool.process(
+        &[
+            instructions::set_claim_manager(&member.pubkey(), &keys.pool(), Some(manager.pubkey())),
+            instructions::commit_lottery_draw(&member.pubkey(), &keys.pool()),
+        ],
+        &[&member],
+    )
+    .await
+    .unwrap();
+    pool.create_token_account(&member.pubkey(), &keys).await;
+
+    // A certain win doubles the day's 2,400
+    pool.warp_hours(24).await;
+    let batch =
+        instructions::claim_for_members(&keys, &manager.pubkey(), &[(member.pubkey(), 0, None)]);
+    pool.process(&[batch], &[&manager]).await.unwrap();
+    assert_eq!(pool.wallet_balance(&member.pubkey()).await, 4_800);
+    let claimed = pool.user(&member.pubkey()).await;
+    assert_eq!(claimed.lottery_commit_slot, 0);
+    assert_eq!(claimed.lottery_wins, 1);
+}
+
+#[tokio::test]
+async fn managers_credit_their_members_referrers() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_referral_bps(&authority.pubkey(), 1_000)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let manager = pool.new_user().await;
+    let referrer = pool.new_user().await;
+    let member = pool.new_user().await;
+    let keys = pool.keys;
+    pool.register(&referrer).await.unwrap();
+    pool.process(
+        &[
+            instructions::register_user_with_referrer(
+                &member.pubkey(),
+                &keys.pool(),
+                &referrer.pubkey(),
+                Vec::new(),
+                0,
+            ),
+            instructions::set_claim_manager(&member.pubkey(), &keys.pool(), Some(manager.pubkey())),
+        ],
+        &[&member],
+    )
+    .await
+    .unwrap();
+    pool.create_token_account(&member.pubkey(), &keys).await;
+
+    pool.warp_hours(24).await;
+    let without_referrer =
+        instructions::claim_for_members(&keys, &manager.pubkey(), &[(member.pubkey(), 0, None)]);
+    let result = pool.process(&[without_referrer], &[&manager]).await;
+    assert_program_error(result, ErrorCode::ReferrerAccountRequired);
+
+    let batch = instructions::claim_for_members(
+        &keys,
+        &manager.pubkey(),
+        &[(member.pubkey(), 0, Some(referrer.pubkey()))],
+    );
+    pool.process(&[batch], &[&manager]).await.unwrap();
+    assert_eq!(pool.wallet_balance(&member.pubkey()).await, 2_400);
+    assert_eq!(pool.user(&referrer.pubkey()).await.referral_earned, 240);
+    assert_eq!(pool.pool().await.total_referral_owed, 240);
+}
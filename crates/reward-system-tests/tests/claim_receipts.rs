@@ -0,0 +1,80 @@
+use reward_system_client::{
+    instructions,
+    pda::find_claim_receipt_address,
+    reward_system::{instruction, ClaimReceipt, ErrorCode},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::signature::Signer;
+
+const WEEK: i64 = 7 * 24 * 3_600;
+
+#[tokio::test]
+async fn receipt_pools_reject_cranked_and_settled_claims() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let user = pool.new_user().await;
+    let cranker = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.register(&cranker).await.unwrap();
+    // Direct claims before receipts are kept open the token accounts both paths pay into
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+    pool.claim(&cranker).await.unwrap();
+
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_receipt_policy(&authority.pubkey(), WEEK)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    pool.warp_hours(24).await;
+
+    let crank = instructions::crank_claim(&pool.keys, &cranker.pubkey(), &user.pubkey(), 0, None);
+    let result = pool.process(&[crank], &[&cranker]).await;
+    assert_program_error(result, ErrorCode::ReceiptsRequireDirectClaim);
+
+    let settle =
+        instructions::settle_users(&pool.keys, &cranker.pubkey(), &[(user.pubkey(), 0, None)]);
+    let result = pool.process(&[settle], &[&cranker]).await;
+    assert_program_error(result, ErrorCode::ReceiptsRequireDirectClaim);
+    assert_eq!(pool.user(&user.pubkey()).await.total_claims, 1);
+}
+
+#[tokio::test]
+async fn native_claims_write_a_receipt() {
+    let mut pool = TestPool::start_native(PoolParams::default()).await;
+    pool.fund_sol_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_receipt_policy(&authority.pubkey(), WEEK)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(24).await;
+
+    let claim = instructions::claim_native_rewards(&pool.keys, &user.pubkey(), 0, 0);
+    let result = pool.process(&[claim], &[&user]).await;
+    assert_program_error(result, ErrorCode::ClaimReceiptRequired);
+
+    let receipt_address = find_claim_receipt_address(&pool.keys.pool(), &user.pubkey(), 0).0;
+    let mut accounts = instructions::claim_native_rewards_accounts(&pool.keys, &user.pubkey(), 0);
+    accounts.claim_receipt = Some(receipt_address);
+    let claim = instructions::build(
+        accounts,
+        instruction::ClaimNativeRewards {
+            min_expected_amount: 0,
+        },
+    );
+    pool.process(&[claim], &[&user]).await.unwrap();
+
+    let receipt: ClaimReceipt = pool.account(&receipt_address).await;
+    assert_eq!(receipt.user, user.pubkey());
+    assert_eq!(receipt.claim_index, 0);
+    assert_eq!(receipt.amount, 2_400);
+    assert_eq!(receipt.payer, user.pubkey());
+    assert_eq!(receipt.expires_at, receipt.timestamp + WEEK);
+}
@@ -0,0 +1,109 @@
+use reward_system_client::{
+    instructions,
+    pda::{find_claim_session_address, find_user_address},
+    reward_system::{instruction, ClaimSession, ErrorCode},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn claims_above_the_cap_are_paid_in_parts() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_max_claim_per_tx(
+            &authority.pubkey(),
+            1_000,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(24).await;
+
+    let session = find_claim_session_address(&find_user_address(&user.pubkey()).0).0;
+    let mut accounts = instructions::claim_rewards_accounts(&pool.keys, &user.pubkey(), 0);
+    accounts.claim_session = Some(session);
+    let claim = instructions::build(
+        accounts,
+        instruction::ClaimRewards {
+            min_expected_amount: 0,
+            amount: None,
+        },
+    );
+    pool.process(&[claim], &[&user]).await.unwrap();
+    let state: ClaimSession = pool.account(&session).await;
+    assert_eq!(state.total_amount, 2_400);
+    assert_eq!(state.paid_amount, 0);
+    assert_eq!(pool.user(&user.pubkey()).await.open_claim_sessions, 1);
+    assert_eq!(pool.pool().await.total_session_locked, 2_400);
+
+    let part = instructions::claim_session_part(&pool.keys, &user.pubkey());
+    for paid in [1_000, 2_000, 2_400] {
+        pool.process(std::slice::from_ref(&part), &[&user])
+            .await
+            .unwrap();
+        assert_eq!(pool.wallet_balance(&user.pubkey()).await, paid);
+    }
+    assert!(pool
+        .context
+        .banks_client
+        .get_account(session)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(pool.user(&user.pubkey()).await.open_claim_sessions, 0);
+    assert_eq!(pool.pool().await.total_session_locked, 0);
+}
+
+#[tokio::test]
+async fn claims_above_the_cap_need_a_session_and_smaller_ones_refuse_it() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_max_claim_per_tx(
+            &authority.pubkey(),
+            1_000,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(24).await;
+    let result = pool.claim(&user).await;
+    assert_program_error(result, ErrorCode::ClaimSessionRequired);
+
+    // Under the raised cap the claim fits in one transfer
+    pool.process(
+        &[instructions::set_max_claim_per_tx(
+            &authority.pubkey(),
+            5_000,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let session = find_claim_session_address(&find_user_address(&user.pubkey()).0).0;
+    let mut accounts = instructions::claim_rewards_accounts(&pool.keys, &user.pubkey(), 0);
+    accounts.claim_session = Some(session);
+    let claim = instructions::build(
+        accounts,
+        instruction::ClaimRewards {
+            min_expected_amount: 0,
+            amount: None,
+        },
+    );
+    let result = pool.process(&[claim], &[&user]).await;
+    assert_program_error(result, ErrorCode::ClaimSessionNotNeeded);
+
+    pool.claim(&user).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_400);
+}
@@ -0,0 +1,98 @@
+use reward_system_client::{instructions, reward_system::ErrorCode};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn unclaimed_rewards_compound_hourly() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+
+    let result = pool
+        .process(
+            &[instructions::set_compound_growth(
+                &authority.pubkey(),
+                10_001,
+            )],
+            &[&authority],
+        )
+        .await;
+    assert_program_error(result, ErrorCode::InvalidBasisPoints);
+    pool.process(
+        &[instructions::set_compound_growth(&authority.pubkey(), 10)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+
+    // 100 an hour growing 0.1% an hour: 100 * (1.001^24 - 1) / 0.001 = 2,427.8
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_427);
+
+    // Back to linear accrual
+    pool.process(
+        &[instructions::set_compound_growth(&authority.pubkey(), 0)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_427 + 2_400);
+}
+
+#[tokio::test]
+async fn compounded_claims_stop_at_the_daily_cap() {
+    // Linear accrual stays under the cap; compounding would take it past
+    let mut pool = TestPool::start(PoolParams {
+        max_daily_reward: 2_420,
+        ..PoolParams::default()
+    })
+    .await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_compound_growth(&authority.pubkey(), 10)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_420);
+
+    // Two days compound to 4,912 but still pay one capped claim
+    pool.warp_hours(48).await;
+    pool.claim(&user).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2 * 2_420);
+}
+
+#[tokio::test]
+async fn growth_changes_only_compound_hours_after_them() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+
+    pool.warp_hours(12).await;
+    pool.process(
+        &[instructions::set_compound_growth(&authority.pubkey(), 10)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    pool.warp_hours(12).await;
+    pool.claim(&user).await.unwrap();
+
+    // The first 1,200 accrued linearly and only grows from the change on:
+    // 1,200 * 1.001^12 + 1,200 * (1.001^12 - 1) / 0.001 / 12 = 1,214.6 + 1,206.6
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 1_214 + 1_206);
+}
@@ -0,0 +1,46 @@
+//! Compute budget regression tests. They need the compiled program, so plain `cargo test`
+//! ignores them; CI's `program` job runs them after `anchor build`. To run them locally:
+//!
+//! ```bash
+//! anchor build && SBF_OUT_DIR=target/deploy cargo test -p reward-system-tests --test compute_units -- --ignored
+//! ```
+
+use reward_system_client::instructions;
+use reward_system_tests::{program_test, PoolParams, TestPool};
+use solana_sdk::signature::Signer;
+
+/// Compute units a plain vault claim may use: no optional features enabled, the user's
+/// token account already created. Lower it when a change makes the claim cheaper; the
+/// failure message reports the units actually used.
+const CLAIM_COMPUTE_BUDGET: u64 = 60_000;
+
+async fn start_compiled() -> TestPool {
+    assert!(
+        std::env::var_os("SBF_OUT_DIR").is_some() || std::env::var_os("BPF_OUT_DIR").is_some(),
+        "set SBF_OUT_DIR to the directory holding reward_system.so"
+    );
+    let mut test = program_test();
+    test.prefer_bpf(true);
+    TestPool::start_with(test, PoolParams::default()).await
+}
+
+#[tokio::test]
+#[ignore = "needs the compiled program in SBF_OUT_DIR"]
+async fn claim_stays_within_compute_budget() {
+    let mut pool = start_compiled().await;
+    pool.fund_vault(1_000_000).await;
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+
+    // The first claim also creates the user's token account; measure the second
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+    pool.warp_hours(24).await;
+
+    let ix = instructions::claim_rewards(&pool.keys, &user.pubkey(), 0, 0, None);
+    let units = pool.compute_units(&[ix], &[&user]).await;
+    assert!(
+        units <= CLAIM_COMPUTE_BUDGET,
+        "claim_rewards used {units} compute units, over the {CLAIM_COMPUTE_BUDGET} budget"
+    );
+}
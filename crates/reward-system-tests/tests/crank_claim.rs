@@ -0,0 +1,58 @@
+use reward_system_client::instructions;
+use reward_system_tests::{PoolParams, TestPool};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn cranked_claims_credit_the_referrer_and_reveal_the_lottery() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[
+            instructions::set_crank_tip_bps(&authority.pubkey(), 1_000),
+            instructions::set_referral_bps(&authority.pubkey(), 1_000),
+            instructions::set_lottery(&authority.pubkey(), 5_000, 20_000),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let referrer = pool.new_user().await;
+    let cranker = pool.new_user().await;
+    pool.register(&referrer).await.unwrap();
+    pool.register(&cranker).await.unwrap();
+    let user = pool.new_user().await;
+    let register = instructions::register_user_with_referrer(
+        &user.pubkey(),
+        &pool.keys.pool(),
+        &referrer.pubkey(),
+        Vec::new(),
+        0,
+    );
+    let commit = instructions::commit_lottery_draw(&user.pubkey(), &pool.keys.pool());
+    pool.process(&[register, commit], &[&user]).await.unwrap();
+
+    // The cranker's direct claim opens the token account its tip goes to
+    pool.warp_hours(24).await;
+    pool.claim(&cranker).await.unwrap();
+    let crank = instructions::crank_claim(
+        &pool.keys,
+        &cranker.pubkey(),
+        &user.pubkey(),
+        0,
+        Some(&referrer.pubkey()),
+    );
+    pool.process(&[crank], &[&cranker]).await.unwrap();
+
+    let claimed = pool.user(&user.pubkey()).await;
+    assert_eq!(claimed.total_claims, 1);
+    assert_eq!(claimed.lottery_commit_slot, 0);
+    // 2,400 for the day plus any lottery bonus, 10% of it to the cranker
+    let paid = pool.wallet_balance(&user.pubkey()).await;
+    assert_eq!(paid, (2_400 + claimed.lottery_bonus_earned) * 9 / 10);
+    assert_eq!(
+        pool.user(&referrer.pubkey()).await.referral_earned,
+        paid / 10
+    );
+}
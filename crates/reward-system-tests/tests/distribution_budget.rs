@@ -0,0 +1,66 @@
+use reward_system_client::{
+    find_user_address,
+    instructions::{self, PoolConfigUpdate},
+    reward_system::{instruction, ErrorCode},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn referral_payouts_stop_at_the_pool_budget() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[
+            instructions::set_referral_bps(&authority.pubkey(), 1_000),
+            instructions::update_pool_config(
+                &authority.pubkey(),
+                PoolConfigUpdate {
+                    max_total_distribution: Some(2_500),
+                    ..Default::default()
+                },
+            ),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let referrer = pool.new_user().await;
+    pool.register(&referrer).await.unwrap();
+    let referred = pool.new_user().await;
+    let register = instructions::register_user_with_referrer(
+        &referred.pubkey(),
+        &pool.keys.pool(),
+        &referrer.pubkey(),
+        Vec::new(),
+        0,
+    );
+    pool.process(&[register], &[&referred]).await.unwrap();
+
+    pool.warp_hours(24).await;
+    let mut accounts = instructions::claim_rewards_accounts(&pool.keys, &referred.pubkey(), 0);
+    accounts.referrer_account = Some(find_user_address(&referrer.pubkey()).0);
+    let claim = instructions::build(
+        accounts,
+        instruction::ClaimRewards {
+            min_expected_amount: 0,
+            amount: None,
+        },
+    );
+    pool.process(&[claim], &[&referred]).await.unwrap();
+    assert_eq!(pool.user(&referrer.pubkey()).await.referral_earned, 240);
+
+    // Only 100 of the 2,500 budget is left, so 140 of the 240 stays owed
+    let payout = instructions::claim_referral_earnings(&pool.keys, &referrer.pubkey());
+    pool.process(std::slice::from_ref(&payout), &[&referrer])
+        .await
+        .unwrap();
+    assert_eq!(pool.wallet_balance(&referrer.pubkey()).await, 100);
+    assert_eq!(pool.user(&referrer.pubkey()).await.referral_paid, 100);
+    assert_eq!(pool.pool().await.total_distributed, 2_500);
+
+    let result = pool.process(&[payout], &[&referrer]).await;
+    assert_program_error(result, ErrorCode::PoolBudgetExhausted);
+}
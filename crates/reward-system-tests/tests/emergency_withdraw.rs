@@ -0,0 +1,87 @@
+use reward_system_client::{
+    instructions,
+    reward_system::{ErrorCode, MIN_EMERGENCY_WITHDRAW_DELAY_SECS},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool, HOUR};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn announced_withdrawals_execute_after_the_delay() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(100_000).await;
+    let authority = pool.authority.insecure_clone();
+    let keys = pool.keys;
+    pool.create_token_account(&authority.pubkey(), &keys).await;
+    let destination = keys.token_account(&authority.pubkey());
+
+    let announce =
+        instructions::announce_emergency_withdraw(&authority.pubkey(), &destination, 60_000);
+    pool.process(&[announce], &[&authority]).await.unwrap();
+    let withdraw = instructions::emergency_withdraw(&keys, &destination, 60_000);
+    let result = pool
+        .process(std::slice::from_ref(&withdraw), &[&authority])
+        .await;
+    assert_program_error(result, ErrorCode::WithdrawalTimelockActive);
+
+    pool.warp_hours(24).await;
+    let other_amount = instructions::emergency_withdraw(&keys, &destination, 70_000);
+    let result = pool.process(&[other_amount], &[&authority]).await;
+    assert_program_error(result, ErrorCode::WithdrawalNotAnnounced);
+    pool.process(&[withdraw], &[&authority]).await.unwrap();
+    assert_eq!(pool.token_balance(&destination).await, 60_000);
+    assert_eq!(pool.vault_balance().await, 40_000);
+    assert!(pool.pool().await.pending_withdrawal.is_none());
+}
+
+#[tokio::test]
+async fn withdrawals_can_be_cancelled_and_the_delay_stays_above_the_minimum() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(100_000).await;
+    let authority = pool.authority.insecure_clone();
+    let keys = pool.keys;
+    pool.create_token_account(&authority.pubkey(), &keys).await;
+    let destination = keys.token_account(&authority.pubkey());
+    let announce = |amount| {
+        instructions::announce_emergency_withdraw(&authority.pubkey(), &destination, amount)
+    };
+
+    pool.process(&[announce(60_000)], &[&authority])
+        .await
+        .unwrap();
+    let result = pool.process(&[announce(50_000)], &[&authority]).await;
+    assert_program_error(result, ErrorCode::WithdrawalAlreadyPending);
+
+    let cancel = instructions::cancel_emergency_withdraw(&authority.pubkey());
+    pool.process(std::slice::from_ref(&cancel), &[&authority])
+        .await
+        .unwrap();
+    pool.warp_hours(24).await;
+    let withdraw = instructions::emergency_withdraw(&keys, &destination, 60_000);
+    let result = pool.process(&[withdraw], &[&authority]).await;
+    assert_program_error(result, ErrorCode::NoPendingWithdrawal);
+    let result = pool.process(&[cancel], &[&authority]).await;
+    assert_program_error(result, ErrorCode::NoPendingWithdrawal);
+
+    let too_short = instructions::set_emergency_withdraw_delay(
+        &authority.pubkey(),
+        MIN_EMERGENCY_WITHDRAW_DELAY_SECS - 1,
+    );
+    let result = pool.process(&[too_short], &[&authority]).await;
+    assert_program_error(result, ErrorCode::InvalidWithdrawDelay);
+
+    // A longer delay applies to the next announcement
+    let longer = instructions::set_emergency_withdraw_delay(&authority.pubkey(), 48 * HOUR);
+    pool.process(&[longer], &[&authority]).await.unwrap();
+    pool.process(&[announce(50_000)], &[&authority])
+        .await
+        .unwrap();
+    let withdraw = instructions::emergency_withdraw(&keys, &destination, 50_000);
+    pool.warp_hours(24).await;
+    let result = pool
+        .process(std::slice::from_ref(&withdraw), &[&authority])
+        .await;
+    assert_program_error(result, ErrorCode::WithdrawalTimelockActive);
+    pool.warp_hours(24).await;
+    pool.process(&[withdraw], &[&authority]).await.unwrap();
+    assert_eq!(pool.token_balance(&destination).await, 50_000);
+}
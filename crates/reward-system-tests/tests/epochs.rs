@@ -0,0 +1,86 @@
+use reward_system_client::{
+    instructions,
+    pda::find_epoch_address,
+    reward_system::{instruction, Epoch, ErrorCode},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool, HOUR};
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+};
+
+/// `claim_rewards` by `user` debiting epoch `index`
+fn claim_in_epoch(pool: &TestPool, user: &Keypair, index: u64) -> Instruction {
+    let mut accounts = instructions::claim_rewards_accounts(&pool.keys, &user.pubkey(), 0);
+    accounts.epoch = Some(find_epoch_address(&pool.keys.pool(), index).0);
+    instructions::build(
+        accounts,
+        instruction::ClaimRewards {
+            min_expected_amount: 0,
+            amount: None,
+        },
+    )
+}
+
+#[tokio::test]
+async fn epoch_budgets_cap_claims_until_the_next_epoch_is_rolled() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    let (first, second) = (pool.new_user().await, pool.new_user().await);
+    pool.register(&first).await.unwrap();
+    pool.register(&second).await.unwrap();
+    pool.process(
+        &[instructions::start_epochs(
+            &authority.pubkey(),
+            48 * HOUR,
+            3_000,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    // Epoch 0 covers one 2,400 claim but not a second
+    pool.warp_hours(24).await;
+    pool.process(&[claim_in_epoch(&pool, &first, 0)], &[&first])
+        .await
+        .unwrap();
+    let result = pool
+        .process(&[claim_in_epoch(&pool, &second, 0)], &[&second])
+        .await;
+    assert_program_error(result, ErrorCode::EpochBudgetExhausted);
+
+    let pool_address = pool.keys.pool();
+    let roll = instructions::roll_epoch(&pool_address, &second.pubkey(), 1);
+    let result = pool.process(std::slice::from_ref(&roll), &[&second]).await;
+    assert_program_error(result, ErrorCode::EpochStillRunning);
+
+    // The new config applies from the rolled epoch on
+    pool.process(
+        &[instructions::set_epoch_config(
+            &authority.pubkey(),
+            48 * HOUR,
+            10_000,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    pool.warp_hours(24).await;
+    pool.process(&[roll], &[&second]).await.unwrap();
+    assert_eq!(pool.pool().await.current_epoch, 1);
+
+    let result = pool
+        .process(&[claim_in_epoch(&pool, &second, 0)], &[&second])
+        .await;
+    assert_program_error(result, ErrorCode::EpochNotCurrent);
+    pool.process(&[claim_in_epoch(&pool, &second, 1)], &[&second])
+        .await
+        .unwrap();
+    assert_eq!(pool.wallet_balance(&second.pubkey()).await, 4_800);
+    let epoch: Epoch = pool.account(&find_epoch_address(&pool_address, 1).0).await;
+    assert_eq!(epoch.budget, 10_000);
+    assert_eq!(epoch.distributed_this_epoch, 4_800);
+    assert_eq!(epoch.claim_count, 1);
+}
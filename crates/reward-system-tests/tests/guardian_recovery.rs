@@ -0,0 +1,258 @@
+use reward_system_client::{
+    find_user_address, instructions,
+    pda::find_vesting_position_address,
+    reward_system::{instruction, ErrorCode, VestingSchedule},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool, HOUR};
+use solana_sdk::{
+    instruction::Instruction,
+    signature::{Keypair, Signer},
+};
+
+const TIMELOCK_SECS: i64 = 24 * HOUR;
+
+#[tokio::test]
+async fn guardians_move_the_account_after_the_timelock() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let lost = pool.new_user().await;
+    let recovered = pool.new_user().await;
+    let guardians = [Keypair::new(), Keypair::new(), Keypair::new()];
+    pool.register(&lost).await.unwrap();
+    pool.process(
+        &[instructions::set_recovery_guardians(
+            &lost.pubkey(),
+            guardians.iter().map(Signer::pubkey).collect(),
+            2,
+            TIMELOCK_SECS,
+        )],
+        &[&lost],
+    )
+    .await
+    .unwrap();
+    pool.warp_hours(24).await;
+    pool.claim(&lost).await.unwrap();
+    let before = pool.user(&lost.pubkey()).await;
+
+    let execute =
+        instructions::execute_recovery(&pool.keys.pool(), &lost.pubkey(), &recovered.pubkey());
+    pool.process(
+        &[instructions::approve_recovery(
+            &guardians[0].pubkey(),
+            &lost.pubkey(),
+            &recovered.pubkey(),
+        )],
+        &[&guardians[0]],
+    )
+    .await
+    .unwrap();
+    pool.warp_secs(TIMELOCK_SECS).await;
+    let result = pool
+        .process(std::slice::from_ref(&execute), &[&recovered])
+        .await;
+    assert_program_error(result, ErrorCode::RecoveryThresholdNotMet);
+
+    pool.process(
+        &[instructions::approve_recovery(
+            &guardians[2].pubkey(),
+            &lost.pubkey(),
+            &recovered.pubkey(),
+        )],
+        &[&guardians[2]],
+    )
+    .await
+    .unwrap();
+    pool.process(&[execute], &[&recovered]).await.unwrap();
+
+    let after = pool.user(&recovered.pubkey()).await;
+    assert_eq!(after.authority, recovered.pubkey());
+    assert_eq!(after.total_earned, before.total_earned);
+    assert_eq!(after.registration_timestamp, before.registration_timestamp);
+    let old_account = pool
+        .context
+        .banks_client
+        .get_account(find_user_address(&lost.pubkey()).0)
+        .await
+        .unwrap();
+    assert!(old_account.is_none());
+}
+
+#[tokio::test]
+async fn the_original_key_can_cancel_a_recovery() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let owner = pool.new_user().await;
+    let attacker = pool.new_user().await;
+    let guardian = Keypair::new();
+    pool.register(&owner).await.unwrap();
+    pool.process(
+        &[instructions::set_recovery_guardians(
+            &owner.pubkey(),
+            vec![guardian.pubkey()],
+            1,
+            TIMELOCK_SECS,
+        )],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+
+    let outsider = Keypair::new();
+    let result = pool
+        .process(
+            &[instructions::approve_recovery(
+                &outsider.pubkey(),
+                &owner.pubkey(),
+                &attacker.pubkey(),
+            )],
+            &[&outsider],
+        )
+        .await;
+    assert_program_error(result, ErrorCode::NotGuardian);
+
+    pool.process(
+        &[instructions::approve_recovery(
+            &guardian.pubkey(),
+            &owner.pubkey(),
+            &attacker.pubkey(),
+        )],
+        &[&guardian],
+    )
+    .await
+    .unwrap();
+    let execute =
+        instructions::execute_recovery(&pool.keys.pool(), &owner.pubkey(), &attacker.pubkey());
+    let result = pool
+        .process(std::slice::from_ref(&execute), &[&attacker])
+        .await;
+    assert_program_error(result, ErrorCode::RecoveryTimelockActive);
+
+    pool.process(&[instructions::cancel_recovery(&owner.pubkey())], &[&owner])
+        .await
+        .unwrap();
+    pool.warp_secs(TIMELOCK_SECS).await;
+    let result = pool.process(&[execute], &[&attacker]).await;
+    assert_program_error(result, ErrorCode::NoPendingRecovery);
+    assert_eq!(pool.user(&owner.pubkey()).await.authority, owner.pubkey());
+}
+
+/// Have two of three new guardians approve moving `lost`'s account to `recovered`, and
+/// wait out the timelock, returning the `execute_recovery` instruction
+async fn approve_recovery(pool: &mut TestPool, lost: &Keypair, recovered: &Keypair) -> Instruction {
+    let guardians = [Keypair::new(), Keypair::new(), Keypair::new()];
+    pool.process(
+        &[instructions::set_recovery_guardians(
+            &lost.pubkey(),
+            guardians.iter().map(Signer::pubkey).collect(),
+            2,
+            TIMELOCK_SECS,
+        )],
+        &[lost],
+    )
+    .await
+    .unwrap();
+    for guardian in &guardians[..2] {
+        pool.process(
+            &[instructions::approve_recovery(
+                &guardian.pubkey(),
+                &lost.pubkey(),
+                &recovered.pubkey(),
+            )],
+            &[guardian],
+        )
+        .await
+        .unwrap();
+    }
+    pool.warp_secs(TIMELOCK_SECS).await;
+    instructions::execute_recovery(&pool.keys.pool(), &lost.pubkey(), &recovered.pubkey())
+}
+
+#[tokio::test]
+async fn open_vesting_positions_block_recovery() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_vesting_schedule(
+            &authority.pubkey(),
+            Some(VestingSchedule {
+                cliff_secs: 0,
+                duration_secs: 24 * HOUR,
+            }),
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let lost = pool.new_user().await;
+    let recovered = pool.new_user().await;
+    pool.register(&lost).await.unwrap();
+
+    pool.warp_hours(24).await;
+    let user_account = find_user_address(&lost.pubkey()).0;
+    let mut accounts = instructions::claim_rewards_accounts(&pool.keys, &lost.pubkey(), 0);
+    accounts.vesting_position = Some(find_vesting_position_address(&user_account, 0).0);
+    let claim = instructions::build(
+        accounts,
+        instruction::ClaimRewards {
+            min_expected_amount: 0,
+            amount: None,
+        },
+    );
+    pool.process(&[claim], &[&lost]).await.unwrap();
+
+    // The position is seeded by the old user account and would be stranded
+    let execute = approve_recovery(&mut pool, &lost, &recovered).await;
+    let result = pool
+        .process(std::slice::from_ref(&execute), &[&recovered])
+        .await;
+    assert_program_error(result, ErrorCode::OpenClaimsBlockRotation);
+
+    pool.process(
+        &[instructions::release_vested(&pool.keys, &lost.pubkey(), 0)],
+        &[&lost],
+    )
+    .await
+    .unwrap();
+    pool.process(&[execute], &[&recovered]).await.unwrap();
+}
+
+#[tokio::test]
+async fn referrals_follow_a_recovered_referrer() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_referral_bps(&authority.pubkey(), 1_000)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let lost = pool.new_user().await;
+    let recovered = pool.new_user().await;
+    pool.register(&lost).await.unwrap();
+    let referee = pool.new_user().await;
+    let register = instructions::register_user_with_referrer(
+        &referee.pubkey(),
+        &pool.keys.pool(),
+        &lost.pubkey(),
+        Vec::new(),
+        0,
+    );
+    pool.process(&[register], &[&referee]).await.unwrap();
+
+    let execute = approve_recovery(&mut pool, &lost, &recovered).await;
+    pool.process(&[execute], &[&recovered]).await.unwrap();
+
+    let mut accounts = instructions::claim_rewards_accounts(&pool.keys, &referee.pubkey(), 0);
+    accounts.referrer_account = Some(find_user_address(&recovered.pubkey()).0);
+    let claim = instructions::build(
+        accounts,
+        instruction::ClaimRewards {
+            min_expected_amount: 0,
+            amount: None,
+        },
+    );
+    pool.process(&[claim], &[&referee]).await.unwrap();
+    let earned = pool.wallet_balance(&referee.pubkey()).await / 10;
+    assert_eq!(pool.user(&recovered.pubkey()).await.referral_earned, earned);
+}
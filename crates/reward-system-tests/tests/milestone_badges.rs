@@ -0,0 +1,97 @@
+use anchor_lang::{prelude::AccountInfo, solana_program::entrypoint::ProgramResult};
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id, metadata::mpl_token_metadata,
+    token::spl_token,
+};
+use reward_system_client::{
+    find_user_address, instructions,
+    pda::find_badge_mint_address,
+    reward_system::{ErrorCode, MilestoneKind},
+};
+use reward_system_tests::{assert_program_error, program_test, PoolParams, TestPool};
+use solana_program_test::processor;
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+
+/// Stand-in Token Metadata program: accepts the metadata and master edition CPIs
+/// without creating either account
+fn process_metadata(
+    _program_id: &Pubkey,
+    _accounts: &[AccountInfo],
+    _data: &[u8],
+) -> ProgramResult {
+    Ok(())
+}
+
+async fn start() -> TestPool {
+    let mut program_test = program_test();
+    program_test.add_program(
+        "mpl_token_metadata",
+        mpl_token_metadata::ID,
+        processor!(process_metadata),
+    );
+    let mut pool = TestPool::start_with(program_test, PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_badge_milestone(
+            &authority.pubkey(),
+            0,
+            MilestoneKind::ClaimCount,
+            1,
+            "First Claim".to_string(),
+            "FIRST".to_string(),
+            "https://example.com/first.json".to_string(),
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    pool
+}
+
+#[tokio::test]
+async fn users_mint_the_badge_once_the_milestone_is_reached() {
+    let mut pool = start().await;
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+
+    let pool_address = pool.keys.pool();
+    pool.process(
+        &[instructions::mint_milestone_badge(
+            &pool_address,
+            &user.pubkey(),
+            0,
+        )],
+        &[&user],
+    )
+    .await
+    .unwrap();
+    let badge_mint = find_badge_mint_address(&find_user_address(&user.pubkey()).0, 0).0;
+    let badge_account =
+        get_associated_token_address_with_program_id(&user.pubkey(), &badge_mint, &spl_token::ID);
+    assert_eq!(pool.token_balance(&badge_account).await, 1);
+    assert_eq!(pool.user(&user.pubkey()).await.badges_minted, 0b1);
+}
+
+#[tokio::test]
+async fn badges_cannot_be_minted_before_the_milestone() {
+    let mut pool = start().await;
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+
+    let pool_address = pool.keys.pool();
+    let result = pool
+        .process(
+            &[instructions::mint_milestone_badge(
+                &pool_address,
+                &user.pubkey(),
+                0,
+            )],
+            &[&user],
+        )
+        .await;
+    assert_program_error(result, ErrorCode::MilestoneNotReached);
+    assert_eq!(pool.user(&user.pubkey()).await.badges_minted, 0);
+}
@@ -0,0 +1,149 @@
+use reward_system_client::{
+    instructions::{self, PoolConfigUpdate},
+    pda::find_user_address,
+    reward_system::{instruction, ErrorCode},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    sysvar::slot_hashes,
+};
+
+/// Claim `user`'s lamports, returning how many they received
+async fn claim_native(pool: &mut TestPool, user: &Keypair) -> u64 {
+    let before = pool.sol_balance(&user.pubkey()).await;
+    let claim = instructions::claim_native_rewards(&pool.keys, &user.pubkey(), 0, 0);
+    pool.process(&[claim], &[user]).await.unwrap();
+    pool.sol_balance(&user.pubkey()).await - before
+}
+
+#[tokio::test]
+async fn short_sol_vault_carries_the_unpaid_claim_forward() {
+    let mut pool = TestPool::start_native(PoolParams::default()).await;
+    pool.fund_sol_vault(1_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::update_pool_config(
+            &authority.pubkey(),
+            PoolConfigUpdate {
+                allow_partial_payout: Some(true),
+                ..Default::default()
+            },
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+
+    // 2,400 is owed but the vault only has 1,000 above its rent floor
+    pool.warp_hours(24).await;
+    assert_eq!(claim_native(&mut pool, &user).await, 1_000);
+    assert_eq!(pool.user(&user.pubkey()).await.accrued_unclaimed, 1_400);
+    assert_eq!(pool.pool().await.total_carried_over, 1_400);
+
+    pool.fund_sol_vault(10_000).await;
+    pool.warp_hours(24).await;
+    assert_eq!(claim_native(&mut pool, &user).await, 1_400 + 2_400);
+    assert_eq!(pool.user(&user.pubkey()).await.accrued_unclaimed, 0);
+    assert_eq!(pool.pool().await.total_carried_over, 0);
+}
+
+#[tokio::test]
+async fn native_claims_reveal_the_committed_lottery_draw() {
+    let mut pool = TestPool::start_native(PoolParams::default()).await;
+    pool.fund_sol_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_lottery(
+            &authority.pubkey(),
+            10_000,
+            20_000,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    let commit = instructions::commit_lottery_draw(&user.pubkey(), &pool.keys.pool());
+    pool.process(std::slice::from_ref(&commit), &[&user])
+        .await
+        .unwrap();
+
+    // A certain win doubles the day's 2,400
+    pool.warp_hours(24).await;
+    let before = pool.sol_balance(&user.pubkey()).await;
+    let mut accounts = instructions::claim_native_rewards_accounts(&pool.keys, &user.pubkey(), 0);
+    accounts.slot_hashes = Some(slot_hashes::ID);
+    let claim = instructions::build(
+        accounts,
+        instruction::ClaimNativeRewards {
+            min_expected_amount: 0,
+        },
+    );
+    pool.process(&[claim], &[&user]).await.unwrap();
+    assert_eq!(pool.sol_balance(&user.pubkey()).await - before, 4_800);
+
+    let claimed = pool.user(&user.pubkey()).await;
+    assert_eq!(claimed.lottery_commit_slot, 0);
+    assert_eq!(claimed.lottery_wins, 1);
+    // The draw was consumed, so the user can commit to the next one
+    pool.process(&[commit], &[&user]).await.unwrap();
+}
+
+#[tokio::test]
+async fn native_claims_credit_the_referrer() {
+    let mut pool = TestPool::start_native(PoolParams::default()).await;
+    pool.fund_sol_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_referral_bps(&authority.pubkey(), 1_000)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let referrer = pool.new_user().await;
+    pool.register(&referrer).await.unwrap();
+    let user = pool.new_user().await;
+    let register = instructions::register_user_with_referrer(
+        &user.pubkey(),
+        &pool.keys.pool(),
+        &referrer.pubkey(),
+        Vec::new(),
+        0,
+    );
+    pool.process(&[register], &[&user]).await.unwrap();
+
+    pool.warp_hours(24).await;
+    let mut accounts = instructions::claim_native_rewards_accounts(&pool.keys, &user.pubkey(), 0);
+    accounts.referrer_account = Some(find_user_address(&referrer.pubkey()).0);
+    let claim = instructions::build(
+        accounts,
+        instruction::ClaimNativeRewards {
+            min_expected_amount: 0,
+        },
+    );
+    pool.process(&[claim], &[&user]).await.unwrap();
+    assert_eq!(pool.user(&referrer.pubkey()).await.referral_earned, 240);
+    assert_eq!(pool.pool().await.total_referral_owed, 240);
+
+    // The referrer is paid in lamports from the SOL vault
+    let before = pool.sol_balance(&referrer.pubkey()).await;
+    let payout = instructions::claim_native_referral_earnings(&pool.keys, &referrer.pubkey());
+    pool.process(std::slice::from_ref(&payout), &[&referrer])
+        .await
+        .unwrap();
+    assert_eq!(pool.sol_balance(&referrer.pubkey()).await - before, 240);
+    assert_eq!(pool.user(&referrer.pubkey()).await.referral_paid, 240);
+    assert_eq!(pool.pool().await.total_referral_owed, 0);
+
+    // Nothing is left to pay out a second time
+    pool.warp_secs(1).await;
+    let result = pool.process(&[payout], &[&referrer]).await;
+    assert_program_error(result, ErrorCode::NoRewardsAvailable);
+}
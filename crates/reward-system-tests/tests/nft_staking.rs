@@ -0,0 +1,211 @@
+use anchor_lang::{prelude::Pubkey, solana_program::program_pack::Pack, AnchorSerialize};
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    metadata::mpl_token_metadata::{
+        self,
+        accounts::Metadata,
+        types::{Collection, Key},
+    },
+    token::spl_token,
+};
+use reward_system_client::{
+    instructions,
+    pda::{find_metadata_address, find_staked_nft_address},
+    reward_system::{ErrorCode, NftStakingConfig},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+
+/// A one-of-one mint held by `owner`, with metadata naming `collection`
+async fn mint_nft(pool: &mut TestPool, owner: &Pubkey, collection: Collection) -> Pubkey {
+    let mint = Keypair::new();
+    let payer = pool.context.payer.pubkey();
+    let rent = pool.context.banks_client.get_rent().await.unwrap();
+    pool.process(
+        &[
+            system_instruction::create_account(
+                &payer,
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_mint2(
+                &spl_token::ID,
+                &mint.pubkey(),
+                &payer,
+                None,
+                0,
+            )
+            .unwrap(),
+        ],
+        &[&mint],
+    )
+    .await
+    .unwrap();
+    pool.create_associated_account(owner, &mint.pubkey(), &spl_token::ID)
+        .await;
+    let owner_account =
+        get_associated_token_address_with_program_id(owner, &mint.pubkey(), &spl_token::ID);
+    let mint_to = spl_token::instruction::mint_to(
+        &spl_token::ID,
+        &mint.pubkey(),
+        &owner_account,
+        &payer,
+        &[],
+        1,
+    )
+    .unwrap();
+    pool.process(&[mint_to], &[]).await.unwrap();
+
+    let metadata = Metadata {
+        key: Key::MetadataV1,
+        update_authority: payer,
+        mint: mint.pubkey(),
+        name: "Staker".to_string(),
+        symbol: "STK".to_string(),
+        uri: String::new(),
+        seller_fee_basis_points: 0,
+        creators: None,
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: None,
+        collection: Some(collection),
+        uses: None,
+        collection_details: None,
+        programmable_config: None,
+    };
+    let data = metadata.try_to_vec().unwrap();
+    let account = Account {
+        lamports: rent.minimum_balance(data.len()),
+        data,
+        owner: mpl_token_metadata::ID,
+        executable: false,
+        rent_epoch: 0,
+    };
+    pool.context.set_account(
+        &find_metadata_address(&mint.pubkey()).0,
+        &AccountSharedData::from(account),
+    );
+    mint.pubkey()
+}
+
+async fn enable_nft_staking(pool: &mut TestPool, collection: Pubkey) {
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_nft_staking(
+            &authority.pubkey(),
+            Some(NftStakingConfig {
+                collection,
+                reward_rate_per_hour: 50,
+            }),
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn staked_nft_earns_the_hourly_rate_and_comes_back_on_unstake() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let collection = Pubkey::new_unique();
+    enable_nft_staking(&mut pool, collection).await;
+
+    let owner = pool.new_user().await;
+    let nft = mint_nft(
+        &mut pool,
+        &owner.pubkey(),
+        Collection {
+            verified: true,
+            key: collection,
+        },
+    )
+    .await;
+    let keys = pool.keys;
+    let owner_nft_account =
+        get_associated_token_address_with_program_id(&owner.pubkey(), &nft, &spl_token::ID);
+    pool.process(
+        &[instructions::stake_nft(
+            &keys,
+            &owner.pubkey(),
+            &nft,
+            &spl_token::ID,
+        )],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+    assert_eq!(pool.token_balance(&owner_nft_account).await, 0);
+
+    pool.warp_hours(24).await;
+    pool.process(
+        &[instructions::unstake_nft(
+            &keys,
+            &owner.pubkey(),
+            &nft,
+            &spl_token::ID,
+        )],
+        &[&owner],
+    )
+    .await
+    .unwrap();
+    assert_eq!(pool.token_balance(&owner_nft_account).await, 1);
+    assert_eq!(pool.wallet_balance(&owner.pubkey()).await, 50 * 24);
+    let staked_nft = find_staked_nft_address(&keys.pool(), &nft).0;
+    assert!(pool
+        .context
+        .banks_client
+        .get_account(staked_nft)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test]
+async fn nfts_outside_the_verified_collection_cannot_be_staked() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let collection = Pubkey::new_unique();
+    enable_nft_staking(&mut pool, collection).await;
+    let owner = pool.new_user().await;
+    let keys = pool.keys;
+
+    let unverified = mint_nft(
+        &mut pool,
+        &owner.pubkey(),
+        Collection {
+            verified: false,
+            key: collection,
+        },
+    )
+    .await;
+    let other_collection = mint_nft(
+        &mut pool,
+        &owner.pubkey(),
+        Collection {
+            verified: true,
+            key: Pubkey::new_unique(),
+        },
+    )
+    .await;
+    for nft in [unverified, other_collection] {
+        let result = pool
+            .process(
+                &[instructions::stake_nft(
+                    &keys,
+                    &owner.pubkey(),
+                    &nft,
+                    &spl_token::ID,
+                )],
+                &[&owner],
+            )
+            .await;
+        assert_program_error(result, ErrorCode::NftNotInCollection);
+    }
+}
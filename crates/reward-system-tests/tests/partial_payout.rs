@@ -0,0 +1,160 @@
+use reward_system_client::instructions::{self, PoolConfigUpdate};
+use reward_system_tests::{PoolParams, TestPool};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn short_vault_carries_the_unpaid_claim_forward() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::update_pool_config(
+            &authority.pubkey(),
+            PoolConfigUpdate {
+                allow_partial_payout: Some(true),
+                ..Default::default()
+            },
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+
+    // 2,400 is owed but the vault only holds 1,000
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 1_000);
+    assert_eq!(pool.user(&user.pubkey()).await.accrued_unclaimed, 1_400);
+    let state = pool.pool().await;
+    assert_eq!(state.total_distributed, 1_000);
+    assert_eq!(state.total_carried_over, 1_400);
+
+    // Once the vault is refilled the remainder is paid with the next window
+    pool.fund_vault(10_000).await;
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+    assert_eq!(
+        pool.wallet_balance(&user.pubkey()).await,
+        1_000 + 1_400 + 2_400
+    );
+    assert_eq!(pool.user(&user.pubkey()).await.accrued_unclaimed, 0);
+    assert_eq!(pool.pool().await.total_carried_over, 0);
+}
+
+#[tokio::test]
+async fn short_vault_on_a_burning_pool_burns_each_share_once() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_200).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[
+            instructions::update_pool_config(
+                &authority.pubkey(),
+                PoolConfigUpdate {
+                    allow_partial_payout: Some(true),
+                    ..Default::default()
+                },
+            ),
+            instructions::set_burn_bps(&authority.pubkey(), 2_500),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+
+    // 2,400 is owed (1,800 paid, 600 burned) but the vault only covers half of it
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 900);
+    assert_eq!(pool.user(&user.pubkey()).await.accrued_unclaimed, 1_200);
+    let state = pool.pool().await;
+    assert_eq!(state.total_burned, 300);
+    assert_eq!(state.total_distributed, 900);
+    assert_eq!(pool.vault_balance().await, 0);
+
+    // The carried-over half burns its share only when it is paid
+    pool.fund_vault(10_000).await;
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 900 + 2_700);
+    assert_eq!(pool.user(&user.pubkey()).await.accrued_unclaimed, 0);
+    let state = pool.pool().await;
+    assert_eq!(state.total_burned, 1_200);
+    assert_eq!(state.total_carried_over, 0);
+}
+
+#[tokio::test]
+async fn short_vault_carries_a_managed_claim_forward() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(3_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::update_pool_config(
+            &authority.pubkey(),
+            PoolConfigUpdate {
+                allow_partial_payout: Some(true),
+                ..Default::default()
+            },
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let manager = pool.new_user().await;
+    let keys = pool.keys;
+    let mut members = Vec::new();
+    for _ in 0..3 {
+        let member = pool.new_user().await;
+        pool.register(&member).await.unwrap();
+        pool.process(
+            &[instructions::set_claim_manager(
+                &member.pubkey(),
+                &keys.pool(),
+                Some(manager.pubkey()),
+            )],
+            &[&member],
+        )
+        .await
+        .unwrap();
+        pool.create_token_account(&member.pubkey(), &keys).await;
+        members.push(member.pubkey());
+    }
+
+    // 7,200 is owed: the first member is paid in full, the second gets the 600 left and
+    // the third is left for a later batch
+    pool.warp_hours(24).await;
+    let batch = instructions::claim_for_members(
+        &keys,
+        &manager.pubkey(),
+        &members
+            .iter()
+            .map(|member| (*member, 0, None))
+            .collect::<Vec<_>>(),
+    );
+    pool.process(&[batch], &[&manager]).await.unwrap();
+    assert_eq!(pool.wallet_balance(&members[0]).await, 2_400);
+    assert_eq!(pool.wallet_balance(&members[1]).await, 600);
+    let short = pool.user(&members[1]).await;
+    assert_eq!(short.accrued_unclaimed, 1_800);
+    assert_eq!(short.total_claims, 1);
+    assert_eq!(pool.user(&members[2]).await.total_claims, 0);
+    let state = pool.pool().await;
+    assert_eq!(state.total_distributed, 3_000);
+    assert_eq!(state.total_carried_over, 1_800);
+
+    // The carried-over share is paid with the member's next claim
+    pool.fund_vault(10_000).await;
+    pool.warp_hours(24).await;
+    let batch = instructions::claim_for_members(&keys, &manager.pubkey(), &[(members[1], 0, None)]);
+    pool.process(&[batch], &[&manager]).await.unwrap();
+    assert_eq!(pool.wallet_balance(&members[1]).await, 600 + 1_800 + 2_400);
+    assert_eq!(pool.user(&members[1]).await.accrued_unclaimed, 0);
+    assert_eq!(pool.pool().await.total_carried_over, 0);
+}
@@ -0,0 +1,65 @@
+use anchor_spl::token::spl_token;
+use reward_system_client::{
+    find_user_address, instructions, reward_system::ErrorCode, DistributionMode, PoolKeys,
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn members_cannot_use_another_pool() {
+    let params = PoolParams::default();
+    let mut pool = TestPool::start(params).await;
+    pool.fund_vault(1_000_000).await;
+
+    // A second pool over the same mint, with a funded vault
+    let other_authority = pool.new_user().await;
+    let other = PoolKeys::new(other_authority.pubkey(), pool.keys.mint, spl_token::ID);
+    pool.process(
+        &[instructions::initialize_pool(
+            &other,
+            params.reward_rate_per_hour,
+            params.min_claim_interval_secs,
+            params.max_daily_reward,
+            params.max_accrual_hours,
+            DistributionMode::Vault,
+        )],
+        &[&other_authority],
+    )
+    .await
+    .unwrap();
+    let mint_authority = pool.authority.insecure_clone();
+    pool.process(
+        &[spl_token::instruction::mint_to(
+            &spl_token::ID,
+            &other.mint,
+            &other.vault(),
+            &mint_authority.pubkey(),
+            &[],
+            1_000_000,
+        )
+        .unwrap()],
+        &[&mint_authority],
+    )
+    .await
+    .unwrap();
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(24).await;
+
+    let claim = instructions::claim_rewards(&other, &user.pubkey(), 0, 0, None);
+    let result = pool.process(&[claim], &[&user]).await;
+    assert_program_error(result, ErrorCode::UserPoolMismatch);
+
+    let quote = instructions::get_claim_quote(&find_user_address(&user.pubkey()).0, &other.pool());
+    let result = pool.process(&[quote], &[]).await;
+    assert_program_error(result, ErrorCode::UserPoolMismatch);
+
+    let metadata = instructions::set_user_metadata(&user.pubkey(), &other.pool(), "alice", "");
+    let result = pool.process(&[metadata], &[&user]).await;
+    assert_program_error(result, ErrorCode::UserPoolMismatch);
+
+    assert_eq!(pool.token_balance(&other.vault()).await, 1_000_000);
+    pool.claim(&user).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_400);
+}
@@ -0,0 +1,259 @@
+use anchor_lang::{AccountDeserialize, AccountSerialize, Space};
+use reward_system_client::{
+    find_user_address, instructions,
+    reward_system::{
+        instruction, AccrualModel, ErrorCode, PendingYieldVenue, ACCOUNT_RESERVED_WORDS,
+        POOL_RESERVED_WORDS, POOL_VERSION, UNCOUNTED, USER_ACCOUNT_VERSION,
+    },
+    RewardPool, UserAccount,
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+
+/// Bytes a version 5 pool serialized, and the size of its account
+fn version_5_lengths(serialized: usize, account: usize) -> (usize, usize) {
+    let appended = 8 * ACCOUNT_RESERVED_WORDS;
+    // Version 8's pending and previous accrual models, serialized at their defaults
+    let models = 1 + (1 + 8 + 8 + 2 + 1);
+    (
+        serialized - 2 - appended - models,
+        account
+            - (1 + 32)
+            - (1 + PendingYieldVenue::INIT_SPACE)
+            - appended
+            - (1 + 2 * AccrualModel::INIT_SPACE),
+    )
+}
+
+#[tokio::test]
+async fn migration_ignores_bytes_left_behind_by_a_shrunk_vec() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let address = pool.keys.pool();
+    let mut account = pool
+        .context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut legacy = RewardPool::try_deserialize(&mut account.data.as_slice()).unwrap();
+    let (granter, removed_granter, proceeds) = (
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+        Pubkey::new_unique(),
+    );
+    legacy.version = 5;
+    legacy.boost_proceeds_destination = Some(proceeds);
+
+    // Write the pool as version 5 with two boost granters, then again with one, which
+    // leaves the last 32 bytes of the longer write behind where version 6 appends fields
+    legacy.boost_granter_programs = vec![granter, removed_granter];
+    let mut data = Vec::new();
+    legacy.try_serialize(&mut data).unwrap();
+    let (v5_written, v5_len) = version_5_lengths(data.len(), account.data.len());
+    data.truncate(v5_written);
+    legacy.boost_granter_programs = vec![granter];
+    let mut shorter = Vec::new();
+    legacy.try_serialize(&mut shorter).unwrap();
+    let (v5_written, _) = version_5_lengths(shorter.len(), account.data.len());
+    data[..v5_written].copy_from_slice(&shorter[..v5_written]);
+    assert!(data[v5_written..].iter().any(|byte| *byte != 0));
+    data.resize(v5_len, 0);
+    account.data = data;
+    pool.context.set_account(&address, &account.into());
+
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::migrate_pool(&authority.pubkey())],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let migrated = pool.pool().await;
+    assert_eq!(migrated.version, POOL_VERSION);
+    assert_eq!(migrated.boost_granter_programs, vec![granter]);
+    assert_eq!(migrated.boost_proceeds_destination, Some(proceeds));
+    assert_eq!(migrated.yield_deposit_account, None);
+    assert_eq!(migrated.pending_yield_venue, None);
+    assert_eq!(migrated.denylist_len, UNCOUNTED);
+    assert_eq!(migrated.region_policy_count, UNCOUNTED);
+    assert_eq!(migrated._reserved, [0; POOL_RESERVED_WORDS]);
+    assert_eq!(migrated.pending_model, None);
+    assert_eq!(migrated.previous_model, migrated.accrual_model());
+}
+
+#[tokio::test]
+async fn version_2_user_accounts_grow_to_the_current_layout() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+
+    // Version 3 appended the two profile string length prefixes, version 5 the referral key
+    let address = find_user_address(&user.pubkey()).0;
+    let mut account = pool
+        .context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut legacy = UserAccount::try_deserialize(&mut account.data.as_slice()).unwrap();
+    legacy.version = 2;
+    let mut data = Vec::new();
+    legacy.try_serialize(&mut data).unwrap();
+    data.truncate(data.len() - 2 * 4 - 32);
+    account.data = data;
+    pool.context.set_account(&address, &account.into());
+
+    pool.process(
+        &[instructions::migrate_user(
+            &pool.context.payer.pubkey(),
+            &user.pubkey(),
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+    let account = pool
+        .context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.data.len(), 8 + UserAccount::INIT_SPACE);
+    let migrated = pool.user(&user.pubkey()).await;
+    assert_eq!(migrated.version, USER_ACCOUNT_VERSION);
+    assert_eq!(migrated.nickname, "");
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_400);
+}
+
+/// Rewrite `wallet`'s user account in the version 4 layout, which ended at the profile
+/// strings, after `edit` has been applied to it
+async fn downgrade_to_version_4(
+    pool: &mut TestPool,
+    wallet: &Pubkey,
+    edit: impl FnOnce(&mut UserAccount),
+) {
+    let address = find_user_address(wallet).0;
+    let mut account = pool
+        .context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut legacy = UserAccount::try_deserialize(&mut account.data.as_slice()).unwrap();
+    legacy.version = 4;
+    edit(&mut legacy);
+    let mut data = Vec::new();
+    legacy.try_serialize(&mut data).unwrap();
+    data.truncate(data.len() - 32);
+    account.data = data;
+    pool.context.set_account(&address, &account.into());
+}
+
+#[tokio::test]
+async fn version_4_accounts_keep_their_profile_and_referrals() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_referral_bps(&authority.pubkey(), 1_000)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let referrer = pool.new_user().await;
+    pool.register(&referrer).await.unwrap();
+    let set_nickname =
+        instructions::set_user_metadata(&referrer.pubkey(), &pool.keys.pool(), "referrer", "");
+    pool.process(&[set_nickname], &[&referrer]).await.unwrap();
+    let referee = pool.new_user().await;
+    let register = instructions::register_user_with_referrer(
+        &referee.pubkey(),
+        &pool.keys.pool(),
+        &referrer.pubkey(),
+        Vec::new(),
+        0,
+    );
+    pool.process(&[register], &[&referee]).await.unwrap();
+
+    // Version 4 referees recorded their referrer's wallet
+    downgrade_to_version_4(&mut pool, &referrer.pubkey(), |_| {}).await;
+    let referrer_wallet = referrer.pubkey();
+    downgrade_to_version_4(&mut pool, &referee.pubkey(), |legacy| {
+        legacy.referrer = Some(referrer_wallet)
+    })
+    .await;
+    let payer = pool.context.payer.pubkey();
+    pool.process(
+        &[
+            instructions::migrate_user(&payer, &referrer.pubkey()),
+            instructions::migrate_user(&payer, &referee.pubkey()),
+        ],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let account = pool
+        .context
+        .banks_client
+        .get_account(find_user_address(&referrer.pubkey()).0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        account.data.len(),
+        UserAccount::space_with_metadata("referrer", "")
+    );
+    let migrated = pool.user(&referrer.pubkey()).await;
+    assert_eq!(migrated.version, USER_ACCOUNT_VERSION);
+    assert_eq!(migrated.nickname, "referrer");
+    assert_eq!(migrated.referral_key, referrer.pubkey());
+
+    pool.warp_hours(24).await;
+    let mut accounts = instructions::claim_rewards_accounts(&pool.keys, &referee.pubkey(), 0);
+    accounts.referrer_account = Some(find_user_address(&referrer.pubkey()).0);
+    let claim = instructions::build(
+        accounts,
+        instruction::ClaimRewards {
+            min_expected_amount: 0,
+            amount: None,
+        },
+    );
+    pool.process(&[claim], &[&referee]).await.unwrap();
+    assert_eq!(pool.user(&referrer.pubkey()).await.referral_earned, 240);
+}
+
+#[tokio::test]
+async fn accounts_from_a_newer_version_are_not_migrated() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+
+    let address = find_user_address(&user.pubkey()).0;
+    let mut account = pool
+        .context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut newer = UserAccount::try_deserialize(&mut account.data.as_slice()).unwrap();
+    newer.version = USER_ACCOUNT_VERSION + 1;
+    let mut data = Vec::new();
+    newer.try_serialize(&mut data).unwrap();
+    account.data = data;
+    pool.context.set_account(&address, &account.into());
+
+    let payer = pool.context.payer.pubkey();
+    let result = pool
+        .process(&[instructions::migrate_user(&payer, &user.pubkey())], &[])
+        .await;
+    assert_program_error(result, ErrorCode::UnsupportedAccountVersion);
+}
@@ -0,0 +1,134 @@
+use anchor_spl::token::spl_token;
+use reward_system_client::{
+    instructions,
+    pda::find_sol_vault_address,
+    reward_system::{instruction, ErrorCode},
+    RegistrationFee,
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::{
+    program_pack::Pack,
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+
+const FEE: u64 = 5_000_000;
+
+#[tokio::test]
+async fn lamport_fee_is_paid_into_the_sol_vault() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_registration_fee(
+            &authority.pubkey(),
+            Some(RegistrationFee::Lamports(FEE)),
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let user = pool.new_user().await;
+
+    let result = pool.register(&user).await;
+    assert_program_error(result, ErrorCode::RegistrationFeeAccountsRequired);
+
+    let mut accounts = instructions::register_user_accounts(&user.pubkey(), &pool.keys.pool());
+    instructions::pay_registration_fee(&mut accounts, &pool.keys, RegistrationFee::Lamports(FEE));
+    let register = instructions::build(
+        accounts,
+        instruction::RegisterUser {
+            proof: Vec::new(),
+            terms_version: 0,
+        },
+    );
+    pool.process(&[register], &[&user]).await.unwrap();
+
+    let sol_vault = find_sol_vault_address(&pool.keys.pool()).0;
+    let collected = pool
+        .context
+        .banks_client
+        .get_balance(sol_vault)
+        .await
+        .unwrap();
+    assert_eq!(collected, FEE);
+    assert_eq!(pool.pool().await.participant_count, 1);
+}
+
+#[tokio::test]
+async fn token_fee_is_paid_into_the_vault() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_registration_fee(
+            &authority.pubkey(),
+            Some(RegistrationFee::Tokens(1_000)),
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    // The fee can come from any token account the user owns
+    let user = pool.new_user().await;
+    let source = Keypair::new();
+    let rent = pool.context.banks_client.get_rent().await.unwrap();
+    let payer = pool.context.payer.pubkey();
+    pool.process(
+        &[
+            system_instruction::create_account(
+                &payer,
+                &source.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::ID,
+            ),
+            spl_token::instruction::initialize_account3(
+                &spl_token::ID,
+                &source.pubkey(),
+                &pool.keys.mint,
+                &user.pubkey(),
+            )
+            .unwrap(),
+            spl_token::instruction::mint_to(
+                &spl_token::ID,
+                &pool.keys.mint,
+                &source.pubkey(),
+                &authority.pubkey(),
+                &[],
+                3_000,
+            )
+            .unwrap(),
+        ],
+        &[&source, &authority],
+    )
+    .await
+    .unwrap();
+
+    let mut accounts = instructions::register_user_accounts(&user.pubkey(), &pool.keys.pool());
+    instructions::pay_registration_fee(&mut accounts, &pool.keys, RegistrationFee::Tokens(1_000));
+    accounts.fee_token_account = Some(source.pubkey());
+    let register = instructions::build(
+        accounts,
+        instruction::RegisterUser {
+            proof: Vec::new(),
+            terms_version: 0,
+        },
+    );
+    pool.process(&[register], &[&user]).await.unwrap();
+    assert_eq!(pool.token_balance(&source.pubkey()).await, 2_000);
+    assert_eq!(pool.vault_balance().await, 1_000);
+
+    // A zero fee needs no fee accounts
+    pool.process(
+        &[instructions::set_registration_fee(
+            &authority.pubkey(),
+            Some(RegistrationFee::Tokens(0)),
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let free = pool.new_user().await;
+    pool.register(&free).await.unwrap();
+    assert_eq!(pool.pool().await.participant_count, 2);
+}
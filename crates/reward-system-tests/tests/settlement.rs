@@ -0,0 +1,203 @@
+use reward_system_client::{
+    find_user_address, instructions,
+    pda::{find_denylist_address, find_epoch_address},
+    reward_system::{accounts, instruction, Epoch},
+};
+use reward_system_tests::{PoolParams, TestPool, HOUR};
+use solana_sdk::{signature::Signer, system_program};
+
+#[tokio::test]
+async fn settle_users_pays_eligible_users_and_tips_the_cranker() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_crank_tip_bps(&authority.pubkey(), 1_000)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let settled = pool.new_user().await;
+    let recent = pool.new_user().await;
+    let no_token_account = pool.new_user().await;
+    let cranker = pool.new_user().await;
+    for user in [&settled, &recent, &no_token_account, &cranker] {
+        pool.register(user).await.unwrap();
+    }
+    // Direct claims create the token accounts the page pays into
+    pool.warp_hours(24).await;
+    for user in [&settled, &recent, &cranker] {
+        pool.claim(user).await.unwrap();
+    }
+
+    pool.warp_hours(24).await;
+    pool.claim(&recent).await.unwrap();
+    let page = [
+        (settled.pubkey(), 0, None),
+        (recent.pubkey(), 0, None),
+        (no_token_account.pubkey(), 0, None),
+    ];
+    let settle = instructions::settle_users(&pool.keys, &cranker.pubkey(), &page);
+    pool.process(&[settle], &[&cranker]).await.unwrap();
+
+    // 2,400 for the day, 10% of it to the cranker
+    assert_eq!(pool.wallet_balance(&settled.pubkey()).await, 2_400 + 2_160);
+    assert_eq!(pool.wallet_balance(&cranker.pubkey()).await, 2_400 + 240);
+    assert_eq!(pool.user(&settled.pubkey()).await.total_claims, 2);
+    assert_eq!(pool.user(&recent.pubkey()).await.total_claims, 2);
+    assert_eq!(pool.user(&no_token_account.pubkey()).await.total_claims, 0);
+    let stats = pool.pool().await;
+    assert_eq!(stats.total_crank_tips, 240);
+    assert_eq!(stats.total_distributed, 5 * 2_400);
+}
+
+#[tokio::test]
+async fn settled_users_credit_their_referrer_and_reveal_the_lottery() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[
+            instructions::set_referral_bps(&authority.pubkey(), 1_000),
+            instructions::set_lottery(&authority.pubkey(), 5_000, 20_000),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let referrer = pool.new_user().await;
+    let cranker = pool.new_user().await;
+    pool.register(&referrer).await.unwrap();
+    pool.register(&cranker).await.unwrap();
+    let referred = pool.new_user().await;
+    let register = instructions::register_user_with_referrer(
+        &referred.pubkey(),
+        &pool.keys.pool(),
+        &referrer.pubkey(),
+        Vec::new(),
+        0,
+    );
+    pool.process(&[register], &[&referred]).await.unwrap();
+
+    // Direct claims open the token accounts; the commit is revealed by the page
+    pool.warp_hours(24).await;
+    let mut accounts = instructions::claim_rewards_accounts(&pool.keys, &referred.pubkey(), 0);
+    accounts.referrer_account = Some(find_user_address(&referrer.pubkey()).0);
+    let claim = instructions::build(
+        accounts,
+        instruction::ClaimRewards {
+            min_expected_amount: 0,
+            amount: None,
+        },
+    );
+    pool.process(&[claim], &[&referred]).await.unwrap();
+    pool.claim(&cranker).await.unwrap();
+    let commit = instructions::commit_lottery_draw(&referred.pubkey(), &pool.keys.pool());
+    pool.process(&[commit], &[&referred]).await.unwrap();
+    assert_eq!(pool.user(&referrer.pubkey()).await.referral_earned, 240);
+
+    pool.warp_hours(24).await;
+    let page = [(referred.pubkey(), 0, Some(referrer.pubkey()))];
+    let settle = instructions::settle_users(&pool.keys, &cranker.pubkey(), &page);
+    pool.process(&[settle], &[&cranker]).await.unwrap();
+
+    let settled = pool.user(&referred.pubkey()).await;
+    assert_eq!(settled.total_claims, 2);
+    assert_eq!(settled.lottery_commit_slot, 0);
+    let paid = 2_400 + settled.lottery_bonus_earned;
+    assert_eq!(pool.wallet_balance(&referred.pubkey()).await, 2_400 + paid);
+    assert_eq!(
+        pool.user(&referrer.pubkey()).await.referral_earned,
+        240 + paid / 10
+    );
+}
+
+#[tokio::test]
+async fn settle_users_skips_denylisted_users() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+
+    let denylisted = pool.new_user().await;
+    let settled = pool.new_user().await;
+    let cranker = pool.new_user().await;
+    for user in [&denylisted, &settled, &cranker] {
+        pool.register(user).await.unwrap();
+    }
+    pool.warp_hours(24).await;
+    for user in [&denylisted, &settled, &cranker] {
+        pool.claim(user).await.unwrap();
+    }
+    let deny = instructions::build(
+        accounts::AddToDenylist {
+            reward_pool: pool.keys.pool(),
+            denylist_entry: find_denylist_address(&pool.keys.pool(), &denylisted.pubkey()).0,
+            authority: authority.pubkey(),
+            system_program: system_program::ID,
+        },
+        instruction::AddToDenylist {
+            address: denylisted.pubkey(),
+            reason_code: 1,
+        },
+    );
+    pool.process(&[deny], &[&authority]).await.unwrap();
+
+    // The denylisted user is passed over without failing the rest of the page
+    pool.warp_hours(24).await;
+    let page = [(denylisted.pubkey(), 0, None), (settled.pubkey(), 0, None)];
+    let settle = instructions::settle_users(&pool.keys, &cranker.pubkey(), &page);
+    pool.process(&[settle], &[&cranker]).await.unwrap();
+
+    assert_eq!(pool.user(&denylisted.pubkey()).await.total_claims, 1);
+    assert_eq!(pool.user(&settled.pubkey()).await.total_claims, 2);
+    assert_eq!(pool.wallet_balance(&settled.pubkey()).await, 2 * 2_400);
+}
+
+#[tokio::test]
+async fn settle_users_skips_users_the_epoch_budget_cannot_cover() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let users = [
+        pool.new_user().await,
+        pool.new_user().await,
+        pool.new_user().await,
+    ];
+    let cranker = pool.new_user().await;
+    for user in users.iter().chain([&cranker]) {
+        pool.register(user).await.unwrap();
+    }
+    // Direct claims open the token accounts, before the epochs start
+    pool.warp_hours(24).await;
+    for user in users.iter().chain([&cranker]) {
+        pool.claim(user).await.unwrap();
+    }
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::start_epochs(
+            &authority.pubkey(),
+            7 * 24 * HOUR,
+            5_000,
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    // The epoch covers two of the three 2,400 claims; the third is skipped, not the page
+    pool.warp_hours(24).await;
+    let epoch = find_epoch_address(&pool.keys.pool(), 0).0;
+    let mut accounts = instructions::settle_users_accounts(&pool.keys, &cranker.pubkey());
+    accounts.epoch = Some(epoch);
+    let page: Vec<_> = users.iter().map(|user| (user.pubkey(), 0, None)).collect();
+    let settle = instructions::settle_users_with(&pool.keys, accounts, &page);
+    pool.process(&[settle], &[&cranker]).await.unwrap();
+
+    assert_eq!(pool.user(&users[0].pubkey()).await.total_claims, 2);
+    assert_eq!(pool.user(&users[1].pubkey()).await.total_claims, 2);
+    assert_eq!(pool.user(&users[2].pubkey()).await.total_claims, 1);
+    let epoch: Epoch = pool.account(&epoch).await;
+    assert_eq!(epoch.distributed_this_epoch, 4_800);
+    assert_eq!(epoch.claim_count, 2);
+}
@@ -0,0 +1,206 @@
+use anchor_lang::Space;
+use reward_system_client::{
+    instructions,
+    pda::{find_rent_sponsor_address, find_user_address},
+    reward_system::{ErrorCode, RentSponsor, UserAccount},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::{
+    signature::{Keypair, Signer},
+    system_instruction,
+};
+
+const CAMPAIGN: u64 = 1;
+
+#[tokio::test]
+async fn sponsor_pays_token_accounts_within_the_daily_limit() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let authority = pool.authority.insecure_clone();
+    let sponsor = find_rent_sponsor_address(&pool.keys.pool(), CAMPAIGN).0;
+    let payer = pool.context.payer.pubkey();
+    pool.process(
+        &[
+            instructions::create_rent_sponsor(&authority.pubkey(), CAMPAIGN, 1_000_000_000),
+            system_instruction::transfer(&payer, &sponsor, 1_000_000_000),
+            instructions::set_sponsorship_daily_limit(&authority.pubkey(), 1),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let first = pool.new_user().await;
+    let second = pool.new_user().await;
+    pool.register(&first).await.unwrap();
+    pool.register(&second).await.unwrap();
+
+    let wallet_lamports = pool
+        .context
+        .banks_client
+        .get_balance(first.pubkey())
+        .await
+        .unwrap();
+    let sponsored =
+        instructions::create_token_account_sponsored(&pool.keys, &first.pubkey(), CAMPAIGN);
+    pool.process(std::slice::from_ref(&sponsored), &[&first])
+        .await
+        .unwrap();
+
+    let token_account = pool.keys.token_account(&first.pubkey());
+    let created = pool
+        .context
+        .banks_client
+        .get_account(token_account)
+        .await
+        .unwrap()
+        .expect("token account created");
+    let rent_sponsor: RentSponsor = pool.account(&sponsor).await;
+    assert_eq!(rent_sponsor.spent_lamports, created.lamports);
+    let wallet_after = pool
+        .context
+        .banks_client
+        .get_balance(first.pubkey())
+        .await
+        .unwrap();
+    assert_eq!(wallet_after, wallet_lamports);
+
+    // Existing accounts are left alone and don't count against the limit
+    pool.process(&[sponsored], &[&first]).await.unwrap();
+    let rent_sponsor: RentSponsor = pool.account(&sponsor).await;
+    assert_eq!(rent_sponsor.spent_lamports, created.lamports);
+
+    let sponsored =
+        instructions::create_token_account_sponsored(&pool.keys, &second.pubkey(), CAMPAIGN);
+    let result = pool
+        .process(std::slice::from_ref(&sponsored), &[&second])
+        .await;
+    assert_program_error(result, ErrorCode::SponsorshipDailyLimitReached);
+
+    pool.warp_hours(24).await;
+    pool.process(&[sponsored], &[&second]).await.unwrap();
+    assert_eq!(pool.pool().await.sponsored_today, 1);
+}
+
+#[tokio::test]
+async fn sponsored_registration_tops_up_a_prefunded_user_account() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let authority = pool.authority.insecure_clone();
+    let sponsor = find_rent_sponsor_address(&pool.keys.pool(), CAMPAIGN).0;
+    let payer = pool.context.payer.pubkey();
+    pool.process(
+        &[
+            instructions::create_rent_sponsor(&authority.pubkey(), CAMPAIGN, 1_000_000_000),
+            system_instruction::transfer(&payer, &sponsor, 1_000_000_000),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    // Anyone can send lamports to the predictable user PDA ahead of registration
+    let user = pool.new_user().await;
+    let user_address = find_user_address(&user.pubkey()).0;
+    let prefunded = pool
+        .context
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(0);
+    pool.process(
+        &[system_instruction::transfer(
+            &payer,
+            &user_address,
+            prefunded,
+        )],
+        &[],
+    )
+    .await
+    .unwrap();
+
+    let register = instructions::register_user_sponsored(
+        &user.pubkey(),
+        &pool.keys.pool(),
+        CAMPAIGN,
+        Vec::new(),
+        0,
+    );
+    pool.process(std::slice::from_ref(&register), &[&user])
+        .await
+        .unwrap();
+
+    let created = pool
+        .context
+        .banks_client
+        .get_account(user_address)
+        .await
+        .unwrap()
+        .expect("user account created");
+    let rent_sponsor: RentSponsor = pool.account(&sponsor).await;
+    assert_eq!(rent_sponsor.spent_lamports, created.lamports - prefunded);
+    assert_eq!(rent_sponsor.sponsored_count, 1);
+    assert_eq!(pool.user(&user.pubkey()).await.authority, user.pubkey());
+
+    let result = pool.process(&[register], &[&user]).await;
+    assert_program_error(result, ErrorCode::UserAlreadyRegistered);
+}
+
+#[tokio::test]
+async fn sponsored_registration_needs_no_sol_and_stops_at_the_budget() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let authority = pool.authority.insecure_clone();
+    let sponsor = find_rent_sponsor_address(&pool.keys.pool(), CAMPAIGN).0;
+    let payer = pool.context.payer.pubkey();
+    let user_rent = pool
+        .context
+        .banks_client
+        .get_rent()
+        .await
+        .unwrap()
+        .minimum_balance(8 + UserAccount::INIT_SPACE);
+    pool.process(
+        &[
+            instructions::create_rent_sponsor(&authority.pubkey(), CAMPAIGN, user_rent),
+            system_instruction::transfer(&payer, &sponsor, 1_000_000_000),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    // Wallets that were never funded
+    let first = Keypair::new();
+    let second = Keypair::new();
+    pool.process(
+        &[instructions::register_user_sponsored(
+            &first.pubkey(),
+            &pool.keys.pool(),
+            CAMPAIGN,
+            Vec::new(),
+            0,
+        )],
+        &[&first],
+    )
+    .await
+    .unwrap();
+    assert_eq!(pool.user(&first.pubkey()).await.authority, first.pubkey());
+    assert_eq!(pool.sol_balance(&first.pubkey()).await, 0);
+    let rent_sponsor: RentSponsor = pool.account(&sponsor).await;
+    assert_eq!(rent_sponsor.spent_lamports, user_rent);
+    assert_eq!(rent_sponsor.sponsored_count, 1);
+    assert_eq!(pool.pool().await.participant_count, 1);
+
+    let result = pool
+        .process(
+            &[instructions::register_user_sponsored(
+                &second.pubkey(),
+                &pool.keys.pool(),
+                CAMPAIGN,
+                Vec::new(),
+                0,
+            )],
+            &[&second],
+        )
+        .await;
+    assert_program_error(result, ErrorCode::RentSponsorBudgetExceeded);
+}
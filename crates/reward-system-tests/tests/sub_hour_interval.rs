@@ -0,0 +1,92 @@
+use anchor_lang::{AccountDeserialize, AccountSerialize, Space};
+use reward_system_client::{
+    instructions,
+    reward_system::{
+        AccrualModel, ErrorCode, PendingYieldVenue, ACCOUNT_RESERVED_WORDS, POOL_VERSION,
+    },
+    RewardPool,
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool, HOUR};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn fifteen_minute_pools_accrue_whole_intervals() {
+    let mut pool = TestPool::start(PoolParams {
+        reward_rate_per_hour: 60,
+        min_claim_interval_secs: 900,
+        ..PoolParams::default()
+    })
+    .await;
+    pool.fund_vault(1_000_000).await;
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+
+    pool.warp_secs(14 * 60).await;
+    let result = pool.claim(&user).await;
+    assert_program_error(result, ErrorCode::ClaimTooSoon);
+
+    // 40 minutes in: two whole 15-minute intervals at one token a minute
+    pool.warp_secs(26 * 60).await;
+    pool.claim(&user).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 30);
+
+    pool.warp_secs(15 * 60).await;
+    pool.claim(&user).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 45);
+}
+
+#[tokio::test]
+async fn migrated_pools_convert_their_interval_to_seconds() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+
+    // Rewrite the pool as version 4 left it: the interval in hours, and without the
+    // yield deposit account, pending venue, reserved words and accrual models appended
+    // since
+    let address = pool.keys.pool();
+    let mut account = pool
+        .context
+        .banks_client
+        .get_account(address)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut legacy = RewardPool::try_deserialize(&mut account.data.as_slice()).unwrap();
+    legacy.version = 4;
+    legacy._legacy_min_claim_interval_hours = 24;
+    legacy.min_claim_interval_secs = 0;
+    let mut data = Vec::new();
+    legacy.try_serialize(&mut data).unwrap();
+    let v4_len = account.data.len()
+        - (1 + 32)
+        - (1 + PendingYieldVenue::INIT_SPACE)
+        - 8 * ACCOUNT_RESERVED_WORDS
+        - (1 + 2 * AccrualModel::INIT_SPACE);
+    assert!(data.len() <= v4_len);
+    data.resize(v4_len, 0);
+    account.data = data;
+    pool.context.set_account(&address, &account.into());
+
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::migrate_pool(&authority.pubkey())],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let migrated = pool.pool().await;
+    assert_eq!(migrated.version, POOL_VERSION);
+    assert_eq!(migrated.min_claim_interval_secs, 24 * HOUR as u64);
+    assert_eq!(migrated._legacy_min_claim_interval_hours, 0);
+    assert_eq!(migrated.total_referral_owed, 0);
+    assert_eq!(migrated.total_carried_over, 0);
+
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(23).await;
+    let result = pool.claim(&user).await;
+    assert_program_error(result, ErrorCode::ClaimTooSoon);
+    pool.warp_hours(1).await;
+    pool.claim(&user).await.unwrap();
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_400);
+}
@@ -0,0 +1,78 @@
+use anchor_spl::memo;
+use reward_system_client::{
+    instructions,
+    reward_system::{instruction, ErrorCode},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn claims_carry_a_memo_when_enabled() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_transfer_memos(&authority.pubkey(), true)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(24).await;
+
+    let result = pool.claim(&user).await;
+    assert_program_error(result, ErrorCode::MemoProgramRequired);
+
+    let mut accounts = instructions::claim_rewards_accounts(&pool.keys, &user.pubkey(), 0);
+    accounts.memo_program = Some(memo::ID);
+    let claim = instructions::build(
+        accounts,
+        instruction::ClaimRewards {
+            min_expected_amount: 0,
+            amount: None,
+        },
+    );
+    let logs = pool.logs(&[claim], &[&user]).await;
+
+    let expected = format!("claim:{}:0", pool.keys.pool());
+    assert!(
+        logs.iter().any(|line| line.contains(&expected)),
+        "no {expected} memo in {logs:#?}"
+    );
+    assert_eq!(pool.wallet_balance(&user.pubkey()).await, 2_400);
+}
+
+#[tokio::test]
+async fn emergency_withdrawals_carry_a_memo() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+
+    // A claim before memos are on opens the token account the withdrawal goes to
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    pool.warp_hours(24).await;
+    pool.claim(&user).await.unwrap();
+    let destination = pool.keys.token_account(&user.pubkey());
+
+    pool.process(
+        &[
+            instructions::set_transfer_memos(&authority.pubkey(), true),
+            instructions::announce_emergency_withdraw(&authority.pubkey(), &destination, 10_000),
+        ],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    pool.warp_hours(24).await;
+    let withdraw = instructions::emergency_withdraw(&pool.keys, &destination, 10_000);
+    let logs = pool.logs(&[withdraw], &[&authority]).await;
+
+    let expected = format!("emergency_withdraw:{}:10000", pool.keys.pool());
+    assert!(
+        logs.iter().any(|line| line.contains(&expected)),
+        "no {expected} memo in {logs:#?}"
+    );
+    assert_eq!(pool.token_balance(&destination).await, 2_400 + 10_000);
+}
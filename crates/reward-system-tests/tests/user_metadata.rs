@@ -0,0 +1,80 @@
+use reward_system_client::{
+    instructions,
+    pda::find_user_address,
+    reward_system::{ErrorCode, UserAccount, MAX_NICKNAME_LEN},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::{pubkey::Pubkey, signature::Signer};
+
+const URI: &str = "https://example.com/profiles/alice.json";
+
+async fn account_size(pool: &mut TestPool, address: &Pubkey) -> (usize, u64) {
+    let account = pool
+        .context
+        .banks_client
+        .get_account(*address)
+        .await
+        .unwrap()
+        .expect("user account exists");
+    (account.data.len(), account.lamports)
+}
+
+#[tokio::test]
+async fn metadata_resizes_the_user_account() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let user = pool.new_user().await;
+    pool.register(&user).await.unwrap();
+    let user_account = find_user_address(&user.pubkey()).0;
+    let pool_key = pool.keys.pool();
+    let (registered_len, registered_lamports) = account_size(&mut pool, &user_account).await;
+    assert_eq!(registered_len, UserAccount::space_with_metadata("", ""));
+
+    pool.process(
+        &[instructions::set_user_metadata(
+            &user.pubkey(),
+            &pool_key,
+            "alice",
+            URI,
+        )],
+        &[&user],
+    )
+    .await
+    .unwrap();
+    let stored = pool.user(&user.pubkey()).await;
+    assert_eq!(stored.nickname, "alice");
+    assert_eq!(stored.metadata_uri, URI);
+    let (len, lamports) = account_size(&mut pool, &user_account).await;
+    assert_eq!(len, UserAccount::space_with_metadata("alice", URI));
+    assert!(lamports > registered_lamports);
+
+    // Clearing shrinks the account back and refunds the extra rent
+    pool.process(
+        &[instructions::set_user_metadata(
+            &user.pubkey(),
+            &pool_key,
+            "",
+            "",
+        )],
+        &[&user],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        account_size(&mut pool, &user_account).await,
+        (registered_len, registered_lamports)
+    );
+
+    let too_long = "a".repeat(MAX_NICKNAME_LEN + 1);
+    let result = pool
+        .process(
+            &[instructions::set_user_metadata(
+                &user.pubkey(),
+                &pool_key,
+                &too_long,
+                "",
+            )],
+            &[&user],
+        )
+        .await;
+    assert_program_error(result, ErrorCode::UserMetadataTooLong);
+}
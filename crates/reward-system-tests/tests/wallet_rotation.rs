@@ -0,0 +1,232 @@
+use reward_system_client::{
+    instructions,
+    pda::{find_user_address, find_vesting_position_address},
+    reward_system::{instruction, ErrorCode, VestingSchedule, WALLET_ROTATION_DELAY_SECS},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool, HOUR};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn rotation_moves_the_account_after_the_delay() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let old_wallet = pool.new_user().await;
+    let new_wallet = pool.new_user().await;
+    pool.register(&old_wallet).await.unwrap();
+    pool.warp_hours(24).await;
+    pool.claim(&old_wallet).await.unwrap();
+    let before = pool.user(&old_wallet.pubkey()).await;
+
+    pool.process(
+        &[instructions::initiate_wallet_rotation(
+            &old_wallet.pubkey(),
+            &new_wallet.pubkey(),
+        )],
+        &[&old_wallet],
+    )
+    .await
+    .unwrap();
+    let complete = instructions::complete_wallet_rotation(
+        &pool.keys.pool(),
+        &old_wallet.pubkey(),
+        &new_wallet.pubkey(),
+    );
+    let result = pool
+        .process(std::slice::from_ref(&complete), &[&new_wallet])
+        .await;
+    assert_program_error(result, ErrorCode::WalletRotationTimelockActive);
+
+    pool.warp_secs(WALLET_ROTATION_DELAY_SECS).await;
+    pool.process(&[complete], &[&new_wallet]).await.unwrap();
+
+    let after = pool.user(&new_wallet.pubkey()).await;
+    assert_eq!(after.authority, new_wallet.pubkey());
+    assert_eq!(after.total_earned, before.total_earned);
+    assert_eq!(after.total_claims, before.total_claims);
+    assert_eq!(after.current_streak, before.current_streak);
+    assert_eq!(after.registration_timestamp, before.registration_timestamp);
+    assert_eq!(after.last_claim_timestamp, before.last_claim_timestamp);
+    let old_account = pool
+        .context
+        .banks_client
+        .get_account(find_user_address(&old_wallet.pubkey()).0)
+        .await
+        .unwrap();
+    assert!(old_account.is_none());
+
+    // The new key claims on the inherited schedule
+    pool.warp_hours(24).await;
+    pool.claim(&new_wallet).await.unwrap();
+    assert_eq!(
+        pool.user(&new_wallet.pubkey()).await.total_claims,
+        before.total_claims + 1
+    );
+}
+
+#[tokio::test]
+async fn cancelled_rotation_cannot_complete() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    let old_wallet = pool.new_user().await;
+    let new_wallet = pool.new_user().await;
+    pool.register(&old_wallet).await.unwrap();
+
+    let initiate =
+        instructions::initiate_wallet_rotation(&old_wallet.pubkey(), &new_wallet.pubkey());
+    pool.process(std::slice::from_ref(&initiate), &[&old_wallet])
+        .await
+        .unwrap();
+    pool.process(
+        &[instructions::cancel_wallet_rotation(&old_wallet.pubkey())],
+        &[&old_wallet],
+    )
+    .await
+    .unwrap();
+
+    pool.warp_secs(WALLET_ROTATION_DELAY_SECS).await;
+    let complete = instructions::complete_wallet_rotation(
+        &pool.keys.pool(),
+        &old_wallet.pubkey(),
+        &new_wallet.pubkey(),
+    );
+    assert!(pool.process(&[complete], &[&new_wallet]).await.is_err());
+    assert_eq!(
+        pool.user(&old_wallet.pubkey()).await.authority,
+        old_wallet.pubkey()
+    );
+
+    // A rotation can be started again once the previous one is gone
+    pool.process(&[initiate], &[&old_wallet]).await.unwrap();
+}
+
+#[tokio::test]
+async fn open_vesting_positions_block_rotation() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_vesting_schedule(
+            &authority.pubkey(),
+            Some(VestingSchedule {
+                cliff_secs: 0,
+                duration_secs: 24 * HOUR,
+            }),
+        )],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let old_wallet = pool.new_user().await;
+    let new_wallet = pool.new_user().await;
+    pool.register(&old_wallet).await.unwrap();
+
+    pool.warp_hours(24).await;
+    let user_account = find_user_address(&old_wallet.pubkey()).0;
+    let mut accounts = instructions::claim_rewards_accounts(&pool.keys, &old_wallet.pubkey(), 0);
+    accounts.vesting_position = Some(find_vesting_position_address(&user_account, 0).0);
+    let claim = instructions::build(
+        accounts,
+        instruction::ClaimRewards {
+            min_expected_amount: 0,
+            amount: None,
+        },
+    );
+    pool.process(&[claim], &[&old_wallet]).await.unwrap();
+    assert_eq!(
+        pool.user(&old_wallet.pubkey()).await.open_vesting_positions,
+        1
+    );
+
+    // The position is seeded by the old user account and would be stranded
+    let initiate =
+        instructions::initiate_wallet_rotation(&old_wallet.pubkey(), &new_wallet.pubkey());
+    let result = pool
+        .process(std::slice::from_ref(&initiate), &[&old_wallet])
+        .await;
+    assert_program_error(result, ErrorCode::OpenClaimsBlockRotation);
+
+    pool.warp_hours(24).await;
+    pool.process(
+        &[instructions::release_vested(
+            &pool.keys,
+            &old_wallet.pubkey(),
+            0,
+        )],
+        &[&old_wallet],
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        pool.user(&old_wallet.pubkey()).await.open_vesting_positions,
+        0
+    );
+    assert_eq!(pool.wallet_balance(&old_wallet.pubkey()).await, 2_400);
+
+    pool.process(&[initiate], &[&old_wallet]).await.unwrap();
+}
+
+#[tokio::test]
+async fn referrals_follow_a_rotated_referrer() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(1_000_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_referral_bps(&authority.pubkey(), 1_000)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+    let old_wallet = pool.new_user().await;
+    let new_wallet = pool.new_user().await;
+    pool.register(&old_wallet).await.unwrap();
+    let referee = pool.new_user().await;
+    let register = instructions::register_user_with_referrer(
+        &referee.pubkey(),
+        &pool.keys.pool(),
+        &old_wallet.pubkey(),
+        Vec::new(),
+        0,
+    );
+    pool.process(&[register], &[&referee]).await.unwrap();
+
+    pool.process(
+        &[instructions::initiate_wallet_rotation(
+            &old_wallet.pubkey(),
+            &new_wallet.pubkey(),
+        )],
+        &[&old_wallet],
+    )
+    .await
+    .unwrap();
+    pool.warp_secs(WALLET_ROTATION_DELAY_SECS).await;
+    let complete = instructions::complete_wallet_rotation(
+        &pool.keys.pool(),
+        &old_wallet.pubkey(),
+        &new_wallet.pubkey(),
+    );
+    pool.process(&[complete], &[&new_wallet]).await.unwrap();
+    // The old key registering again doesn't take over the account's referrals
+    pool.register(&old_wallet).await.unwrap();
+
+    let claim_crediting = |referrer| {
+        let mut accounts = instructions::claim_rewards_accounts(&pool.keys, &referee.pubkey(), 0);
+        accounts.referrer_account = Some(find_user_address(&referrer).0);
+        instructions::build(
+            accounts,
+            instruction::ClaimRewards {
+                min_expected_amount: 0,
+                amount: None,
+            },
+        )
+    };
+    let to_old_wallet = claim_crediting(old_wallet.pubkey());
+    let to_new_wallet = claim_crediting(new_wallet.pubkey());
+    let result = pool.process(&[to_old_wallet], &[&referee]).await;
+    assert_program_error(result, ErrorCode::ReferrerAccountRequired);
+    pool.process(&[to_new_wallet], &[&referee]).await.unwrap();
+    let earned = pool.wallet_balance(&referee.pubkey()).await / 10;
+    assert_eq!(
+        pool.user(&new_wallet.pubkey()).await.referral_earned,
+        earned
+    );
+    assert_eq!(pool.user(&old_wallet.pubkey()).await.referral_earned, 0);
+}
@@ -0,0 +1,61 @@
+use reward_system_client::{
+    find_user_address, instructions,
+    reward_system::{instruction, ErrorCode},
+};
+use reward_system_tests::{assert_program_error, PoolParams, TestPool};
+use solana_sdk::signature::Signer;
+
+#[tokio::test]
+async fn excess_excludes_referral_bonuses_and_carried_over_rewards() {
+    let mut pool = TestPool::start(PoolParams::default()).await;
+    pool.fund_vault(20_000).await;
+    let authority = pool.authority.insecure_clone();
+    pool.process(
+        &[instructions::set_referral_bps(&authority.pubkey(), 1_000)],
+        &[&authority],
+    )
+    .await
+    .unwrap();
+
+    let referrer = pool.new_user().await;
+    pool.register(&referrer).await.unwrap();
+    let referred = pool.new_user().await;
+    let register = instructions::register_user_with_referrer(
+        &referred.pubkey(),
+        &pool.keys.pool(),
+        &referrer.pubkey(),
+        Vec::new(),
+        0,
+    );
+    pool.process(&[register], &[&referred]).await.unwrap();
+
+    // Take 1,000 of the day's 2,400: 1,400 carries over and the referrer is owed 100
+    pool.warp_hours(24).await;
+    let mut accounts = instructions::claim_rewards_accounts(&pool.keys, &referred.pubkey(), 0);
+    accounts.referrer_account = Some(find_user_address(&referrer.pubkey()).0);
+    let claim = instructions::build(
+        accounts,
+        instruction::ClaimRewards {
+            min_expected_amount: 0,
+            amount: Some(1_000),
+        },
+    );
+    pool.process(&[claim], &[&referred]).await.unwrap();
+    let stats = pool.pool().await;
+    assert_eq!(stats.total_carried_over, 1_400);
+    assert_eq!(stats.total_referral_owed, 100);
+
+    // Two participants at up to 4,800 per 48-hour window, plus 1,500 owed, out of 19,000
+    let destination = pool.keys.token_account(&referred.pubkey());
+    let too_much = instructions::withdraw_excess(&pool.keys, &destination, 7_901);
+    let result = pool.process(&[too_much], &[&authority]).await;
+    assert_program_error(result, ErrorCode::ExceedsExcessFunds);
+    let excess = instructions::withdraw_excess(&pool.keys, &destination, 7_900);
+    pool.process(&[excess], &[&authority]).await.unwrap();
+    assert_eq!(pool.vault_balance().await, 11_100);
+
+    // Paying the referrer releases its share of the reserve
+    let payout = instructions::claim_referral_earnings(&pool.keys, &referrer.pubkey());
+    pool.process(&[payout], &[&referrer]).await.unwrap();
+    assert_eq!(pool.pool().await.total_referral_owed, 0);
+}
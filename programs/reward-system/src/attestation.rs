@@ -0,0 +1,118 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{ed25519_program, sysvar::instructions},
+};
+
+use crate::ErrorCode;
+
+/// Size of the Ed25519 program's per-signature offsets record
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+/// Header before the offsets: signature count and a padding byte
+const SIGNATURE_OFFSETS_START: usize = 2;
+/// Instruction index meaning "the Ed25519 instruction itself"
+const THIS_INSTRUCTION: u16 = u16::MAX;
+
+/// Message an attestor signs to vouch for `user` until `expires_at`
+pub fn attestation_message(user: &Pubkey, expires_at: i64) -> [u8; 40] {
+    let mut message = [0u8; 40];
+    message[..32].copy_from_slice(user.as_ref());
+    message[32..].copy_from_slice(&expires_at.to_le_bytes());
+    message
+}
+
+/// Require the instruction right before the current one to be an Ed25519 program check of
+/// `attestor`'s signature over `message`. The Ed25519 program fails the whole transaction
+/// on a bad signature, so only the signer and message need checking here.
+pub fn verify_attestation(
+    instructions_sysvar: &AccountInfo,
+    attestor: &Pubkey,
+    message: &[u8],
+) -> Result<()> {
+    let current = instructions::load_current_index_checked(instructions_sysvar)?;
+    let previous = current
+        .checked_sub(1)
+        .ok_or(error!(ErrorCode::AttestationMissing))?;
+    let ix = instructions::load_instruction_at_checked(previous.into(), instructions_sysvar)?;
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        ErrorCode::AttestationMissing
+    );
+
+    let (signer, signed) = parse_ed25519(&ix.data).ok_or(error!(ErrorCode::InvalidAttestation))?;
+    require!(
+        signer == attestor.as_ref() && signed == message,
+        ErrorCode::InvalidAttestation
+    );
+    Ok(())
+}
+
+/// Public key and message of a single-signature Ed25519 instruction whose data is all
+/// inline; anything else is rejected
+fn parse_ed25519(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    if data.first() != Some(&1) {
+        return None;
+    }
+    let offsets =
+        data.get(SIGNATURE_OFFSETS_START..SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN)?;
+    let field = |i: usize| u16::from_le_bytes([offsets[2 * i], offsets[2 * i + 1]]);
+    let (signature_ix, public_key_offset, public_key_ix) = (field(1), field(2), field(3));
+    let (message_offset, message_len, message_ix) = (field(4), field(5), field(6));
+    if [signature_ix, public_key_ix, message_ix] != [THIS_INSTRUCTION; 3] {
+        return None;
+    }
+
+    let public_key_offset = usize::from(public_key_offset);
+    let message_offset = usize::from(message_offset);
+    let public_key = data.get(public_key_offset..public_key_offset + 32)?;
+    let message = data.get(message_offset..message_offset + usize::from(message_len))?;
+    Some((public_key, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ed25519 instruction data in the layout the Ed25519 program expects
+    fn ed25519_data(public_key: &Pubkey, message: &[u8], data_ix: u16) -> Vec<u8> {
+        let public_key_offset = (SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN) as u16;
+        let signature_offset = public_key_offset + 32;
+        let message_offset = signature_offset + 64;
+
+        let mut data = vec![1, 0];
+        for field in [
+            signature_offset,
+            data_ix,
+            public_key_offset,
+            data_ix,
+            message_offset,
+            message.len() as u16,
+            data_ix,
+        ] {
+            data.extend_from_slice(&field.to_le_bytes());
+        }
+        data.extend_from_slice(public_key.as_ref());
+        data.extend_from_slice(&[0; 64]);
+        data.extend_from_slice(message);
+        data
+    }
+
+    #[test]
+    fn parses_inline_signer_and_message() {
+        let attestor = Pubkey::new_unique();
+        let message = attestation_message(&Pubkey::new_unique(), 1_700_000_000);
+        let data = ed25519_data(&attestor, &message, THIS_INSTRUCTION);
+
+        let (signer, signed) = parse_ed25519(&data).unwrap();
+        assert_eq!(signer, attestor.as_ref());
+        assert_eq!(signed, message);
+    }
+
+    #[test]
+    fn rejects_data_from_other_instructions() {
+        let data = ed25519_data(&Pubkey::new_unique(), b"message", 0);
+
+        assert!(parse_ed25519(&data).is_none());
+        assert!(parse_ed25519(&data[..10]).is_none());
+    }
+}
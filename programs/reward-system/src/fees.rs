@@ -0,0 +1,68 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program_pack::Pack;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType,
+        StateWithExtensions,
+    },
+    state::{Account as AccountState, Mint as MintState},
+};
+
+use crate::ErrorCode;
+
+/// Fee the mint's transfer-fee extension withholds when `amount` is transferred.
+/// Legacy SPL mints and Token-2022 mints without the extension charge nothing.
+pub fn transfer_fee(mint: &AccountInfo, amount: u64, epoch: u64) -> Result<u64> {
+    // Only Token-2022 mints can carry extensions; skip unpacking the rest
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&data)?;
+
+    match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_epoch_fee(epoch, amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow)),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Gross amount that must be sent so the recipient receives exactly `net_amount`
+pub fn gross_up(mint: &AccountInfo, net_amount: u64, epoch: u64) -> Result<u64> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(net_amount);
+    }
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&data)?;
+
+    match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => {
+            let fee = config
+                .calculate_inverse_epoch_fee(epoch, net_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            net_amount
+                .checked_add(fee)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))
+        }
+        Err(_) => Ok(net_amount),
+    }
+}
+
+/// Size of an associated token account for `mint`, including the extensions Token-2022
+/// adds to it, so its rent can be paid before the associated token program creates it
+pub fn associated_account_len(mint: &AccountInfo) -> Result<usize> {
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(AccountState::LEN);
+    }
+    let data = mint.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<MintState>::unpack(&data)?;
+
+    let mut extensions =
+        ExtensionType::get_required_init_account_extensions(&mint_state.get_extension_types()?);
+    extensions.push(ExtensionType::ImmutableOwner);
+    Ok(ExtensionType::try_calculate_account_len::<AccountState>(
+        &extensions,
+    )?)
+}
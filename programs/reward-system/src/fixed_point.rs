@@ -0,0 +1,165 @@
+//! Q64.64 fixed-point arithmetic for sub-unit reward rates.
+//!
+//! A [`Q64`] stores `value * 2^64` in a `u128`: the high 64 bits are whole units and the
+//! low 64 bits the fraction, so a rate like half a base unit per hour is exact. Results
+//! only become integers at the end of a calculation, rounded as the caller asks.
+//!
+//! The arithmetic lives in the `reward-math` crate so off-chain services compute the same
+//! results; the types here add the Anchor serialization the program's accounts need.
+
+use anchor_lang::prelude::*;
+
+use crate::ErrorCode;
+
+pub use reward_math::fixed_point::FRACTION_BITS;
+
+/// How a result that falls between two integers is resolved. Applied to an amount the
+/// pool pays out, `Floor` favors the pool and `Ceil` the recipient; applied to a
+/// deduction (a penalty, crank tip or withholding) the favor is reversed. `HalfEven` is
+/// unbiased over many claims.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub enum RoundingMode {
+    /// Toward zero
+    #[default]
+    Floor,
+    /// Away from zero
+    Ceil,
+    /// To the nearest integer, ties to the even one (banker's rounding)
+    HalfEven,
+}
+
+impl From<RoundingMode> for reward_math::RoundingMode {
+    fn from(rounding: RoundingMode) -> Self {
+        match rounding {
+            RoundingMode::Floor => Self::Floor,
+            RoundingMode::Ceil => Self::Ceil,
+            RoundingMode::HalfEven => Self::HalfEven,
+        }
+    }
+}
+
+/// `(whole + fraction / 2^64) / denominator`, rounded to an integer
+pub fn div_to_int(
+    whole: u128,
+    fraction: u64,
+    denominator: u64,
+    rounding: RoundingMode,
+) -> Result<u64> {
+    reward_math::div_to_int(whole, fraction, denominator, rounding.into())
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))
+}
+
+/// `a * b / denominator` without intermediate overflow, rounded to an integer
+pub fn mul_div(a: u64, b: u64, denominator: u64, rounding: RoundingMode) -> Result<u64> {
+    reward_math::mul_div(a, b, denominator, rounding.into())
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))
+}
+
+/// Unsigned Q64.64 fixed-point number
+#[derive(
+    AnchorSerialize,
+    AnchorDeserialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+)]
+pub struct Q64(pub u128);
+
+// `InitSpace` can't be derived for tuple structs
+impl anchor_lang::Space for Q64 {
+    const INIT_SPACE: usize = 16;
+}
+
+impl From<Q64> for reward_math::Q64 {
+    fn from(value: Q64) -> Self {
+        Self(value.0)
+    }
+}
+
+impl Q64 {
+    pub const ZERO: Self = Self(0);
+
+    pub fn from_int(value: u64) -> Self {
+        Self((value as u128) << FRACTION_BITS)
+    }
+
+    pub fn from_parts(whole: u64, fraction: u64) -> Self {
+        Self((whole as u128) << FRACTION_BITS | fraction as u128)
+    }
+
+    /// `numerator / denominator`, rounded to the nearest representable value
+    pub fn from_ratio(numerator: u64, denominator: u64, rounding: RoundingMode) -> Result<Self> {
+        reward_math::Q64::from_ratio(numerator, denominator, rounding.into())
+            .map(|ratio| Self(ratio.0))
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))
+    }
+
+    pub fn whole(self) -> u64 {
+        (self.0 >> FRACTION_BITS) as u64
+    }
+
+    pub fn fraction(self) -> u64 {
+        self.0 as u64
+    }
+
+    /// `self * value / denominator`, computed exactly and rounded to an integer
+    pub fn mul_div_int(self, value: u64, denominator: u64, rounding: RoundingMode) -> Result<u64> {
+        reward_math::Q64::from(self)
+            .mul_div_int(value, denominator, rounding.into())
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_a_unit_per_hour_accrues_exactly() {
+        let half = Q64::from_ratio(1, 2, RoundingMode::Floor).unwrap();
+        assert_eq!(half, Q64::from_parts(0, 1 << 63));
+
+        assert_eq!(half.mul_div_int(3, 1, RoundingMode::Floor).unwrap(), 1);
+        assert_eq!(half.mul_div_int(3, 1, RoundingMode::Ceil).unwrap(), 2);
+        assert_eq!(half.mul_div_int(4, 1, RoundingMode::Floor).unwrap(), 2);
+        // 1.5 hours at 0.5/hour, computed in seconds
+        assert_eq!(
+            half.mul_div_int(5_400, 3_600, RoundingMode::HalfEven)
+                .unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn rounding_modes_resolve_remainders() {
+        assert_eq!(mul_div(7, 1, 2, RoundingMode::Floor).unwrap(), 3);
+        assert_eq!(mul_div(7, 1, 2, RoundingMode::Ceil).unwrap(), 4);
+        assert_eq!(mul_div(7, 1, 2, RoundingMode::HalfEven).unwrap(), 4);
+        assert_eq!(mul_div(5, 1, 2, RoundingMode::HalfEven).unwrap(), 2);
+        assert_eq!(mul_div(8, 1, 3, RoundingMode::HalfEven).unwrap(), 3);
+        assert_eq!(mul_div(6, 2, 3, RoundingMode::Ceil).unwrap(), 4);
+    }
+
+    #[test]
+    fn large_products_do_not_overflow_midway() {
+        assert_eq!(
+            mul_div(u64::MAX, 10_000, 10_000, RoundingMode::Floor).unwrap(),
+            u64::MAX
+        );
+        assert_eq!(
+            Q64::from_int(u64::MAX)
+                .mul_div_int(u64::MAX, u64::MAX, RoundingMode::Ceil)
+                .unwrap(),
+            u64::MAX
+        );
+        assert!(mul_div(u64::MAX, 2, 1, RoundingMode::Floor).is_err());
+        assert!(mul_div(1, 1, 0, RoundingMode::Floor).is_err());
+    }
+}
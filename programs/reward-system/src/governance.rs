@@ -0,0 +1,178 @@
+//! SPL Governance (Realms) control of a pool.
+//!
+//! A DAO controls a pool by making its governance's native treasury the pool authority.
+//! The treasury is a PDA with no private key: it signs only when the governance program
+//! executes a passed proposal, which invokes this program with the treasury seeds. Admin
+//! instructions therefore keep their `Signer` authority and work unchanged under that CPI.
+//!
+//! The helpers below build proposal transactions in the layout the governance program
+//! stores and later executes (`InstructionData`, passed to `insert_transaction`).
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{instruction::Instruction, pubkey},
+    InstructionData,
+};
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+
+/// SPL Governance program deployed by Realms
+pub const GOVERNANCE_PROGRAM_ID: Pubkey = pubkey!("GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCir");
+pub const NATIVE_TREASURY_SEED: &[u8] = b"native-treasury";
+
+/// Native treasury of `governance`, the address a DAO-controlled pool uses as authority
+pub fn native_treasury(governance: &Pubkey, governance_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[NATIVE_TREASURY_SEED, governance.as_ref()],
+        governance_program,
+    )
+    .0
+}
+
+/// Mirrors spl-governance's `AccountMetaData`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProposalAccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/// Mirrors spl-governance's `InstructionData`: one instruction of a proposal transaction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProposalInstruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<ProposalAccountMeta>,
+    pub data: Vec<u8>,
+}
+
+impl From<Instruction> for ProposalInstruction {
+    fn from(ix: Instruction) -> Self {
+        Self {
+            program_id: ix.program_id,
+            accounts: ix
+                .accounts
+                .into_iter()
+                .map(|meta| ProposalAccountMeta {
+                    pubkey: meta.pubkey,
+                    is_signer: meta.is_signer,
+                    is_writable: meta.is_writable,
+                })
+                .collect(),
+            data: ix.data,
+        }
+    }
+}
+
+fn pool_address(authority: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"reward_pool", authority.as_ref()], &crate::ID).0
+}
+
+/// Proposal instruction calling `update_pool_config` on the pool owned by `treasury`
+pub fn update_pool_config(
+    treasury: Pubkey,
+    update: crate::PoolConfigUpdate,
+) -> ProposalInstruction {
+    let accounts = crate::accounts::UpdatePoolConfig {
+        reward_pool: pool_address(&treasury),
+        authority: treasury,
+    };
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: accounts.to_account_metas(None),
+        data: crate::instruction::UpdatePoolConfig { update }.data(),
+    }
+    .into()
+}
+
+/// Proposal instruction calling `announce_emergency_withdraw` on the pool owned by
+/// `treasury`; a later proposal executes it with [`emergency_withdraw`]
+pub fn announce_emergency_withdraw(
+    treasury: Pubkey,
+    destination: Pubkey,
+    amount: u64,
+) -> ProposalInstruction {
+    let accounts = crate::accounts::AnnounceEmergencyWithdraw {
+        reward_pool: pool_address(&treasury),
+        destination,
+        authority: treasury,
+    };
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: accounts.to_account_metas(None),
+        data: crate::instruction::AnnounceEmergencyWithdraw { amount }.data(),
+    }
+    .into()
+}
+
+/// Proposal instruction calling `emergency_withdraw` on the pool owned by `treasury`
+pub fn emergency_withdraw(
+    treasury: Pubkey,
+    mint: Pubkey,
+    token_program: Pubkey,
+    destination: Pubkey,
+    amount: u64,
+) -> ProposalInstruction {
+    let reward_pool = pool_address(&treasury);
+    let accounts = crate::accounts::EmergencyWithdraw {
+        reward_pool,
+        vault: get_associated_token_address_with_program_id(&reward_pool, &mint, &token_program),
+        destination,
+        authority: treasury,
+        mint,
+        token_program,
+        // Always attached, so the proposal executes whether or not the pool writes memos
+        memo_program: Some(anchor_spl::memo::ID),
+    };
+
+    Instruction {
+        program_id: crate::ID,
+        accounts: accounts.to_account_metas(None),
+        data: crate::instruction::EmergencyWithdraw { amount }.data(),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn treasury_signs_the_proposal_instruction() {
+        let governance = Pubkey::new_unique();
+        let treasury = native_treasury(&governance, &GOVERNANCE_PROGRAM_ID);
+        let ix = emergency_withdraw(
+            treasury,
+            Pubkey::new_unique(),
+            anchor_spl::token::ID,
+            Pubkey::new_unique(),
+            1_000,
+        );
+
+        assert_eq!(ix.program_id, crate::ID);
+        assert_eq!(ix.accounts[0].pubkey, pool_address(&treasury));
+        let signers: Vec<_> = ix.accounts.iter().filter(|meta| meta.is_signer).collect();
+        assert_eq!(signers.len(), 1);
+        assert_eq!(signers[0].pubkey, treasury);
+        assert_eq!(ix.data[8..], 1_000u64.to_le_bytes());
+    }
+
+    #[test]
+    fn serializes_in_the_governance_layout() {
+        let ix = ProposalInstruction {
+            program_id: crate::ID,
+            accounts: vec![ProposalAccountMeta {
+                pubkey: crate::ID,
+                is_signer: true,
+                is_writable: false,
+            }],
+            data: vec![7],
+        };
+
+        let bytes = ix.try_to_vec().unwrap();
+        // program_id, account count, one 34-byte meta, data length, data
+        assert_eq!(bytes.len(), 32 + 4 + 34 + 4 + 1);
+        assert_eq!(bytes[68..70], [1, 0]);
+        assert_eq!(ProposalInstruction::try_from_slice(&bytes).unwrap(), ix);
+    }
+}
@@ -1,9 +1,48 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::{AccountMeta, Instruction},
+        keccak,
+        program::{invoke, set_return_data},
+        program_option::COption,
+        sysvar::{instructions, slot_hashes},
+    },
+    system_program, Discriminator,
+};
 use anchor_spl::{
-    associated_token::AssociatedToken,
-    token::{self, Mint, Token, TokenAccount, Transfer},
+    associated_token::{self, get_associated_token_address_with_program_id, AssociatedToken},
+    memo::{build_memo, BuildMemo, Memo},
+    metadata::{
+        create_master_edition_v3, create_metadata_accounts_v3, mpl_token_metadata::types::DataV2,
+        CreateMasterEditionV3, CreateMetadataAccountsV3, Metadata, MetadataAccount,
+    },
+    token::{spl_token, Token, Transfer},
+    token_interface::{
+        self, Burn, CloseAccount, Mint, MintTo, TokenAccount, TokenInterface, TransferChecked,
+    },
 };
 
+/// `msg!` for informational logs on hot paths such as `claim_rewards`, compiled in only
+/// with the `verbose-logs` feature. Formatting a log line costs more compute than most of
+/// the claim math, so production builds leave them out; errors still log with `msg!`.
+macro_rules! verbose_msg {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "verbose-logs")]
+        msg!($($arg)*);
+    };
+}
+
+pub mod attestation;
+pub mod fees;
+pub mod fixed_point;
+pub mod governance;
+pub mod math;
+pub mod merkle;
+pub mod oracle;
+
+use fixed_point::{RoundingMode, Q64};
+use math::{compute_reward, RewardBreakdown};
+
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
 #[program]
@@ -12,126 +51,855 @@ pub mod reward_system {
 
     /// Initialize the reward pool with configuration parameters
     pub fn initialize_pool(
-        ctx: Context<InitializePool>,
+        mut ctx: Context<InitializePool>,
         reward_rate_per_hour: u64,
-        min_claim_interval_hours: u64,
+        min_claim_interval_secs: u64,
         max_daily_reward: u64,
+        max_accrual_hours: u64,
+        distribution_mode: DistributionMode,
     ) -> Result<()> {
-        let pool = &mut ctx.accounts.reward_pool;
-        let clock = Clock::get()?;
+        init_pool(
+            &mut ctx,
+            reward_rate_per_hour,
+            min_claim_interval_secs,
+            max_daily_reward,
+            max_accrual_hours,
+            distribution_mode,
+        )
+    }
 
-        pool.authority = ctx.accounts.authority.key();
-        pool.mint = ctx.accounts.mint.key();
-        pool.vault = ctx.accounts.vault.key();
-        pool.reward_rate_per_hour = reward_rate_per_hour;
-        pool.min_claim_interval_hours = min_claim_interval_hours;
-        pool.max_daily_reward = max_daily_reward;
-        pool.total_distributed = 0;
-        pool.participant_count = 0;
-        pool.is_active = true;
-        pool.created_at = clock.unix_timestamp;
-        pool.bump = ctx.bumps.reward_pool;
+    /// Initialize an onboarding faucet pool: one small fixed claim per day per wallet,
+    /// a strict lifetime cap, and a captcha verifier that must co-sign every claim
+    pub fn initialize_faucet_pool(
+        mut ctx: Context<InitializePool>,
+        per_claim_amount: u64,
+        lifetime_cap_per_wallet: u64,
+        captcha_verifier: Pubkey,
+    ) -> Result<()> {
+        require!(
+            per_claim_amount > 0 && lifetime_cap_per_wallet >= per_claim_amount,
+            ErrorCode::InvalidFaucetConfig
+        );
+
+        init_pool(
+            &mut ctx,
+            per_claim_amount,
+            FAUCET_CLAIM_INTERVAL_HOURS * math::SECONDS_PER_HOUR as u64,
+            per_claim_amount,
+            FAUCET_CLAIM_INTERVAL_HOURS,
+            DistributionMode::Vault,
+        )?;
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.faucet = Some(FaucetConfig {
+            lifetime_cap_per_wallet,
+            captcha_verifier,
+        });
 
-        msg!("Reward pool initialized with rate: {} per hour", reward_rate_per_hour);
+        msg!(
+            "Faucet pool initialized: {} per claim, {} lifetime cap",
+            per_claim_amount,
+            lifetime_cap_per_wallet
+        );
         Ok(())
     }
 
-    /// Register a user in the reward system
-    pub fn register_user(ctx: Context<RegisterUser>) -> Result<()> {
-        let user_account = &mut ctx.accounts.user_account;
+    /// Initialize a Vault pool from a vetted preset (amounts scale with the mint's
+    /// decimals). Safe-mode pools need a guardian distinct from the authority, and emergency
+    /// withdrawals go through a proposal the guardian can veto during `timelock_secs`.
+    pub fn initialize_pool_safe(
+        mut ctx: Context<InitializePool>,
+        preset: PoolPreset,
+        guardian: Pubkey,
+        timelock_secs: i64,
+    ) -> Result<()> {
+        require!(
+            guardian != Pubkey::default()
+                && guardian != ctx.accounts.authority.key()
+                && timelock_secs >= MIN_SAFE_TIMELOCK_SECS,
+            ErrorCode::InvalidSafeModeConfig
+        );
+
+        let unit = 10u64
+            .checked_pow(u32::from(ctx.accounts.mint.decimals))
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        let (tokens_per_hour, min_claim_interval_hours, max_daily_tokens, max_accrual_hours) =
+            preset.params();
+        let reward_rate_per_hour = tokens_per_hour
+            .checked_mul(unit)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        let max_daily_reward = max_daily_tokens
+            .checked_mul(unit)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        init_pool(
+            &mut ctx,
+            reward_rate_per_hour,
+            min_claim_interval_hours * math::SECONDS_PER_HOUR as u64,
+            max_daily_reward,
+            max_accrual_hours,
+            DistributionMode::Vault,
+        )?;
+
         let pool = &mut ctx.accounts.reward_pool;
-        let clock = Clock::get()?;
+        pool.safe_mode = Some(SafeModeConfig {
+            guardian,
+            timelock_secs,
+        });
 
-        user_account.authority = ctx.accounts.authority.key();
-        user_account.total_earned = 0;
-        user_account.total_claims = 0;
-        user_account.last_claim_timestamp = 0;
-        user_account.registration_timestamp = clock.unix_timestamp;
-        user_account.is_active = true;
-        user_account.bump = ctx.bumps.user_account;
+        msg!(
+            "Safe-mode pool initialized with {:?} preset, guardian {}, {}s timelock",
+            preset,
+            guardian,
+            timelock_secs
+        );
+        Ok(())
+    }
 
-        pool.participant_count = pool.participant_count.checked_add(1).unwrap();
+    /// Register a user in the reward system. `proof` is checked against the pool's
+    /// allowlist root when one is set and ignored otherwise; `terms_version` must match the
+    /// pool's current terms.
+    pub fn register_user(
+        ctx: Context<RegisterUser>,
+        proof: Vec<[u8; 32]>,
+        terms_version: u32,
+    ) -> Result<()> {
+        let registered = register(
+            &mut ctx.accounts.reward_pool,
+            Registration {
+                authority: ctx.accounts.authority.key(),
+                bump: ctx.bumps.user_account,
+                denylist_entry: &ctx.accounts.denylist_entry,
+                sibling_account: &ctx.accounts.membership,
+                proof: &proof,
+                terms_version,
+                attestation: None,
+                referrer: None,
+                fee: Some(RegistrationFeeAccounts {
+                    payer: &ctx.accounts.authority,
+                    system_program: &ctx.accounts.system_program,
+                    sol_vault: ctx.accounts.sol_vault.as_ref(),
+                    fee_token_account: ctx.accounts.fee_token_account.as_ref(),
+                    vault: ctx.accounts.vault.as_ref(),
+                    mint: ctx.accounts.mint.as_ref(),
+                    token_program: ctx.accounts.token_program.as_ref(),
+                }),
+            },
+        )?;
+        ctx.accounts.user_account.set_inner(registered);
 
         msg!("User registered: {}", ctx.accounts.authority.key());
         Ok(())
     }
 
+    /// Register a user vouched for by the pool's attestor. The transaction must include,
+    /// right before this instruction, an Ed25519 program instruction verifying the
+    /// attestor's signature over `attestation::attestation_message(user, expires_at)`.
+    pub fn register_user_with_attestation(
+        ctx: Context<RegisterUserWithAttestation>,
+        expires_at: i64,
+        proof: Vec<[u8; 32]>,
+        terms_version: u32,
+    ) -> Result<()> {
+        let registered = register(
+            &mut ctx.accounts.reward_pool,
+            Registration {
+                authority: ctx.accounts.authority.key(),
+                bump: ctx.bumps.user_account,
+                denylist_entry: &ctx.accounts.denylist_entry,
+                sibling_account: &ctx.accounts.membership,
+                proof: &proof,
+                terms_version,
+                attestation: Some((&ctx.accounts.instructions, expires_at)),
+                referrer: None,
+                fee: Some(RegistrationFeeAccounts {
+                    payer: &ctx.accounts.authority,
+                    system_program: &ctx.accounts.system_program,
+                    sol_vault: ctx.accounts.sol_vault.as_ref(),
+                    fee_token_account: ctx.accounts.fee_token_account.as_ref(),
+                    vault: ctx.accounts.vault.as_ref(),
+                    mint: ctx.accounts.mint.as_ref(),
+                    token_program: ctx.accounts.token_program.as_ref(),
+                }),
+            },
+        )?;
+        ctx.accounts.user_account.set_inner(registered);
+
+        msg!("User registered with attestation: {}", ctx.accounts.authority.key());
+        Ok(())
+    }
+
+    /// Set (or clear) the attestor whose signature registration requires (admin only).
+    /// While set, users can only register through `register_user_with_attestation`.
+    pub fn set_attestor(ctx: Context<UpdatePoolConfig>, attestor: Option<Pubkey>) -> Result<()> {
+        ctx.accounts.reward_pool.attestor = attestor;
+
+        msg!("Registration attestor set: {:?}", attestor);
+        Ok(())
+    }
+
+    /// Set (or clear) the fee users pay to register (admin only): lamports into the pool's
+    /// `sol_vault`, or tokens of the pool mint into its vault. Sponsored registrations are
+    /// exempt, since their users may hold nothing to pay with.
+    pub fn set_registration_fee(
+        ctx: Context<UpdatePoolConfig>,
+        fee: Option<RegistrationFee>,
+    ) -> Result<()> {
+        ctx.accounts.reward_pool.registration_fee = fee;
+
+        msg!("Registration fee set: {:?}", fee);
+        Ok(())
+    }
+
+    /// Register a user referred by `referrer`, who must already be registered in the pool.
+    /// The referrer earns the pool's referral bps on every token claim the user makes.
+    pub fn register_user_with_referrer(
+        ctx: Context<RegisterUserWithReferrer>,
+        referrer: Pubkey,
+        proof: Vec<[u8; 32]>,
+        terms_version: u32,
+    ) -> Result<()> {
+        let registered = register(
+            &mut ctx.accounts.reward_pool,
+            Registration {
+                authority: ctx.accounts.authority.key(),
+                bump: ctx.bumps.user_account,
+                denylist_entry: &ctx.accounts.denylist_entry,
+                sibling_account: &ctx.accounts.membership,
+                proof: &proof,
+                terms_version,
+                attestation: None,
+                referrer: Some(&ctx.accounts.referrer_account),
+                fee: Some(RegistrationFeeAccounts {
+                    payer: &ctx.accounts.authority,
+                    system_program: &ctx.accounts.system_program,
+                    sol_vault: ctx.accounts.sol_vault.as_ref(),
+                    fee_token_account: ctx.accounts.fee_token_account.as_ref(),
+                    vault: ctx.accounts.vault.as_ref(),
+                    mint: ctx.accounts.mint.as_ref(),
+                    token_program: ctx.accounts.token_program.as_ref(),
+                }),
+            },
+        )?;
+        ctx.accounts.user_account.set_inner(registered);
+
+        let referrer_account = &mut ctx.accounts.referrer_account;
+        referrer_account.referral_count = referrer_account
+            .referral_count
+            .checked_add(1)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        msg!(
+            "User registered: {} (referred by {})",
+            ctx.accounts.authority.key(),
+            referrer
+        );
+        Ok(())
+    }
+
+    /// Join a pool other than the one the wallet registered in. The membership is a user
+    /// account seeded by the pool as well as the wallet, so a wallet can hold one per
+    /// pool; the per-user instructions take it in place of the registration account, and
+    /// `claim_all` claims from all of them at once. Pools whose claims `claim_all` always
+    /// refuses can't be joined.
+    pub fn join_pool(
+        ctx: Context<JoinPool>,
+        proof: Vec<[u8; 32]>,
+        terms_version: u32,
+    ) -> Result<()> {
+        ensure_claimable_together(&ctx.accounts.reward_pool)?;
+        let registered = register(
+            &mut ctx.accounts.reward_pool,
+            Registration {
+                authority: ctx.accounts.authority.key(),
+                bump: ctx.bumps.user_account,
+                denylist_entry: &ctx.accounts.denylist_entry,
+                sibling_account: &ctx.accounts.registration,
+                proof: &proof,
+                terms_version,
+                attestation: None,
+                referrer: None,
+                fee: Some(RegistrationFeeAccounts {
+                    payer: &ctx.accounts.authority,
+                    system_program: &ctx.accounts.system_program,
+                    sol_vault: ctx.accounts.sol_vault.as_ref(),
+                    fee_token_account: ctx.accounts.fee_token_account.as_ref(),
+                    vault: ctx.accounts.vault.as_ref(),
+                    mint: ctx.accounts.mint.as_ref(),
+                    token_program: ctx.accounts.token_program.as_ref(),
+                }),
+            },
+        )?;
+        ctx.accounts.user_account.set_inner(UserAccount {
+            is_membership: true,
+            ..registered
+        });
+
+        msg!("Pool joined: {}", ctx.accounts.authority.key());
+        Ok(())
+    }
+
+    /// Resync a user's `next_eligible_at` after the pool's claim interval changed.
+    /// Permissionless so schedulers can re-arm themselves.
+    pub fn refresh_claim_schedule(ctx: Context<RefreshClaimSchedule>) -> Result<()> {
+        let user_account = &mut ctx.accounts.user_account;
+        user_account.refresh_next_eligible_at(&ctx.accounts.reward_pool)?;
+
+        msg!("Next eligible claim at {}", user_account.next_eligible_at);
+        Ok(())
+    }
+
     /// Calculate and return available rewards for a user
     pub fn calculate_rewards(ctx: Context<CalculateRewards>) -> Result<u64> {
         let user_account = &ctx.accounts.user_account;
         let pool = &ctx.accounts.reward_pool;
         let clock = Clock::get()?;
 
-        require!(pool.is_active, ErrorCode::PoolNotActive);
+        require!(!pool.claims_paused, ErrorCode::ClaimsPaused);
+        require!(user_account.banned_at == 0, ErrorCode::UserBanned);
         require!(user_account.is_active, ErrorCode::UserNotActive);
 
-        let current_timestamp = clock.unix_timestamp;
-        let hours_since_last_claim = if user_account.last_claim_timestamp == 0 {
-            // First time claiming - calculate from registration
-            ((current_timestamp - user_account.registration_timestamp) / 3600) as u64
+        let breakdown = compute_reward(pool, user_account, clock.unix_timestamp)?;
+
+        // Check minimum claim interval
+        ensure_claim_interval(pool, user_account, &breakdown, clock.unix_timestamp)?;
+
+        msg!(
+            "Calculated reward: {} for {} hours (streak {}, +{} bonus)",
+            breakdown.reward_amount,
+            breakdown.accrual_hours,
+            breakdown.streak,
+            breakdown.streak_bonus
+        );
+        Ok(breakdown.reward_amount)
+    }
+
+    /// Read-only snapshot of everything a claim UI needs, returned as packed return data
+    pub fn preview_claim(ctx: Context<PreviewClaim>) -> Result<ClaimPreview> {
+        let user_account = &ctx.accounts.user_account;
+        let pool = &ctx.accounts.reward_pool;
+        let clock = Clock::get()?;
+        let now = clock.unix_timestamp;
+
+        let breakdown = compute_reward(pool, user_account, now)?;
+        let vault_balance = ctx.accounts.vault.amount;
+        let accrual_hours_remaining = if pool.max_accrual_hours > 0 {
+            pool.max_accrual_hours.saturating_sub(breakdown.hours_since_last_claim)
         } else {
-            ((current_timestamp - user_account.last_claim_timestamp) / 3600) as u64
+            u64::MAX
         };
 
-        // Check minimum claim interval
+        Ok(ClaimPreview {
+            pool_active: !pool.claims_paused,
+            user_active: user_account.is_active,
+            reward_rate_per_hour: math::rate_per_hour_at(pool, now)?,
+            min_claim_interval_secs: pool.min_claim_interval_secs,
+            max_daily_reward: pool.max_daily_reward,
+            max_accrual_hours: pool.max_accrual_hours,
+            breakdown,
+            daily_cap_remaining: math::daily_cap_remaining(pool, &breakdown),
+            accrual_hours_remaining,
+            seconds_until_claimable: math::seconds_until_claimable(pool, user_account, now)?,
+            vault_balance,
+            vault_can_cover: vault_balance >= breakdown.reward_amount,
+            timestamp: now,
+        })
+    }
+
+    /// Compact claim quote for any user, returned as return data. Needs no signer, so
+    /// clients can simulate it against any RPC.
+    pub fn get_claim_quote(ctx: Context<GetClaimQuote>) -> Result<ClaimQuote> {
+        let user_account = &ctx.accounts.user_account;
+        let pool = &ctx.accounts.reward_pool;
+        let breakdown = compute_reward(pool, user_account, Clock::get()?.unix_timestamp)?;
+
+        Ok(ClaimQuote {
+            pending_amount: breakdown.reward_amount,
+            hours_accrued: breakdown.accrual_hours,
+            eligible_at: math::eligible_at(pool, user_account)?,
+            daily_cap_remaining: math::daily_cap_remaining(pool, &breakdown),
+            applied_multiplier: breakdown.multiplier_bps,
+            secs_accrued: breakdown.accrual_secs,
+        })
+    }
+
+    /// Current runway throttle factor in bps (10_000 = unthrottled), returned as return data
+    pub fn get_throttle_factor(ctx: Context<GetThrottleFactor>) -> Result<u16> {
+        math::runway_throttle_bps(
+            &ctx.accounts.reward_pool,
+            ctx.accounts.vault.amount,
+            Clock::get()?.unix_timestamp,
+        )
+    }
+
+    /// Rank all pools for a mint by comparable metrics, returned as return data.
+    ///
+    /// Remaining accounts are passed in pairs of `[reward_pool, vault]`, where `vault` is
+    /// the SOL vault for native pools and any account for minted pools; pools for other
+    /// mints are ignored.
+    pub fn compare_pools<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ComparePools>,
+        mint: Pubkey,
+    ) -> Result<Vec<PoolMetrics>> {
+        let remaining = ctx.remaining_accounts;
         require!(
-            hours_since_last_claim >= pool.min_claim_interval_hours,
-            ErrorCode::ClaimTooSoon
+            remaining.len() % 2 == 0 && remaining.len() / 2 <= MAX_COMPARED_POOLS,
+            ErrorCode::InvalidPoolAccounts
         );
 
-        // Calculate reward amount
-        let reward_amount = hours_since_last_claim
-            .checked_mul(pool.reward_rate_per_hour)
-            .unwrap()
-            .min(pool.max_daily_reward);
+        let now = Clock::get()?.unix_timestamp;
+        let mut metrics = Vec::with_capacity(remaining.len() / 2);
+        for pair in remaining.chunks(2) {
+            let pool: Account<RewardPool> = Account::try_from(&pair[0])?;
+            if pool.mint != mint {
+                continue;
+            }
+
+            // Minted pools are capped by the distribution budget alone; funded pools are
+            // also capped by what their vault can actually pay out
+            let budget = pool_budget_remaining(&pool).unwrap_or(0);
+            let remaining_budget = match pool.distribution_mode {
+                DistributionMode::Vault => {
+                    let vault: InterfaceAccount<TokenAccount> =
+                        InterfaceAccount::try_from(&pair[1])?;
+                    require_keys_eq!(vault.key(), pool.vault, ErrorCode::InvalidPoolAccounts);
+                    budget.min(vault.amount)
+                }
+                DistributionMode::MintOnClaim => budget,
+                DistributionMode::NativeSol => {
+                    let pool_key = pool.key();
+                    let sol_vault = Pubkey::create_program_address(
+                        &[b"sol_vault", pool_key.as_ref(), &[pool.sol_vault_bump]],
+                        ctx.program_id,
+                    )
+                    .map_err(|_| error!(ErrorCode::InvalidPoolAccounts))?;
+                    require_keys_eq!(pair[1].key(), sol_vault, ErrorCode::InvalidPoolAccounts);
+                    // Only lamports above the rent-exempt floor are spendable
+                    budget.min(
+                        pair[1]
+                            .lamports()
+                            .saturating_sub(Rent::get()?.minimum_balance(0)),
+                    )
+                }
+            };
 
-        msg!("Calculated reward: {} for {} hours", reward_amount, hours_since_last_claim);
-        Ok(reward_amount)
+            metrics.push(PoolMetrics {
+                pool: pool.key(),
+                is_active: !pool.claims_paused,
+                effective_daily_reward: math::rate_per_hour_at(&pool, now)?
+                    .saturating_mul(24)
+                    .min(pool.max_daily_reward),
+                remaining_budget,
+                accrual_end: pool.accrual_end,
+                participant_count: pool.participant_count,
+                total_distributed: pool.total_distributed,
+            });
+        }
+
+        // Active pools first, then best effective rate, then deepest budget
+        metrics.sort_by(|a, b| {
+            b.is_active
+                .cmp(&a.is_active)
+                .then(b.effective_daily_reward.cmp(&a.effective_daily_reward))
+                .then(b.remaining_budget.cmp(&a.remaining_budget))
+        });
+
+        Ok(metrics)
     }
 
-    /// Claim accumulated rewards
+    /// Claim accumulated rewards. With `amount` set, claims only that much and leaves the
+    /// rest accrued in `accrued_unclaimed` for a later claim; a pending lottery draw needs
+    /// the whole accrual claimed. USD-priced pools accrue (and take `amount`) in USD
+    /// micro-units, converted to tokens at the oracle price. On pools allowing partial
+    /// payouts, whatever a short vault can't pay stays accrued the same way.
     pub fn claim_rewards(
         ctx: Context<ClaimRewards>,
-        expected_amount: u64,
+        min_expected_amount: u64,
+        amount: Option<u64>,
     ) -> Result<()> {
+        ensure_not_denylisted(
+            &ctx.accounts.denylist_entry,
+            ctx.accounts.reward_pool.key(),
+            ctx.accounts.user_account.authority,
+        )?;
+
+        // Signs the payout CPI; taken before `pool` borrows the account mutably
+        let pool_info = ctx.accounts.reward_pool.to_account_info();
         let user_account = &mut ctx.accounts.user_account;
         let pool = &mut ctx.accounts.reward_pool;
         let clock = Clock::get()?;
 
-        require!(pool.is_active, ErrorCode::PoolNotActive);
+        require!(!pool.claims_paused, ErrorCode::ClaimsPaused);
+        require!(user_account.banned_at == 0, ErrorCode::UserBanned);
         require!(user_account.is_active, ErrorCode::UserNotActive);
+        require!(
+            user_account.terms_version >= pool.terms_version,
+            ErrorCode::TermsNotAccepted
+        );
+        require!(user_account.paused_at == 0, ErrorCode::AccrualPaused);
+        reverify_nft_boost(user_account, ctx.accounts.nft_token_account.as_ref());
 
         let current_timestamp = clock.unix_timestamp;
-        let hours_since_last_claim = if user_account.last_claim_timestamp == 0 {
-            ((current_timestamp - user_account.registration_timestamp) / 3600) as u64
-        } else {
-            ((current_timestamp - user_account.last_claim_timestamp) / 3600) as u64
-        };
+        apply_due_config(pool, current_timestamp);
+        let mut breakdown = compute_reward(pool, user_account, current_timestamp)?;
+
+        ensure_claim_interval(pool, user_account, &breakdown, current_timestamp)?;
+
+        // Partial claims take only part of the accrual and carry the rest forward
+        if let Some(amount) = amount {
+            require!(
+                amount > 0 && amount <= breakdown.reward_amount,
+                ErrorCode::InvalidClaimAmount
+            );
+            // A pending draw's roll can be read once its slot has passed, so it is revealed
+            // against the whole accrual rather than a sliver claimed to waste a losing roll
+            require!(
+                user_account.lottery_commit_slot == 0 || amount == breakdown.reward_amount,
+                ErrorCode::LotteryDrawPending
+            );
+            breakdown.usd_priced_reward = fixed_point::mul_div(
+                breakdown.usd_priced_reward,
+                amount,
+                breakdown.reward_amount,
+                RoundingMode::Floor,
+            )?;
+            breakdown.carryover = breakdown.reward_amount - amount;
+            breakdown.reward_amount = amount;
+        }
+
+        // Accrual from before a switch of denomination keeps the one it accrued in; the
+        // USD part is priced by the pool's feed, or the one it was priced by until then
+        let mut reward_amount = breakdown.reward_amount - breakdown.usd_priced_reward;
+        if breakdown.usd_priced_reward > 0 {
+            let pricing = pool
+                .usd_pricing
+                .or(pool.previous_model.usd_pricing)
+                .ok_or(error!(ErrorCode::PriceFeedRequired))?;
+            let price_feed = ctx
+                .accounts
+                .price_feed
+                .as_ref()
+                .ok_or(error!(ErrorCode::PriceFeedRequired))?;
+            let price = oracle::load_price(price_feed, &pricing, current_timestamp)?;
+            reward_amount = reward_amount
+                .checked_add(oracle::usd_to_tokens(
+                    breakdown.usd_priced_reward,
+                    &price,
+                    ctx.accounts.mint.decimals,
+                )?)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        }
+
+        // A committed lottery draw is revealed (and consumed) by this claim
+        reward_amount = reveal_lottery(
+            pool,
+            user_account,
+            ctx.accounts.slot_hashes.as_ref(),
+            clock.slot,
+            reward_amount,
+        )?;
+
+        require!(reward_amount > 0, ErrorCode::NoRewardsAvailable);
+
+        // Faucet pools need a captcha co-signature and stop at the per-wallet lifetime cap
+        if let Some(faucet) = pool.faucet {
+            let verifier = ctx
+                .accounts
+                .captcha_verifier
+                .as_ref()
+                .ok_or(error!(ErrorCode::CaptchaAttestationRequired))?;
+            require_keys_eq!(
+                verifier.key(),
+                faucet.captcha_verifier,
+                ErrorCode::CaptchaAttestationRequired
+            );
+
+            let lifetime_remaining = faucet
+                .lifetime_cap_per_wallet
+                .saturating_sub(user_account.total_earned);
+            require!(lifetime_remaining > 0, ErrorCode::FaucetLifetimeCapReached);
+            reward_amount = reward_amount.min(lifetime_remaining);
+        }
+        reward_amount = reward_amount
+            .min(lifetime_reward_remaining(pool, user_account)?)
+            .min(pool_budget_remaining(pool)?);
+
+        // Runway throttling scales claims down pro-rata while the vault can't cover a full
+        // round of claims
+        let throttle_bps =
+            math::runway_throttle_bps(pool, ctx.accounts.vault.amount, current_timestamp)?;
+        reward_amount = math::apply_throttle(reward_amount, throttle_bps)?;
+
+        ensure_co_signed(user_account, reward_amount, ctx.accounts.co_signer.as_ref())?;
+
+        // Region policy gates: blocked regions fail, others may reduce or withhold
+        if pool.require_region_attestation {
+            require!(
+                user_account.region_attested_at != 0,
+                ErrorCode::RegionAttestationRequired
+            );
+        }
+        let (reward_amount, withheld_amount) =
+            match load_region_policy(&ctx.accounts.region_policy)? {
+                Some(policy) => {
+                    math::apply_region_policy(reward_amount, &policy.action, pool.rounding)?
+                }
+                None => (reward_amount, 0),
+            };
+
+        // Deflationary pools burn their share of the claim from the vault instead of
+        // paying it out. The burn is made once the payout is known, as a short vault only
+        // burns the share of the claim it pays.
+        let mut burn_amount = claim_burn_amount(pool, reward_amount)?;
+        let reward_amount = reward_amount
+            .checked_sub(burn_amount)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+
+        // Taken before `record_claim` moves the user's last claim forward
+        let first_claim_today =
+            u64::from(math::is_first_claim_of_day(user_account, current_timestamp));
+
+        // Receipt pools keep a durable record of each claim, paid for by the claimer
+        write_claim_receipt(
+            pool,
+            user_account,
+            ctx.accounts.claim_receipt.as_mut(),
+            ctx.accounts.authority.key(),
+            ctx.bumps.claim_receipt,
+            reward_amount,
+            current_timestamp,
+        )?;
+
+        // Vesting pools lock the claim in a position released later via `release_vested`
+        if let Some(schedule) = pool.vesting {
+            require!(
+                reward_amount >= min_expected_amount,
+                ErrorCode::BelowMinimumExpected
+            );
+
+            let position = ctx
+                .accounts
+                .vesting_position
+                .as_mut()
+                .ok_or(error!(ErrorCode::VestingPositionRequired))?;
+            burn_from_vault(
+                pool,
+                &ctx.accounts.vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.token_program,
+                burn_amount,
+                1,
+            )?;
+            position.set_inner(VestingPosition {
+                owner: user_account.authority,
+                pool: pool.key(),
+                claim_index: user_account.total_claims,
+                total_amount: reward_amount,
+                released_amount: 0,
+                start_timestamp: current_timestamp,
+                cliff_secs: schedule.cliff_secs,
+                duration_secs: schedule.duration_secs,
+                bump: ctx.bumps.vesting_position,
+            });
+            user_account.open_vesting_positions = user_account
+                .open_vesting_positions
+                .checked_add(1)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
 
+            record_epoch_claim(
+                pool,
+                ctx.accounts.epoch.as_mut(),
+                reward_amount,
+                1,
+                current_timestamp,
+            )?;
+            record_daily_stats(
+                pool,
+                ctx.accounts.daily_stats.as_mut(),
+                reward_amount,
+                1,
+                first_claim_today,
+                current_timestamp,
+            )?;
+
+            user_account.total_withheld = user_account
+                .total_withheld
+                .checked_add(withheld_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            let released = user_account.record_claim(pool, &breakdown, current_timestamp)?;
+            pool.track_carryover(released, breakdown.carryover);
+
+            pool.total_vesting_locked = pool
+                .total_vesting_locked
+                .checked_add(reward_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            pool.total_withheld = pool
+                .total_withheld
+                .checked_add(withheld_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+            deactivate_if_budget_spent(pool);
+
+            verbose_msg!("Rewards claimed into vesting: {} tokens", reward_amount);
+            return Ok(());
+        }
         require!(
-            hours_since_last_claim >= pool.min_claim_interval_hours,
-            ErrorCode::ClaimTooSoon
+            ctx.accounts.vesting_position.is_none(),
+            ErrorCode::VestingNotEnabled
         );
 
-        let reward_amount = hours_since_last_claim
-            .checked_mul(pool.reward_rate_per_hour)
-            .unwrap()
-            .min(pool.max_daily_reward);
+        // Claims above the per-transaction cap are locked into a claim session and paid
+        // out in capped parts via `claim_session_part`
+        if pool.max_claim_per_tx > 0 && reward_amount > pool.max_claim_per_tx {
+            require!(
+                reward_amount >= min_expected_amount,
+                ErrorCode::BelowMinimumExpected
+            );
+
+            let session = ctx
+                .accounts
+                .claim_session
+                .as_mut()
+                .ok_or(error!(ErrorCode::ClaimSessionRequired))?;
+            burn_from_vault(
+                pool,
+                &ctx.accounts.vault,
+                &ctx.accounts.mint,
+                &ctx.accounts.token_program,
+                burn_amount,
+                1,
+            )?;
+            session.set_inner(ClaimSession {
+                owner: user_account.authority,
+                pool: pool.key(),
+                total_amount: reward_amount,
+                paid_amount: 0,
+                created_at: current_timestamp,
+                bump: ctx.bumps.claim_session,
+            });
+            user_account.open_claim_sessions = user_account
+                .open_claim_sessions
+                .checked_add(1)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+            record_epoch_claim(
+                pool,
+                ctx.accounts.epoch.as_mut(),
+                reward_amount,
+                1,
+                current_timestamp,
+            )?;
+            record_daily_stats(
+                pool,
+                ctx.accounts.daily_stats.as_mut(),
+                reward_amount,
+                1,
+                first_claim_today,
+                current_timestamp,
+            )?;
+
+            user_account.total_withheld = user_account
+                .total_withheld
+                .checked_add(withheld_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            let released = user_account.record_claim(pool, &breakdown, current_timestamp)?;
+            pool.track_carryover(released, breakdown.carryover);
 
-        // Verify expected amount matches calculated amount (within small tolerance)
+            pool.total_session_locked = pool
+                .total_session_locked
+                .checked_add(reward_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            pool.total_withheld = pool
+                .total_withheld
+                .checked_add(withheld_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+            deactivate_if_budget_spent(pool);
+
+            verbose_msg!(
+                "Rewards claimed into a session: {} tokens in parts of {}",
+                reward_amount,
+                pool.max_claim_per_tx
+            );
+            return Ok(());
+        }
         require!(
-            expected_amount == reward_amount,
-            ErrorCode::AmountMismatch
+            ctx.accounts.claim_session.is_none(),
+            ErrorCode::ClaimSessionNotNeeded
         );
 
-        require!(reward_amount > 0, ErrorCode::NoRewardsAvailable);
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let (payout_amount, fee_amount) = match pool.distribution_mode {
+            DistributionMode::Vault => {
+                // With gross-up enabled the pool absorbs the mint's transfer fee
+                let gross_amount = if pool.gross_up_transfer_fees {
+                    fees::gross_up(&mint_info, reward_amount, clock.epoch)?
+                } else {
+                    reward_amount
+                };
 
-        // Transfer tokens from vault to user
-        let cpi_accounts = Transfer {
-            from: ctx.accounts.vault.to_account_info(),
-            to: ctx.accounts.user_token_account.to_account_info(),
-            authority: ctx.accounts.reward_pool.to_account_info(),
+                // Make sure the vault can cover the payout and burn before attempting the
+                // transfer
+                let required = gross_amount
+                    .checked_add(burn_amount)
+                    .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+                let vault_balance = ctx.accounts.vault.amount;
+                if vault_balance < required && pool.yield_deployed > 0 {
+                    // Deployed funds must be withdrawn to the vault before claims can use them
+                    return err!(ErrorCode::YieldWithdrawalRequired);
+                }
+                let covered = math::vault_payout(pool, vault_balance, required)?;
+
+                // A short vault pays and burns what it holds in proportion; the unpaid share
+                // of the claim stays accrued for a later claim, which burns its own share
+                if covered < required {
+                    burn_amount =
+                        fixed_point::mul_div(burn_amount, covered, required, RoundingMode::Floor)?;
+                    carry_unpaid_share(&mut breakdown, covered, required)?;
+                }
+                let payout_amount = covered - burn_amount;
+
+                let fee_amount = fees::transfer_fee(&mint_info, payout_amount, clock.epoch)?;
+                (payout_amount, fee_amount)
+            }
+            // Minted tokens never pass through a transfer, so no transfer fee applies
+            DistributionMode::MintOnClaim => (reward_amount, 0),
+            DistributionMode::NativeSol => return err!(ErrorCode::WrongDistributionMode),
         };
+        let net_amount = payout_amount
+            .checked_sub(fee_amount)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+
+        // Slippage bound: the reward may have grown since simulation, but never below the floor
+        require!(
+            net_amount >= min_expected_amount,
+            ErrorCode::BelowMinimumExpected
+        );
+
+        burn_from_vault(
+            pool,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            burn_amount,
+            1,
+        )?;
+
+        record_epoch_claim(
+            pool,
+            ctx.accounts.epoch.as_mut(),
+            payout_amount,
+            1,
+            current_timestamp,
+        )?;
+        record_daily_stats(
+            pool,
+            ctx.accounts.daily_stats.as_mut(),
+            payout_amount,
+            1,
+            first_claim_today,
+            current_timestamp,
+        )?;
 
         let seeds = &[
             b"reward_pool",
@@ -139,60 +907,532 @@ pub fn claim_rewards(
             &[pool.bump],
         ];
         let signer = &[&seeds[..]];
-
         let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
 
-        token::transfer(cpi_ctx, reward_amount)?;
+        // Rewards go to the owner's ATA unless the owner routes them elsewhere; a claim
+        // delegate cannot redirect them
+        let destination_info = match ctx.accounts.destination.as_ref() {
+            Some(destination) => {
+                require_keys_eq!(
+                    ctx.accounts.authority.key(),
+                    user_account.authority,
+                    ErrorCode::DestinationRequiresOwner
+                );
+                destination.to_account_info()
+            }
+            None => ctx.accounts.user_token_account.to_account_info(),
+        };
+
+        let claim_index = user_account.total_claims;
+        write_transfer_memo(pool, ctx.accounts.memo_program.as_ref(), || {
+            format!("claim:{}:{}", pool.key(), claim_index)
+        })?;
+
+        match pool.distribution_mode {
+            DistributionMode::Vault => {
+                // Transfer tokens from vault to user
+                let cpi_accounts = TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: mint_info,
+                    to: destination_info,
+                    authority: pool_info,
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+                token_interface::transfer_checked(
+                    cpi_ctx,
+                    payout_amount,
+                    ctx.accounts.mint.decimals,
+                )?;
+            }
+            DistributionMode::MintOnClaim => {
+                // Mint tokens directly to the user with the pool PDA as mint authority
+                let cpi_accounts = MintTo {
+                    mint: mint_info,
+                    to: destination_info,
+                    authority: pool_info,
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+                token_interface::mint_to(cpi_ctx, payout_amount)?;
+            }
+            DistributionMode::NativeSol => return err!(ErrorCode::WrongDistributionMode),
+        }
+
+        // Update user account (net of transfer fees)
+        user_account.total_earned = user_account
+            .total_earned
+            .checked_add(net_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        user_account.total_fees_paid = user_account
+            .total_fees_paid
+            .checked_add(fee_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        user_account.total_withheld = user_account
+            .total_withheld
+            .checked_add(withheld_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        let released = user_account.record_claim(pool, &breakdown, current_timestamp)?;
+        pool.track_carryover(released, breakdown.carryover);
+
+        // Update pool statistics (gross, including transfer fees)
+        pool.total_distributed = pool
+            .total_distributed
+            .checked_add(payout_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_transfer_fees = pool
+            .total_transfer_fees
+            .checked_add(fee_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_withheld = pool
+            .total_withheld
+            .checked_add(withheld_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        record_mint_stats(
+            pool,
+            ctx.accounts.mint_stats.as_mut(),
+            payout_amount,
+            1,
+            current_timestamp,
+        )?;
+        deactivate_if_budget_spent(pool);
+        if let Some(leaderboard) = &ctx.accounts.leaderboard {
+            leaderboard
+                .load_mut()?
+                .record(user_account.authority, user_account.total_earned);
+        }
+
+        let referral_bonus = credit_referral(
+            pool,
+            user_account,
+            ctx.accounts.referrer_account.as_deref_mut(),
+            net_amount,
+        )?;
+        pool.total_referral_owed = pool
+            .total_referral_owed
+            .checked_add(referral_bonus)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        // Sponsor matching into the sponsor's destination, limited by its remaining budget
+        if let Some(sponsor_key) = pool.matching_sponsor {
+            let sponsor = ctx
+                .accounts
+                .matching_sponsor
+                .as_mut()
+                .ok_or(error!(ErrorCode::MatchingAccountsRequired))?;
+            let matching_vault = ctx
+                .accounts
+                .matching_vault
+                .as_ref()
+                .ok_or(error!(ErrorCode::MatchingAccountsRequired))?;
+            let destination = ctx
+                .accounts
+                .matching_destination
+                .as_ref()
+                .ok_or(error!(ErrorCode::MatchingAccountsRequired))?;
+            require_keys_eq!(sponsor.key(), sponsor_key, ErrorCode::MatchingAccountsRequired);
+            require_keys_eq!(
+                matching_vault.key(),
+                sponsor.vault,
+                ErrorCode::MatchingAccountsRequired
+            );
+            require_keys_eq!(
+                destination.key(),
+                sponsor.destination,
+                ErrorCode::MatchingAccountsRequired
+            );
+
+            let remaining_budget = sponsor.budget.saturating_sub(sponsor.matched_total);
+            let matched_amount = if sponsor.is_active {
+                math::apply_bps(net_amount, sponsor.ratio_bps, pool.rounding)?
+                    .min(remaining_budget)
+                    .min(matching_vault.amount)
+            } else {
+                0
+            };
+
+            if matched_amount > 0 {
+                let pool_key = pool.key();
+                let sponsor_seeds = &[b"matching_sponsor", pool_key.as_ref(), &[sponsor.bump]];
+                let sponsor_signer = &[&sponsor_seeds[..]];
+                let cpi_accounts = TransferChecked {
+                    from: matching_vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: destination.to_account_info(),
+                    authority: sponsor.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    cpi_accounts,
+                    sponsor_signer,
+                );
+                token_interface::transfer_checked(
+                    cpi_ctx,
+                    matched_amount,
+                    ctx.accounts.mint.decimals,
+                )?;
 
-        // Update user account
-        user_account.total_earned = user_account.total_earned.checked_add(reward_amount).unwrap();
-        user_account.total_claims = user_account.total_claims.checked_add(1).unwrap();
-        user_account.last_claim_timestamp = current_timestamp;
+                sponsor.matched_total = sponsor
+                    .matched_total
+                    .checked_add(matched_amount)
+                    .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+                sponsor.match_count = sponsor
+                    .match_count
+                    .checked_add(1)
+                    .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
 
-        // Update pool statistics
-        pool.total_distributed = pool.total_distributed.checked_add(reward_amount).unwrap();
+                emit!(MatchingContribution {
+                    pool: pool_key,
+                    user: user_account.authority,
+                    sponsor: sponsor.sponsor,
+                    destination: sponsor.destination,
+                    claim_amount: net_amount,
+                    matched_amount,
+                    remaining_budget: remaining_budget - matched_amount,
+                });
+            }
+        }
 
-        msg!("Rewards claimed: {} tokens", reward_amount);
+        verbose_msg!(
+            "Rewards claimed: {} tokens ({} gross, {} transfer fee)",
+            net_amount,
+            payout_amount,
+            fee_amount
+        );
         Ok(())
     }
 
-    /// Update pool configuration (admin only)
+    /// Update pool configuration (admin only); fields of `update` left `None` keep their
+    /// current value
     pub fn update_pool_config(
         ctx: Context<UpdatePoolConfig>,
-        reward_rate_per_hour: Option<u64>,
-        min_claim_interval_hours: Option<u64>,
-        max_daily_reward: Option<u64>,
-        is_active: Option<bool>,
+        update: PoolConfigUpdate,
     ) -> Result<()> {
+        let PoolConfigUpdate {
+            reward_rate_per_hour,
+            min_claim_interval_secs,
+            max_daily_reward,
+            max_accrual_hours,
+            claims_paused,
+            registrations_paused,
+            accrual_paused,
+            allow_partial_payout,
+            gross_up_transfer_fees,
+            max_lifetime_reward_per_user,
+            max_total_distribution,
+            deactivate_when_budget_spent,
+            max_claims_per_day,
+        } = update;
         let pool = &mut ctx.accounts.reward_pool;
+        let now = Clock::get()?.unix_timestamp;
+        apply_due_config(pool, now);
 
-        if let Some(rate) = reward_rate_per_hour {
-            pool.reward_rate_per_hour = rate;
+        // With a config delay, accrual-affecting changes are scheduled instead of
+        // rewriting what users have already accrued
+        let accrual_change = reward_rate_per_hour.is_some()
+            || max_daily_reward.is_some()
+            || max_accrual_hours.is_some();
+        if pool.config_delay_secs > 0 && accrual_change {
+            let base = pool.next_config();
+            let rate = reward_rate_per_hour.map_or(base.reward_rate(), Q64::from_int);
+            let pending = PendingConfig {
+                reward_rate_per_hour: rate.whole(),
+                reward_rate_fraction: rate.fraction(),
+                max_daily_reward: max_daily_reward.unwrap_or(base.max_daily_reward),
+                max_accrual_hours: max_accrual_hours.unwrap_or(base.max_accrual_hours),
+                effective_at: now
+                    .checked_add(pool.config_delay_secs)
+                    .ok_or(error!(ErrorCode::ArithmeticOverflow))?,
+            };
+            pool.pending_config = Some(pending);
+            msg!("Rate and cap changes take effect at {}", pending.effective_at);
+        } else {
+            if let Some(rate) = reward_rate_per_hour {
+                pool.reward_rate_per_hour = rate;
+                pool.reward_rate_fraction = 0;
+            }
+            if let Some(max_reward) = max_daily_reward {
+                pool.max_daily_reward = max_reward;
+            }
+            if let Some(accrual_hours) = max_accrual_hours {
+                pool.max_accrual_hours = accrual_hours;
+            }
         }
-        if let Some(interval) = min_claim_interval_hours {
-            pool.min_claim_interval_hours = interval;
+        if let Some(interval) = min_claim_interval_secs {
+            pool.min_claim_interval_secs = interval;
         }
-        if let Some(max_reward) = max_daily_reward {
-            pool.max_daily_reward = max_reward;
+        if let Some(paused) = claims_paused {
+            pool.claims_paused = paused;
         }
-        if let Some(active) = is_active {
-            pool.is_active = active;
+        if let Some(paused) = registrations_paused {
+            pool.registrations_paused = paused;
+        }
+        if let Some(paused) = accrual_paused {
+            pool.set_accrual_paused(paused, now)?;
+        }
+        if let Some(partial) = allow_partial_payout {
+            pool.allow_partial_payout = partial;
+        }
+        if let Some(gross_up) = gross_up_transfer_fees {
+            pool.gross_up_transfer_fees = gross_up;
+        }
+        if let Some(lifetime_cap) = max_lifetime_reward_per_user {
+            pool.max_lifetime_reward_per_user = lifetime_cap;
+        }
+        if let Some(total_cap) = max_total_distribution {
+            pool.max_total_distribution = total_cap;
+        }
+        if let Some(deactivate) = deactivate_when_budget_spent {
+            pool.deactivate_when_budget_spent = deactivate;
+        }
+        if let Some(max_claims) = max_claims_per_day {
+            pool.max_claims_per_day = max_claims;
         }
 
         msg!("Pool configuration updated");
         Ok(())
     }
 
-    /// Emergency withdraw (admin only)
+    /// Set a fractional per-hour reward rate (admin only), e.g. half a base unit per hour.
+    /// Scheduled like `update_pool_config` rate changes on pools with a config delay.
+    pub fn set_reward_rate(ctx: Context<UpdatePoolConfig>, rate: Q64) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        let now = Clock::get()?.unix_timestamp;
+        apply_due_config(pool, now);
+
+        if pool.config_delay_secs > 0 {
+            let pending = PendingConfig {
+                reward_rate_per_hour: rate.whole(),
+                reward_rate_fraction: rate.fraction(),
+                effective_at: now
+                    .checked_add(pool.config_delay_secs)
+                    .ok_or(error!(ErrorCode::ArithmeticOverflow))?,
+                ..pool.next_config()
+            };
+            pool.pending_config = Some(pending);
+            msg!("Rate change takes effect at {}", pending.effective_at);
+        } else {
+            pool.reward_rate_per_hour = rate.whole();
+            pool.reward_rate_fraction = rate.fraction();
+            msg!("Reward rate updated: {:?}", rate);
+        }
+        Ok(())
+    }
+
+    /// Choose how reward amounts, multipliers and fee splits are rounded (admin only).
+    /// `Floor` keeps every rounding error in the pool's favor on what it pays out,
+    /// `Ceil` gives it to the user, and `HalfEven` splits it evenly over many claims.
+    pub fn set_rounding_mode(ctx: Context<UpdatePoolConfig>, rounding: RoundingMode) -> Result<()> {
+        ctx.accounts.reward_pool.rounding = rounding;
+
+        msg!("Rounding mode set to {:?}", rounding);
+        Ok(())
+    }
+
+    /// Delay before rate and cap changes from `update_pool_config`, and accrual model
+    /// changes, take effect (admin only); 0 applies them immediately
+    pub fn set_config_delay(ctx: Context<UpdatePoolConfig>, delay_secs: i64) -> Result<()> {
+        require!(delay_secs >= 0, ErrorCode::InvalidConfigDelay);
+        ctx.accounts.reward_pool.config_delay_secs = delay_secs;
+
+        msg!("Config delay set to {}s", delay_secs);
+        Ok(())
+    }
+
+    /// Apply a scheduled config change once it is due (permissionless). Claims apply it
+    /// too; accrual already uses the new rate from `effective_at` either way.
+    pub fn apply_pending_config(ctx: Context<ApplyPendingConfig>) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            matches!(pool.pending_config, Some(pending) if now >= pending.effective_at),
+            ErrorCode::PendingConfigNotDue
+        );
+        apply_due_config(pool, now);
+        Ok(())
+    }
+
+    /// Upgrade a pool account to the current layout (admin only): grow it to the current
+    /// size, paid by the authority, and fill in defaults for fields added since its version
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        let info = ctx.accounts.reward_pool.to_account_info();
+        grow_account(
+            &info,
+            &ctx.accounts.authority,
+            &ctx.accounts.system_program,
+            8 + RewardPool::INIT_SPACE,
+        )?;
+
+        let mut pool = RewardPool::decode_older(&info.try_borrow_data()?)?;
+        let from_version = pool.version;
+        pool.migrate()?;
+        let mut data = info.try_borrow_mut_data()?;
+        let mut tail = &mut data[..];
+        pool.try_serialize(&mut tail)?;
+        tail.fill(0);
+
+        msg!("Pool migrated from version {} to {}", from_version, pool.version);
+        Ok(())
+    }
+
+    /// Upgrade a user account to the current layout (permissionless; `payer` covers any
+    /// extra rent)
+    pub fn migrate_user(ctx: Context<MigrateUser>) -> Result<()> {
+        let info = ctx.accounts.user_account.to_account_info();
+        // Older layouts end early, so the fields appended since are read from zeros
+        let mut data = info.try_borrow_data()?.to_vec();
+        data.resize(data.len() + 8 + UserAccount::INIT_SPACE, 0);
+        let mut user_account = UserAccount::try_deserialize(&mut &data[..])?;
+        let from_version = user_account.version;
+        user_account.migrate()?;
+
+        grow_account(
+            &info,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+            UserAccount::space_with_metadata(&user_account.nickname, &user_account.metadata_uri),
+        )?;
+        user_account.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+
+        msg!(
+            "User account migrated from version {} to {}",
+            from_version,
+            user_account.version
+        );
+        Ok(())
+    }
+
+    /// Announce an emergency withdrawal of `amount` to `destination` (admin only); it can
+    /// be executed with `emergency_withdraw` once the pool's withdrawal delay has passed
+    pub fn announce_emergency_withdraw(
+        ctx: Context<AnnounceEmergencyWithdraw>,
+        amount: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        require!(pool.safe_mode.is_none(), ErrorCode::SafeModeTimelocked);
+        require!(
+            pool.pending_withdrawal.is_none(),
+            ErrorCode::WithdrawalAlreadyPending
+        );
+
+        let executable_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(pool.emergency_withdraw_delay_secs)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        let destination = ctx.accounts.destination.key();
+        pool.pending_withdrawal = Some(PendingWithdrawal {
+            amount,
+            destination,
+            executable_at,
+        });
+
+        emit!(EmergencyWithdrawAnnounced {
+            pool: pool.key(),
+            amount,
+            destination,
+            executable_at,
+        });
+        msg!(
+            "Emergency withdrawal of {} announced, executable at {}",
+            amount,
+            executable_at
+        );
+        Ok(())
+    }
+
+    /// Cancel an announced emergency withdrawal (admin only)
+    pub fn cancel_emergency_withdraw(ctx: Context<UpdatePoolConfig>) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        require!(pool.safe_mode.is_none(), ErrorCode::SafeModeTimelocked);
+        let cancelled = pool
+            .pending_withdrawal
+            .take()
+            .ok_or(error!(ErrorCode::NoPendingWithdrawal))?;
+
+        emit!(EmergencyWithdrawCancelled {
+            pool: pool.key(),
+            amount: cancelled.amount,
+            destination: cancelled.destination,
+        });
+        msg!("Emergency withdrawal cancelled");
+        Ok(())
+    }
+
+    /// Set how long announced emergency withdrawals wait before they can execute (admin
+    /// only); a change does not affect an already announced withdrawal
+    pub fn set_emergency_withdraw_delay(
+        ctx: Context<UpdatePoolConfig>,
+        delay_secs: i64,
+    ) -> Result<()> {
+        require!(
+            delay_secs >= MIN_EMERGENCY_WITHDRAW_DELAY_SECS,
+            ErrorCode::InvalidWithdrawDelay
+        );
+        ctx.accounts.reward_pool.emergency_withdraw_delay_secs = delay_secs;
+
+        msg!("Emergency withdrawal delay set to {}s", delay_secs);
+        Ok(())
+    }
+
+    /// Withdraw vault funds above the pool's reserved liabilities (admin only). Unlike
+    /// `emergency_withdraw` this needs no announcement, since it cannot touch funds owed
+    /// to participants.
+    pub fn withdraw_excess(ctx: Context<WithdrawExcess>, amount: u64) -> Result<()> {
+        let pool = &ctx.accounts.reward_pool;
+        require!(
+            pool.distribution_mode == DistributionMode::Vault,
+            ErrorCode::WrongDistributionMode
+        );
+        let reserve = math::reserved_liabilities(pool, Clock::get()?.unix_timestamp)?;
+        let excess = ctx.accounts.vault.amount.saturating_sub(reserve);
+        require!(amount <= excess, ErrorCode::ExceedsExcessFunds);
+
+        let seeds = &[
+            b"reward_pool",
+            pool.authority.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+        emit!(ExcessWithdrawn {
+            pool: pool.key(),
+            amount,
+            reserve,
+            destination: ctx.accounts.destination.key(),
+        });
+        msg!("Withdrew {} excess tokens above a {} reserve", amount, reserve);
+        Ok(())
+    }
+
+    /// Execute an announced emergency withdrawal after its delay (admin only); `amount` and
+    /// `destination` must match the announcement
     pub fn emergency_withdraw(
         ctx: Context<EmergencyWithdraw>,
         amount: u64,
     ) -> Result<()> {
+        require!(
+            ctx.accounts.reward_pool.safe_mode.is_none(),
+            ErrorCode::SafeModeTimelocked
+        );
+        take_announced_withdrawal(
+            &mut ctx.accounts.reward_pool,
+            amount,
+            ctx.accounts.destination.key(),
+        )?;
         let pool = &ctx.accounts.reward_pool;
 
-        let cpi_accounts = Transfer {
+        let cpi_accounts = TransferChecked {
             from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
             to: ctx.accounts.destination.to_account_info(),
             authority: ctx.accounts.reward_pool.to_account_info(),
         };
@@ -207,188 +1447,11569 @@ pub fn emergency_withdraw(
         let cpi_program = ctx.accounts.token_program.to_account_info();
         let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
 
-        token::transfer(cpi_ctx, amount)?;
+        write_transfer_memo(pool, ctx.accounts.memo_program.as_ref(), || {
+            format!("emergency_withdraw:{}:{}", pool.key(), amount)
+        })?;
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
 
         msg!("Emergency withdrawal: {} tokens", amount);
         Ok(())
     }
-}
-
-#[derive(Accounts)]
-pub struct InitializePool<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
 
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + RewardPool::INIT_SPACE,
-        seeds = [b"reward_pool", authority.key().as_ref()],
-        bump
-    )]
-    pub reward_pool: Account<'info, RewardPool>,
+    /// Propose an emergency withdrawal from a safe-mode pool (admin only); it can be
+    /// executed once the timelock passes unless the guardian vetoes it first
+    pub fn propose_emergency_withdraw(
+        ctx: Context<TimelockedWithdraw>,
+        amount: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        let safe_mode = pool.safe_mode.ok_or(error!(ErrorCode::SafeModeNotEnabled))?;
+        require!(
+            pool.pending_withdrawal.is_none(),
+            ErrorCode::WithdrawalAlreadyPending
+        );
 
-    pub mint: Account<'info, Mint>,
+        let executable_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(safe_mode.timelock_secs)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.pending_withdrawal = Some(PendingWithdrawal {
+            amount,
+            destination: ctx.accounts.destination.key(),
+            executable_at,
+        });
+
+        msg!(
+            "Emergency withdrawal of {} proposed, executable at {}",
+            amount,
+            executable_at
+        );
+        Ok(())
+    }
+
+    /// Execute a proposed emergency withdrawal after its timelock (admin only)
+    pub fn execute_emergency_withdraw(ctx: Context<TimelockedWithdraw>) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        let pending = pool
+            .pending_withdrawal
+            .ok_or(error!(ErrorCode::NoPendingWithdrawal))?;
+        require_keys_eq!(
+            ctx.accounts.destination.key(),
+            pending.destination,
+            ErrorCode::NoPendingWithdrawal
+        );
+        require!(
+            Clock::get()?.unix_timestamp >= pending.executable_at,
+            ErrorCode::WithdrawalTimelockActive
+        );
+        pool.pending_withdrawal = None;
+
+        let seeds = &[
+            b"reward_pool",
+            pool.authority.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+        token_interface::transfer_checked(cpi_ctx, pending.amount, ctx.accounts.mint.decimals)?;
+
+        msg!("Emergency withdrawal: {} tokens", pending.amount);
+        Ok(())
+    }
+
+    /// Cancel a proposed emergency withdrawal (guardian only)
+    pub fn veto_emergency_withdraw(ctx: Context<VetoEmergencyWithdraw>) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        let safe_mode = pool.safe_mode.ok_or(error!(ErrorCode::SafeModeNotEnabled))?;
+        require_keys_eq!(
+            ctx.accounts.guardian.key(),
+            safe_mode.guardian,
+            ErrorCode::NotPoolGuardian
+        );
+        require!(
+            pool.pending_withdrawal.take().is_some(),
+            ErrorCode::NoPendingWithdrawal
+        );
+
+        msg!("Emergency withdrawal vetoed by guardian");
+        Ok(())
+    }
+
+    /// Denominate the pool's rate and caps in USD micro-units, priced by a Pyth feed at claim
+    /// time (admin only); `None` returns the pool to token-denominated rewards. Scheduled
+    /// like a rate change: claims spanning it pay what accrued before it in the old
+    /// denomination, splitting the reward pro rata by what was emitted on each side.
+    pub fn set_usd_pricing(
+        ctx: Context<UpdatePoolConfig>,
+        pricing: Option<UsdPricing>,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        if let Some(pricing) = pricing {
+            require!(
+                pool.distribution_mode != DistributionMode::NativeSol,
+                ErrorCode::WrongDistributionMode
+            );
+            require!(
+                pricing.feed_id != [0; 32]
+                    && pricing.max_price_age_secs > 0
+                    && u64::from(pricing.max_confidence_bps) <= math::BPS_DENOMINATOR,
+                ErrorCode::InvalidUsdPricing
+            );
+        }
+        let model = AccrualModel {
+            usd_pricing: pricing,
+            ..pool.next_accrual_model()
+        };
+        let effective_at = schedule_accrual_model(pool, model, Clock::get()?.unix_timestamp)?;
+
+        msg!("USD pricing {:?} takes effect at {}", pricing, effective_at);
+        Ok(())
+    }
+
+    /// Share a fixed hourly budget among participants (admin only): each user earns
+    /// `budget_per_hour / participant_count` per hour, but never less than `floor_rate_per_hour`.
+    /// A zero budget returns the pool to its flat `reward_rate_per_hour`. Scheduled like a
+    /// rate change, so time accrued before it takes effect keeps the old rate.
+    pub fn set_participant_budget(
+        ctx: Context<UpdatePoolConfig>,
+        budget_per_hour: u64,
+        floor_rate_per_hour: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        let model = AccrualModel {
+            budget_per_hour,
+            floor_rate_per_hour,
+            ..pool.next_accrual_model()
+        };
+        let effective_at = schedule_accrual_model(pool, model, Clock::get()?.unix_timestamp)?;
+
+        msg!(
+            "Participant budget of {} per hour, floor {} per user, takes effect at {}",
+            budget_per_hour,
+            floor_rate_per_hour,
+            effective_at
+        );
+        Ok(())
+    }
+
+    /// Set or clear a decaying emission schedule (admin only); while set it replaces
+    /// `reward_rate_per_hour`. Scheduled like a rate change, so time accrued before it
+    /// takes effect keeps the old rate.
+    pub fn set_emission_schedule(
+        ctx: Context<UpdatePoolConfig>,
+        schedule: Option<EmissionSchedule>,
+    ) -> Result<()> {
+        if let Some(schedule) = schedule {
+            require!(
+                schedule.start_rate > 0
+                    && schedule.decay_interval_secs >= MIN_EMISSION_DECAY_INTERVAL_SECS
+                    && schedule.decay_bps as u64 <= math::BPS_DENOMINATOR,
+                ErrorCode::InvalidEmissionSchedule
+            );
+        }
+
+        let pool = &mut ctx.accounts.reward_pool;
+        let model = AccrualModel {
+            emission: schedule,
+            ..pool.next_accrual_model()
+        };
+        let effective_at = schedule_accrual_model(pool, model, Clock::get()?.unix_timestamp)?;
+
+        msg!(
+            "Emission schedule {:?} takes effect at {}",
+            schedule,
+            effective_at
+        );
+        Ok(())
+    }
+
+    /// Schedule the yield venue idle vault funds may be deployed to (admin only):
+    /// `venue_program`, the venue-owned `deposit_account` deployed funds are transferred
+    /// into, and the liquid balance to keep on top of reserved liabilities. A venue can
+    /// hold vault funds, so the change waits out the longer of the config delay and the
+    /// emergency withdrawal delay and is then applied with `apply_yield_venue`; clearing
+    /// the venue applies at once. Safe-mode pools can't use yield venues.
+    pub fn set_yield_venue(
+        ctx: Context<UpdatePoolConfig>,
+        venue_program: Option<Pubkey>,
+        deposit_account: Option<Pubkey>,
+        min_liquid_balance: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        require!(pool.safe_mode.is_none(), ErrorCode::SafeModeTimelocked);
+        require!(
+            pool.distribution_mode == DistributionMode::Vault,
+            ErrorCode::WrongDistributionMode
+        );
+        require!(
+            venue_program.is_some() == deposit_account.is_some(),
+            ErrorCode::YieldVenueNotWhitelisted
+        );
+        require!(
+            venue_program == pool.yield_venue || pool.yield_deployed == 0,
+            ErrorCode::YieldFundsDeployed
+        );
+
+        if venue_program.is_none() {
+            pool.yield_venue = None;
+            pool.yield_deposit_account = None;
+            pool.yield_min_liquid = min_liquid_balance;
+            pool.pending_yield_venue = None;
+            msg!("Yield venue cleared");
+            return Ok(());
+        }
+
+        let delay = pool
+            .config_delay_secs
+            .max(pool.emergency_withdraw_delay_secs);
+        let pending = PendingYieldVenue {
+            venue_program,
+            deposit_account,
+            min_liquid_balance,
+            effective_at: Clock::get()?
+                .unix_timestamp
+                .checked_add(delay)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?,
+        };
+        pool.pending_yield_venue = Some(pending);
+
+        msg!(
+            "Yield venue {:?} takes effect at {}",
+            venue_program,
+            pending.effective_at
+        );
+        Ok(())
+    }
+
+    /// Apply the yield venue scheduled by `set_yield_venue` once its delay has passed
+    /// (admin only)
+    pub fn apply_yield_venue(ctx: Context<UpdatePoolConfig>) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        let now = Clock::get()?.unix_timestamp;
+        let pending = pool
+            .pending_yield_venue
+            .filter(|pending| now >= pending.effective_at)
+            .ok_or(error!(ErrorCode::PendingConfigNotDue))?;
+        require!(pool.safe_mode.is_none(), ErrorCode::SafeModeTimelocked);
+        require!(
+            pending.venue_program == pool.yield_venue || pool.yield_deployed == 0,
+            ErrorCode::YieldFundsDeployed
+        );
+
+        pool.yield_venue = pending.venue_program;
+        pool.yield_deposit_account = pending.deposit_account;
+        pool.yield_min_liquid = pending.min_liquid_balance;
+        pool.pending_yield_venue = None;
+
+        msg!("Yield venue set: {:?}", pending.venue_program);
+        Ok(())
+    }
+
+    /// Deploy idle vault funds to the yield venue (admin only): `amount` is transferred
+    /// to the venue's deposit account, then the venue is called with `data` and the
+    /// remaining accounts, without the pool signing. The vault must keep its reserved
+    /// liabilities plus the liquid reserve, and its authorities must be unchanged.
+    pub fn deploy_to_yield<'info>(
+        ctx: Context<'_, '_, '_, 'info, YieldVenueCall<'info>>,
+        amount: u64,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let pool = &ctx.accounts.reward_pool;
+        require!(pool.safe_mode.is_none(), ErrorCode::SafeModeTimelocked);
+        require!(amount > 0, ErrorCode::InvalidYieldAmount);
+        let deposit_account = ctx
+            .accounts
+            .venue_token_account
+            .as_ref()
+            .ok_or(error!(ErrorCode::YieldVenueNotWhitelisted))?;
+        let reserve = math::reserved_liabilities(pool, Clock::get()?.unix_timestamp)?
+            .saturating_add(pool.yield_min_liquid);
+        let before = ctx.accounts.vault.amount;
+        require!(
+            before.saturating_sub(amount) >= reserve && before >= amount,
+            ErrorCode::YieldReserveBreached
+        );
+        let authorities = vault_authorities(&ctx.accounts.vault);
+
+        let seeds = &[
+            b"reward_pool",
+            pool.authority.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: deposit_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+        invoke_yield_venue(&ctx.accounts.venue_program, ctx.remaining_accounts, data)?;
+        ctx.accounts.vault.reload()?;
+        require!(
+            before.checked_sub(ctx.accounts.vault.amount) == Some(amount)
+                && vault_authorities(&ctx.accounts.vault) == authorities,
+            ErrorCode::YieldVenueMismatch
+        );
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.yield_deployed = pool
+            .yield_deployed
+            .checked_add(amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        emit!(YieldRebalanced {
+            pool: pool.key(),
+            venue_program: ctx.accounts.venue_program.key(),
+            deposited: amount,
+            withdrawn: 0,
+            yield_deployed: pool.yield_deployed,
+            liquid_balance: ctx.accounts.vault.amount,
+        });
+        msg!("Deployed {} tokens to the yield venue", amount);
+        Ok(())
+    }
+
+    /// Withdraw funds from the yield venue back to the vault (admin only). The venue is
+    /// called without the pool signing and must only add to the vault, leaving its
+    /// authorities unchanged. Anything received above the deployed principal is counted as
+    /// yield.
+    pub fn withdraw_from_yield<'info>(
+        ctx: Context<'_, '_, '_, 'info, YieldVenueCall<'info>>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let before = ctx.accounts.vault.amount;
+        let authorities = vault_authorities(&ctx.accounts.vault);
+        invoke_yield_venue(&ctx.accounts.venue_program, ctx.remaining_accounts, data)?;
+        ctx.accounts.vault.reload()?;
+        require!(
+            vault_authorities(&ctx.accounts.vault) == authorities,
+            ErrorCode::YieldVenueMismatch
+        );
+        let received = ctx
+            .accounts
+            .vault
+            .amount
+            .checked_sub(before)
+            .filter(|received| *received > 0)
+            .ok_or(error!(ErrorCode::YieldVenueMismatch))?;
+
+        let pool = &mut ctx.accounts.reward_pool;
+        let principal = received.min(pool.yield_deployed);
+        pool.yield_deployed -= principal;
+        pool.yield_earned = pool
+            .yield_earned
+            .checked_add(received - principal)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        emit!(YieldRebalanced {
+            pool: pool.key(),
+            venue_program: ctx.accounts.venue_program.key(),
+            deposited: 0,
+            withdrawn: received,
+            yield_deployed: pool.yield_deployed,
+            liquid_balance: ctx.accounts.vault.amount,
+        });
+        msg!(
+            "Withdrew {} tokens from the yield venue ({} yield)",
+            received,
+            received - principal
+        );
+        Ok(())
+    }
+
+    /// Delegate a slice of the pool's budget to a downstream distributor program (admin
+    /// only). `delegate_authority` — typically a PDA of `delegate_program` — signs draws via
+    /// CPI, up to `allowance` tokens in total. Like an emergency withdrawal, the allowance
+    /// only becomes drawable after the pool's emergency withdrawal delay, and safe-mode
+    /// pools need their guardian to co-sign.
+    pub fn create_budget_delegation(
+        ctx: Context<CreateBudgetDelegation>,
+        delegate_program: Pubkey,
+        delegate_authority: Pubkey,
+        allowance: u64,
+    ) -> Result<()> {
+        let pool = &ctx.accounts.reward_pool;
+        require!(
+            pool.distribution_mode != DistributionMode::NativeSol,
+            ErrorCode::WrongDistributionMode
+        );
+        ensure_guardian_approved(pool, ctx.accounts.guardian.as_ref())?;
+        let effective_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(pool.emergency_withdraw_delay_secs)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        ctx.accounts.delegation.set_inner(BudgetDelegation {
+            pool: pool.key(),
+            delegate_program,
+            delegate_authority,
+            allowance: 0,
+            drawn: 0,
+            is_active: true,
+            bump: ctx.bumps.delegation,
+            pending_allowance: allowance,
+            pending_allowance_at: effective_at,
+        });
+
+        msg!(
+            "Budget of {} delegated to program {}",
+            allowance,
+            delegate_program
+        );
+        Ok(())
+    }
+
+    /// Change a delegation's total allowance or pause it (admin only). Lowering the
+    /// allowance applies at once and cancels any pending raise; raising it waits out the
+    /// emergency withdrawal delay and, on safe-mode pools, needs the guardian.
+    pub fn update_budget_delegation(
+        ctx: Context<UpdateBudgetDelegation>,
+        allowance: Option<u64>,
+        is_active: Option<bool>,
+    ) -> Result<()> {
+        let pool = &ctx.accounts.reward_pool;
+        let delegation = &mut ctx.accounts.delegation;
+        let now = Clock::get()?.unix_timestamp;
+        delegation.apply_due_allowance(now);
+        if let Some(allowance) = allowance {
+            require!(
+                allowance >= delegation.drawn,
+                ErrorCode::InvalidDelegationAllowance
+            );
+            if allowance <= delegation.allowance {
+                delegation.allowance = allowance;
+                delegation.pending_allowance = 0;
+                delegation.pending_allowance_at = 0;
+            } else {
+                ensure_guardian_approved(pool, ctx.accounts.guardian.as_ref())?;
+                delegation.pending_allowance = allowance;
+                delegation.pending_allowance_at = now
+                    .checked_add(pool.emergency_withdraw_delay_secs)
+                    .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+                msg!(
+                    "Allowance of {} takes effect at {}",
+                    allowance,
+                    delegation.pending_allowance_at
+                );
+            }
+        }
+        if let Some(active) = is_active {
+            delegation.is_active = active;
+        }
+
+        msg!(
+            "Budget delegation updated: {} of {} drawn",
+            delegation.drawn,
+            delegation.allowance
+        );
+        Ok(())
+    }
+
+    /// Draw from a delegated budget into any token account of the pool's mint
+    /// (delegate authority only, usually via CPI from the distributor program). Vault
+    /// draws can't dip into the pool's reserved liabilities.
+    pub fn draw_delegated_budget(ctx: Context<DrawDelegatedBudget>, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let delegation = &mut ctx.accounts.delegation;
+        delegation.apply_due_allowance(now);
+        require!(delegation.is_active, ErrorCode::DelegationNotActive);
+        require!(amount > 0, ErrorCode::NoRewardsAvailable);
+        let drawn = delegation
+            .drawn
+            .checked_add(amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        require!(
+            drawn <= delegation.allowance,
+            ErrorCode::DelegationAllowanceExceeded
+        );
+        delegation.drawn = drawn;
+
+        let pool = &ctx.accounts.reward_pool;
+        ensure_within_budget(pool, amount)?;
+        let seeds = &[
+            b"reward_pool",
+            pool.authority.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+
+        match pool.distribution_mode {
+            DistributionMode::Vault => {
+                require!(
+                    ctx.accounts.vault.amount >= amount,
+                    ErrorCode::InsufficientVaultFunds
+                );
+                let excess = ctx
+                    .accounts
+                    .vault
+                    .amount
+                    .saturating_sub(math::reserved_liabilities(pool, now)?);
+                require!(amount <= excess, ErrorCode::ExceedsExcessFunds);
+                let cpi_accounts = TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+            }
+            DistributionMode::MintOnClaim => {
+                let cpi_accounts = MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.destination.to_account_info(),
+                    authority: pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::mint_to(cpi_ctx, amount)?;
+            }
+            DistributionMode::NativeSol => return err!(ErrorCode::WrongDistributionMode),
+        }
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.total_distributed = pool
+            .total_distributed
+            .checked_add(amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        deactivate_if_budget_spent(pool);
+
+        emit!(BudgetDrawn {
+            pool: pool.key(),
+            delegate_program: delegation.delegate_program,
+            destination: ctx.accounts.destination.key(),
+            amount,
+            remaining: delegation.allowance - delegation.drawn,
+        });
+        msg!("Delegated budget drawn: {} tokens", amount);
+        Ok(())
+    }
+
+    /// Claim accumulated rewards from a native SOL pool, paid in lamports
+    pub fn claim_native_rewards(
+        ctx: Context<ClaimNativeRewards>,
+        min_expected_amount: u64,
+    ) -> Result<()> {
+        ensure_not_denylisted(
+            &ctx.accounts.denylist_entry,
+            ctx.accounts.reward_pool.key(),
+            ctx.accounts.authority.key(),
+        )?;
+
+        let user_account = &mut ctx.accounts.user_account;
+        let pool = &mut ctx.accounts.reward_pool;
+        let clock = Clock::get()?;
+
+        require!(
+            pool.distribution_mode == DistributionMode::NativeSol,
+            ErrorCode::WrongDistributionMode
+        );
+        require!(!pool.claims_paused, ErrorCode::ClaimsPaused);
+        require!(user_account.banned_at == 0, ErrorCode::UserBanned);
+        require!(user_account.is_active, ErrorCode::UserNotActive);
+        require!(
+            user_account.terms_version >= pool.terms_version,
+            ErrorCode::TermsNotAccepted
+        );
+        require!(user_account.paused_at == 0, ErrorCode::AccrualPaused);
+        reverify_nft_boost(user_account, ctx.accounts.nft_token_account.as_ref());
+
+        let current_timestamp = clock.unix_timestamp;
+        apply_due_config(pool, current_timestamp);
+        let mut breakdown = compute_reward(pool, user_account, current_timestamp)?;
+
+        ensure_claim_interval(pool, user_account, &breakdown, current_timestamp)?;
+
+        // A committed lottery draw is revealed (and consumed) by this claim
+        let reward_amount = reveal_lottery(
+            pool,
+            user_account,
+            ctx.accounts.slot_hashes.as_ref(),
+            clock.slot,
+            breakdown.reward_amount,
+        )?
+        .min(lifetime_reward_remaining(pool, user_account)?)
+        .min(pool_budget_remaining(pool)?);
+
+        require!(reward_amount > 0, ErrorCode::NoRewardsAvailable);
+        ensure_co_signed(user_account, reward_amount, ctx.accounts.co_signer.as_ref())?;
+
+        if pool.require_region_attestation {
+            require!(
+                user_account.region_attested_at != 0,
+                ErrorCode::RegionAttestationRequired
+            );
+        }
+        let (reward_amount, withheld_amount) =
+            match load_region_policy(&ctx.accounts.region_policy)? {
+                Some(policy) => {
+                    math::apply_region_policy(reward_amount, &policy.action, pool.rounding)?
+                }
+                None => (reward_amount, 0),
+            };
+
+        // The SOL vault must stay rent exempt, so only lamports above the floor are spendable
+        let vault_info = ctx.accounts.sol_vault.to_account_info();
+        let vault_balance = vault_info
+            .lamports()
+            .saturating_sub(Rent::get()?.minimum_balance(0));
+        let payout_amount = math::vault_payout(pool, vault_balance, reward_amount)?;
+
+        // A short vault pays what it holds; the unpaid share of the claim stays accrued for
+        // a later claim
+        if payout_amount < reward_amount {
+            carry_unpaid_share(&mut breakdown, payout_amount, reward_amount)?;
+        }
+
+        require!(
+            payout_amount >= min_expected_amount,
+            ErrorCode::BelowMinimumExpected
+        );
+
+        write_claim_receipt(
+            pool,
+            user_account,
+            ctx.accounts.claim_receipt.as_mut(),
+            ctx.accounts.authority.key(),
+            ctx.bumps.claim_receipt,
+            payout_amount,
+            current_timestamp,
+        )?;
+
+        let pool_key = pool.key();
+        let seeds = &[b"sol_vault", pool_key.as_ref(), &[pool.sol_vault_bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = system_program::Transfer {
+            from: vault_info,
+            to: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+
+        system_program::transfer(cpi_ctx, payout_amount)?;
+
+        user_account.total_earned = user_account
+            .total_earned
+            .checked_add(payout_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        user_account.total_withheld = user_account
+            .total_withheld
+            .checked_add(withheld_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        let first_claim_of_day = math::is_first_claim_of_day(user_account, current_timestamp);
+        let released = user_account.record_claim(pool, &breakdown, current_timestamp)?;
+        pool.track_carryover(released, breakdown.carryover);
+
+        pool.total_distributed = pool
+            .total_distributed
+            .checked_add(payout_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_withheld = pool
+            .total_withheld
+            .checked_add(withheld_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        record_mint_stats(
+            pool,
+            ctx.accounts.mint_stats.as_mut(),
+            payout_amount,
+            1,
+            current_timestamp,
+        )?;
+        deactivate_if_budget_spent(pool);
+        record_epoch_claim(
+            pool,
+            ctx.accounts.epoch.as_mut(),
+            payout_amount,
+            1,
+            current_timestamp,
+        )?;
+        record_daily_stats(
+            pool,
+            ctx.accounts.daily_stats.as_mut(),
+            payout_amount,
+            1,
+            u64::from(first_claim_of_day),
+            current_timestamp,
+        )?;
+
+        let referral_bonus = credit_referral(
+            pool,
+            user_account,
+            ctx.accounts.referrer_account.as_deref_mut(),
+            payout_amount,
+        )?;
+        pool.total_referral_owed = pool
+            .total_referral_owed
+            .checked_add(referral_bonus)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        msg!("Rewards claimed: {} lamports", payout_amount);
+        Ok(())
+    }
+
+    /// Execute an announced emergency withdrawal of lamports from a native SOL pool after
+    /// its delay (admin only)
+    pub fn emergency_withdraw_native(
+        ctx: Context<EmergencyWithdrawNative>,
+        amount: u64,
+    ) -> Result<()> {
+        take_announced_withdrawal(
+            &mut ctx.accounts.reward_pool,
+            amount,
+            ctx.accounts.destination.key(),
+        )?;
+        let pool = &ctx.accounts.reward_pool;
+        let pool_key = pool.key();
+        let seeds = &[b"sol_vault", pool_key.as_ref(), &[pool.sol_vault_bump]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = system_program::Transfer {
+            from: ctx.accounts.sol_vault.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+
+        system_program::transfer(cpi_ctx, amount)?;
+
+        msg!("Emergency withdrawal: {} lamports", amount);
+        Ok(())
+    }
+
+    /// Configure (or disable) vesting of claimed rewards (admin only)
+    pub fn set_vesting_schedule(
+        ctx: Context<UpdatePoolConfig>,
+        schedule: Option<VestingSchedule>,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+
+        if let Some(schedule) = schedule {
+            require!(
+                schedule.cliff_secs >= 0 && schedule.duration_secs >= 0,
+                ErrorCode::InvalidVestingSchedule
+            );
+            require!(
+                pool.distribution_mode != DistributionMode::NativeSol,
+                ErrorCode::WrongDistributionMode
+            );
+        }
+        pool.vesting = schedule;
+
+        msg!("Vesting schedule updated");
+        Ok(())
+    }
+
+    /// Configure streak bonuses; a zero window turns streak tracking off
+    pub fn set_streak_policy(
+        ctx: Context<UpdatePoolConfig>,
+        streak_window_hours: u64,
+        streak_bonus_bps: u16,
+        max_streak_bonus_bps: u16,
+        streak_insurance_fee: u64,
+    ) -> Result<()> {
+        require!(
+            u64::from(streak_bonus_bps) <= math::BPS_DENOMINATOR
+                && u64::from(max_streak_bonus_bps) <= math::BPS_DENOMINATOR,
+            ErrorCode::InvalidBasisPoints
+        );
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.streak_window_hours = streak_window_hours;
+        pool.streak_bonus_bps = streak_bonus_bps;
+        pool.max_streak_bonus_bps = max_streak_bonus_bps;
+        pool.streak_insurance_fee = streak_insurance_fee;
+
+        msg!(
+            "Streak policy updated: {}h window, {} bps per claim, {} bps max, {} insurance fee",
+            streak_window_hours,
+            streak_bonus_bps,
+            max_streak_bonus_bps,
+            streak_insurance_fee
+        );
+        Ok(())
+    }
+
+    /// Configure the cadence modifier; a zero target turns it off
+    pub fn set_cadence_policy(
+        ctx: Context<UpdatePoolConfig>,
+        cadence_target_hours: u64,
+        cadence_tolerance_hours: u64,
+        cadence_bonus_bps: u16,
+        cadence_penalty_bps: u16,
+    ) -> Result<()> {
+        require!(
+            u64::from(cadence_bonus_bps) <= math::BPS_DENOMINATOR
+                && u64::from(cadence_penalty_bps) <= math::BPS_DENOMINATOR,
+            ErrorCode::InvalidBasisPoints
+        );
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.cadence_target_hours = cadence_target_hours;
+        pool.cadence_tolerance_hours = cadence_tolerance_hours;
+        pool.cadence_bonus_bps = cadence_bonus_bps;
+        pool.cadence_penalty_bps = cadence_penalty_bps;
+
+        msg!(
+            "Cadence policy updated: {}h ± {}h target, +{} / -{} bps",
+            cadence_target_hours,
+            cadence_tolerance_hours,
+            cadence_bonus_bps,
+            cadence_penalty_bps
+        );
+        Ok(())
+    }
+
+    /// Set a promotional reward multiplier of 1x to `MAX_USER_MULTIPLIER_BPS` on a user,
+    /// optionally expiring at a future `expires_at`, or clear it with 0 and no expiry
+    pub fn set_user_multiplier(
+        ctx: Context<SetUserMultiplier>,
+        multiplier_bps: u16,
+        expires_at: Option<i64>,
+    ) -> Result<()> {
+        if multiplier_bps == 0 {
+            require!(expires_at.is_none(), ErrorCode::InvalidUserMultiplier);
+        } else {
+            require!(
+                (math::BPS_DENOMINATOR..=MAX_USER_MULTIPLIER_BPS)
+                    .contains(&u64::from(multiplier_bps)),
+                ErrorCode::InvalidUserMultiplier
+            );
+        }
+        if let Some(expires_at) = expires_at {
+            require!(
+                expires_at > Clock::get()?.unix_timestamp,
+                ErrorCode::InvalidUserMultiplier
+            );
+        }
+
+        let user_account = &mut ctx.accounts.user_account;
+        user_account.multiplier_bps = multiplier_bps;
+        user_account.multiplier_expires_at = expires_at.unwrap_or(0);
+
+        msg!(
+            "Multiplier for {} set to {} bps (expires at {})",
+            user_account.authority,
+            multiplier_bps,
+            user_account.multiplier_expires_at
+        );
+        Ok(())
+    }
+
+    /// Whitelist programs allowed to grant boosts via CPI, and cap the boosts they can
+    /// grant (admin only)
+    pub fn set_boost_granters(
+        ctx: Context<UpdatePoolConfig>,
+        programs: Vec<Pubkey>,
+        max_granted_boost_bps: u16,
+    ) -> Result<()> {
+        require!(
+            programs.len() <= MAX_BOOST_GRANTERS
+                && u64::from(max_granted_boost_bps) >= math::BPS_DENOMINATOR,
+            ErrorCode::InvalidBoostGranters
+        );
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.boost_granter_programs = programs;
+        pool.max_granted_boost_bps = max_granted_boost_bps;
+
+        msg!(
+            "{} boost granter programs, up to {} bps",
+            pool.boost_granter_programs.len(),
+            max_granted_boost_bps
+        );
+        Ok(())
+    }
+
+    /// Give a user a temporary reward multiplier for `duration_secs`. Only callable via CPI
+    /// from a whitelisted program, which signs with its `[b"boost_granter"]` PDA. The grant
+    /// is kept apart from the authority's `set_user_multiplier`, and the higher of the two
+    /// applies while both are active.
+    pub fn grant_boost(
+        ctx: Context<GrantBoost>,
+        granter_program: Pubkey,
+        multiplier_bps: u16,
+        duration_secs: i64,
+    ) -> Result<()> {
+        let pool = &ctx.accounts.reward_pool;
+        require!(
+            pool.boost_granter_programs.contains(&granter_program),
+            ErrorCode::UnauthorizedBoostGranter
+        );
+        require!(
+            multiplier_bps <= pool.max_granted_boost_bps && duration_secs > 0,
+            ErrorCode::InvalidBoost
+        );
+
+        let user_account = &mut ctx.accounts.user_account;
+        user_account.granted_boost_bps = multiplier_bps;
+        user_account.granted_boost_expires_at = Clock::get()?
+            .unix_timestamp
+            .checked_add(duration_secs)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        emit!(BoostGranted {
+            pool: pool.key(),
+            user: user_account.authority,
+            granter_program,
+            multiplier_bps,
+            expires_at: user_account.granted_boost_expires_at,
+        });
+        Ok(())
+    }
+
+    /// Price the boosts users can buy with `purchase_boost` (admin only). Tiers with a zero
+    /// price can't be bought. Purchases go to `proceeds_destination`, a token account of
+    /// the pool mint, or are burned when it is `None`.
+    pub fn set_boost_tiers(
+        ctx: Context<UpdatePoolConfig>,
+        tiers: [BoostTier; MAX_BOOST_TIERS],
+        proceeds_destination: Option<Pubkey>,
+    ) -> Result<()> {
+        require!(
+            tiers
+                .iter()
+                .all(|tier| tier.price_per_hour == 0 || tier.bonus_bps > 0),
+            ErrorCode::InvalidBoost
+        );
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.boost_tiers = tiers;
+        pool.boost_proceeds_destination = proceeds_destination;
+
+        msg!(
+            "Boost tiers set: {:?}, proceeds to {:?}",
+            tiers,
+            proceeds_destination
+        );
+        Ok(())
+    }
+
+    /// Burn `burn_bps` of every claim from the vault instead of paying it out (admin
+    /// only). Burns count against the pool's distribution budget. Vault pools only.
+    pub fn set_burn_bps(ctx: Context<UpdatePoolConfig>, burn_bps: u16) -> Result<()> {
+        require!(
+            u64::from(burn_bps) <= math::BPS_DENOMINATOR,
+            ErrorCode::InvalidBasisPoints
+        );
+        let pool = &mut ctx.accounts.reward_pool;
+        require!(
+            burn_bps == 0 || pool.distribution_mode == DistributionMode::Vault,
+            ErrorCode::WrongDistributionMode
+        );
+        pool.burn_bps = burn_bps;
+
+        msg!("Claim burn set to {} bps", burn_bps);
+        Ok(())
+    }
+
+    /// Switch the pool between linear and compound accrual (admin only). With
+    /// `growth_bps` above zero, unclaimed rewards grow by that many basis points every
+    /// hour until claimed. Scheduled like a rate change: hours before it takes effect keep
+    /// the old growth.
+    pub fn set_compound_growth(ctx: Context<UpdatePoolConfig>, growth_bps: u16) -> Result<()> {
+        require!(
+            u64::from(growth_bps) <= math::BPS_DENOMINATOR,
+            ErrorCode::InvalidBasisPoints
+        );
+        let pool = &mut ctx.accounts.reward_pool;
+        let model = AccrualModel {
+            compound_growth_bps: growth_bps,
+            ..pool.next_accrual_model()
+        };
+        let effective_at = schedule_accrual_model(pool, model, Clock::get()?.unix_timestamp)?;
+
+        msg!(
+            "Compound growth of {} bps per hour takes effect at {}",
+            growth_bps,
+            effective_at
+        );
+        Ok(())
+    }
+
+    /// Buy boost `tier` for `duration_hours`, paying its hourly price in pool tokens, which
+    /// are burned or sent to the pool's boost proceeds destination. The boost is a bonus on
+    /// accrual from now until it expires, prorated like auction boosts. Buying the active
+    /// tier again extends it.
+    pub fn purchase_boost(
+        ctx: Context<PurchaseBoost>,
+        duration_hours: u64,
+        tier: u8,
+    ) -> Result<()> {
+        let pool = &ctx.accounts.reward_pool;
+        let user_account = &ctx.accounts.user_account;
+        let now = Clock::get()?.unix_timestamp;
+
+        require!(
+            pool.distribution_mode != DistributionMode::NativeSol,
+            ErrorCode::WrongDistributionMode
+        );
+        require!(user_account.banned_at == 0, ErrorCode::UserBanned);
+        require!(user_account.is_active, ErrorCode::UserNotActive);
+        let boost = pool
+            .boost_tiers
+            .get(usize::from(tier))
+            .copied()
+            .filter(|boost| boost.price_per_hour > 0)
+            .ok_or(error!(ErrorCode::BoostTierUnavailable))?;
+        require!(
+            duration_hours > 0 && duration_hours <= MAX_BOOST_PURCHASE_HOURS,
+            ErrorCode::InvalidBoost
+        );
+        let price = boost
+            .price_per_hour
+            .checked_mul(duration_hours)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        let duration_secs = i64::try_from(duration_hours)
+            .ok()
+            .and_then(|hours| hours.checked_mul(math::SECONDS_PER_HOUR))
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        // Extend a running boost of the same tier; anything else needs the previous boost to
+        // have expired and been claimed, or its unpaid bonus would be lost
+        let starts_at = if user_account.purchased_boost_ends_at > now {
+            require!(
+                user_account.purchased_boost_bps == boost.bonus_bps,
+                ErrorCode::PurchasedBoostActive
+            );
+            user_account.purchased_boost_starts_at
+        } else {
+            require!(
+                user_account.purchased_boost_bps == 0
+                    || user_account.purchased_boost_ends_at <= math::accrual_start(user_account),
+                ErrorCode::PurchasedBoostActive
+            );
+            now
+        };
+        let ends_at = user_account
+            .purchased_boost_ends_at
+            .max(now)
+            .checked_add(duration_secs)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        match pool.boost_proceeds_destination {
+            Some(destination) => {
+                let treasury = ctx
+                    .accounts
+                    .proceeds_destination
+                    .as_ref()
+                    .ok_or(error!(ErrorCode::BoostTierUnavailable))?;
+                require_keys_eq!(treasury.key(), destination, ErrorCode::BoostTierUnavailable);
+
+                let cpi_accounts = TransferChecked {
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: treasury.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+                token_interface::transfer_checked(cpi_ctx, price, ctx.accounts.mint.decimals)?;
+            }
+            None => {
+                let cpi_accounts = Burn {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    from: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.authority.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+                token_interface::burn(cpi_ctx, price)?;
+            }
+        }
+
+        let user_account = &mut ctx.accounts.user_account;
+        user_account.purchased_boost_bps = boost.bonus_bps;
+        user_account.purchased_boost_starts_at = starts_at;
+        user_account.purchased_boost_ends_at = ends_at;
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.total_boost_sales = pool
+            .total_boost_sales
+            .checked_add(price)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        emit!(BoostPurchased {
+            pool: pool.key(),
+            user: user_account.authority,
+            tier,
+            bonus_bps: boost.bonus_bps,
+            price,
+            burned: pool.boost_proceeds_destination.is_none(),
+            expires_at: ends_at,
+        });
+        Ok(())
+    }
+
+    /// Ban a user (admin only): deactivates the account and forfeits anything accrued so far
+    pub fn ban_user(ctx: Context<BanUser>) -> Result<()> {
+        let user_account = &mut ctx.accounts.user_account;
+        require!(user_account.banned_at == 0, ErrorCode::UserBanned);
+
+        let now = Clock::get()?.unix_timestamp;
+        user_account.is_active = false;
+        user_account.banned_at = now;
+        // Restart accrual and drop carried-over rewards and pending activity points, so the
+        // ban forfeits everything owed so far
+        user_account.last_claim_timestamp = now;
+        ctx.accounts
+            .reward_pool
+            .track_carryover(user_account.accrued_unclaimed, 0);
+        user_account.accrued_unclaimed = 0;
+        user_account.activity_points = 0;
+        user_account.checkpoint_pool_pause(&ctx.accounts.reward_pool, now);
+
+        msg!("User banned: {}", user_account.authority);
+        Ok(())
+    }
+
+    /// Lift a ban (admin only); accrual restarts from the unban, not the ban
+    pub fn unban_user(ctx: Context<BanUser>) -> Result<()> {
+        let user_account = &mut ctx.accounts.user_account;
+        require!(user_account.banned_at != 0, ErrorCode::UserNotBanned);
+
+        let now = Clock::get()?.unix_timestamp;
+        user_account.is_active = true;
+        user_account.banned_at = 0;
+        user_account.last_claim_timestamp = now;
+        user_account.checkpoint_pool_pause(&ctx.accounts.reward_pool, now);
+        user_account.paused_seconds = 0;
+        if user_account.paused_at != 0 {
+            user_account.paused_at = now;
+        }
+        user_account.refresh_next_eligible_at(&ctx.accounts.reward_pool)?;
+
+        msg!("User unbanned: {}", user_account.authority);
+        Ok(())
+    }
+
+    /// Turn the account into a shared one: claims above `solo_claim_limit` then need
+    /// `co_signer`'s signature too. Setting or clearing the co-signer needs the current
+    /// co-signer (if any) and the new one (if any) to sign.
+    pub fn set_co_signer(
+        ctx: Context<SetCoSigner>,
+        co_signer: Option<Pubkey>,
+        solo_claim_limit: u64,
+    ) -> Result<()> {
+        let user_account = &mut ctx.accounts.user_account;
+
+        if let Some(current) = user_account.co_signer {
+            let signer = ctx
+                .accounts
+                .current_co_signer
+                .as_ref()
+                .ok_or(error!(ErrorCode::CoSignatureRequired))?;
+            require_keys_eq!(signer.key(), current, ErrorCode::CoSignatureRequired);
+        }
+        if let Some(new) = co_signer {
+            require_keys_neq!(new, user_account.authority, ErrorCode::InvalidCoSigner);
+            let signer = ctx
+                .accounts
+                .new_co_signer
+                .as_ref()
+                .ok_or(error!(ErrorCode::CoSignatureRequired))?;
+            require_keys_eq!(signer.key(), new, ErrorCode::CoSignatureRequired);
+        }
+
+        user_account.co_signer = co_signer;
+        user_account.solo_claim_limit = if co_signer.is_some() {
+            solo_claim_limit
+        } else {
+            0
+        };
+
+        match co_signer {
+            Some(co_signer) => msg!(
+                "Co-signer {} set; solo claims up to {}",
+                co_signer,
+                solo_claim_limit
+            ),
+            None => msg!("Co-signer removed"),
+        }
+        Ok(())
+    }
+
+    /// Attach a matching sponsor to the pool (authority and sponsor co-sign).
+    ///
+    /// The sponsor funds the matching vault; each direct claim then sends `ratio_bps` of the
+    /// user's net reward from it to `destination`, until `budget` has been matched. Claims
+    /// locked into vesting positions are not matched.
+    pub fn create_matching_sponsor(
+        ctx: Context<CreateMatchingSponsor>,
+        ratio_bps: u16,
+        budget: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        require!(
+            pool.distribution_mode != DistributionMode::NativeSol,
+            ErrorCode::WrongDistributionMode
+        );
+
+        let sponsor = &mut ctx.accounts.matching_sponsor;
+        sponsor.pool = pool.key();
+        sponsor.sponsor = ctx.accounts.sponsor.key();
+        sponsor.vault = ctx.accounts.matching_vault.key();
+        sponsor.destination = ctx.accounts.destination.key();
+        sponsor.ratio_bps = ratio_bps;
+        sponsor.budget = budget;
+        sponsor.matched_total = 0;
+        sponsor.match_count = 0;
+        sponsor.is_active = true;
+        sponsor.bump = ctx.bumps.matching_sponsor;
+        pool.matching_sponsor = Some(sponsor.key());
+
+        msg!(
+            "Matching sponsor {} created: {} bps up to {}",
+            sponsor.sponsor,
+            ratio_bps,
+            budget
+        );
+        Ok(())
+    }
+
+    /// Adjust the matching ratio or budget, or pause matching (sponsor only)
+    pub fn update_matching_sponsor(
+        ctx: Context<UpdateMatchingSponsor>,
+        ratio_bps: Option<u16>,
+        budget: Option<u64>,
+        is_active: Option<bool>,
+    ) -> Result<()> {
+        let sponsor = &mut ctx.accounts.matching_sponsor;
+
+        if let Some(ratio) = ratio_bps {
+            sponsor.ratio_bps = ratio;
+        }
+        if let Some(budget) = budget {
+            sponsor.budget = budget;
+        }
+        if let Some(active) = is_active {
+            sponsor.is_active = active;
+        }
+
+        msg!("Matching sponsor {} updated", sponsor.sponsor);
+        Ok(())
+    }
+
+    /// Withdraw unused matching funds back to the sponsor (sponsor only)
+    pub fn withdraw_matching_funds(ctx: Context<WithdrawMatchingFunds>, amount: u64) -> Result<()> {
+        let sponsor = &ctx.accounts.matching_sponsor;
+        let seeds = &[b"matching_sponsor", sponsor.pool.as_ref(), &[sponsor.bump]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.sponsor_token_account.to_account_info(),
+            authority: sponsor.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+        msg!("Withdrew {} matching tokens", amount);
+        Ok(())
+    }
+
+    /// Enable (or disable, with `None`) the NFT holder boost for the pool
+    pub fn set_nft_boost(
+        ctx: Context<UpdatePoolConfig>,
+        config: Option<NftBoostConfig>,
+    ) -> Result<()> {
+        ctx.accounts.reward_pool.nft_boost = config;
+
+        msg!("NFT boost updated");
+        Ok(())
+    }
+
+    /// Record the pool's NFT boost on the caller's account after checking that they hold an
+    /// NFT from the configured collection
+    pub fn register_nft_boost(ctx: Context<RegisterNftBoost>) -> Result<()> {
+        let config = ctx
+            .accounts
+            .reward_pool
+            .nft_boost
+            .ok_or(error!(ErrorCode::NftBoostNotEnabled))?;
+
+        let nft_mint = &ctx.accounts.nft_mint;
+        verify_collection_nft(
+            nft_mint,
+            &ctx.accounts.nft_token_account,
+            &ctx.accounts.nft_metadata,
+            config.collection,
+        )?;
+
+        let user_account = &mut ctx.accounts.user_account;
+        user_account.nft_boost_mint = Some(nft_mint.key());
+        user_account.nft_boost_bps = config.boost_bps;
+
+        msg!(
+            "NFT boost of {} bps registered with {}",
+            config.boost_bps,
+            nft_mint.key()
+        );
+        Ok(())
+    }
+
+    /// Pay the pool's insurance fee to protect the caller's streak across one missed window
+    pub fn buy_streak_insurance(ctx: Context<BuyStreakInsurance>) -> Result<()> {
+        let pool = &ctx.accounts.reward_pool;
+        let user_account = &ctx.accounts.user_account;
+
+        require!(
+            pool.streak_window_hours > 0 && pool.streak_insurance_fee > 0,
+            ErrorCode::StreakInsuranceUnavailable
+        );
+        require!(!user_account.streak_insured, ErrorCode::StreakAlreadyInsured);
+
+        let fee = pool.streak_insurance_fee;
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.user_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, fee, ctx.accounts.mint.decimals)?;
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.total_streak_insurance_fees = pool
+            .total_streak_insurance_fees
+            .checked_add(fee)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        ctx.accounts.user_account.streak_insured = true;
+
+        msg!("Streak insured for {} at a fee of {}", ctx.accounts.authority.key(), fee);
+        Ok(())
+    }
+
+    /// Open a boost auction for one epoch: bidders compete for `config.slots` boosts of
+    /// `config.boost_bps` that apply to accrual between `config.boost_starts_at` and
+    /// `config.boost_ends_at`.
+    ///
+    /// Winning bids are burned, or sent to `config.proceeds_destination` when one is given.
+    pub fn create_boost_auction(
+        ctx: Context<CreateBoostAuction>,
+        epoch: u64,
+        config: BoostAuctionConfig,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        require!(
+            config.slots > 0
+                && config.boost_bps > 0
+                && config.bidding_ends_at > clock.unix_timestamp
+                && config.boost_starts_at >= config.bidding_ends_at
+                && config.boost_ends_at > config.boost_starts_at,
+            ErrorCode::InvalidAuctionConfig
+        );
+        require!(
+            ctx.accounts.reward_pool.distribution_mode != DistributionMode::NativeSol,
+            ErrorCode::WrongDistributionMode
+        );
+
+        let auction = &mut ctx.accounts.auction;
+        auction.pool = ctx.accounts.reward_pool.key();
+        auction.epoch = epoch;
+        auction.escrow = ctx.accounts.escrow.key();
+        auction.bidding_ends_at = config.bidding_ends_at;
+        auction.boost_starts_at = config.boost_starts_at;
+        auction.boost_ends_at = config.boost_ends_at;
+        auction.slots = config.slots;
+        auction.boost_bps = config.boost_bps;
+        auction.min_bid = config.min_bid;
+        auction.proceeds_destination = config.proceeds_destination;
+        auction.bid_count = 0;
+        auction.total_proceeds = 0;
+        auction.settled = false;
+        auction.bump = ctx.bumps.auction;
+
+        msg!(
+            "Boost auction for epoch {} opened: {} slots at {} bps",
+            epoch,
+            config.slots,
+            config.boost_bps
+        );
+        Ok(())
+    }
+
+    /// Escrow a bid in the reward token for a boost slot (one bid per user per auction)
+    pub fn place_boost_bid(ctx: Context<PlaceBoostBid>, amount: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        let auction = &mut ctx.accounts.auction;
+
+        require!(
+            clock.unix_timestamp < auction.bidding_ends_at,
+            ErrorCode::AuctionClosed
+        );
+        require!(amount >= auction.min_bid && amount > 0, ErrorCode::BidTooLow);
+        require!(
+            auction.bid_count < MAX_AUCTION_BIDS,
+            ErrorCode::AuctionFull
+        );
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.bidder_token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.escrow.to_account_info(),
+            authority: ctx.accounts.bidder.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+
+        auction.bid_count = auction
+            .bid_count
+            .checked_add(1)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        ctx.accounts.bid.set_inner(BoostBid {
+            auction: auction.key(),
+            bidder: ctx.accounts.bidder.key(),
+            amount,
+            placed_at: clock.unix_timestamp,
+            won: false,
+            bump: ctx.bumps.bid,
+        });
+
+        msg!("Boost bid of {} placed for epoch {}", amount, auction.epoch);
+        Ok(())
+    }
+
+    /// Pick the winning bids once bidding has ended (crank).
+    ///
+    /// Remaining accounts must be every `BoostBid` of the auction. The highest bids win,
+    /// earlier bids breaking ties; their tokens are burned or sent to the proceeds destination.
+    pub fn settle_boost_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleBoostAuction<'info>>,
+    ) -> Result<()> {
+        let clock = Clock::get()?;
+        let auction_key = ctx.accounts.auction.key();
+        let auction = &ctx.accounts.auction;
+
+        require!(!auction.settled, ErrorCode::AuctionAlreadySettled);
+        require!(
+            clock.unix_timestamp >= auction.bidding_ends_at,
+            ErrorCode::AuctionNotEnded
+        );
+        require!(
+            ctx.remaining_accounts.len() == auction.bid_count as usize,
+            ErrorCode::InvalidBidAccounts
+        );
+
+        let mut bids: Vec<Account<BoostBid>> = Vec::with_capacity(ctx.remaining_accounts.len());
+        for bid_info in ctx.remaining_accounts {
+            let bid: Account<BoostBid> = Account::try_from(bid_info)?;
+            let (expected_bid, _) = Pubkey::find_program_address(
+                &[b"boost_bid", auction_key.as_ref(), bid.bidder.as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(bid_info.key(), expected_bid, ErrorCode::InvalidBidAccounts);
+            require!(
+                !bids.iter().any(|seen| seen.key() == bid_info.key()),
+                ErrorCode::InvalidBidAccounts
+            );
+            bids.push(bid);
+        }
+
+        bids.sort_by(|a, b| b.amount.cmp(&a.amount).then(a.placed_at.cmp(&b.placed_at)));
+
+        let mut proceeds: u64 = 0;
+        for bid in bids.iter_mut().take(auction.slots as usize) {
+            bid.won = true;
+            proceeds = proceeds
+                .checked_add(bid.amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            bid.exit(ctx.program_id)?;
+        }
+
+        let epoch_bytes = auction.epoch.to_le_bytes();
+        let seeds = &[
+            b"boost_auction",
+            auction.pool.as_ref(),
+            epoch_bytes.as_ref(),
+            &[auction.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+
+        if proceeds > 0 {
+            match auction.proceeds_destination {
+                Some(destination) => {
+                    let treasury = ctx
+                        .accounts
+                        .treasury
+                        .as_ref()
+                        .ok_or(error!(ErrorCode::InvalidAuctionConfig))?;
+                    require_keys_eq!(treasury.key(), destination, ErrorCode::InvalidAuctionConfig);
+
+                    let cpi_accounts = TransferChecked {
+                        from: ctx.accounts.escrow.to_account_info(),
+                        mint: ctx.accounts.mint.to_account_info(),
+                        to: treasury.to_account_info(),
+                        authority: auction.to_account_info(),
+                    };
+                    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                    token_interface::transfer_checked(
+                        cpi_ctx,
+                        proceeds,
+                        ctx.accounts.mint.decimals,
+                    )?;
+                }
+                None => {
+                    let cpi_accounts = Burn {
+                        mint: ctx.accounts.mint.to_account_info(),
+                        from: ctx.accounts.escrow.to_account_info(),
+                        authority: auction.to_account_info(),
+                    };
+                    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                    token_interface::burn(cpi_ctx, proceeds)?;
+                }
+            }
+        }
+
+        let auction = &mut ctx.accounts.auction;
+        auction.settled = true;
+        auction.total_proceeds = proceeds;
+
+        msg!(
+            "Boost auction for epoch {} settled: {} winners, {} proceeds",
+            auction.epoch,
+            bids.len().min(auction.slots as usize),
+            proceeds
+        );
+        Ok(())
+    }
+
+    /// Close a bid after settlement: winners get the boost recorded on their user account,
+    /// losers get their escrowed tokens back
+    pub fn finalize_boost_bid(ctx: Context<FinalizeBoostBid>) -> Result<()> {
+        let auction = &ctx.accounts.auction;
+        let bid = &ctx.accounts.bid;
+        require!(auction.settled, ErrorCode::AuctionNotSettled);
+
+        if bid.won {
+            let user_account = &mut ctx.accounts.user_account;
+            user_account.auction_boost_bps = auction.boost_bps;
+            user_account.auction_boost_starts_at = auction.boost_starts_at;
+            user_account.auction_boost_ends_at = auction.boost_ends_at;
+
+            msg!("Boost of {} bps won for epoch {}", auction.boost_bps, auction.epoch);
+        } else {
+            let epoch_bytes = auction.epoch.to_le_bytes();
+            let seeds = &[
+                b"boost_auction",
+                auction.pool.as_ref(),
+                epoch_bytes.as_ref(),
+                &[auction.bump],
+            ];
+            let signer = &[&seeds[..]];
+
+            let cpi_accounts = TransferChecked {
+                from: ctx.accounts.escrow.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.bidder_token_account.to_account_info(),
+                authority: auction.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            token_interface::transfer_checked(cpi_ctx, bid.amount, ctx.accounts.mint.decimals)?;
+
+            msg!("Losing bid of {} refunded", bid.amount);
+        }
+        Ok(())
+    }
+
+    /// Withdraw the unlocked portion of a vesting position, closing it once fully released
+    pub fn release_vested(ctx: Context<ReleaseVested>) -> Result<()> {
+        let clock = Clock::get()?;
+        let position = &mut ctx.accounts.vesting_position;
+
+        let vested = math::vested_amount(
+            position.total_amount,
+            position.start_timestamp,
+            position.cliff_secs,
+            position.duration_secs,
+            clock.unix_timestamp,
+        )?;
+        let releasable = vested
+            .checked_sub(position.released_amount)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+        require!(releasable > 0, ErrorCode::NothingToRelease);
+        position.released_amount = vested;
+        let fully_released = position.released_amount == position.total_amount;
+
+        let pool = &ctx.accounts.reward_pool;
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let seeds = &[
+            b"reward_pool",
+            pool.authority.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+
+        let (payout_amount, fee_amount) = match pool.distribution_mode {
+            DistributionMode::Vault => {
+                let gross_amount = if pool.gross_up_transfer_fees {
+                    fees::gross_up(&mint_info, releasable, clock.epoch)?
+                } else {
+                    releasable
+                };
+                let vault_balance = ctx.accounts.vault.amount;
+                if vault_balance < gross_amount {
+                    msg!(
+                        "Insufficient vault funds: {} available, {} required",
+                        vault_balance,
+                        gross_amount
+                    );
+                    return err!(ErrorCode::InsufficientVaultFunds);
+                }
+
+                let cpi_accounts = TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: mint_info.clone(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.reward_pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+                token_interface::transfer_checked(
+                    cpi_ctx,
+                    gross_amount,
+                    ctx.accounts.mint.decimals,
+                )?;
+
+                let fee_amount = fees::transfer_fee(&mint_info, gross_amount, clock.epoch)?;
+                (gross_amount, fee_amount)
+            }
+            DistributionMode::MintOnClaim => {
+                let cpi_accounts = MintTo {
+                    mint: mint_info,
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.reward_pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+                token_interface::mint_to(cpi_ctx, releasable)?;
+                (releasable, 0)
+            }
+            DistributionMode::NativeSol => return err!(ErrorCode::WrongDistributionMode),
+        };
+        let net_amount = payout_amount
+            .checked_sub(fee_amount)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+
+        let user_account = &mut ctx.accounts.user_account;
+        user_account.total_earned = user_account
+            .total_earned
+            .checked_add(net_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        user_account.total_fees_paid = user_account
+            .total_fees_paid
+            .checked_add(fee_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.total_vesting_locked = pool
+            .total_vesting_locked
+            .checked_sub(releasable)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+        pool.total_distributed = pool
+            .total_distributed
+            .checked_add(payout_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_transfer_fees = pool
+            .total_transfer_fees
+            .checked_add(fee_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        if fully_released {
+            // Positions opened before the count was kept aren't in it
+            ctx.accounts.user_account.open_vesting_positions = ctx
+                .accounts
+                .user_account
+                .open_vesting_positions
+                .saturating_sub(1);
+            ctx.accounts
+                .vesting_position
+                .close(ctx.accounts.authority.to_account_info())?;
+        }
+
+        msg!("Released {} vested tokens", net_amount);
+        Ok(())
+    }
+
+    /// Pay the next part of an open claim session, up to the pool's per-transaction cap.
+    /// The session closes once fully paid.
+    pub fn claim_session_part(ctx: Context<ClaimSessionPart>) -> Result<()> {
+        let clock = Clock::get()?;
+        let pool = &ctx.accounts.reward_pool;
+        let session = &mut ctx.accounts.claim_session;
+
+        let remaining = session
+            .total_amount
+            .checked_sub(session.paid_amount)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+        let part = if pool.max_claim_per_tx > 0 {
+            remaining.min(pool.max_claim_per_tx)
+        } else {
+            remaining
+        };
+        session.paid_amount = session
+            .paid_amount
+            .checked_add(part)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        let fully_paid = session.paid_amount == session.total_amount;
+
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let seeds = &[
+            b"reward_pool",
+            pool.authority.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+
+        let (payout_amount, fee_amount) = match pool.distribution_mode {
+            DistributionMode::Vault => {
+                let gross_amount = if pool.gross_up_transfer_fees {
+                    fees::gross_up(&mint_info, part, clock.epoch)?
+                } else {
+                    part
+                };
+                let vault_balance = ctx.accounts.vault.amount;
+                if vault_balance < gross_amount {
+                    msg!(
+                        "Insufficient vault funds: {} available, {} required",
+                        vault_balance,
+                        gross_amount
+                    );
+                    return err!(ErrorCode::InsufficientVaultFunds);
+                }
+
+                let cpi_accounts = TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: mint_info.clone(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.reward_pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+                token_interface::transfer_checked(
+                    cpi_ctx,
+                    gross_amount,
+                    ctx.accounts.mint.decimals,
+                )?;
+
+                let fee_amount = fees::transfer_fee(&mint_info, gross_amount, clock.epoch)?;
+                (gross_amount, fee_amount)
+            }
+            DistributionMode::MintOnClaim => {
+                let cpi_accounts = MintTo {
+                    mint: mint_info,
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.reward_pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+                token_interface::mint_to(cpi_ctx, part)?;
+                (part, 0)
+            }
+            DistributionMode::NativeSol => return err!(ErrorCode::WrongDistributionMode),
+        };
+        let net_amount = payout_amount
+            .checked_sub(fee_amount)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+
+        let user_account = &mut ctx.accounts.user_account;
+        user_account.total_earned = user_account
+            .total_earned
+            .checked_add(net_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        user_account.total_fees_paid = user_account
+            .total_fees_paid
+            .checked_add(fee_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.total_session_locked = pool
+            .total_session_locked
+            .checked_sub(part)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+        pool.total_distributed = pool
+            .total_distributed
+            .checked_add(payout_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_transfer_fees = pool
+            .total_transfer_fees
+            .checked_add(fee_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        if fully_paid {
+            // Sessions opened before the count was kept aren't in it
+            ctx.accounts.user_account.open_claim_sessions = ctx
+                .accounts
+                .user_account
+                .open_claim_sessions
+                .saturating_sub(1);
+            ctx.accounts
+                .claim_session
+                .close(ctx.accounts.authority.to_account_info())?;
+        }
+
+        msg!("Claim session part paid: {} tokens", net_amount);
+        Ok(())
+    }
+
+    /// Cap a single claim transfer (admin only); larger claims go through a claim session.
+    /// 0 removes the cap.
+    pub fn set_max_claim_per_tx(
+        ctx: Context<UpdatePoolConfig>,
+        max_claim_per_tx: u64,
+    ) -> Result<()> {
+        ctx.accounts.reward_pool.max_claim_per_tx = max_claim_per_tx;
+
+        msg!("Max claim per transaction set to {}", max_claim_per_tx);
+        Ok(())
+    }
+
+    /// Claim for any eligible user without their signature, paying the cranker a tip of
+    /// `crank_tip_bps` out of the claim. The rest goes to the user's associated token
+    /// account. Like batch claims, the NFT boost does not apply and pools with faucet,
+    /// vesting or matching rules need direct claims. The referrer is credited and a pending
+    /// lottery draw revealed as in a direct claim, so pass the referrer's account and the
+    /// SlotHashes sysvar when they apply.
+    pub fn crank_claim(ctx: Context<CrankClaim>) -> Result<()> {
+        ensure_not_denylisted(
+            &ctx.accounts.denylist_entry,
+            ctx.accounts.reward_pool.key(),
+            ctx.accounts.user_account.authority,
+        )?;
+
+        let user_account = &mut ctx.accounts.user_account;
+        let pool = &mut ctx.accounts.reward_pool;
+        let clock = Clock::get()?;
+        let current_timestamp = clock.unix_timestamp;
+
+        require!(!pool.claims_paused, ErrorCode::ClaimsPaused);
+        require!(
+            pool.distribution_mode != DistributionMode::NativeSol,
+            ErrorCode::WrongDistributionMode
+        );
+        require!(pool.faucet.is_none(), ErrorCode::FaucetRequiresDirectClaim);
+        require!(pool.vesting.is_none(), ErrorCode::VestingRequiresDirectClaim);
+        require!(
+            pool.usd_pricing.is_none(),
+            ErrorCode::UsdPricingRequiresDirectClaim
+        );
+        require!(
+            pool.matching_sponsor.is_none(),
+            ErrorCode::MatchingRequiresDirectClaim
+        );
+        require!(
+            pool.receipt_expiry_secs == 0,
+            ErrorCode::ReceiptsRequireDirectClaim
+        );
+        require!(user_account.banned_at == 0, ErrorCode::UserBanned);
+        require!(user_account.is_active, ErrorCode::UserNotActive);
+        require!(
+            user_account.terms_version >= pool.terms_version,
+            ErrorCode::TermsNotAccepted
+        );
+        require!(user_account.paused_at == 0, ErrorCode::AccrualPaused);
+
+        apply_due_config(pool, current_timestamp);
+        let unboosted = UserAccount {
+            nft_boost_bps: 0,
+            ..(**user_account).clone()
+        };
+        let breakdown = compute_reward(pool, &unboosted, current_timestamp)?;
+        ensure_claim_interval(pool, user_account, &breakdown, current_timestamp)?;
+        require!(
+            breakdown.usd_priced_reward == 0,
+            ErrorCode::UsdPricingRequiresDirectClaim
+        );
+        let reward_amount = reveal_lottery(
+            pool,
+            user_account,
+            ctx.accounts.slot_hashes.as_ref(),
+            clock.slot,
+            breakdown.reward_amount,
+        )?;
+
+        let throttle_bps =
+            math::runway_throttle_bps(pool, ctx.accounts.vault.amount, current_timestamp)?;
+        let reward_amount = math::apply_throttle(reward_amount, throttle_bps)?
+            .min(lifetime_reward_remaining(pool, user_account)?)
+            .min(pool_budget_remaining(pool)?);
+        require!(reward_amount > 0, ErrorCode::NoRewardsAvailable);
+        ensure_co_signed(user_account, reward_amount, None)?;
+        require!(
+            pool.max_claim_per_tx == 0 || reward_amount <= pool.max_claim_per_tx,
+            ErrorCode::ClaimSessionRequired
+        );
+
+        if pool.require_region_attestation {
+            require!(
+                user_account.region_attested_at != 0,
+                ErrorCode::RegionAttestationRequired
+            );
+        }
+        let (reward_amount, withheld_amount) =
+            match load_region_policy(&ctx.accounts.region_policy)? {
+                Some(policy) => {
+                    math::apply_region_policy(reward_amount, &policy.action, pool.rounding)?
+                }
+                None => (reward_amount, 0),
+            };
+        let burn_amount = claim_burn_amount(pool, reward_amount)?;
+        let reward_amount = reward_amount
+            .checked_sub(burn_amount)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+        burn_from_vault(
+            pool,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            burn_amount,
+            1,
+        )?;
+
+        let tip = math::apply_bps(reward_amount, pool.crank_tip_bps, pool.rounding)?;
+        let user_amount = reward_amount
+            .checked_sub(tip)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let seeds = &[
+            b"reward_pool",
+            pool.authority.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        if pool.distribution_mode == DistributionMode::Vault {
+            require!(
+                ctx.accounts.vault.amount.saturating_sub(burn_amount) >= reward_amount,
+                ErrorCode::InsufficientVaultFunds
+            );
+        }
+        let mut fee_amounts = [0u64; 2];
+        let payouts = [
+            (ctx.accounts.user_token_account.to_account_info(), user_amount),
+            (ctx.accounts.cranker_token_account.to_account_info(), tip),
+        ];
+        for (i, (to, amount)) in payouts.into_iter().enumerate() {
+            if amount == 0 {
+                continue;
+            }
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            if pool.distribution_mode == DistributionMode::Vault {
+                let cpi_accounts = TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: mint_info.clone(),
+                    to,
+                    authority: pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+                fee_amounts[i] = fees::transfer_fee(&mint_info, amount, clock.epoch)?;
+            } else {
+                let cpi_accounts = MintTo {
+                    mint: mint_info.clone(),
+                    to,
+                    authority: pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::mint_to(cpi_ctx, amount)?;
+            }
+        }
+        let [user_fee, tip_fee] = fee_amounts;
+        let net_amount = user_amount
+            .checked_sub(user_fee)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+
+        user_account.total_earned = user_account
+            .total_earned
+            .checked_add(net_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        user_account.total_fees_paid = user_account
+            .total_fees_paid
+            .checked_add(user_fee)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        user_account.total_withheld = user_account
+            .total_withheld
+            .checked_add(withheld_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        let first_claim_of_day = math::is_first_claim_of_day(user_account, current_timestamp);
+        let released = user_account.record_claim(pool, &breakdown, current_timestamp)?;
+        pool.track_carryover(released, breakdown.carryover);
+
+        pool.total_distributed = pool
+            .total_distributed
+            .checked_add(reward_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_transfer_fees = pool
+            .total_transfer_fees
+            .checked_add(user_fee)
+            .and_then(|total| total.checked_add(tip_fee))
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_withheld = pool
+            .total_withheld
+            .checked_add(withheld_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_crank_tips = pool
+            .total_crank_tips
+            .checked_add(tip)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        record_mint_stats(
+            pool,
+            ctx.accounts.mint_stats.as_mut(),
+            reward_amount,
+            1,
+            current_timestamp,
+        )?;
+        deactivate_if_budget_spent(pool);
+        record_epoch_claim(
+            pool,
+            ctx.accounts.epoch.as_mut(),
+            reward_amount,
+            1,
+            current_timestamp,
+        )?;
+        record_daily_stats(
+            pool,
+            ctx.accounts.daily_stats.as_mut(),
+            reward_amount,
+            1,
+            u64::from(first_claim_of_day),
+            current_timestamp,
+        )?;
+        let referral_bonus = credit_referral(
+            pool,
+            user_account,
+            ctx.accounts.referrer_account.as_deref_mut(),
+            net_amount,
+        )?;
+        pool.total_referral_owed = pool
+            .total_referral_owed
+            .checked_add(referral_bonus)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        emit!(CrankClaimed {
+            pool: pool.key(),
+            user: user_account.authority,
+            cranker: ctx.accounts.cranker.key(),
+            amount: net_amount,
+            tip,
+        });
+        msg!(
+            "Cranked claim for {}: {} tokens, {} tip",
+            user_account.authority,
+            net_amount,
+            tip
+        );
+        Ok(())
+    }
+
+    /// Push payout: settle every eligible user in a page, paying each one's claim to their
+    /// associated token account and the cranker a `crank_tip_bps` tip out of each claim,
+    /// as `crank_claim` does for a single user.
+    ///
+    /// Remaining accounts are passed in groups of
+    /// `[user_account, user_token_account, denylist_entry, region_policy, referrer_account]`,
+    /// where the first four are as for `claim_for_members` and the referrer account is the
+    /// referrer's user account, or the program ID for users without one. Users who can't be
+    /// paid (too soon, paused, capped, denylisted, no token account, unrevealable lottery
+    /// draw, nothing to claim, more than the epoch has left) are skipped rather than failing
+    /// the page, and the page stops early once the vault can't cover the next claim.
+    pub fn settle_users<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleUsers<'info>>,
+    ) -> Result<()> {
+        let remaining = ctx.remaining_accounts;
+        require!(
+            !remaining.is_empty() && remaining.len() % 5 == 0,
+            ErrorCode::InvalidMemberAccounts
+        );
+        apply_due_config(&mut ctx.accounts.reward_pool, Clock::get()?.unix_timestamp);
+
+        let pool = &ctx.accounts.reward_pool;
+        require!(!pool.claims_paused, ErrorCode::ClaimsPaused);
+        require!(
+            pool.distribution_mode != DistributionMode::NativeSol,
+            ErrorCode::WrongDistributionMode
+        );
+        require!(pool.faucet.is_none(), ErrorCode::FaucetRequiresDirectClaim);
+        require!(pool.vesting.is_none(), ErrorCode::VestingRequiresDirectClaim);
+        require!(
+            pool.usd_pricing.is_none(),
+            ErrorCode::UsdPricingRequiresDirectClaim
+        );
+        require!(
+            pool.matching_sponsor.is_none(),
+            ErrorCode::MatchingRequiresDirectClaim
+        );
+        require!(
+            pool.receipt_expiry_secs == 0,
+            ErrorCode::ReceiptsRequireDirectClaim
+        );
+
+        let clock = Clock::get()?;
+        let current_timestamp = clock.unix_timestamp;
+        let cranker = ctx.accounts.cranker.key();
+        let mut vault_balance = ctx.accounts.vault.amount;
+        // Throttle every user by the same factor, fixed before the page pays anyone
+        let throttle_bps = math::runway_throttle_bps(pool, vault_balance, current_timestamp)?;
+        let mut budget_remaining = pool_budget_remaining(pool)?;
+        // Checked per user, so a claim the epoch can't cover skips that user alone
+        let mut epoch_remaining =
+            epoch_budget_remaining(pool, ctx.accounts.epoch.as_ref(), current_timestamp)?;
+        let mut batch_distributed: u64 = 0;
+        let mut batch_fees: u64 = 0;
+        let mut batch_withheld: u64 = 0;
+        let mut batch_tips: u64 = 0;
+        let mut batch_burned: u64 = 0;
+        let mut batch_released: u64 = 0;
+        let mut batch_referral: u64 = 0;
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let mut users_settled: u64 = 0;
+        let mut users_skipped: u64 = 0;
+        let mut first_claims_of_day: u64 = 0;
+
+        let seeds = &[
+            b"reward_pool",
+            pool.authority.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        for user_accounts in remaining.chunks(5) {
+            let (user_info, token_info, denylist_info, region_policy_info, referrer_info) = (
+                &user_accounts[0],
+                &user_accounts[1],
+                &user_accounts[2],
+                &user_accounts[3],
+                &user_accounts[4],
+            );
+
+            let mut user: Account<UserAccount> = Account::try_from(user_info)?;
+            let (expected_user, _) = Pubkey::find_program_address(
+                &[b"user_account", user.pool_seed(), user.authority.as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(
+                user_info.key(),
+                expected_user,
+                ErrorCode::InvalidMemberAccounts
+            );
+            require_keys_eq!(user.pool, pool.key(), ErrorCode::UserPoolMismatch);
+            let (expected_denylist_entry, _) = Pubkey::find_program_address(
+                &[
+                    b"denylist",
+                    pool.key().as_ref(),
+                    user.authority.as_ref(),
+                ],
+                ctx.program_id,
+            );
+            require_keys_eq!(
+                denylist_info.key(),
+                expected_denylist_entry,
+                ErrorCode::InvalidMemberAccounts
+            );
+            if !denylist_info.data_is_empty() {
+                emit!(DenylistBlocked {
+                    pool: pool.key(),
+                    address: user.authority,
+                });
+                msg!("Skipping user {}: denylisted", user.authority);
+                users_skipped += 1;
+                continue;
+            }
+            require_keys_eq!(
+                token_info.key(),
+                get_associated_token_address_with_program_id(
+                    &user.authority,
+                    &pool.mint,
+                    &ctx.accounts.token_program.key(),
+                ),
+                ErrorCode::InvalidMemberAccounts
+            );
+
+            if token_info.data_is_empty() {
+                msg!("Skipping user {}: no token account", user.authority);
+                users_skipped += 1;
+                continue;
+            }
+            if !user.is_active || user.banned_at != 0 {
+                msg!("Skipping inactive user {}", user.authority);
+                users_skipped += 1;
+                continue;
+            }
+            if user.terms_version < pool.terms_version {
+                msg!("Skipping user {}: terms not accepted", user.authority);
+                users_skipped += 1;
+                continue;
+            }
+            if user.paused_at != 0 {
+                msg!("Skipping user {}: accrual paused", user.authority);
+                users_skipped += 1;
+                continue;
+            }
+            if user.lottery_commit_slot != 0
+                && (user.lottery_commit_slot >= clock.slot
+                    || (pool.lottery_chance_bps > 0 && ctx.accounts.slot_hashes.is_none()))
+            {
+                msg!("Skipping user {}: lottery draw not revealable", user.authority);
+                users_skipped += 1;
+                continue;
+            }
+
+            // Pages cannot re-check NFT ownership, so the NFT boost only applies to direct claims
+            let unboosted = UserAccount {
+                nft_boost_bps: 0,
+                ..(*user).clone()
+            };
+            let breakdown = compute_reward(pool, &unboosted, current_timestamp)?;
+            if !breakdown.interval_met {
+                msg!("Skipping user {}: claim too soon", user.authority);
+                users_skipped += 1;
+                continue;
+            }
+            if breakdown.usd_priced_reward > 0 {
+                msg!("Skipping user {}: USD-priced accrual", user.authority);
+                users_skipped += 1;
+                continue;
+            }
+            if pool.max_claims_per_day > 0
+                && math::claims_today(&user, current_timestamp) >= pool.max_claims_per_day
+            {
+                msg!("Skipping user {}: daily claim limit reached", user.authority);
+                users_skipped += 1;
+                continue;
+            }
+
+            let reward_amount = reveal_lottery(
+                pool,
+                &mut user,
+                ctx.accounts.slot_hashes.as_ref(),
+                clock.slot,
+                breakdown.reward_amount,
+            )?;
+            let reward_amount = math::apply_throttle(reward_amount, throttle_bps)?
+                .min(lifetime_reward_remaining(pool, &user)?)
+                .min(budget_remaining);
+            if reward_amount == 0 {
+                msg!("Skipping user {}: nothing to claim", user.authority);
+                users_skipped += 1;
+                continue;
+            }
+            if user.co_signer.is_some() && reward_amount > user.solo_claim_limit {
+                msg!("Skipping user {}: co-signature required", user.authority);
+                users_skipped += 1;
+                continue;
+            }
+            if pool.max_claim_per_tx > 0 && reward_amount > pool.max_claim_per_tx {
+                msg!("Skipping user {}: claim session required", user.authority);
+                users_skipped += 1;
+                continue;
+            }
+
+            if pool.require_region_attestation && user.region_attested_at == 0 {
+                msg!("Skipping user {}: region not attested", user.authority);
+                users_skipped += 1;
+                continue;
+            }
+            let (expected_region_policy, _) = Pubkey::find_program_address(
+                &[
+                    b"region_policy",
+                    pool.key().as_ref(),
+                    &user.region_code.to_le_bytes(),
+                ],
+                ctx.program_id,
+            );
+            require_keys_eq!(
+                region_policy_info.key(),
+                expected_region_policy,
+                ErrorCode::InvalidMemberAccounts
+            );
+            let (reward_amount, withheld_amount) = match load_region_policy(region_policy_info)? {
+                Some(policy) => {
+                    math::apply_region_policy(reward_amount, &policy.action, pool.rounding)?
+                }
+                None => (reward_amount, 0),
+            };
+
+            if pool.distribution_mode == DistributionMode::Vault && vault_balance < reward_amount {
+                msg!("Vault exhausted; stopping before {}", user.authority);
+                break;
+            }
+            // Burns are made in one instruction for the whole page
+            let burn_amount = claim_burn_amount(pool, reward_amount)?;
+            let reward_amount = reward_amount
+                .checked_sub(burn_amount)
+                .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+            if epoch_remaining.is_some_and(|remaining| reward_amount > remaining) {
+                msg!("Skipping user {}: epoch budget exhausted", user.authority);
+                users_skipped += 1;
+                continue;
+            }
+            vault_balance = vault_balance.saturating_sub(burn_amount);
+            budget_remaining = budget_remaining.saturating_sub(burn_amount);
+            batch_burned = batch_burned
+                .checked_add(burn_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            let tip = math::apply_bps(reward_amount, pool.crank_tip_bps, pool.rounding)?;
+            let user_amount = reward_amount
+                .checked_sub(tip)
+                .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let fee_amount = if pool.distribution_mode == DistributionMode::Vault {
+                let cpi_accounts = TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: mint_info.clone(),
+                    to: token_info.clone(),
+                    authority: ctx.accounts.reward_pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::transfer_checked(
+                    cpi_ctx,
+                    user_amount,
+                    ctx.accounts.mint.decimals,
+                )?;
+                vault_balance = vault_balance
+                    .checked_sub(reward_amount)
+                    .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+                fees::transfer_fee(&mint_info, user_amount, clock.epoch)?
+            } else {
+                let cpi_accounts = MintTo {
+                    mint: mint_info.clone(),
+                    to: token_info.clone(),
+                    authority: ctx.accounts.reward_pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::mint_to(cpi_ctx, user_amount)?;
+                0
+            };
+            let net_amount = user_amount
+                .checked_sub(fee_amount)
+                .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+
+            user.total_earned = user
+                .total_earned
+                .checked_add(net_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            user.total_fees_paid = user
+                .total_fees_paid
+                .checked_add(fee_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            user.total_withheld = user
+                .total_withheld
+                .checked_add(withheld_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            if math::is_first_claim_of_day(&user, current_timestamp) {
+                first_claims_of_day += 1;
+            }
+            let released = user.record_claim(pool, &breakdown, current_timestamp)?;
+            batch_released = batch_released.saturating_add(released);
+            user.exit(ctx.program_id)?;
+            if user.referrer.is_some() && pool.referral_bps > 0 {
+                let mut referrer: Account<UserAccount> = Account::try_from(referrer_info)
+                    .map_err(|_| error!(ErrorCode::ReferrerAccountRequired))?;
+                let (expected_referrer, _) = Pubkey::find_program_address(
+                    &[b"user_account", referrer.authority.as_ref()],
+                    ctx.program_id,
+                );
+                require_keys_eq!(
+                    referrer_info.key(),
+                    expected_referrer,
+                    ErrorCode::InvalidMemberAccounts
+                );
+                require_keys_eq!(referrer.pool, pool.key(), ErrorCode::UserPoolMismatch);
+                let bonus = credit_referral(pool, &user, Some(&mut *referrer), net_amount)?;
+                batch_referral = batch_referral
+                    .checked_add(bonus)
+                    .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+                referrer.exit(ctx.program_id)?;
+            }
+
+            emit!(CrankClaimed {
+                pool: pool.key(),
+                user: user.authority,
+                cranker,
+                amount: net_amount,
+                tip,
+            });
+
+            batch_distributed = batch_distributed
+                .checked_add(reward_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            budget_remaining = budget_remaining.saturating_sub(reward_amount);
+            epoch_remaining = epoch_remaining.map(|remaining| remaining - reward_amount);
+            batch_fees = batch_fees
+                .checked_add(fee_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            batch_withheld = batch_withheld
+                .checked_add(withheld_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            batch_tips = batch_tips
+                .checked_add(tip)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            users_settled = users_settled
+                .checked_add(1)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        }
+
+        // Tips are paid in one transfer for the whole page
+        if batch_tips > 0 {
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let to = ctx.accounts.cranker_token_account.to_account_info();
+            if ctx.accounts.reward_pool.distribution_mode == DistributionMode::Vault {
+                let cpi_accounts = TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: mint_info.clone(),
+                    to,
+                    authority: ctx.accounts.reward_pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::transfer_checked(cpi_ctx, batch_tips, ctx.accounts.mint.decimals)?;
+                batch_fees = fees::transfer_fee(&mint_info, batch_tips, clock.epoch)?
+                    .checked_add(batch_fees)
+                    .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            } else {
+                let cpi_accounts = MintTo {
+                    mint: mint_info.clone(),
+                    to,
+                    authority: ctx.accounts.reward_pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::mint_to(cpi_ctx, batch_tips)?;
+            }
+        }
+
+        let pool = &mut ctx.accounts.reward_pool;
+        burn_from_vault(
+            pool,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            batch_burned,
+            users_settled,
+        )?;
+        pool.track_carryover(batch_released, 0);
+        pool.total_referral_owed = pool
+            .total_referral_owed
+            .checked_add(batch_referral)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_distributed = pool
+            .total_distributed
+            .checked_add(batch_distributed)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_transfer_fees = pool
+            .total_transfer_fees
+            .checked_add(batch_fees)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_withheld = pool
+            .total_withheld
+            .checked_add(batch_withheld)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_crank_tips = pool
+            .total_crank_tips
+            .checked_add(batch_tips)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        record_mint_stats(
+            pool,
+            ctx.accounts.mint_stats.as_mut(),
+            batch_distributed,
+            users_settled,
+            current_timestamp,
+        )?;
+        deactivate_if_budget_spent(pool);
+        record_epoch_claim(
+            pool,
+            ctx.accounts.epoch.as_mut(),
+            batch_distributed,
+            users_settled,
+            current_timestamp,
+        )?;
+        record_daily_stats(
+            pool,
+            ctx.accounts.daily_stats.as_mut(),
+            batch_distributed,
+            users_settled,
+            first_claims_of_day,
+            current_timestamp,
+        )?;
+
+        msg!(
+            "Settled {} users ({} skipped) for {} tokens, {} tips to {}",
+            users_settled,
+            users_skipped,
+            batch_distributed,
+            batch_tips,
+            cranker
+        );
+        Ok(())
+    }
+
+    /// Switch the pool between raw mint units and normalized 9-decimal accrual (admin only).
+    /// The rate and daily cap change units with it, so both are restated here.
+    pub fn set_normalized_accrual(
+        ctx: Context<SetNormalizedAccrual>,
+        enabled: bool,
+        reward_rate_per_hour: u64,
+        max_daily_reward: u64,
+    ) -> Result<()> {
+        let mint_decimals = ctx.accounts.mint.decimals;
+        require!(
+            !enabled || mint_decimals <= math::NORMALIZED_DECIMALS,
+            ErrorCode::UnsupportedMintDecimals
+        );
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.normalized_accrual = enabled;
+        pool.mint_decimals = mint_decimals;
+        pool.reward_rate_per_hour = reward_rate_per_hour;
+        pool.reward_rate_fraction = 0;
+        pool.max_daily_reward = max_daily_reward;
+
+        msg!(
+            "Normalized accrual {} at {} per hour",
+            if enabled { "enabled" } else { "disabled" },
+            reward_rate_per_hour
+        );
+        Ok(())
+    }
+
+    /// Schedule the campaign (admin only): nothing accrues before `accrual_start` and accrual
+    /// stops at `accrual_end`, while claims for what accrued stay open. 0 leaves either
+    /// side unbounded.
+    pub fn set_accrual_window(
+        ctx: Context<UpdatePoolConfig>,
+        accrual_start: i64,
+        accrual_end: i64,
+    ) -> Result<()> {
+        require!(
+            accrual_start >= 0 && (accrual_end == 0 || accrual_end > accrual_start),
+            ErrorCode::InvalidAccrualWindow
+        );
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.accrual_start = accrual_start;
+        pool.accrual_end = accrual_end;
+
+        msg!("Accrual window set: {} to {}", accrual_start, accrual_end);
+        Ok(())
+    }
+
+    /// Start running the pool in epochs of `duration_secs`, each capped at `budget`
+    /// distributed tokens (admin only). Epoch 0 starts now.
+    pub fn start_epochs(ctx: Context<StartEpochs>, duration_secs: i64, budget: u64) -> Result<()> {
+        require!(duration_secs > 0 && budget > 0, ErrorCode::InvalidEpochConfig);
+        let pool = &mut ctx.accounts.reward_pool;
+        require!(pool.epoch_duration_secs == 0, ErrorCode::EpochsAlreadyStarted);
+
+        let now = Clock::get()?.unix_timestamp;
+        let end_time = now
+            .checked_add(duration_secs)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        ctx.accounts.epoch.set_inner(Epoch {
+            pool: pool.key(),
+            index: 0,
+            start_time: now,
+            end_time,
+            budget,
+            distributed_this_epoch: 0,
+            claim_count: 0,
+            bump: ctx.bumps.epoch,
+        });
+        pool.epoch_duration_secs = duration_secs;
+        pool.epoch_budget = budget;
+        pool.current_epoch = 0;
+
+        msg!("Epochs started: {}s each, budget {}", duration_secs, budget);
+        Ok(())
+    }
+
+    /// Change the duration and budget used from the next epoch on (admin only)
+    pub fn set_epoch_config(
+        ctx: Context<UpdatePoolConfig>,
+        duration_secs: i64,
+        budget: u64,
+    ) -> Result<()> {
+        require!(duration_secs > 0 && budget > 0, ErrorCode::InvalidEpochConfig);
+        let pool = &mut ctx.accounts.reward_pool;
+        require!(pool.epoch_duration_secs > 0, ErrorCode::EpochsNotStarted);
+        pool.epoch_duration_secs = duration_secs;
+        pool.epoch_budget = budget;
+
+        msg!("Epoch config updated: {}s each, budget {}", duration_secs, budget);
+        Ok(())
+    }
+
+    /// Open the next epoch once the current one has ended (permissionless; the caller pays
+    /// rent for the new epoch account)
+    pub fn roll_epoch(ctx: Context<RollEpoch>, next_index: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let current = &ctx.accounts.current_epoch;
+        require!(now >= current.end_time, ErrorCode::EpochStillRunning);
+
+        let pool = &mut ctx.accounts.reward_pool;
+        let end_time = now
+            .checked_add(pool.epoch_duration_secs)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        ctx.accounts.next_epoch.set_inner(Epoch {
+            pool: pool.key(),
+            index: next_index,
+            start_time: now,
+            end_time,
+            budget: pool.epoch_budget,
+            distributed_this_epoch: 0,
+            claim_count: 0,
+            bump: ctx.bumps.next_epoch,
+        });
+        pool.current_epoch = next_index;
+
+        emit!(EpochRolled {
+            pool: pool.key(),
+            index: next_index,
+            previous_distributed: current.distributed_this_epoch,
+            previous_claims: current.claim_count,
+            start_time: now,
+            end_time,
+            budget: pool.epoch_budget,
+        });
+        msg!("Epoch {} started", next_index);
+        Ok(())
+    }
+
+    /// Enable or disable runway-aware claim throttling (admin only)
+    pub fn set_runway_throttle(ctx: Context<UpdatePoolConfig>, enabled: bool) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        require!(
+            pool.distribution_mode == DistributionMode::Vault,
+            ErrorCode::WrongDistributionMode
+        );
+        pool.runway_throttle = enabled;
+
+        msg!("Runway throttle {}", if enabled { "enabled" } else { "disabled" });
+        Ok(())
+    }
+
+    /// Tag claim and emergency-withdrawal transfers with an SPL Memo (admin only), for
+    /// exchanges and accounting tools that reconcile incoming transfers by memo. Claims
+    /// then need `memo_program`.
+    pub fn set_transfer_memos(ctx: Context<UpdatePoolConfig>, enabled: bool) -> Result<()> {
+        ctx.accounts.reward_pool.transfer_memos = enabled;
+
+        msg!("Transfer memos {}", if enabled { "enabled" } else { "disabled" });
+        Ok(())
+    }
+
+    /// Set the share of cranked claims paid to the cranker (admin only)
+    pub fn set_crank_tip_bps(ctx: Context<UpdatePoolConfig>, crank_tip_bps: u16) -> Result<()> {
+        require!(
+            u64::from(crank_tip_bps) <= math::BPS_DENOMINATOR,
+            ErrorCode::InvalidBasisPoints
+        );
+        ctx.accounts.reward_pool.crank_tip_bps = crank_tip_bps;
+
+        msg!("Crank tip set to {} bps", crank_tip_bps);
+        Ok(())
+    }
+
+    /// Approve (or revoke) a manager wallet allowed to trigger claims on the user's behalf
+    pub fn set_claim_manager(
+        ctx: Context<SetClaimManager>,
+        manager: Option<Pubkey>,
+    ) -> Result<()> {
+        let user_account = &mut ctx.accounts.user_account;
+        user_account.claim_manager = manager;
+
+        match manager {
+            Some(manager) => msg!("Claim manager set: {}", manager),
+            None => msg!("Claim manager cleared"),
+        }
+        Ok(())
+    }
+
+    /// Let `delegate` (e.g. a hot wallet) sign `claim_rewards` for the caller; rewards still
+    /// land in the caller's own associated token account
+    pub fn set_claim_delegate(
+        ctx: Context<SetClaimDelegate>,
+        delegate: Option<Pubkey>,
+    ) -> Result<()> {
+        let user_account = &mut ctx.accounts.user_account;
+        user_account.claim_delegate = delegate;
+
+        match delegate {
+            Some(delegate) => msg!("Claim delegate set: {}", delegate),
+            None => msg!("Claim delegate cleared"),
+        }
+        Ok(())
+    }
+
+    /// Set the caller's leaderboard `nickname` and profile `metadata_uri` (empty clears
+    /// either). The account is resized to fit, paying or refunding the rent difference.
+    pub fn set_user_metadata(
+        ctx: Context<SetUserMetadata>,
+        nickname: String,
+        metadata_uri: String,
+    ) -> Result<()> {
+        require!(
+            nickname.len() <= MAX_NICKNAME_LEN && metadata_uri.len() <= MAX_USER_METADATA_URI_LEN,
+            ErrorCode::UserMetadataTooLong
+        );
+        let user_account = &mut ctx.accounts.user_account;
+        user_account.nickname = nickname;
+        user_account.metadata_uri = metadata_uri;
+
+        msg!("User metadata set: {:?}", user_account.nickname);
+        Ok(())
+    }
+
+    /// Claim for every member that approved the signing manager.
+    ///
+    /// Remaining accounts are passed in groups of
+    /// `[user_account, user_token_account, denylist_entry, region_policy, referrer_account]`,
+    /// where the token account must be the member's own associated token account, the
+    /// denylist entry and region policy are the member's PDAs (which may not exist), and the
+    /// referrer account is the member's referrer's user account, or any placeholder (such as
+    /// the program ID) for members without one. Members with nothing claimable yet, or with
+    /// a lottery draw that can't be revealed yet, are skipped rather than failing the batch.
+    /// On pools allowing partial payouts, a short vault pays what it holds and the batch
+    /// stops there; the unpaid share stays accrued as it does for direct claims.
+    pub fn claim_for_members<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimForMembers<'info>>,
+    ) -> Result<()> {
+        let remaining = ctx.remaining_accounts;
+        require!(
+            !remaining.is_empty() && remaining.len() % 5 == 0,
+            ErrorCode::InvalidMemberAccounts
+        );
+        apply_due_config(&mut ctx.accounts.reward_pool, Clock::get()?.unix_timestamp);
+
+        let pool = &ctx.accounts.reward_pool;
+        require!(!pool.claims_paused, ErrorCode::ClaimsPaused);
+        require!(
+            pool.distribution_mode != DistributionMode::NativeSol,
+            ErrorCode::WrongDistributionMode
+        );
+        // Faucet claims must each carry the claimant's own captcha attestation
+        require!(pool.faucet.is_none(), ErrorCode::FaucetRequiresDirectClaim);
+        require!(pool.vesting.is_none(), ErrorCode::VestingRequiresDirectClaim);
+        require!(
+            pool.usd_pricing.is_none(),
+            ErrorCode::UsdPricingRequiresDirectClaim
+        );
+        require!(
+            pool.matching_sponsor.is_none(),
+            ErrorCode::MatchingRequiresDirectClaim
+        );
+        require!(
+            pool.receipt_expiry_secs == 0,
+            ErrorCode::ReceiptsRequireDirectClaim
+        );
+
+        let clock = Clock::get()?;
+        let current_timestamp = clock.unix_timestamp;
+        let manager = ctx.accounts.manager.key();
+        let mut vault_balance = ctx.accounts.vault.amount;
+        // Throttle every member by the same factor, fixed before the batch pays anyone
+        let throttle_bps = math::runway_throttle_bps(pool, vault_balance, current_timestamp)?;
+        let mut budget_remaining = pool_budget_remaining(pool)?;
+        let mut batch_distributed: u64 = 0;
+        let mut batch_fees: u64 = 0;
+        let mut batch_withheld: u64 = 0;
+        let mut batch_burned: u64 = 0;
+        let mut batch_released: u64 = 0;
+        let mut batch_carried: u64 = 0;
+        let mut batch_referral: u64 = 0;
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let mut members_paid: u64 = 0;
+        let mut first_claims_of_day: u64 = 0;
+
+        let seeds = &[
+            b"reward_pool",
+            pool.authority.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        for member_accounts in remaining.chunks(5) {
+            let (member_info, token_info, denylist_info, region_policy_info, referrer_info) = (
+                &member_accounts[0],
+                &member_accounts[1],
+                &member_accounts[2],
+                &member_accounts[3],
+                &member_accounts[4],
+            );
+
+            let mut member: Account<UserAccount> = Account::try_from(member_info)?;
+            let (expected_member, _) = Pubkey::find_program_address(
+                &[b"user_account", member.pool_seed(), member.authority.as_ref()],
+                ctx.program_id,
+            );
+            require_keys_eq!(
+                member_info.key(),
+                expected_member,
+                ErrorCode::InvalidMemberAccounts
+            );
+            require_keys_eq!(member.pool, pool.key(), ErrorCode::UserPoolMismatch);
+            require!(
+                member.claim_manager == Some(manager),
+                ErrorCode::NotClaimManager
+            );
+            let (expected_denylist_entry, _) = Pubkey::find_program_address(
+                &[
+                    b"denylist",
+                    pool.key().as_ref(),
+                    member.authority.as_ref(),
+                ],
+                ctx.program_id,
+            );
+            require_keys_eq!(
+                denylist_info.key(),
+                expected_denylist_entry,
+                ErrorCode::InvalidMemberAccounts
+            );
+            ensure_not_denylisted(denylist_info, pool.key(), member.authority)?;
+            require_keys_eq!(
+                token_info.key(),
+                get_associated_token_address_with_program_id(
+                    &member.authority,
+                    &pool.mint,
+                    &ctx.accounts.token_program.key(),
+                ),
+                ErrorCode::InvalidMemberAccounts
+            );
+
+            if !member.is_active {
+                msg!("Skipping inactive member {}", member.authority);
+                continue;
+            }
+            if member.terms_version < pool.terms_version {
+                msg!("Skipping member {}: terms not accepted", member.authority);
+                continue;
+            }
+            if member.paused_at != 0 {
+                msg!("Skipping member {}: accrual paused", member.authority);
+                continue;
+            }
+            if member.lottery_commit_slot != 0
+                && (member.lottery_commit_slot >= clock.slot
+                    || (pool.lottery_chance_bps > 0 && ctx.accounts.slot_hashes.is_none()))
+            {
+                msg!("Skipping member {}: lottery draw not revealable", member.authority);
+                continue;
+            }
+
+            // Batches cannot re-check NFT ownership, so the NFT boost only applies to direct claims
+            let unboosted = UserAccount {
+                nft_boost_bps: 0,
+                ..(*member).clone()
+            };
+            let mut breakdown = compute_reward(pool, &unboosted, current_timestamp)?;
+            if !breakdown.interval_met {
+                msg!("Skipping member {}: claim too soon", member.authority);
+                continue;
+            }
+            if breakdown.usd_priced_reward > 0 {
+                msg!("Skipping member {}: USD-priced accrual", member.authority);
+                continue;
+            }
+            if pool.max_claims_per_day > 0
+                && math::claims_today(&member, current_timestamp) >= pool.max_claims_per_day
+            {
+                msg!("Skipping member {}: daily claim limit reached", member.authority);
+                continue;
+            }
+
+            if pool.max_lifetime_reward_per_user > 0
+                && member.total_earned >= pool.max_lifetime_reward_per_user
+            {
+                msg!("Skipping member {}: lifetime cap reached", member.authority);
+                continue;
+            }
+            let reward_amount = reveal_lottery(
+                pool,
+                &mut member,
+                ctx.accounts.slot_hashes.as_ref(),
+                clock.slot,
+                breakdown.reward_amount,
+            )?;
+            let reward_amount = math::apply_throttle(reward_amount, throttle_bps)?
+                .min(lifetime_reward_remaining(pool, &member)?)
+                .min(budget_remaining);
+            if reward_amount == 0 {
+                continue;
+            }
+            if member.co_signer.is_some() && reward_amount > member.solo_claim_limit {
+                msg!("Skipping member {}: co-signature required", member.authority);
+                continue;
+            }
+
+            if pool.require_region_attestation && member.region_attested_at == 0 {
+                msg!("Skipping member {}: region not attested", member.authority);
+                continue;
+            }
+            let (expected_region_policy, _) = Pubkey::find_program_address(
+                &[
+                    b"region_policy",
+                    pool.key().as_ref(),
+                    &member.region_code.to_le_bytes(),
+                ],
+                ctx.program_id,
+            );
+            require_keys_eq!(
+                region_policy_info.key(),
+                expected_region_policy,
+                ErrorCode::InvalidMemberAccounts
+            );
+            let (reward_amount, withheld_amount) = match load_region_policy(region_policy_info)? {
+                Some(policy) => {
+                    math::apply_region_policy(reward_amount, &policy.action, pool.rounding)?
+                }
+                None => (reward_amount, 0),
+            };
+            // Members after the one a short vault paid in part get nothing left to pay
+            if pool.distribution_mode == DistributionMode::Vault
+                && pool.allow_partial_payout
+                && vault_balance == 0
+            {
+                msg!("Vault exhausted; stopping before {}", member.authority);
+                break;
+            }
+            // Burns are made in one instruction for the whole batch
+            let burn_amount = claim_burn_amount(pool, reward_amount)?;
+            let reward_amount = reward_amount
+                .checked_sub(burn_amount)
+                .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+
+            let (payout_amount, fee_amount, burn_amount) = match pool.distribution_mode {
+                DistributionMode::Vault => {
+                    let gross_amount = if pool.gross_up_transfer_fees {
+                        fees::gross_up(&mint_info, reward_amount, clock.epoch)?
+                    } else {
+                        reward_amount
+                    };
+                    let required = gross_amount
+                        .checked_add(burn_amount)
+                        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+                    if vault_balance < required && pool.yield_deployed > 0 {
+                        return err!(ErrorCode::YieldWithdrawalRequired);
+                    }
+                    let covered = math::vault_payout(pool, vault_balance, required)?;
+                    vault_balance = vault_balance
+                        .checked_sub(covered)
+                        .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+
+                    // A short vault pays and burns what it holds in proportion; the unpaid
+                    // share of the claim stays accrued for a later claim
+                    let burn_amount = if covered < required {
+                        carry_unpaid_share(&mut breakdown, covered, required)?;
+                        fixed_point::mul_div(burn_amount, covered, required, RoundingMode::Floor)?
+                    } else {
+                        burn_amount
+                    };
+                    let payout_amount = covered - burn_amount;
+
+                    let cpi_accounts = TransferChecked {
+                        from: ctx.accounts.vault.to_account_info(),
+                        mint: mint_info.clone(),
+                        to: token_info.clone(),
+                        authority: ctx.accounts.reward_pool.to_account_info(),
+                    };
+                    let cpi_program = ctx.accounts.token_program.to_account_info();
+                    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+                    token_interface::transfer_checked(
+                        cpi_ctx,
+                        payout_amount,
+                        ctx.accounts.mint.decimals,
+                    )?;
+
+                    let fee_amount = fees::transfer_fee(&mint_info, payout_amount, clock.epoch)?;
+                    (payout_amount, fee_amount, burn_amount)
+                }
+                DistributionMode::MintOnClaim => {
+                    let cpi_accounts = MintTo {
+                        mint: mint_info.clone(),
+                        to: token_info.clone(),
+                        authority: ctx.accounts.reward_pool.to_account_info(),
+                    };
+                    let cpi_program = ctx.accounts.token_program.to_account_info();
+                    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+                    token_interface::mint_to(cpi_ctx, reward_amount)?;
+                    (reward_amount, 0, burn_amount)
+                }
+                DistributionMode::NativeSol => return err!(ErrorCode::WrongDistributionMode),
+            };
+            let net_amount = payout_amount
+                .checked_sub(fee_amount)
+                .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+            budget_remaining = budget_remaining.saturating_sub(burn_amount);
+            batch_burned = batch_burned
+                .checked_add(burn_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+            member.total_earned = member
+                .total_earned
+                .checked_add(net_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            member.total_fees_paid = member
+                .total_fees_paid
+                .checked_add(fee_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            member.total_withheld = member
+                .total_withheld
+                .checked_add(withheld_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            if math::is_first_claim_of_day(&member, current_timestamp) {
+                first_claims_of_day += 1;
+            }
+            let released = member.record_claim(pool, &breakdown, current_timestamp)?;
+            batch_released = batch_released.saturating_add(released);
+            batch_carried = batch_carried.saturating_add(breakdown.carryover);
+            member.exit(ctx.program_id)?;
+            if member.referrer.is_some() && pool.referral_bps > 0 {
+                let mut referrer: Account<UserAccount> = Account::try_from(referrer_info)
+                    .map_err(|_| error!(ErrorCode::ReferrerAccountRequired))?;
+                let (expected_referrer, _) = Pubkey::find_program_address(
+                    &[b"user_account", referrer.authority.as_ref()],
+                    ctx.program_id,
+                );
+                require_keys_eq!(
+                    referrer_info.key(),
+                    expected_referrer,
+                    ErrorCode::InvalidMemberAccounts
+                );
+                require_keys_eq!(referrer.pool, pool.key(), ErrorCode::UserPoolMismatch);
+                let bonus = credit_referral(pool, &member, Some(&mut *referrer), net_amount)?;
+                batch_referral = batch_referral
+                    .checked_add(bonus)
+                    .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+                referrer.exit(ctx.program_id)?;
+            }
+
+            batch_distributed = batch_distributed
+                .checked_add(payout_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            budget_remaining = budget_remaining.saturating_sub(payout_amount);
+            batch_fees = batch_fees
+                .checked_add(fee_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            batch_withheld = batch_withheld
+                .checked_add(withheld_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            members_paid = members_paid
+                .checked_add(1)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        }
+
+        let pool = &mut ctx.accounts.reward_pool;
+        burn_from_vault(
+            pool,
+            &ctx.accounts.vault,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            batch_burned,
+            members_paid,
+        )?;
+        pool.track_carryover(batch_released, batch_carried);
+        pool.total_referral_owed = pool
+            .total_referral_owed
+            .checked_add(batch_referral)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_distributed = pool
+            .total_distributed
+            .checked_add(batch_distributed)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_transfer_fees = pool
+            .total_transfer_fees
+            .checked_add(batch_fees)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_withheld = pool
+            .total_withheld
+            .checked_add(batch_withheld)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        record_mint_stats(
+            pool,
+            ctx.accounts.mint_stats.as_mut(),
+            batch_distributed,
+            members_paid,
+            current_timestamp,
+        )?;
+        deactivate_if_budget_spent(pool);
+        record_epoch_claim(
+            pool,
+            ctx.accounts.epoch.as_mut(),
+            batch_distributed,
+            members_paid,
+            current_timestamp,
+        )?;
+        record_daily_stats(
+            pool,
+            ctx.accounts.daily_stats.as_mut(),
+            batch_distributed,
+            members_paid,
+            first_claims_of_day,
+            current_timestamp,
+        )?;
+
+        msg!(
+            "Manager {} claimed {} tokens for {} members",
+            manager,
+            batch_distributed,
+            members_paid
+        );
+        Ok(())
+    }
+
+    /// Claim from every pool the signer belongs to in one transaction.
+    ///
+    /// Remaining accounts are passed in `[reward_pool, user_account, vault, destination]`
+    /// groups, where the user account is the signer's registration account or `join_pool`
+    /// membership in that pool and the destination is the signer's associated token
+    /// account for the pool's mint. Each group emits `PoolClaimed`, or `PoolClaimFailed`
+    /// with the error a direct claim would have returned, without failing the other pools.
+    /// That includes groups whose accounts don't match their pool, and claims that need
+    /// accounts a group doesn't have: pools paying native SOL, minting, burning or on
+    /// Token-2022, pools with faucets, vesting, USD pricing, matching, receipts, stats,
+    /// epochs, denylist entries or region policies, and users with referrers or NFT boosts.
+    pub fn claim_all<'info>(ctx: Context<'_, '_, 'info, 'info, ClaimAll<'info>>) -> Result<()> {
+        let remaining = ctx.remaining_accounts;
+        require!(
+            !remaining.is_empty() && remaining.len() % 4 == 0,
+            ErrorCode::InvalidClaimAllAccounts
+        );
+
+        let clock = Clock::get()?;
+        let authority = ctx.accounts.authority.key();
+        let slot_hashes = ctx.accounts.slot_hashes.as_ref();
+        let mut pools_claimed: u64 = 0;
+
+        for group in remaining.chunks(4) {
+            let (pool_info, vault_info, destination_info) = (&group[0], &group[2], &group[3]);
+
+            let PooledClaim {
+                mut pool,
+                mut user_account,
+                breakdown,
+                payout_amount,
+            } = match prepare_pooled_claim(group, authority, slot_hashes, &clock) {
+                Ok(claim) => claim,
+                Err(error) => {
+                    msg!("Claim from pool {} failed: {}", pool_info.key(), error);
+                    emit!(PoolClaimFailed {
+                        pool: pool_info.key(),
+                        user: authority,
+                        error_code: error_code_number(&error),
+                    });
+                    continue;
+                }
+            };
+
+            let seeds = &[b"reward_pool", pool.authority.as_ref(), &[pool.bump]];
+            let signer = &[&seeds[..]];
+            let cpi_accounts = Transfer {
+                from: vault_info.clone(),
+                to: destination_info.clone(),
+                authority: pool_info.clone(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                cpi_accounts,
+                signer,
+            );
+            anchor_spl::token::transfer(cpi_ctx, payout_amount)?;
+
+            user_account.total_earned = user_account
+                .total_earned
+                .checked_add(payout_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            let released = user_account.record_claim(&pool, &breakdown, clock.unix_timestamp)?;
+            pool.track_carryover(released, breakdown.carryover);
+
+            pool.total_distributed = pool
+                .total_distributed
+                .checked_add(payout_amount)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            deactivate_if_budget_spent(&mut pool);
+            pool.exit(ctx.program_id)?;
+            user_account.exit(ctx.program_id)?;
+
+            emit!(PoolClaimed {
+                pool: pool.key(),
+                user: authority,
+                amount: payout_amount,
+            });
+            pools_claimed = pools_claimed
+                .checked_add(1)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        }
+
+        msg!(
+            "{} claimed from {} of {} pools",
+            authority,
+            pools_claimed,
+            remaining.len() / 4
+        );
+        Ok(())
+    }
+
+    /// Configure the dormancy sweep policy (admin only)
+    pub fn set_dormancy_policy(
+        ctx: Context<UpdatePoolConfig>,
+        dormancy_period_secs: i64,
+        dormancy_grace_secs: i64,
+        rent_recovery_fund: Pubkey,
+    ) -> Result<()> {
+        require!(
+            dormancy_period_secs >= 0 && dormancy_grace_secs >= 0,
+            ErrorCode::InvalidDormancyPolicy
+        );
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.dormancy_period_secs = dormancy_period_secs;
+        pool.dormancy_grace_secs = dormancy_grace_secs;
+        pool.rent_recovery_fund = rent_recovery_fund;
+
+        msg!(
+            "Dormancy policy updated: {}s dormancy, {}s grace",
+            dormancy_period_secs,
+            dormancy_grace_secs
+        );
+        Ok(())
+    }
+
+    /// Flag a dormant user account for sweeping and emit an on-chain warning (crank)
+    pub fn warn_dormant_user(ctx: Context<WarnDormantUser>) -> Result<()> {
+        let pool = &ctx.accounts.reward_pool;
+        let user_account = &mut ctx.accounts.user_account;
+        let clock = Clock::get()?;
+
+        require!(pool.dormancy_period_secs > 0, ErrorCode::DormancySweepDisabled);
+
+        let last_activity = last_activity_timestamp(user_account);
+        let dormant_at = last_activity
+            .checked_add(pool.dormancy_period_secs)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        require!(clock.unix_timestamp >= dormant_at, ErrorCode::UserNotDormant);
+
+        let sweepable_at = clock
+            .unix_timestamp
+            .checked_add(pool.dormancy_grace_secs)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        user_account.dormancy_warned_at = clock.unix_timestamp;
+
+        emit!(DormancyWarning {
+            pool: pool.key(),
+            user: user_account.authority,
+            last_activity,
+            sweepable_at,
+        });
+
+        msg!("Dormancy warning issued for {}", user_account.authority);
+        Ok(())
+    }
+
+    /// Close a warned user account that stayed dormant through the grace period (admin only).
+    /// Rent goes to the pool's recovery fund and the participant slot is freed.
+    pub fn sweep_dormant_user(ctx: Context<SweepDormantUser>) -> Result<()> {
+        let user_account = &ctx.accounts.user_account;
+        let pool = &mut ctx.accounts.reward_pool;
+        let clock = Clock::get()?;
+
+        require!(pool.dormancy_period_secs > 0, ErrorCode::DormancySweepDisabled);
+        require!(
+            user_account.dormancy_warned_at != 0,
+            ErrorCode::DormancyWarningRequired
+        );
+        // Any activity after the warning cancels it
+        require!(
+            last_activity_timestamp(user_account) < user_account.dormancy_warned_at,
+            ErrorCode::UserNotDormant
+        );
+
+        let sweepable_at = user_account
+            .dormancy_warned_at
+            .checked_add(pool.dormancy_grace_secs)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        require!(
+            clock.unix_timestamp >= sweepable_at,
+            ErrorCode::DormancyGracePeriodActive
+        );
+
+        pool.participant_count = pool
+            .participant_count
+            .checked_sub(1)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+
+        msg!("Swept dormant user account: {}", user_account.authority);
+        Ok(())
+    }
+
+    /// Create a rent sponsorship campaign for a pool (admin only).
+    /// The sponsor account is funded by transferring lamports to it directly.
+    pub fn create_rent_sponsor(
+        ctx: Context<CreateRentSponsor>,
+        campaign_id: u64,
+        budget_lamports: u64,
+    ) -> Result<()> {
+        let sponsor = &mut ctx.accounts.rent_sponsor;
+
+        sponsor.pool = ctx.accounts.reward_pool.key();
+        sponsor.campaign_id = campaign_id;
+        sponsor.budget_lamports = budget_lamports;
+        sponsor.spent_lamports = 0;
+        sponsor.sponsored_count = 0;
+        sponsor.is_active = true;
+        sponsor.bump = ctx.bumps.rent_sponsor;
+
+        msg!(
+            "Rent sponsor campaign {} created with budget: {} lamports",
+            campaign_id,
+            budget_lamports
+        );
+        Ok(())
+    }
+
+    /// Update a rent sponsorship campaign's budget or enable/disable it (admin only)
+    pub fn update_rent_sponsor(
+        ctx: Context<UpdateRentSponsor>,
+        budget_lamports: Option<u64>,
+        is_active: Option<bool>,
+    ) -> Result<()> {
+        let sponsor = &mut ctx.accounts.rent_sponsor;
+
+        if let Some(budget) = budget_lamports {
+            sponsor.budget_lamports = budget;
+        }
+        if let Some(active) = is_active {
+            sponsor.is_active = active;
+        }
+
+        msg!("Rent sponsor campaign {} updated", sponsor.campaign_id);
+        Ok(())
+    }
+
+    /// Register a user with the account rent paid by a sponsorship campaign
+    pub fn register_user_sponsored(
+        ctx: Context<RegisterUserSponsored>,
+        campaign_id: u64,
+        proof: Vec<[u8; 32]>,
+        terms_version: u32,
+    ) -> Result<()> {
+        // Sponsored registrations are exempt from the registration fee
+        let user_account = register(
+            &mut ctx.accounts.reward_pool,
+            Registration {
+                authority: ctx.accounts.authority.key(),
+                bump: ctx.bumps.user_account,
+                denylist_entry: &ctx.accounts.denylist_entry,
+                sibling_account: &ctx.accounts.membership,
+                proof: &proof,
+                terms_version,
+                attestation: None,
+                referrer: None,
+                fee: None,
+            },
+        )?;
+
+        let clock = Clock::get()?;
+        let space = 8 + UserAccount::INIT_SPACE;
+        let rent_lamports = Rent::get()?.minimum_balance(space);
+
+        // Like Anchor's `init`, but the sponsor pays: anyone may have sent lamports to the
+        // predictable PDA, so only an already initialized account counts as registered
+        let user_info = ctx.accounts.user_account.to_account_info();
+        require!(
+            user_info.data_is_empty() && user_info.owner == &system_program::ID,
+            ErrorCode::UserAlreadyRegistered
+        );
+
+        let authority_key = ctx.accounts.authority.key();
+        let user_seeds = &[
+            b"user_account",
+            authority_key.as_ref(),
+            &[ctx.bumps.user_account],
+        ];
+        let user_signer = &[&user_seeds[..]];
+        let system_program = ctx.accounts.system_program.to_account_info();
+
+        system_program::allocate(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                system_program::Allocate {
+                    account_to_allocate: user_info.clone(),
+                },
+                user_signer,
+            ),
+            space as u64,
+        )?;
+        system_program::assign(
+            CpiContext::new_with_signer(
+                system_program,
+                system_program::Assign {
+                    account_to_assign: user_info.clone(),
+                },
+                user_signer,
+            ),
+            ctx.program_id,
+        )?;
+
+        // Top up only the shortfall, after the CPIs so the sponsor's debit never has to
+        // cross one
+        let sponsor = &mut ctx.accounts.rent_sponsor;
+        pay_sponsored_rent(
+            &mut ctx.accounts.reward_pool,
+            sponsor,
+            &user_info,
+            rent_lamports.saturating_sub(user_info.lamports()),
+            clock.unix_timestamp,
+        )?;
+
+        user_account.try_serialize(&mut &mut user_info.try_borrow_mut_data()?[..])?;
+
+        sponsor.sponsored_count = sponsor
+            .sponsored_count
+            .checked_add(1)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        msg!(
+            "User registered: {} (rent sponsored by campaign {})",
+            authority_key,
+            campaign_id
+        );
+        Ok(())
+    }
+
+    /// Create a registered user's associated token account for the pool mint with the rent
+    /// paid by a sponsorship campaign, so a user holding no SOL can receive their first
+    /// claim. Meant to run right before `claim_rewards` in the same transaction; does
+    /// nothing if the account already exists.
+    pub fn create_token_account_sponsored(
+        ctx: Context<CreateTokenAccountSponsored>,
+        campaign_id: u64,
+    ) -> Result<()> {
+        let token_account_info = ctx.accounts.user_token_account.to_account_info();
+        if !token_account_info.data_is_empty() {
+            return Ok(());
+        }
+
+        // Prefund the address so the associated token program takes nothing from the user
+        let space = fees::associated_account_len(&ctx.accounts.mint.to_account_info())?;
+        let rent_lamports = Rent::get()?
+            .minimum_balance(space)
+            .saturating_sub(token_account_info.lamports());
+        pay_sponsored_rent(
+            &mut ctx.accounts.reward_pool,
+            &mut ctx.accounts.rent_sponsor,
+            &token_account_info,
+            rent_lamports,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        // The sponsor rides along as a trailing account so the runtime sees its debit
+        // next to the token account's credit; the associated token program ignores it
+        let authority = ctx.accounts.authority.to_account_info();
+        let sponsor_info = ctx.accounts.rent_sponsor.to_account_info();
+        let instruction = Instruction {
+            program_id: associated_token::ID,
+            accounts: vec![
+                AccountMeta::new(authority.key(), true),
+                AccountMeta::new(token_account_info.key(), false),
+                AccountMeta::new_readonly(authority.key(), false),
+                AccountMeta::new_readonly(ctx.accounts.mint.key(), false),
+                AccountMeta::new_readonly(system_program::ID, false),
+                AccountMeta::new_readonly(ctx.accounts.token_program.key(), false),
+                AccountMeta::new(sponsor_info.key(), false),
+            ],
+            // `AssociatedTokenAccountInstruction::CreateIdempotent`
+            data: vec![1],
+        };
+        invoke(
+            &instruction,
+            &[
+                authority,
+                token_account_info,
+                ctx.accounts.mint.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                sponsor_info,
+                ctx.accounts.associated_token_program.to_account_info(),
+            ],
+        )?;
+
+        msg!(
+            "Token account created for {} (rent sponsored by campaign {})",
+            ctx.accounts.authority.key(),
+            campaign_id
+        );
+        Ok(())
+    }
+
+    /// Cap how many accounts sponsorship campaigns may fund per UTC day, across the user
+    /// registrations and token accounts they pay for (admin only; 0 = unlimited)
+    pub fn set_sponsorship_daily_limit(
+        ctx: Context<UpdatePoolConfig>,
+        max_sponsored_per_day: u32,
+    ) -> Result<()> {
+        ctx.accounts.reward_pool.max_sponsored_per_day = max_sponsored_per_day;
+
+        msg!("Sponsorship limit set to {} per day", max_sponsored_per_day);
+        Ok(())
+    }
+
+    /// Add an address to the pool's denylist (admin only)
+    pub fn add_to_denylist(
+        ctx: Context<AddToDenylist>,
+        address: Pubkey,
+        reason_code: u16,
+    ) -> Result<()> {
+        let entry = &mut ctx.accounts.denylist_entry;
+        let clock = Clock::get()?;
+
+        entry.pool = ctx.accounts.reward_pool.key();
+        entry.address = address;
+        entry.reason_code = reason_code;
+        entry.added_at = clock.unix_timestamp;
+        entry.bump = ctx.bumps.denylist_entry;
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.denylist_len = pool.denylist_len.saturating_add(1);
+
+        msg!("Denylisted address: {} (reason {})", address, reason_code);
+        Ok(())
+    }
+
+    /// Authorize `verifier` to credit activity points to the pool's users (admin only)
+    pub fn add_activity_verifier(
+        ctx: Context<AddActivityVerifier>,
+        verifier: Pubkey,
+    ) -> Result<()> {
+        let entry = &mut ctx.accounts.activity_verifier;
+        entry.pool = ctx.accounts.reward_pool.key();
+        entry.verifier = verifier;
+        entry.points_recorded = 0;
+        entry.added_at = Clock::get()?.unix_timestamp;
+        entry.bump = ctx.bumps.activity_verifier;
+
+        msg!("Activity verifier added: {}", verifier);
+        Ok(())
+    }
+
+    /// Revoke an activity verifier (admin only)
+    pub fn remove_activity_verifier(ctx: Context<RemoveActivityVerifier>) -> Result<()> {
+        msg!(
+            "Activity verifier removed: {}",
+            ctx.accounts.activity_verifier.verifier
+        );
+        Ok(())
+    }
+
+    /// Credit `points` of verified activity to a user (registered verifiers only); they
+    /// pay out at the pool's `reward_per_activity_point` on the user's next claim
+    pub fn record_activity(ctx: Context<RecordActivity>, points: u64) -> Result<()> {
+        require!(points > 0, ErrorCode::InvalidActivityPoints);
+        let user_account = &mut ctx.accounts.user_account;
+        user_account.activity_points = user_account
+            .activity_points
+            .checked_add(points)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        user_account.total_activity_points = user_account
+            .total_activity_points
+            .checked_add(points)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        let verifier = &mut ctx.accounts.activity_verifier;
+        verifier.points_recorded = verifier
+            .points_recorded
+            .checked_add(points)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        emit!(ActivityRecorded {
+            pool: verifier.pool,
+            user: user_account.authority,
+            verifier: verifier.verifier,
+            points,
+        });
+        Ok(())
+    }
+
+    /// Set the reward paid per activity point on top of time accrual (admin only);
+    /// 0 stops points from paying out
+    pub fn set_activity_reward(
+        ctx: Context<UpdatePoolConfig>,
+        reward_per_activity_point: u64,
+    ) -> Result<()> {
+        ctx.accounts.reward_pool.reward_per_activity_point = reward_per_activity_point;
+
+        msg!("Activity reward set to {} per point", reward_per_activity_point);
+        Ok(())
+    }
+
+    /// Remove an address from the pool's denylist (admin only)
+    pub fn remove_from_denylist(ctx: Context<RemoveFromDenylist>) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        if pool.denylist_len != UNCOUNTED {
+            pool.denylist_len = pool.denylist_len.saturating_sub(1);
+        }
+
+        msg!(
+            "Removed address from denylist: {}",
+            ctx.accounts.denylist_entry.address
+        );
+        Ok(())
+    }
+
+    /// Configure the region attestation verifier and whether attestation is mandatory (admin only)
+    pub fn set_region_verifier(
+        ctx: Context<UpdatePoolConfig>,
+        verifier: Option<Pubkey>,
+        require_attestation: bool,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.region_verifier = verifier;
+        pool.require_region_attestation = require_attestation;
+
+        msg!("Region verifier updated, attestation required: {}", require_attestation);
+        Ok(())
+    }
+
+    /// Create or replace the policy applied to users attested to a region (admin only)
+    pub fn set_region_policy(
+        ctx: Context<SetRegionPolicy>,
+        region_code: u16,
+        action: RegionAction,
+    ) -> Result<()> {
+        match action {
+            RegionAction::ReducedRate { bps } | RegionAction::Withholding { bps } => {
+                require!(
+                    bps as u64 <= math::BPS_DENOMINATOR,
+                    ErrorCode::InvalidBasisPoints
+                );
+            }
+            RegionAction::Blocked => {}
+        }
+
+        let pool = &mut ctx.accounts.reward_pool;
+        let policy = &mut ctx.accounts.region_policy;
+        if policy.pool == Pubkey::default() {
+            pool.region_policy_count = pool.region_policy_count.saturating_add(1);
+        }
+        policy.pool = pool.key();
+        policy.region_code = region_code;
+        policy.action = action;
+        policy.bump = ctx.bumps.region_policy;
+
+        msg!("Region policy set for region {}", region_code);
+        Ok(())
+    }
+
+    /// Remove a region policy so the region is treated normally (admin only)
+    pub fn remove_region_policy(ctx: Context<RemoveRegionPolicy>) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        if pool.region_policy_count != UNCOUNTED {
+            pool.region_policy_count = pool.region_policy_count.saturating_sub(1);
+        }
+
+        msg!(
+            "Region policy removed for region {}",
+            ctx.accounts.region_policy.region_code
+        );
+        Ok(())
+    }
+
+    /// Record a region attestation co-signed by the pool's configured verifier
+    pub fn attest_region(ctx: Context<AttestRegion>, region_code: u16) -> Result<()> {
+        let pool = &ctx.accounts.reward_pool;
+        require!(
+            pool.region_verifier == Some(ctx.accounts.verifier.key()),
+            ErrorCode::InvalidRegionVerifier
+        );
+
+        let user_account = &mut ctx.accounts.user_account;
+        let clock = Clock::get()?;
+        user_account.region_code = region_code;
+        user_account.region_attested_at = clock.unix_timestamp;
+
+        msg!(
+            "Region {} attested for {}",
+            region_code,
+            user_account.authority
+        );
+        Ok(())
+    }
+
+    /// Designate `threshold`-of-N guardians who can move this user account to a new key.
+    ///
+    /// Replacing the guardian set cancels any recovery in progress.
+    pub fn set_recovery_guardians(
+        ctx: Context<SetRecoveryGuardians>,
+        guardians: Vec<Pubkey>,
+        threshold: u8,
+        timelock_secs: i64,
+    ) -> Result<()> {
+        require!(
+            !guardians.is_empty()
+                && guardians.len() <= MAX_GUARDIANS
+                && threshold > 0
+                && usize::from(threshold) <= guardians.len()
+                && timelock_secs >= 0,
+            ErrorCode::InvalidGuardianSet
+        );
+        for (i, guardian) in guardians.iter().enumerate() {
+            require!(
+                !guardians[..i].contains(guardian) && *guardian != ctx.accounts.authority.key(),
+                ErrorCode::InvalidGuardianSet
+            );
+        }
+
+        let recovery = &mut ctx.accounts.recovery;
+        recovery.user_account = ctx.accounts.user_account.key();
+        recovery.guardians = guardians;
+        recovery.threshold = threshold;
+        recovery.timelock_secs = timelock_secs;
+        recovery.pending_authority = None;
+        recovery.approvals = 0;
+        recovery.initiated_at = 0;
+        recovery.bump = ctx.bumps.recovery;
+
+        msg!(
+            "Recovery guardians set: {} of {}",
+            threshold,
+            recovery.guardians.len()
+        );
+        Ok(())
+    }
+
+    /// Start (or approve) migration of a user account to `new_authority` (guardian only)
+    pub fn approve_recovery(ctx: Context<ApproveRecovery>, new_authority: Pubkey) -> Result<()> {
+        let recovery = &mut ctx.accounts.recovery;
+        let clock = Clock::get()?;
+
+        let guardian_index = recovery
+            .guardians
+            .iter()
+            .position(|guardian| *guardian == ctx.accounts.guardian.key())
+            .ok_or(error!(ErrorCode::NotGuardian))?;
+
+        match recovery.pending_authority {
+            Some(pending) => {
+                require_keys_eq!(pending, new_authority, ErrorCode::RecoveryAlreadyPending)
+            }
+            None => {
+                recovery.pending_authority = Some(new_authority);
+                recovery.approvals = 0;
+                recovery.initiated_at = clock.unix_timestamp;
+            }
+        }
+        recovery.approvals |= 1 << guardian_index;
+
+        msg!(
+            "Recovery to {} approved by {} ({} of {})",
+            new_authority,
+            ctx.accounts.guardian.key(),
+            recovery.approvals.count_ones(),
+            recovery.threshold
+        );
+        Ok(())
+    }
+
+    /// Cancel a pending recovery; the original key can do this until it executes
+    pub fn cancel_recovery(ctx: Context<CancelRecovery>) -> Result<()> {
+        let recovery = &mut ctx.accounts.recovery;
+        require!(
+            recovery.pending_authority.is_some(),
+            ErrorCode::NoPendingRecovery
+        );
+
+        recovery.pending_authority = None;
+        recovery.approvals = 0;
+        recovery.initiated_at = 0;
+
+        msg!("Recovery cancelled for {}", ctx.accounts.authority.key());
+        Ok(())
+    }
+
+    /// Move a user account to its recovered key once enough guardians approved and the
+    /// timelock passed. The new account keeps the full claim history and tenure, and the
+    /// referral key its referees credit.
+    ///
+    /// As with wallet rotation, open vesting positions and claim sessions must be closed
+    /// first: they are seeded by the old user account's address and would be stranded.
+    pub fn execute_recovery(ctx: Context<ExecuteRecovery>) -> Result<()> {
+        ensure_not_denylisted(
+            &ctx.accounts.denylist_entry,
+            ctx.accounts.old_user_account.pool,
+            ctx.accounts.new_authority.key(),
+        )?;
+        ensure_not_in_pool(
+            &ctx.accounts.new_sibling_account,
+            ctx.accounts.old_user_account.pool,
+        )?;
+        ensure_no_open_claims(&ctx.accounts.old_user_account)?;
+
+        let recovery = &ctx.accounts.recovery;
+        let clock = Clock::get()?;
+
+        require!(
+            recovery.pending_authority == Some(ctx.accounts.new_authority.key()),
+            ErrorCode::NoPendingRecovery
+        );
+        require!(
+            recovery.approvals.count_ones() >= u32::from(recovery.threshold),
+            ErrorCode::RecoveryThresholdNotMet
+        );
+        let unlocks_at = recovery
+            .initiated_at
+            .checked_add(recovery.timelock_secs)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        require!(
+            clock.unix_timestamp >= unlocks_at,
+            ErrorCode::RecoveryTimelockActive
+        );
+
+        let old_authority = ctx.accounts.old_user_account.authority;
+        let successor = ctx
+            .accounts
+            .old_user_account
+            .succeeded_by(ctx.accounts.new_authority.key(), ctx.bumps.new_user_account);
+        ctx.accounts.new_user_account.set_inner(successor);
+
+        msg!(
+            "User account recovered from {} to {}",
+            old_authority,
+            ctx.accounts.new_authority.key()
+        );
+        Ok(())
+    }
+
+    /// Start moving the caller's user account to `new_authority`, which completes it with
+    /// `complete_wallet_rotation` after `WALLET_ROTATION_DELAY_SECS`. Until then the current
+    /// key keeps claiming and can call `cancel_wallet_rotation`. Open vesting positions and
+    /// claim sessions must be closed first.
+    pub fn initiate_wallet_rotation(
+        ctx: Context<InitiateWalletRotation>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        require!(
+            new_authority != ctx.accounts.authority.key() && new_authority != Pubkey::default(),
+            ErrorCode::InvalidWalletRotation
+        );
+        ensure_no_open_claims(&ctx.accounts.user_account)?;
+
+        let rotation = &mut ctx.accounts.rotation;
+        rotation.user_account = ctx.accounts.user_account.key();
+        rotation.new_authority = new_authority;
+        rotation.initiated_at = Clock::get()?.unix_timestamp;
+        rotation.bump = ctx.bumps.rotation;
+
+        msg!(
+            "Wallet rotation from {} to {} initiated",
+            ctx.accounts.authority.key(),
+            new_authority
+        );
+        Ok(())
+    }
+
+    /// Abandon the caller's pending wallet rotation, refunding its rent
+    pub fn cancel_wallet_rotation(ctx: Context<CancelWalletRotation>) -> Result<()> {
+        msg!(
+            "Wallet rotation to {} cancelled",
+            ctx.accounts.rotation.new_authority
+        );
+        Ok(())
+    }
+
+    /// Move a user account to the key named in its pending rotation (signed by that key)
+    /// once the delay has passed. Like `execute_recovery`, the account at the new key's
+    /// address inherits the full claim history, streaks and timestamps, and the old one is
+    /// closed; accounts derived from the old user account stay with it.
+    pub fn complete_wallet_rotation(ctx: Context<CompleteWalletRotation>) -> Result<()> {
+        ensure_not_denylisted(
+            &ctx.accounts.denylist_entry,
+            ctx.accounts.old_user_account.pool,
+            ctx.accounts.new_authority.key(),
+        )?;
+        ensure_not_in_pool(
+            &ctx.accounts.new_sibling_account,
+            ctx.accounts.old_user_account.pool,
+        )?;
+
+        let unlocks_at = ctx
+            .accounts
+            .rotation
+            .initiated_at
+            .checked_add(WALLET_ROTATION_DELAY_SECS)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        require!(
+            Clock::get()?.unix_timestamp >= unlocks_at,
+            ErrorCode::WalletRotationTimelockActive
+        );
+        // The old key may have opened more while the rotation waited
+        ensure_no_open_claims(&ctx.accounts.old_user_account)?;
+
+        let old_authority = ctx.accounts.old_user_account.authority;
+        let successor = ctx
+            .accounts
+            .old_user_account
+            .succeeded_by(ctx.accounts.new_authority.key(), ctx.bumps.new_user_account);
+        ctx.accounts.new_user_account.set_inner(successor);
+
+        msg!(
+            "Wallet rotated from {} to {}",
+            old_authority,
+            ctx.accounts.new_authority.key()
+        );
+        Ok(())
+    }
+
+    /// Open staking on the pool: stakers of `stake_mint` share `reward_rate_per_hour` reward
+    /// tokens pro rata to their stake, on top of the time-based drip
+    pub fn enable_staking(ctx: Context<EnableStaking>, reward_rate_per_hour: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        require!(pool.stake_mint.is_none(), ErrorCode::StakingAlreadyEnabled);
+        require!(
+            pool.distribution_mode != DistributionMode::NativeSol,
+            ErrorCode::WrongDistributionMode
+        );
+
+        pool.stake_mint = Some(ctx.accounts.stake_mint.key());
+        pool.stake_reward_rate_per_hour = reward_rate_per_hour;
+        pool.total_staked = 0;
+        pool.reward_per_token_stored = 0;
+        pool.stake_rewards_updated_at = Clock::get()?.unix_timestamp;
+
+        msg!(
+            "Staking enabled for {} at {} per hour",
+            ctx.accounts.stake_mint.key(),
+            reward_rate_per_hour
+        );
+        Ok(())
+    }
+
+    /// Change the staking emission rate; rewards accrued so far keep the old rate
+    pub fn set_stake_reward_rate(
+        ctx: Context<UpdatePoolConfig>,
+        reward_rate_per_hour: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        require!(pool.stake_mint.is_some(), ErrorCode::StakingNotEnabled);
+
+        pool.update_stake_rewards(Clock::get()?.unix_timestamp)?;
+        pool.stake_reward_rate_per_hour = reward_rate_per_hour;
+
+        msg!("Stake reward rate updated: {} per hour", reward_rate_per_hour);
+        Ok(())
+    }
+
+    /// Deposit stake tokens into the pool's stake vault
+    pub fn stake_tokens(ctx: Context<StakeTokens>, amount: u64) -> Result<()> {
+        ensure_not_denylisted(
+            &ctx.accounts.denylist_entry,
+            ctx.accounts.reward_pool.key(),
+            ctx.accounts.owner.key(),
+        )?;
+        require!(amount > 0, ErrorCode::InvalidStakeAmount);
+
+        let pool = &mut ctx.accounts.reward_pool;
+        require!(!pool.registrations_paused, ErrorCode::RegistrationsPaused);
+        pool.update_stake_rewards(Clock::get()?.unix_timestamp)?;
+
+        let position = &mut ctx.accounts.stake_position;
+        if position.owner == Pubkey::default() {
+            position.owner = ctx.accounts.owner.key();
+            position.pool = pool.key();
+            position.bump = ctx.bumps.stake_position;
+        }
+        position.settle(pool.reward_per_token_stored)?;
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.owner_stake_account.to_account_info(),
+            mint: ctx.accounts.stake_mint.to_account_info(),
+            to: ctx.accounts.stake_vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.stake_mint.decimals)?;
+
+        position.amount = position
+            .amount
+            .checked_add(amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_staked = pool
+            .total_staked
+            .checked_add(amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        msg!("Staked {} ({} total)", amount, position.amount);
+        Ok(())
+    }
+
+    /// Claim the time-based reward straight into the caller's stake position instead of
+    /// their wallet. Needs staking enabled on the pool's own reward mint; pools with faucet,
+    /// vesting or matching rules need direct claims.
+    pub fn claim_and_compound(ctx: Context<ClaimAndCompound>) -> Result<()> {
+        ensure_not_denylisted(
+            &ctx.accounts.denylist_entry,
+            ctx.accounts.reward_pool.key(),
+            ctx.accounts.owner.key(),
+        )?;
+
+        let user_account = &mut ctx.accounts.user_account;
+        let pool = &mut ctx.accounts.reward_pool;
+        let clock = Clock::get()?;
+        let current_timestamp = clock.unix_timestamp;
+
+        require!(!pool.claims_paused, ErrorCode::ClaimsPaused);
+        require!(pool.faucet.is_none(), ErrorCode::FaucetRequiresDirectClaim);
+        require!(pool.vesting.is_none(), ErrorCode::VestingRequiresDirectClaim);
+        require!(
+            pool.usd_pricing.is_none(),
+            ErrorCode::UsdPricingRequiresDirectClaim
+        );
+        require!(
+            pool.matching_sponsor.is_none(),
+            ErrorCode::MatchingRequiresDirectClaim
+        );
+        require!(
+            pool.receipt_expiry_secs == 0,
+            ErrorCode::ReceiptsRequireDirectClaim
+        );
+        require!(user_account.banned_at == 0, ErrorCode::UserBanned);
+        require!(user_account.is_active, ErrorCode::UserNotActive);
+        require!(
+            user_account.terms_version >= pool.terms_version,
+            ErrorCode::TermsNotAccepted
+        );
+        require!(user_account.paused_at == 0, ErrorCode::AccrualPaused);
+        reverify_nft_boost(user_account, ctx.accounts.nft_token_account.as_ref());
+
+        apply_due_config(pool, current_timestamp);
+        let breakdown = compute_reward(pool, user_account, current_timestamp)?;
+        ensure_claim_interval(pool, user_account, &breakdown, current_timestamp)?;
+        require!(
+            breakdown.usd_priced_reward == 0,
+            ErrorCode::UsdPricingRequiresDirectClaim
+        );
+        let reward_amount = reveal_lottery(
+            pool,
+            user_account,
+            ctx.accounts.slot_hashes.as_ref(),
+            clock.slot,
+            breakdown.reward_amount,
+        )?
+        .min(lifetime_reward_remaining(pool, user_account)?)
+            .min(pool_budget_remaining(pool)?);
+        require!(reward_amount > 0, ErrorCode::NoRewardsAvailable);
+        ensure_co_signed(user_account, reward_amount, ctx.accounts.co_signer.as_ref())?;
+
+        if pool.require_region_attestation {
+            require!(
+                user_account.region_attested_at != 0,
+                ErrorCode::RegionAttestationRequired
+            );
+        }
+        let (reward_amount, withheld_amount) =
+            match load_region_policy(&ctx.accounts.region_policy)? {
+                Some(policy) => {
+                    math::apply_region_policy(reward_amount, &policy.action, pool.rounding)?
+                }
+                None => (reward_amount, 0),
+            };
+
+        let mint_info = ctx.accounts.mint.to_account_info();
+        let seeds = &[
+            b"reward_pool",
+            pool.authority.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+
+        let fee_amount = match pool.distribution_mode {
+            DistributionMode::Vault => {
+                require!(
+                    ctx.accounts.vault.amount >= reward_amount,
+                    ErrorCode::InsufficientVaultFunds
+                );
+                let cpi_accounts = TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: mint_info.clone(),
+                    to: ctx.accounts.stake_vault.to_account_info(),
+                    authority: pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::transfer_checked(
+                    cpi_ctx,
+                    reward_amount,
+                    ctx.accounts.mint.decimals,
+                )?;
+                fees::transfer_fee(&mint_info, reward_amount, clock.epoch)?
+            }
+            DistributionMode::MintOnClaim => {
+                let cpi_accounts = MintTo {
+                    mint: mint_info,
+                    to: ctx.accounts.stake_vault.to_account_info(),
+                    authority: pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::mint_to(cpi_ctx, reward_amount)?;
+                0
+            }
+            DistributionMode::NativeSol => return err!(ErrorCode::WrongDistributionMode),
+        };
+        let net_amount = reward_amount
+            .checked_sub(fee_amount)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+
+        // Settle at the old stake before growing it, like a regular deposit
+        pool.update_stake_rewards(current_timestamp)?;
+        let position = &mut ctx.accounts.stake_position;
+        if position.owner == Pubkey::default() {
+            position.owner = ctx.accounts.owner.key();
+            position.pool = pool.key();
+            position.bump = ctx.bumps.stake_position;
+        }
+        position.settle(pool.reward_per_token_stored)?;
+        position.amount = position
+            .amount
+            .checked_add(net_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_staked = pool
+            .total_staked
+            .checked_add(net_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        user_account.total_earned = user_account
+            .total_earned
+            .checked_add(net_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        user_account.total_fees_paid = user_account
+            .total_fees_paid
+            .checked_add(fee_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        user_account.total_withheld = user_account
+            .total_withheld
+            .checked_add(withheld_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        let first_claim_of_day = math::is_first_claim_of_day(user_account, current_timestamp);
+        let released = user_account.record_claim(pool, &breakdown, current_timestamp)?;
+        pool.track_carryover(released, breakdown.carryover);
+
+        pool.total_distributed = pool
+            .total_distributed
+            .checked_add(reward_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_transfer_fees = pool
+            .total_transfer_fees
+            .checked_add(fee_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_withheld = pool
+            .total_withheld
+            .checked_add(withheld_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        record_mint_stats(
+            pool,
+            ctx.accounts.mint_stats.as_mut(),
+            reward_amount,
+            1,
+            current_timestamp,
+        )?;
+        deactivate_if_budget_spent(pool);
+        record_epoch_claim(
+            pool,
+            ctx.accounts.epoch.as_mut(),
+            reward_amount,
+            1,
+            current_timestamp,
+        )?;
+        record_daily_stats(
+            pool,
+            ctx.accounts.daily_stats.as_mut(),
+            reward_amount,
+            1,
+            u64::from(first_claim_of_day),
+            current_timestamp,
+        )?;
+        let referral_bonus = credit_referral(
+            pool,
+            user_account,
+            ctx.accounts.referrer_account.as_deref_mut(),
+            net_amount,
+        )?;
+        pool.total_referral_owed = pool
+            .total_referral_owed
+            .checked_add(referral_bonus)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        msg!(
+            "Compounded {} into stake ({} total)",
+            net_amount,
+            position.amount
+        );
+        Ok(())
+    }
+
+    /// Withdraw staked tokens; rewards earned so far stay claimable
+    pub fn unstake_tokens(ctx: Context<UnstakeTokens>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        let position = &mut ctx.accounts.stake_position;
+        require!(
+            amount > 0 && amount <= position.amount,
+            ErrorCode::InvalidStakeAmount
+        );
+
+        pool.update_stake_rewards(Clock::get()?.unix_timestamp)?;
+        position.settle(pool.reward_per_token_stored)?;
+
+        let seeds = &[
+            b"reward_pool",
+            pool.authority.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.stake_vault.to_account_info(),
+            mint: ctx.accounts.stake_mint.to_account_info(),
+            to: ctx.accounts.owner_stake_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.stake_mint.decimals)?;
+
+        position.amount -= amount;
+        pool.total_staked = pool
+            .total_staked
+            .checked_sub(amount)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+
+        msg!("Unstaked {} ({} remaining)", amount, position.amount);
+        Ok(())
+    }
+
+    /// Pay out staking rewards earned by a stake position
+    pub fn claim_stake_rewards(ctx: Context<ClaimStakeRewards>) -> Result<()> {
+        ensure_not_denylisted(
+            &ctx.accounts.denylist_entry,
+            ctx.accounts.reward_pool.key(),
+            ctx.accounts.owner.key(),
+        )?;
+
+        let pool = &mut ctx.accounts.reward_pool;
+        let position = &mut ctx.accounts.stake_position;
+        require!(!pool.claims_paused, ErrorCode::ClaimsPaused);
+
+        pool.update_stake_rewards(Clock::get()?.unix_timestamp)?;
+        position.settle(pool.reward_per_token_stored)?;
+
+        let reward_amount = position.pending_rewards;
+        require!(reward_amount > 0, ErrorCode::NoRewardsAvailable);
+
+        let seeds = &[
+            b"reward_pool",
+            pool.authority.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+
+        match pool.distribution_mode {
+            DistributionMode::Vault => {
+                require!(
+                    ctx.accounts.vault.amount >= reward_amount,
+                    ErrorCode::InsufficientVaultFunds
+                );
+                let cpi_accounts = TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.owner_reward_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::transfer_checked(
+                    cpi_ctx,
+                    reward_amount,
+                    ctx.accounts.mint.decimals,
+                )?;
+            }
+            DistributionMode::MintOnClaim => {
+                let cpi_accounts = MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.owner_reward_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::mint_to(cpi_ctx, reward_amount)?;
+            }
+            DistributionMode::NativeSol => return err!(ErrorCode::WrongDistributionMode),
+        }
+
+        position.pending_rewards = 0;
+        position.total_claimed = position
+            .total_claimed
+            .checked_add(reward_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_distributed = pool
+            .total_distributed
+            .checked_add(reward_amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        msg!("Stake rewards claimed: {} tokens", reward_amount);
+        Ok(())
+    }
+
+    /// Enable (or disable, with `None`) NFT staking for the pool
+    pub fn set_nft_staking(
+        ctx: Context<UpdatePoolConfig>,
+        config: Option<NftStakingConfig>,
+    ) -> Result<()> {
+        ctx.accounts.reward_pool.nft_staking = config;
+
+        msg!("NFT staking updated");
+        Ok(())
+    }
+
+    /// Escrow an NFT from the pool's staking collection; it accrues the staking rate hourly
+    pub fn stake_nft(ctx: Context<StakeNft>) -> Result<()> {
+        ensure_not_denylisted(
+            &ctx.accounts.denylist_entry,
+            ctx.accounts.reward_pool.key(),
+            ctx.accounts.owner.key(),
+        )?;
+
+        let pool = &ctx.accounts.reward_pool;
+        require!(!pool.registrations_paused, ErrorCode::RegistrationsPaused);
+        let config = pool
+            .nft_staking
+            .ok_or(error!(ErrorCode::NftStakingNotEnabled))?;
+        verify_collection_nft(
+            &ctx.accounts.nft_mint,
+            &ctx.accounts.owner_nft_account,
+            &ctx.accounts.nft_metadata,
+            config.collection,
+        )?;
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.owner_nft_account.to_account_info(),
+            mint: ctx.accounts.nft_mint.to_account_info(),
+            to: ctx.accounts.escrow.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.nft_token_program.to_account_info(),
+            cpi_accounts,
+        );
+        token_interface::transfer_checked(cpi_ctx, 1, 0)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts.staked_nft.set_inner(StakedNft {
+            owner: ctx.accounts.owner.key(),
+            pool: pool.key(),
+            nft_mint: ctx.accounts.nft_mint.key(),
+            staked_at: now,
+            last_accrual_timestamp: now,
+            total_earned: 0,
+            bump: ctx.bumps.staked_nft,
+        });
+
+        msg!("NFT {} staked", ctx.accounts.nft_mint.key());
+        Ok(())
+    }
+
+    /// Pay out the rewards a staked NFT has accrued
+    pub fn claim_nft_rewards(ctx: Context<ClaimNftRewards>) -> Result<()> {
+        let reward_amount = settle_staked_nft(
+            &mut ctx.accounts.reward_pool,
+            &mut ctx.accounts.staked_nft,
+            &ctx.accounts.vault,
+            &ctx.accounts.owner_reward_account,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+        )?;
+        require!(reward_amount > 0, ErrorCode::NoRewardsAvailable);
+
+        msg!("NFT staking rewards claimed: {} tokens", reward_amount);
+        Ok(())
+    }
+
+    /// Pay out accrued rewards, return the NFT from escrow and close the stake record
+    pub fn unstake_nft(ctx: Context<UnstakeNft>) -> Result<()> {
+        let reward_amount = settle_staked_nft(
+            &mut ctx.accounts.reward_pool,
+            &mut ctx.accounts.staked_nft,
+            &ctx.accounts.vault,
+            &ctx.accounts.owner_reward_account,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+        )?;
+
+        let staked_nft = &ctx.accounts.staked_nft;
+        let seeds = &[
+            b"staked_nft",
+            staked_nft.pool.as_ref(),
+            staked_nft.nft_mint.as_ref(),
+            &[staked_nft.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let nft_token_program = ctx.accounts.nft_token_program.to_account_info();
+
+        let cpi_accounts = TransferChecked {
+            from: ctx.accounts.escrow.to_account_info(),
+            mint: ctx.accounts.nft_mint.to_account_info(),
+            to: ctx.accounts.owner_nft_account.to_account_info(),
+            authority: staked_nft.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(nft_token_program.clone(), cpi_accounts, signer);
+        token_interface::transfer_checked(cpi_ctx, 1, 0)?;
+
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.escrow.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: staked_nft.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(nft_token_program, cpi_accounts, signer);
+        token_interface::close_account(cpi_ctx)?;
+
+        msg!(
+            "NFT {} unstaked with {} tokens paid out",
+            staked_nft.nft_mint,
+            reward_amount
+        );
+        Ok(())
+    }
+
+    /// Create the mint-wide statistics account for a mint (permissionless, payer funds rent)
+    pub fn init_mint_stats(ctx: Context<InitMintStats>) -> Result<()> {
+        let stats = &mut ctx.accounts.mint_stats;
+        stats.mint = ctx.accounts.mint.key();
+        stats.reporting_pools = 0;
+        stats.total_distributed = 0;
+        stats.total_claims = 0;
+        stats.last_claim_at = 0;
+        stats.bump = ctx.bumps.mint_stats;
+
+        msg!("Mint stats created for {}", stats.mint);
+        Ok(())
+    }
+
+    /// Opt the pool in or out of reporting its claims to the mint-wide statistics (admin only)
+    pub fn set_mint_stats_reporting(
+        ctx: Context<SetMintStatsReporting>,
+        enabled: bool,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        let stats = &mut ctx.accounts.mint_stats;
+
+        if enabled != pool.report_mint_stats {
+            stats.reporting_pools = if enabled {
+                stats.reporting_pools.checked_add(1)
+            } else {
+                stats.reporting_pools.checked_sub(1)
+            }
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            pool.report_mint_stats = enabled;
+        }
+
+        msg!("Mint stats reporting set to {}", enabled);
+        Ok(())
+    }
+
+    /// Create a pool's statistics account for UTC day `day_index` (permissionless; the
+    /// caller pays rent). Clients create today's account before the first claim of the day.
+    pub fn init_daily_stats(ctx: Context<InitDailyStats>, day_index: u64) -> Result<()> {
+        let stats = &mut ctx.accounts.daily_stats;
+        stats.pool = ctx.accounts.reward_pool.key();
+        stats.day_index = day_index;
+        stats.claims_count = 0;
+        stats.amount_distributed = 0;
+        stats.unique_claimers = 0;
+        stats.bump = ctx.bumps.daily_stats;
+
+        msg!("Daily stats created for day {}", day_index);
+        Ok(())
+    }
+
+    /// Keep a `ClaimReceipt` for every direct claim for at least `expiry_secs` (admin
+    /// only); 0 turns receipts off. Cranked, settled, member and compounding claims are
+    /// rejected while receipts are kept
+    pub fn set_receipt_policy(ctx: Context<UpdatePoolConfig>, expiry_secs: i64) -> Result<()> {
+        require!(expiry_secs >= 0, ErrorCode::InvalidReceiptExpiry);
+        ctx.accounts.reward_pool.receipt_expiry_secs = expiry_secs;
+
+        msg!("Claim receipts kept for {}s", expiry_secs);
+        Ok(())
+    }
+
+    /// Close an expired claim receipt, returning its rent to whoever paid for it
+    pub fn close_receipt(ctx: Context<CloseReceipt>) -> Result<()> {
+        let receipt = &ctx.accounts.claim_receipt;
+        require!(
+            Clock::get()?.unix_timestamp >= receipt.expires_at,
+            ErrorCode::ReceiptNotExpired
+        );
+
+        msg!("Receipt {} for {} closed", receipt.claim_index, receipt.user);
+        Ok(())
+    }
+
+    /// Configure badge milestone `index` (admin only): users who reach `threshold` claims
+    /// or earned tokens can mint the badge NFT described by `name`, `symbol` and `uri`
+    pub fn set_badge_milestone(
+        ctx: Context<SetBadgeMilestone>,
+        index: u8,
+        kind: MilestoneKind,
+        threshold: u64,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        require!(
+            usize::from(index) < MAX_BADGE_MILESTONES
+                && threshold > 0
+                && name.len() <= MAX_BADGE_NAME_LEN
+                && symbol.len() <= MAX_BADGE_SYMBOL_LEN
+                && uri.len() <= MAX_BADGE_URI_LEN,
+            ErrorCode::InvalidBadgeMilestone
+        );
+
+        ctx.accounts.milestone.set_inner(BadgeMilestone {
+            pool: ctx.accounts.reward_pool.key(),
+            index,
+            kind,
+            threshold,
+            name,
+            symbol,
+            uri,
+            bump: ctx.bumps.milestone,
+        });
+
+        msg!("Badge milestone {} set: {:?} >= {}", index, kind, threshold);
+        Ok(())
+    }
+
+    /// Mint the caller's badge NFT for a milestone they have reached. Each badge is a
+    /// one-of-one Metaplex master edition and can be minted once per user.
+    pub fn mint_milestone_badge(ctx: Context<MintMilestoneBadge>) -> Result<()> {
+        let milestone = &ctx.accounts.milestone;
+        let user_account = &mut ctx.accounts.user_account;
+        let badge_bit = 1u8 << milestone.index;
+
+        require!(
+            user_account.badges_minted & badge_bit == 0,
+            ErrorCode::BadgeAlreadyMinted
+        );
+        let progress = match milestone.kind {
+            MilestoneKind::ClaimCount => user_account.total_claims,
+            MilestoneKind::TotalEarned => user_account.total_earned,
+        };
+        require!(
+            progress >= milestone.threshold,
+            ErrorCode::MilestoneNotReached
+        );
+        user_account.badges_minted |= badge_bit;
+
+        let pool = &ctx.accounts.reward_pool;
+        let seeds = &[b"reward_pool", pool.authority.as_ref(), &[pool.bump]];
+        let signer = &[&seeds[..]];
+        let pool_info = ctx.accounts.reward_pool.to_account_info();
+
+        let cpi_accounts = MintTo {
+            mint: ctx.accounts.badge_mint.to_account_info(),
+            to: ctx.accounts.badge_token_account.to_account_info(),
+            authority: pool_info.clone(),
+        };
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        token_interface::mint_to(
+            CpiContext::new_with_signer(cpi_program, cpi_accounts, signer),
+            1,
+        )?;
+
+        let metadata_program = ctx.accounts.metadata_program.to_account_info();
+        create_metadata_accounts_v3(
+            CpiContext::new_with_signer(
+                metadata_program.clone(),
+                CreateMetadataAccountsV3 {
+                    metadata: ctx.accounts.badge_metadata.to_account_info(),
+                    mint: ctx.accounts.badge_mint.to_account_info(),
+                    mint_authority: pool_info.clone(),
+                    payer: ctx.accounts.authority.to_account_info(),
+                    update_authority: pool_info.clone(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+                signer,
+            ),
+            DataV2 {
+                name: milestone.name.clone(),
+                symbol: milestone.symbol.clone(),
+                uri: milestone.uri.clone(),
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            },
+            false,
+            true,
+            None,
+        )?;
+        // Hands the mint authority to the edition, fixing the supply at one
+        create_master_edition_v3(
+            CpiContext::new_with_signer(
+                metadata_program,
+                CreateMasterEditionV3 {
+                    edition: ctx.accounts.badge_edition.to_account_info(),
+                    mint: ctx.accounts.badge_mint.to_account_info(),
+                    update_authority: pool_info.clone(),
+                    mint_authority: pool_info,
+                    payer: ctx.accounts.authority.to_account_info(),
+                    metadata: ctx.accounts.badge_metadata.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    rent: ctx.accounts.rent.to_account_info(),
+                },
+                signer,
+            ),
+            Some(0),
+        )?;
+
+        emit!(BadgeMinted {
+            pool: pool.key(),
+            user: user_account.authority,
+            index: milestone.index,
+            mint: ctx.accounts.badge_mint.key(),
+        });
+        Ok(())
+    }
+
+    /// Require claims to report to the pool's daily statistics (admin only)
+    pub fn set_daily_stats_reporting(ctx: Context<UpdatePoolConfig>, enabled: bool) -> Result<()> {
+        ctx.accounts.reward_pool.report_daily_stats = enabled;
+
+        msg!("Daily stats reporting set to {}", enabled);
+        Ok(())
+    }
+
+    /// Set the share of each referee's token claims credited to their referrer (admin only)
+    pub fn set_referral_bps(ctx: Context<UpdatePoolConfig>, referral_bps: u16) -> Result<()> {
+        require!(
+            u64::from(referral_bps) <= math::BPS_DENOMINATOR,
+            ErrorCode::InvalidBasisPoints
+        );
+        ctx.accounts.reward_pool.referral_bps = referral_bps;
+
+        msg!("Referral bonus set to {} bps", referral_bps);
+        Ok(())
+    }
+
+    /// Set or rotate the registration allowlist root (admin only); all zeroes opens registration
+    pub fn set_merkle_root(ctx: Context<UpdatePoolConfig>, merkle_root: [u8; 32]) -> Result<()> {
+        ctx.accounts.reward_pool.merkle_root = merkle_root;
+
+        if merkle_root == [0; 32] {
+            msg!("Registration allowlist cleared");
+        } else {
+            msg!("Registration allowlist root set");
+        }
+        Ok(())
+    }
+
+    /// Publish new terms of service (admin only). Bumps the terms version, so every user
+    /// must `acknowledge_terms` again before their next claim.
+    pub fn set_terms(ctx: Context<UpdatePoolConfig>, terms_hash: [u8; 32]) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.terms_hash = terms_hash;
+        pool.terms_version = pool
+            .terms_version
+            .checked_add(1)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+        msg!("Terms updated to version {}", pool.terms_version);
+        Ok(())
+    }
+
+    /// Stop the caller's accrual until `resume_accrual`; history and streaks are kept
+    pub fn pause_accrual(ctx: Context<SetAccrualPause>) -> Result<()> {
+        require!(
+            ctx.accounts.reward_pool.accrual_paused_at == 0,
+            ErrorCode::PoolAccrualPaused
+        );
+        let user_account = &mut ctx.accounts.user_account;
+        require!(user_account.paused_at == 0, ErrorCode::AccrualPaused);
+
+        user_account.paused_at = Clock::get()?.unix_timestamp;
+
+        msg!("Accrual paused for {}", user_account.authority);
+        Ok(())
+    }
+
+    /// Resume accrual; the paused stretch is excluded from the current accrual period
+    pub fn resume_accrual(ctx: Context<SetAccrualPause>) -> Result<()> {
+        let user_account = &mut ctx.accounts.user_account;
+        require!(user_account.paused_at != 0, ErrorCode::AccrualNotPaused);
+
+        let paused_for = Clock::get()?
+            .unix_timestamp
+            .checked_sub(user_account.paused_at)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+        user_account.paused_seconds = user_account
+            .paused_seconds
+            .checked_add(paused_for)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        user_account.total_paused_seconds = user_account
+            .total_paused_seconds
+            .checked_add(paused_for)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        user_account.paused_at = 0;
+        user_account.refresh_next_eligible_at(&ctx.accounts.reward_pool)?;
+
+        msg!(
+            "Accrual resumed for {} after {} seconds",
+            user_account.authority,
+            paused_for
+        );
+        Ok(())
+    }
+
+    /// Declare a past program outage (admin only). Time inside it no longer counts against
+    /// users: multiplier expiries, streak windows and the accrual window are extended by
+    /// the overlap with each user's current accrual period. Replaces any earlier declaration.
+    pub fn declare_outage(ctx: Context<UpdatePoolConfig>, start: i64, end: i64) -> Result<()> {
+        require!(
+            start < end && end <= Clock::get()?.unix_timestamp,
+            ErrorCode::InvalidOutageWindow
+        );
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.outage_start = start;
+        pool.outage_end = end;
+
+        msg!("Outage declared from {} to {}", start, end);
+        Ok(())
+    }
+
+    /// Configure the claim lottery (admin only): each draw wins with `chance_bps`
+    /// probability and multiplies that claim by `multiplier_bps`; 0 chance disables it
+    pub fn set_lottery(
+        ctx: Context<UpdatePoolConfig>,
+        chance_bps: u16,
+        multiplier_bps: u16,
+    ) -> Result<()> {
+        require!(
+            u64::from(chance_bps) <= math::BPS_DENOMINATOR
+                && (chance_bps == 0 || u64::from(multiplier_bps) > math::BPS_DENOMINATOR),
+            ErrorCode::InvalidLotteryConfig
+        );
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.lottery_chance_bps = chance_bps;
+        pool.lottery_multiplier_bps = multiplier_bps;
+
+        msg!(
+            "Lottery set: {} bps chance of a {} bps multiplier",
+            chance_bps,
+            multiplier_bps
+        );
+        Ok(())
+    }
+
+    /// Commit to a lottery draw for the caller's next claim. The draw uses the hash of the
+    /// commit slot, unknown when committing, and can't be re-rolled: only a claim consumes
+    /// it, and a claim more than ~512 slots later loses.
+    pub fn commit_lottery_draw(ctx: Context<CommitLotteryDraw>) -> Result<()> {
+        require!(
+            ctx.accounts.reward_pool.lottery_chance_bps > 0,
+            ErrorCode::LotteryNotEnabled
+        );
+        let user_account = &mut ctx.accounts.user_account;
+        require!(
+            user_account.lottery_commit_slot == 0,
+            ErrorCode::LotteryDrawPending
+        );
+
+        user_account.lottery_commit_slot = Clock::get()?.slot;
+
+        msg!(
+            "Lottery draw committed at slot {}",
+            user_account.lottery_commit_slot
+        );
+        Ok(())
+    }
+
+    /// Create the pool's top-earners leaderboard (permissionless; the caller pays rent).
+    /// Claims passing it keep it up to date.
+    pub fn init_leaderboard(ctx: Context<InitLeaderboard>) -> Result<()> {
+        let mut leaderboard = ctx.accounts.leaderboard.load_init()?;
+        leaderboard.pool = ctx.accounts.reward_pool.key();
+        leaderboard.bump = ctx.bumps.leaderboard;
+
+        msg!("Leaderboard created for pool {}", leaderboard.pool);
+        Ok(())
+    }
+
+    /// Record the Realms governance controlling the pool; its native treasury must be the
+    /// pool authority, so this (like every admin instruction) runs through a proposal
+    pub fn set_governance(
+        ctx: Context<UpdatePoolConfig>,
+        governance: Option<Pubkey>,
+        governance_program: Pubkey,
+    ) -> Result<()> {
+        if let Some(governance) = governance {
+            require_keys_eq!(
+                crate::governance::native_treasury(&governance, &governance_program),
+                ctx.accounts.authority.key(),
+                ErrorCode::InvalidGovernance
+            );
+        }
+        ctx.accounts.reward_pool.governance = governance;
+
+        msg!("Pool governance set: {:?}", governance);
+        Ok(())
+    }
+
+    /// Record the caller's acceptance of the pool's current terms
+    pub fn acknowledge_terms(ctx: Context<AcknowledgeTerms>, terms_version: u32) -> Result<()> {
+        let user_account = &mut ctx.accounts.user_account;
+        let now = Clock::get()?.unix_timestamp;
+        user_account.accept_terms(&ctx.accounts.reward_pool, terms_version, now)?;
+
+        msg!(
+            "User {} accepted terms version {}",
+            user_account.authority,
+            terms_version
+        );
+        Ok(())
+    }
+
+    /// Publish a one-shot airdrop (admin only): `merkle_root` commits to `(wallet, amount)`
+    /// leaves, and at most `total_amount` tokens are paid out across all claims
+    pub fn create_airdrop(
+        ctx: Context<CreateAirdrop>,
+        airdrop_id: u64,
+        merkle_root: [u8; 32],
+        total_amount: u64,
+    ) -> Result<()> {
+        require!(
+            merkle_root != [0; 32] && total_amount > 0,
+            ErrorCode::InvalidAirdropConfig
+        );
+        require!(
+            ctx.accounts.reward_pool.distribution_mode != DistributionMode::NativeSol,
+            ErrorCode::WrongDistributionMode
+        );
+
+        ctx.accounts.airdrop.set_inner(Airdrop {
+            pool: ctx.accounts.reward_pool.key(),
+            airdrop_id,
+            merkle_root,
+            total_amount,
+            claimed_amount: 0,
+            claimed_count: 0,
+            created_at: Clock::get()?.unix_timestamp,
+            bump: ctx.bumps.airdrop,
+        });
+
+        msg!("Airdrop {} created for up to {} tokens", airdrop_id, total_amount);
+        Ok(())
+    }
+
+    /// Claim the caller's airdrop allocation; the receipt PDA makes this one-shot
+    pub fn claim_airdrop(
+        ctx: Context<ClaimAirdrop>,
+        _airdrop_id: u64,
+        amount: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        ensure_not_denylisted(
+            &ctx.accounts.denylist_entry,
+            ctx.accounts.reward_pool.key(),
+            ctx.accounts.claimant.key(),
+        )?;
+
+        let pool = &mut ctx.accounts.reward_pool;
+        let airdrop = &mut ctx.accounts.airdrop;
+        let clock = Clock::get()?;
+        require!(!pool.claims_paused, ErrorCode::ClaimsPaused);
+        require!(amount > 0, ErrorCode::NoRewardsAvailable);
+        require!(
+            merkle::verify(
+                &proof,
+                airdrop.merkle_root,
+                merkle::airdrop_leaf(&ctx.accounts.claimant.key(), amount),
+            ),
+            ErrorCode::InvalidAirdropProof
+        );
+
+        let claimed_amount = airdrop
+            .claimed_amount
+            .checked_add(amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        require!(
+            claimed_amount <= airdrop.total_amount,
+            ErrorCode::AirdropExhausted
+        );
+        ensure_within_budget(pool, amount)?;
+
+        let seeds = &[
+            b"reward_pool",
+            pool.authority.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+
+        match pool.distribution_mode {
+            DistributionMode::Vault => {
+                require!(
+                    ctx.accounts.vault.amount >= amount,
+                    ErrorCode::InsufficientVaultFunds
+                );
+                let cpi_accounts = TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.claimant_token_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+            }
+            DistributionMode::MintOnClaim => {
+                let cpi_accounts = MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.claimant_token_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::mint_to(cpi_ctx, amount)?;
+            }
+            DistributionMode::NativeSol => return err!(ErrorCode::WrongDistributionMode),
+        }
+
+        airdrop.claimed_amount = claimed_amount;
+        airdrop.claimed_count = airdrop
+            .claimed_count
+            .checked_add(1)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.total_distributed = pool
+            .total_distributed
+            .checked_add(amount)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        deactivate_if_budget_spent(pool);
+
+        let receipt = &mut ctx.accounts.receipt;
+        receipt.airdrop = airdrop.key();
+        receipt.claimant = ctx.accounts.claimant.key();
+        receipt.amount = amount;
+        receipt.claimed_at = clock.unix_timestamp;
+        receipt.bump = ctx.bumps.receipt;
+
+        msg!(
+            "Airdrop {} claimed: {} tokens to {}",
+            airdrop.airdrop_id,
+            amount,
+            ctx.accounts.claimant.key()
+        );
+        Ok(())
+    }
+
+    /// Configure who may commit accrual snapshots and how often (admin only)
+    pub fn set_snapshot_policy(
+        ctx: Context<UpdatePoolConfig>,
+        snapshot_cranker: Option<Pubkey>,
+        snapshot_interval_secs: i64,
+    ) -> Result<()> {
+        require!(snapshot_interval_secs >= 0, ErrorCode::InvalidSnapshot);
+
+        let pool = &mut ctx.accounts.reward_pool;
+        pool.snapshot_cranker = snapshot_cranker;
+        pool.snapshot_interval_secs = snapshot_interval_secs;
+
+        msg!(
+            "Snapshot policy updated: cranker {:?}, every {}s",
+            snapshot_cranker,
+            snapshot_interval_secs
+        );
+        Ok(())
+    }
+
+    /// Commit the Merkle root of every user's accrued reward as of `as_of` (snapshot
+    /// cranker only). Leaves use the airdrop encoding, `keccak256(wallet || accrued_le)`,
+    /// so a snapshot root can also seed `create_airdrop`.
+    pub fn commit_accrual_snapshot(
+        ctx: Context<CommitAccrualSnapshot>,
+        merkle_root: [u8; 32],
+        as_of: i64,
+        user_count: u64,
+        total_accrued: u64,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.reward_pool;
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            merkle_root != [0; 32] && as_of <= now && as_of > pool.last_snapshot_as_of,
+            ErrorCode::InvalidSnapshot
+        );
+        if pool.last_snapshot_as_of != 0 {
+            let due_at = pool
+                .last_snapshot_as_of
+                .checked_add(pool.snapshot_interval_secs)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            require!(as_of >= due_at, ErrorCode::SnapshotTooSoon);
+        }
+
+        let index = pool.snapshot_count;
+        ctx.accounts.snapshot.set_inner(AccrualSnapshot {
+            pool: pool.key(),
+            index,
+            merkle_root,
+            as_of,
+            committed_at: now,
+            user_count,
+            total_accrued,
+            bump: ctx.bumps.snapshot,
+        });
+        pool.snapshot_count = index
+            .checked_add(1)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        pool.last_snapshot_as_of = as_of;
+
+        emit!(AccrualSnapshotCommitted {
+            pool: pool.key(),
+            index,
+            merkle_root,
+            as_of,
+            user_count,
+            total_accrued,
+        });
+        msg!("Accrual snapshot {} committed for {} users", index, user_count);
+        Ok(())
+    }
+
+    /// Check that `wallet` had `accrued` rewards in a snapshot; fails unless the proof holds,
+    /// so other programs can rely on it via CPI
+    pub fn verify_snapshot_entry(
+        ctx: Context<VerifySnapshotEntry>,
+        _index: u64,
+        wallet: Pubkey,
+        accrued: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(
+            merkle::verify(
+                &proof,
+                ctx.accounts.snapshot.merkle_root,
+                merkle::airdrop_leaf(&wallet, accrued),
+            ),
+            ErrorCode::InvalidSnapshotProof
+        );
+        Ok(())
+    }
+
+    /// Pay out the caller's accumulated referral bonuses
+    pub fn claim_referral_earnings(ctx: Context<ClaimReferralEarnings>) -> Result<()> {
+        ensure_not_denylisted(
+            &ctx.accounts.denylist_entry,
+            ctx.accounts.reward_pool.key(),
+            ctx.accounts.authority.key(),
+        )?;
+
+        let user_account = &mut ctx.accounts.user_account;
+        let pool = &mut ctx.accounts.reward_pool;
+        require!(!pool.claims_paused, ErrorCode::ClaimsPaused);
+
+        // Earnings beyond the pool's remaining budget stay owed for a later claim
+        let amount = user_account
+            .referral_earned
+            .checked_sub(user_account.referral_paid)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?
+            .min(pool_budget_remaining(pool)?);
+        require!(amount > 0, ErrorCode::NoRewardsAvailable);
+
+        let seeds = &[
+            b"reward_pool",
+            pool.authority.as_ref(),
+            &[pool.bump],
+        ];
+        let signer = &[&seeds[..]];
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+
+        match pool.distribution_mode {
+            DistributionMode::Vault => {
+                require!(
+                    ctx.accounts.vault.amount >= amount,
+                    ErrorCode::InsufficientVaultFunds
+                );
+                let cpi_accounts = TransferChecked {
+                    from: ctx.accounts.vault.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::transfer_checked(cpi_ctx, amount, ctx.accounts.mint.decimals)?;
+            }
+            DistributionMode::MintOnClaim => {
+                let cpi_accounts = MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: pool.to_account_info(),
+                };
+                let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+                token_interface::mint_to(cpi_ctx, amount)?;
+            }
+            DistributionMode::NativeSol => return err!(ErrorCode::WrongDistributionMode),
+        }
+
+        record_referral_payout(pool, user_account, amount)?;
+
+        msg!("Referral earnings claimed: {} tokens", amount);
+        Ok(())
+    }
+
+    /// Pay out the caller's accumulated referral bonuses from a native SOL pool, in lamports
+    pub fn claim_native_referral_earnings(
+        ctx: Context<ClaimNativeReferralEarnings>,
+    ) -> Result<()> {
+        ensure_not_denylisted(
+            &ctx.accounts.denylist_entry,
+            ctx.accounts.reward_pool.key(),
+            ctx.accounts.authority.key(),
+        )?;
+
+        let user_account = &mut ctx.accounts.user_account;
+        let pool = &mut ctx.accounts.reward_pool;
+        require!(
+            pool.distribution_mode == DistributionMode::NativeSol,
+            ErrorCode::WrongDistributionMode
+        );
+        require!(!pool.claims_paused, ErrorCode::ClaimsPaused);
+
+        // Earnings beyond the pool's remaining budget stay owed for a later claim
+        let amount = user_account
+            .referral_earned
+            .checked_sub(user_account.referral_paid)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?
+            .min(pool_budget_remaining(pool)?);
+        require!(amount > 0, ErrorCode::NoRewardsAvailable);
+
+        // The SOL vault must stay rent exempt, so only lamports above the floor are spendable
+        let vault_info = ctx.accounts.sol_vault.to_account_info();
+        require!(
+            vault_info
+                .lamports()
+                .saturating_sub(Rent::get()?.minimum_balance(0))
+                >= amount,
+            ErrorCode::InsufficientVaultFunds
+        );
+
+        let pool_key = pool.key();
+        let seeds = &[b"sol_vault", pool_key.as_ref(), &[pool.sol_vault_bump]];
+        let signer = &[&seeds[..]];
+        let cpi_accounts = system_program::Transfer {
+            from: vault_info,
+            to: ctx.accounts.authority.to_account_info(),
+        };
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            cpi_accounts,
+            signer,
+        );
+        system_program::transfer(cpi_ctx, amount)?;
+
+        record_referral_payout(pool, user_account, amount)?;
+
+        msg!("Referral earnings claimed: {} lamports", amount);
+        Ok(())
+    }
+}
+
+/// Shared pool initialization for `initialize_pool` and its presets
+fn init_pool(
+    ctx: &mut Context<InitializePool>,
+    reward_rate_per_hour: u64,
+    min_claim_interval_secs: u64,
+    max_daily_reward: u64,
+    max_accrual_hours: u64,
+    distribution_mode: DistributionMode,
+) -> Result<()> {
+    let pool_key = ctx.accounts.reward_pool.key();
+    match distribution_mode {
+        DistributionMode::Vault => {}
+        DistributionMode::MintOnClaim => {
+            require!(
+                ctx.accounts.mint.mint_authority == COption::Some(pool_key),
+                ErrorCode::PoolNotMintAuthority
+            );
+        }
+        DistributionMode::NativeSol => {
+            require_keys_eq!(
+                ctx.accounts.mint.key(),
+                spl_token::native_mint::ID,
+                ErrorCode::NativePoolRequiresNativeMint
+            );
+        }
+    }
+    let (_, sol_vault_bump) =
+        Pubkey::find_program_address(&[b"sol_vault", pool_key.as_ref()], ctx.program_id);
+
+    let pool = &mut ctx.accounts.reward_pool;
+    let clock = Clock::get()?;
+
+    pool.authority = ctx.accounts.authority.key();
+    pool.mint = ctx.accounts.mint.key();
+    pool.vault = ctx.accounts.vault.key();
+    pool.reward_rate_per_hour = reward_rate_per_hour;
+    pool.min_claim_interval_secs = min_claim_interval_secs;
+    pool.max_daily_reward = max_daily_reward;
+    pool.max_accrual_hours = max_accrual_hours;
+    pool.distribution_mode = distribution_mode;
+    pool.sol_vault_bump = sol_vault_bump;
+    pool.total_distributed = 0;
+    pool.participant_count = 0;
+    pool.claims_paused = false;
+    pool.registrations_paused = false;
+    pool.accrual_paused_at = 0;
+    pool.total_accrual_paused_secs = 0;
+    pool.allow_partial_payout = false;
+    pool.gross_up_transfer_fees = false;
+    pool.total_transfer_fees = 0;
+    pool.dormancy_period_secs = 0;
+    pool.dormancy_grace_secs = 0;
+    pool.rent_recovery_fund = ctx.accounts.authority.key();
+    pool.region_verifier = None;
+    pool.require_region_attestation = false;
+    pool.total_withheld = 0;
+    pool.faucet = None;
+    pool.vesting = None;
+    pool.total_vesting_locked = 0;
+    pool.streak_window_hours = 0;
+    pool.streak_bonus_bps = 0;
+    pool.max_streak_bonus_bps = 0;
+    pool.streak_insurance_fee = 0;
+    pool.total_streak_insurance_fees = 0;
+    pool.cadence_target_hours = 0;
+    pool.cadence_tolerance_hours = 0;
+    pool.cadence_bonus_bps = 0;
+    pool.cadence_penalty_bps = 0;
+    pool.matching_sponsor = None;
+    pool.nft_boost = None;
+    pool.stake_mint = None;
+    pool.stake_reward_rate_per_hour = 0;
+    pool.total_staked = 0;
+    pool.reward_per_token_stored = 0;
+    pool.stake_rewards_updated_at = 0;
+    pool.nft_staking = None;
+    pool.report_mint_stats = false;
+    pool.referral_bps = 0;
+    pool.merkle_root = [0; 32];
+    pool.terms_hash = [0; 32];
+    pool.terms_version = 0;
+    pool.outage_start = 0;
+    pool.outage_end = 0;
+    pool.max_claim_per_tx = 0;
+    pool.total_session_locked = 0;
+    pool.crank_tip_bps = 0;
+    pool.total_crank_tips = 0;
+    pool.normalized_accrual = false;
+    pool.mint_decimals = 0;
+    pool.safe_mode = None;
+    pool.pending_withdrawal = None;
+    pool.yield_venue = None;
+    pool.yield_deployed = 0;
+    pool.yield_earned = 0;
+    pool.yield_min_liquid = 0;
+    pool.snapshot_cranker = None;
+    pool.snapshot_interval_secs = 0;
+    pool.snapshot_count = 0;
+    pool.last_snapshot_as_of = 0;
+    pool.emission = None;
+    pool.budget_per_hour = 0;
+    pool.floor_rate_per_hour = 0;
+    pool.runway_throttle = false;
+    pool.epoch_duration_secs = 0;
+    pool.epoch_budget = 0;
+    pool.current_epoch = 0;
+    pool.accrual_start = 0;
+    pool.accrual_end = 0;
+    pool.max_lifetime_reward_per_user = 0;
+    pool.max_total_distribution = 0;
+    pool.deactivate_when_budget_spent = false;
+    pool.usd_pricing = None;
+    pool.lottery_chance_bps = 0;
+    pool.lottery_multiplier_bps = 0;
+    pool.governance = None;
+    pool.report_daily_stats = false;
+    pool.receipt_expiry_secs = 0;
+    pool.reward_per_activity_point = 0;
+    pool.boost_granter_programs = Vec::new();
+    pool.max_granted_boost_bps = math::BPS_DENOMINATOR as u16;
+    pool.attestor = None;
+    pool.max_claims_per_day = 0;
+    pool.config_delay_secs = 0;
+    pool.pending_config = None;
+    pool.previous_rate_per_hour = 0;
+    pool.rate_changed_at = 0;
+    pool.emergency_withdraw_delay_secs = MIN_EMERGENCY_WITHDRAW_DELAY_SECS;
+    pool.reward_rate_fraction = 0;
+    pool.previous_rate_fraction = 0;
+    pool.rounding = RoundingMode::Floor;
+    pool.created_at = clock.unix_timestamp;
+    pool.bump = ctx.bumps.reward_pool;
+    pool.version = POOL_VERSION;
+    pool.transfer_memos = false;
+    pool.registration_fee = None;
+    pool.max_sponsored_per_day = 0;
+    pool.sponsored_day = 0;
+    pool.sponsored_today = 0;
+    pool.boost_tiers = [BoostTier::default(); MAX_BOOST_TIERS];
+    pool.total_boost_sales = 0;
+    pool.boost_proceeds_destination = None;
+    pool.burn_bps = 0;
+    pool.total_burned = 0;
+    pool.compound_growth_bps = 0;
+    pool._legacy_min_claim_interval_hours = 0;
+    pool.total_referral_owed = 0;
+    pool.total_carried_over = 0;
+    pool.yield_deposit_account = None;
+    pool.pending_yield_venue = None;
+    pool.pending_model = None;
+    pool.previous_model = AccrualModel::default();
+
+    msg!("Reward pool initialized with rate: {} per hour", reward_rate_per_hour);
+    Ok(())
+}
+
+/// Load the region policy at an (already derived) PDA, if one has been configured
+fn load_region_policy(info: &AccountInfo) -> Result<Option<RegionPolicy>> {
+    if info.data_is_empty() {
+        return Ok(None);
+    }
+
+    if info.owner != &crate::ID {
+        return Err(anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram.into());
+    }
+    // Checks the discriminator; deserializing directly avoids tying `info` to `'info`
+    let policy = RegionPolicy::try_deserialize(&mut &info.data.borrow()[..])?;
+    Ok(Some(policy))
+}
+
+/// Check that `nft_mint` is a single-supply NFT held in `nft_token_account` and verified as a
+/// member of `collection` in its Metaplex metadata
+fn verify_collection_nft(
+    nft_mint: &Mint,
+    nft_token_account: &TokenAccount,
+    nft_metadata: &MetadataAccount,
+    collection: Pubkey,
+) -> Result<()> {
+    require!(
+        nft_mint.decimals == 0 && nft_mint.supply == 1 && nft_token_account.amount == 1,
+        ErrorCode::InvalidBoostNft
+    );
+    let in_collection = match &nft_metadata.collection {
+        Some(member_of) => member_of.verified && member_of.key == collection,
+        None => false,
+    };
+    require!(in_collection, ErrorCode::NftNotInCollection);
+    Ok(())
+}
+
+/// Pay a staked NFT's accrued rewards to its owner and advance its accrual checkpoint
+fn settle_staked_nft<'info>(
+    pool: &mut Account<'info, RewardPool>,
+    staked_nft: &mut Account<'info, StakedNft>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    owner_reward_account: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+) -> Result<u64> {
+    let config = pool
+        .nft_staking
+        .ok_or(error!(ErrorCode::NftStakingNotEnabled))?;
+    let now = Clock::get()?.unix_timestamp;
+    let (accrued_hours, reward_amount) = math::nft_stake_reward(
+        config.reward_rate_per_hour,
+        staked_nft.last_accrual_timestamp,
+        now,
+    )?;
+    if reward_amount == 0 {
+        return Ok(0);
+    }
+
+    let seeds = &[
+        b"reward_pool",
+        pool.authority.as_ref(),
+        &[pool.bump],
+    ];
+    let signer = &[&seeds[..]];
+    let cpi_program = token_program.to_account_info();
+
+    match pool.distribution_mode {
+        DistributionMode::Vault => {
+            require!(
+                vault.amount >= reward_amount,
+                ErrorCode::InsufficientVaultFunds
+            );
+            let cpi_accounts = TransferChecked {
+                from: vault.to_account_info(),
+                mint: mint.to_account_info(),
+                to: owner_reward_account.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token_interface::transfer_checked(cpi_ctx, reward_amount, mint.decimals)?;
+        }
+        DistributionMode::MintOnClaim => {
+            let cpi_accounts = MintTo {
+                mint: mint.to_account_info(),
+                to: owner_reward_account.to_account_info(),
+                authority: pool.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+            token_interface::mint_to(cpi_ctx, reward_amount)?;
+        }
+        DistributionMode::NativeSol => return err!(ErrorCode::WrongDistributionMode),
+    }
+
+    // Only whole hours are paid, so carry the partial hour into the next accrual
+    let accrued_secs = i64::try_from(accrued_hours)
+        .ok()
+        .and_then(|hours| hours.checked_mul(math::SECONDS_PER_HOUR))
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    staked_nft.last_accrual_timestamp = staked_nft
+        .last_accrual_timestamp
+        .checked_add(accrued_secs)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    staked_nft.total_earned = staked_nft
+        .total_earned
+        .checked_add(reward_amount)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    pool.total_distributed = pool
+        .total_distributed
+        .checked_add(reward_amount)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    Ok(reward_amount)
+}
+
+/// Move `lamports` of rent from a sponsorship campaign into `destination`, within the
+/// campaign's budget and the pool's daily sponsorship limit
+fn pay_sponsored_rent(
+    pool: &mut RewardPool,
+    sponsor: &mut Account<RentSponsor>,
+    destination: &AccountInfo,
+    lamports: u64,
+    now: i64,
+) -> Result<()> {
+    require!(sponsor.is_active, ErrorCode::RentSponsorNotActive);
+    let spent_lamports = sponsor
+        .spent_lamports
+        .checked_add(lamports)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    require!(
+        spent_lamports <= sponsor.budget_lamports,
+        ErrorCode::RentSponsorBudgetExceeded
+    );
+
+    let today = math::day_index(now);
+    let sponsored_today = if pool.sponsored_day == today {
+        pool.sponsored_today
+    } else {
+        0
+    };
+    require!(
+        pool.max_sponsored_per_day == 0 || sponsored_today < pool.max_sponsored_per_day,
+        ErrorCode::SponsorshipDailyLimitReached
+    );
+
+    // The sponsor must stay rent exempt after paying
+    let sponsor_info = sponsor.to_account_info();
+    let sponsor_floor = Rent::get()?.minimum_balance(sponsor_info.data_len());
+    let available = sponsor_info
+        .lamports()
+        .checked_sub(sponsor_floor)
+        .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+    require!(
+        available >= lamports,
+        ErrorCode::RentSponsorInsufficientFunds
+    );
+    **sponsor_info.try_borrow_mut_lamports()? -= lamports;
+    **destination.try_borrow_mut_lamports()? += lamports;
+
+    sponsor.spent_lamports = spent_lamports;
+    pool.sponsored_day = today;
+    pool.sponsored_today = sponsored_today + 1;
+    Ok(())
+}
+
+/// A wallet registering in a pool, through any of the registration instructions
+struct Registration<'a, 'info> {
+    authority: Pubkey,
+    bump: u8,
+    denylist_entry: &'a AccountInfo<'info>,
+    /// The wallet's other account seeding: its registration account when joining, its
+    /// membership when registering
+    sibling_account: &'a AccountInfo<'info>,
+    proof: &'a [[u8; 32]],
+    terms_version: u32,
+    /// Instructions sysvar holding the attestor's signature check, and the attestation's
+    /// expiry; required exactly when the pool has an attestor
+    attestation: Option<(&'a AccountInfo<'info>, i64)>,
+    /// User account of the wallet that referred this one
+    referrer: Option<&'a UserAccount>,
+    /// Accounts paying the registration fee; `None` for exempt (sponsored) registrations
+    fee: Option<RegistrationFeeAccounts<'a, 'info>>,
+}
+
+/// Admit a wallet to `pool` and count it as a participant: registrations must be open,
+/// the wallet vouched for by the pool's attestor if it has one, not denylisted, not
+/// already in the pool, on the allowlist and paying any registration fee. Returns the new
+/// user account for the caller to store.
+fn register(pool: &mut Account<RewardPool>, registration: Registration) -> Result<UserAccount> {
+    require!(!pool.registrations_paused, ErrorCode::RegistrationsPaused);
+    if registration.attestation.is_none() {
+        require!(pool.attestor.is_none(), ErrorCode::AttestationRequired);
+    }
+    ensure_not_denylisted(
+        registration.denylist_entry,
+        pool.key(),
+        registration.authority,
+    )?;
+    ensure_not_in_pool(registration.sibling_account, pool.key())?;
+    ensure_allowlisted(pool, &registration.authority, registration.proof)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    if let Some((instructions, expires_at)) = registration.attestation {
+        let attestor = pool
+            .attestor
+            .ok_or(error!(ErrorCode::AttestationNotEnabled))?;
+        require!(now < expires_at, ErrorCode::AttestationExpired);
+        attestation::verify_attestation(
+            instructions,
+            &attestor,
+            &attestation::attestation_message(&registration.authority, expires_at),
+        )?;
+    }
+    if let Some(referrer) = registration.referrer {
+        require_keys_neq!(
+            referrer.authority,
+            registration.authority,
+            ErrorCode::SelfReferral
+        );
+    }
+    if let Some(fee_accounts) = registration.fee {
+        charge_registration_fee(pool, fee_accounts)?;
+    }
+
+    let mut user_account = UserAccount::new(
+        registration.authority,
+        pool.key(),
+        now,
+        registration.bump,
+    );
+    user_account.referrer = registration.referrer.map(|referrer| referrer.referral_key);
+    user_account.refresh_next_eligible_at(pool)?;
+    user_account.checkpoint_pool_pause(pool, now);
+    user_account.accept_terms(pool, registration.terms_version, now)?;
+
+    pool.participant_count = pool
+        .participant_count
+        .checked_add(1)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    Ok(user_account)
+}
+
+/// Registering user's accounts a registration fee can be paid from and into; the
+/// optional ones are only needed for the kind of fee the pool charges
+struct RegistrationFeeAccounts<'a, 'info> {
+    payer: &'a Signer<'info>,
+    system_program: &'a Program<'info, System>,
+    sol_vault: Option<&'a SystemAccount<'info>>,
+    fee_token_account: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    vault: Option<&'a InterfaceAccount<'info, TokenAccount>>,
+    mint: Option<&'a InterfaceAccount<'info, Mint>>,
+    token_program: Option<&'a Interface<'info, TokenInterface>>,
+}
+
+/// Collect the pool's registration fee, if it charges one, from the registering user
+fn charge_registration_fee(
+    pool: &RewardPool,
+    accounts: RegistrationFeeAccounts<'_, '_>,
+) -> Result<()> {
+    match pool.registration_fee {
+        None | Some(RegistrationFee::Lamports(0)) | Some(RegistrationFee::Tokens(0)) => Ok(()),
+        Some(RegistrationFee::Lamports(fee)) => {
+            let sol_vault = accounts
+                .sol_vault
+                .ok_or(error!(ErrorCode::RegistrationFeeAccountsRequired))?;
+            let cpi_accounts = system_program::Transfer {
+                from: accounts.payer.to_account_info(),
+                to: sol_vault.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(accounts.system_program.to_account_info(), cpi_accounts);
+            system_program::transfer(cpi_ctx, fee)
+        }
+        Some(RegistrationFee::Tokens(fee)) => {
+            let (Some(source), Some(vault), Some(mint), Some(token_program)) = (
+                accounts.fee_token_account,
+                accounts.vault,
+                accounts.mint,
+                accounts.token_program,
+            ) else {
+                return err!(ErrorCode::RegistrationFeeAccountsRequired);
+            };
+            let cpi_accounts = TransferChecked {
+                from: source.to_account_info(),
+                mint: mint.to_account_info(),
+                to: vault.to_account_info(),
+                authority: accounts.payer.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(token_program.to_account_info(), cpi_accounts);
+            token_interface::transfer_checked(cpi_ctx, fee, mint.decimals)
+        }
+    }
+}
+
+/// Write `memo` through the SPL Memo program if the pool tags its transfers. Called right
+/// before the transfer it describes, which also satisfies Token-2022 accounts that
+/// require a memo on incoming transfers.
+fn write_transfer_memo(
+    pool: &RewardPool,
+    memo_program: Option<&Program<'_, Memo>>,
+    memo: impl FnOnce() -> String,
+) -> Result<()> {
+    if !pool.transfer_memos {
+        return Ok(());
+    }
+    let memo_program = memo_program.ok_or(error!(ErrorCode::MemoProgramRequired))?;
+    let cpi_ctx = CpiContext::new(memo_program.to_account_info(), BuildMemo {});
+    build_memo(cpi_ctx, memo().as_bytes())
+}
+
+/// Carry forward the share of a claim a short vault left unpaid, where `paid` of `owed`
+/// was covered.
+///
+/// `owed` has been through USD pricing, the lottery, throttling, region policy and any
+/// burn, so it isn't in accrual units. The carryover rejoins the next claim's accrual and
+/// goes through those steps again, so it is kept as the same share of the accrual rather
+/// than as the unpaid amount itself.
+fn carry_unpaid_share(breakdown: &mut RewardBreakdown, paid: u64, owed: u64) -> Result<()> {
+    let unpaid = fixed_point::mul_div(
+        breakdown.reward_amount,
+        owed - paid,
+        owed,
+        RoundingMode::Floor,
+    )?;
+    breakdown.carryover = breakdown
+        .carryover
+        .checked_add(unpaid)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    Ok(())
+}
+
+/// The share of a claim a Vault pool burns under its `burn_bps`
+fn claim_burn_amount(pool: &RewardPool, reward_amount: u64) -> Result<u64> {
+    if pool.distribution_mode != DistributionMode::Vault || pool.burn_bps == 0 {
+        return Ok(0);
+    }
+    math::apply_bps(reward_amount, pool.burn_bps, pool.rounding)
+}
+
+/// Burn `amount` of claimed rewards from the vault, covering `claims` claims, and record it
+fn burn_from_vault<'info>(
+    pool: &mut Account<'info, RewardPool>,
+    vault: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    amount: u64,
+    claims: u64,
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    require!(vault.amount >= amount, ErrorCode::InsufficientVaultFunds);
+
+    let authority = pool.authority;
+    let bump = [pool.bump];
+    let seeds = &[b"reward_pool".as_ref(), authority.as_ref(), &bump];
+    let signer = &[&seeds[..]];
+    let cpi_accounts = Burn {
+        mint: mint.to_account_info(),
+        from: vault.to_account_info(),
+        authority: pool.to_account_info(),
+    };
+    let cpi_ctx =
+        CpiContext::new_with_signer(token_program.to_account_info(), cpi_accounts, signer);
+    token_interface::burn(cpi_ctx, amount)?;
+
+    pool.total_burned = pool
+        .total_burned
+        .checked_add(amount)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    emit!(RewardsBurned {
+        pool: pool.key(),
+        amount,
+        claims,
+        total_burned: pool.total_burned,
+    });
+    Ok(())
+}
+
+/// Add a pool's payouts to its mint-wide statistics when the pool reports them
+fn record_mint_stats(
+    pool: &RewardPool,
+    mint_stats: Option<&mut Account<MintStats>>,
+    distributed: u64,
+    claims: u64,
+    now: i64,
+) -> Result<()> {
+    if !pool.report_mint_stats {
+        return Ok(());
+    }
+
+    let stats = mint_stats.ok_or(error!(ErrorCode::MintStatsRequired))?;
+    stats.total_distributed = stats
+        .total_distributed
+        .checked_add(distributed)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    stats.total_claims = stats
+        .total_claims
+        .checked_add(claims)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    stats.last_claim_at = now;
+    Ok(())
+}
+
+/// Realloc a program account to `space` bytes, topping up its rent from `payer`. The new
+/// tail is zeroed, which decodes as the default of any field appended to the layout.
+fn grow_account<'info>(
+    info: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+) -> Result<()> {
+    if info.data_len() >= space {
+        return Ok(());
+    }
+
+    let shortfall = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(info.lamports());
+    if shortfall > 0 {
+        let cpi_accounts = system_program::Transfer {
+            from: payer.to_account_info(),
+            to: info.clone(),
+        };
+        let cpi_ctx = CpiContext::new(system_program.to_account_info(), cpi_accounts);
+        system_program::transfer(cpi_ctx, shortfall)?;
+    }
+    info.realloc(space, true)?;
+    Ok(())
+}
+
+/// Consume the pool's announced emergency withdrawal, which must match `amount` and
+/// `destination` and have waited out its delay
+fn take_announced_withdrawal(
+    pool: &mut Account<RewardPool>,
+    amount: u64,
+    destination: Pubkey,
+) -> Result<()> {
+    let pending = pool
+        .pending_withdrawal
+        .ok_or(error!(ErrorCode::NoPendingWithdrawal))?;
+    require!(
+        pending.amount == amount && pending.destination == destination,
+        ErrorCode::WithdrawalNotAnnounced
+    );
+    require!(
+        Clock::get()?.unix_timestamp >= pending.executable_at,
+        ErrorCode::WithdrawalTimelockActive
+    );
+    pool.pending_withdrawal = None;
+    Ok(())
+}
+
+/// Apply the pool's scheduled config change if it is due, announcing it
+fn apply_due_config(pool: &mut Account<RewardPool>, now: i64) {
+    if let Some(applied) = pool.apply_pending_config(now) {
+        emit!(PendingConfigApplied {
+            pool: pool.key(),
+            reward_rate_per_hour: applied.reward_rate_per_hour,
+            reward_rate_fraction: applied.reward_rate_fraction,
+            max_daily_reward: applied.max_daily_reward,
+            max_accrual_hours: applied.max_accrual_hours,
+            effective_at: applied.effective_at,
+        });
+    }
+}
+
+/// Schedule an accrual model change like a rate change: it takes effect after the pool's
+/// config delay, at once without one, and either way only for accrual from then on.
+/// Returns when it takes effect.
+fn schedule_accrual_model(
+    pool: &mut Account<RewardPool>,
+    model: AccrualModel,
+    now: i64,
+) -> Result<i64> {
+    apply_due_config(pool, now);
+    let effective_at = now
+        .checked_add(pool.config_delay_secs)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    pool.pending_config = Some(PendingConfig {
+        effective_at,
+        ..pool.next_config()
+    });
+    pool.pending_model = Some(model);
+    apply_due_config(pool, now);
+    Ok(effective_at)
+}
+
+/// Add claims to today's statistics when the pool reports daily stats
+fn record_daily_stats(
+    pool: &RewardPool,
+    daily_stats: Option<&mut Account<DailyStats>>,
+    distributed: u64,
+    claims: u64,
+    unique_claimers: u64,
+    now: i64,
+) -> Result<()> {
+    if !pool.report_daily_stats {
+        return Ok(());
+    }
+
+    let stats = daily_stats.ok_or(error!(ErrorCode::DailyStatsRequired))?;
+    require!(
+        stats.day_index == math::day_index(now),
+        ErrorCode::DailyStatsNotCurrent
+    );
+    stats.claims_count = stats
+        .claims_count
+        .checked_add(claims)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    stats.amount_distributed = stats
+        .amount_distributed
+        .checked_add(distributed)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    stats.unique_claimers = stats
+        .unique_claimers
+        .checked_add(unique_claimers)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    Ok(())
+}
+
+/// Budget left in the pool's current epoch, or `None` if the pool doesn't run in epochs
+fn epoch_budget_remaining(
+    pool: &RewardPool,
+    epoch: Option<&Account<Epoch>>,
+    now: i64,
+) -> Result<Option<u64>> {
+    if pool.epoch_duration_secs == 0 {
+        return Ok(None);
+    }
+
+    let epoch = epoch.ok_or(error!(ErrorCode::EpochAccountRequired))?;
+    require!(
+        epoch.index == pool.current_epoch && now < epoch.end_time,
+        ErrorCode::EpochNotCurrent
+    );
+    Ok(Some(epoch.budget.saturating_sub(epoch.distributed_this_epoch)))
+}
+
+/// Debit payouts from the pool's current epoch budget when the pool runs epochs
+fn record_epoch_claim(
+    pool: &RewardPool,
+    epoch: Option<&mut Account<Epoch>>,
+    distributed: u64,
+    claims: u64,
+    now: i64,
+) -> Result<()> {
+    let Some(remaining) = epoch_budget_remaining(pool, epoch.as_deref(), now)? else {
+        return Ok(());
+    };
+    require!(distributed <= remaining, ErrorCode::EpochBudgetExhausted);
+
+    let epoch = epoch.ok_or(error!(ErrorCode::EpochAccountRequired))?;
+    epoch.distributed_this_epoch = epoch
+        .distributed_this_epoch
+        .checked_add(distributed)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    epoch.claim_count = epoch
+        .claim_count
+        .checked_add(claims)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    Ok(())
+}
+
+/// Drop a user's NFT boost unless `nft_token_account` shows they still hold the NFT
+fn reverify_nft_boost(
+    user_account: &mut UserAccount,
+    nft_token_account: Option<&InterfaceAccount<TokenAccount>>,
+) {
+    let Some(nft_mint) = user_account.nft_boost_mint else {
+        return;
+    };
+    let holds_nft = match nft_token_account {
+        Some(account) => {
+            account.mint == nft_mint
+                && account.owner == user_account.authority
+                && account.amount > 0
+        }
+        None => false,
+    };
+    if !holds_nft {
+        msg!("NFT boost revoked: {} is no longer held", nft_mint);
+        user_account.nft_boost_mint = None;
+        user_account.nft_boost_bps = 0;
+    }
+}
+
+/// Fail (and emit a `DenylistBlocked` event) if the address has a denylist entry.
+/// `entry` must already be verified as the PDA for `(pool, address)`.
+fn ensure_not_denylisted(entry: &AccountInfo, pool: Pubkey, address: Pubkey) -> Result<()> {
+    if entry.data_is_empty() {
+        return Ok(());
+    }
+
+    emit!(DenylistBlocked { pool, address });
+    msg!("Blocked denylisted address: {}", address);
+    err!(ErrorCode::AddressDenylisted)
+}
+
+/// Fail if `user_account` is a user account in `pool`, so a wallet can't accrue through
+/// both its registration account and a membership in the same pool
+fn ensure_not_in_pool(user_account: &AccountInfo, pool: Pubkey) -> Result<()> {
+    if user_account.owner != &crate::ID || user_account.data_is_empty() {
+        return Ok(());
+    }
+
+    // The pool follows the discriminator and authority in every layout version, so this
+    // doesn't need the account migrated first
+    let data = user_account.try_borrow_data()?;
+    let account_pool = data
+        .get(8 + 32..8 + 64)
+        .and_then(|bytes| Pubkey::try_from(bytes).ok())
+        .ok_or(error!(ErrorCode::AlreadyInPool))?;
+    require_keys_neq!(account_pool, pool, ErrorCode::AlreadyInPool);
+    Ok(())
+}
+
+/// Fail with `ClaimTooSoon`, publishing the seconds until the user can retry both as a
+/// `ClaimRetryHint` event and as return data (little-endian i64)
+fn ensure_claim_interval(
+    pool: &Account<RewardPool>,
+    user_account: &UserAccount,
+    breakdown: &RewardBreakdown,
+    now: i64,
+) -> Result<()> {
+    require!(
+        pool.max_claims_per_day == 0
+            || math::claims_today(user_account, now) < pool.max_claims_per_day,
+        ErrorCode::TooManyClaimsToday
+    );
+    if breakdown.interval_met {
+        return Ok(());
+    }
+
+    let retry_after_secs = math::seconds_until_claimable(pool, user_account, now)?;
+    let eligible_at = math::eligible_at(pool, user_account)?;
+    set_return_data(&retry_after_secs.to_le_bytes());
+    emit!(ClaimRetryHint {
+        pool: pool.key(),
+        user: user_account.authority,
+        retry_after_secs,
+        eligible_at,
+    });
+    msg!("Claim too soon; retry in {} seconds", retry_after_secs);
+    err!(ErrorCode::ClaimTooSoon)
+}
+
+/// What the user may still earn under the pool's per-wallet lifetime cap
+/// (`u64::MAX` when uncapped)
+fn lifetime_reward_remaining(pool: &RewardPool, user_account: &UserAccount) -> Result<u64> {
+    if pool.max_lifetime_reward_per_user == 0 {
+        return Ok(u64::MAX);
+    }
+
+    let remaining = pool
+        .max_lifetime_reward_per_user
+        .saturating_sub(user_account.total_earned);
+    require!(remaining > 0, ErrorCode::LifetimeCapReached);
+    Ok(remaining)
+}
+
+/// What the pool may still pay out under its total distribution cap, counting vesting and
+/// session payouts already locked (`u64::MAX` when uncapped)
+fn pool_budget_remaining(pool: &RewardPool) -> Result<u64> {
+    if pool.max_total_distribution == 0 {
+        return Ok(u64::MAX);
+    }
+
+    let committed = pool
+        .total_distributed
+        .saturating_add(pool.total_vesting_locked)
+        .saturating_add(pool.total_session_locked)
+        .saturating_add(pool.total_burned);
+    let remaining = pool.max_total_distribution.saturating_sub(committed);
+    require!(remaining > 0, ErrorCode::PoolBudgetExhausted);
+    Ok(remaining)
+}
+
+/// Fail unless all of `amount` fits in the pool's remaining distribution budget, for
+/// payouts of a fixed amount that can't be trimmed to fit
+fn ensure_within_budget(pool: &RewardPool, amount: u64) -> Result<()> {
+    require!(
+        amount <= pool_budget_remaining(pool)?,
+        ErrorCode::PoolBudgetExhausted
+    );
+    Ok(())
+}
+
+/// Close the campaign once its total distribution cap is spent, if the pool opted in,
+/// by pausing claims and registrations
+fn deactivate_if_budget_spent(pool: &mut RewardPool) {
+    if pool.deactivate_when_budget_spent
+        && pool.max_total_distribution > 0
+        && pool
+            .total_distributed
+            .saturating_add(pool.total_vesting_locked)
+            .saturating_add(pool.total_session_locked)
+            .saturating_add(pool.total_burned)
+            >= pool.max_total_distribution
+    {
+        pool.claims_paused = true;
+        pool.registrations_paused = true;
+        msg!("Pool budget spent; claims and registrations paused");
+    }
+}
+
+/// Reveal the user's committed lottery draw from the commit slot's hash. The claim must
+/// land after the commit slot; once the hash has left the SlotHashes sysvar the draw loses.
+fn draw_lottery(
+    pool: &RewardPool,
+    user_account: &UserAccount,
+    slot_hashes: Option<&UncheckedAccount>,
+    current_slot: u64,
+) -> Result<bool> {
+    let commit_slot = user_account.lottery_commit_slot;
+    require!(current_slot > commit_slot, ErrorCode::LotteryNotRevealable);
+    if pool.lottery_chance_bps == 0 {
+        return Ok(false);
+    }
+
+    let slot_hashes = slot_hashes.ok_or(error!(ErrorCode::SlotHashesRequired))?;
+    let data = slot_hashes.try_borrow_data()?;
+    // Bincode layout: u64 entry count, then (slot u64, hash [u8; 32]) entries, newest first
+    let slot_hash = data
+        .get(8..)
+        .unwrap_or_default()
+        .chunks_exact(40)
+        .find(|entry| entry[..8] == commit_slot.to_le_bytes())
+        .map(|entry| &entry[8..]);
+    let Some(slot_hash) = slot_hash else {
+        msg!("Lottery draw from slot {} expired", commit_slot);
+        return Ok(false);
+    };
+
+    let seed = keccak::hashv(&[
+        slot_hash,
+        user_account.authority.as_ref(),
+        &user_account.total_claims.to_le_bytes(),
+    ])
+    .to_bytes();
+    let roll = u64::from_le_bytes(seed[..8].try_into().unwrap()) % math::BPS_DENOMINATOR;
+    Ok(roll < u64::from(pool.lottery_chance_bps))
+}
+
+/// Reveal and consume the user's pending lottery draw, if any, returning `reward_amount`
+/// with any bonus it won. Every claim path calls this so a paid claim never leaves a
+/// commit pending.
+fn reveal_lottery(
+    pool: &Account<RewardPool>,
+    user_account: &mut UserAccount,
+    slot_hashes: Option<&UncheckedAccount>,
+    current_slot: u64,
+    reward_amount: u64,
+) -> Result<u64> {
+    if user_account.lottery_commit_slot == 0 {
+        return Ok(reward_amount);
+    }
+    let won = draw_lottery(pool, user_account, slot_hashes, current_slot)?;
+    user_account.lottery_commit_slot = 0;
+    if !won {
+        return Ok(reward_amount);
+    }
+
+    let bonus_bps = pool
+        .lottery_multiplier_bps
+        .saturating_sub(math::BPS_DENOMINATOR as u16);
+    let bonus = math::apply_bps(reward_amount, bonus_bps, pool.rounding)?;
+    user_account.lottery_wins = user_account
+        .lottery_wins
+        .checked_add(1)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    user_account.lottery_bonus_earned = user_account
+        .lottery_bonus_earned
+        .checked_add(bonus)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+    emit!(LotteryWon {
+        pool: pool.key(),
+        user: user_account.authority,
+        multiplier_bps: pool.lottery_multiplier_bps,
+        bonus,
+    });
+    reward_amount
+        .checked_add(bonus)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))
+}
+
+/// Credit the referrer's claimable referral balance with `referral_bps` of a claim's net
+/// payout, returning the bonus for the pool's `total_referral_owed`. Every claim path calls
+/// this once the user has been paid.
+fn credit_referral(
+    pool: &Account<RewardPool>,
+    user_account: &UserAccount,
+    referrer_account: Option<&mut UserAccount>,
+    net_amount: u64,
+) -> Result<u64> {
+    let Some(referrer) = user_account.referrer else {
+        return Ok(0);
+    };
+    if pool.referral_bps == 0 {
+        return Ok(0);
+    }
+    let referrer_account = referrer_account.ok_or(error!(ErrorCode::ReferrerAccountRequired))?;
+    require_keys_eq!(
+        referrer_account.referral_key,
+        referrer,
+        ErrorCode::ReferrerAccountRequired
+    );
+
+    let bonus = math::apply_bps(net_amount, pool.referral_bps, pool.rounding)?;
+    referrer_account.referral_earned = referrer_account
+        .referral_earned
+        .checked_add(bonus)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+    emit!(ReferralBonus {
+        pool: pool.key(),
+        referrer: referrer_account.authority,
+        referee: user_account.authority,
+        claim_amount: net_amount,
+        bonus,
+    });
+    Ok(bonus)
+}
+
+/// One pool's claim in `claim_all`, checked and sized but not yet paid
+struct PooledClaim<'info> {
+    pool: Account<'info, RewardPool>,
+    user_account: Account<'info, UserAccount>,
+    breakdown: RewardBreakdown,
+    payout_amount: u64,
+}
+
+/// Fail unless `claim_all`, which takes only the pool, user account, vault and
+/// destination, can claim from `pool`. `join_pool` checks the same, so memberships aren't
+/// opened in pools `claim_all` would always refuse.
+fn ensure_claimable_together(pool: &RewardPool) -> Result<()> {
+    // Minting and burning need the mint account
+    require!(
+        pool.distribution_mode == DistributionMode::Vault,
+        ErrorCode::WrongDistributionMode
+    );
+    require!(pool.burn_bps == 0, ErrorCode::BurnRequiresDirectClaim);
+    require!(pool.faucet.is_none(), ErrorCode::FaucetRequiresDirectClaim);
+    require!(
+        pool.vesting.is_none(),
+        ErrorCode::VestingRequiresDirectClaim
+    );
+    require!(
+        pool.usd_pricing.is_none(),
+        ErrorCode::UsdPricingRequiresDirectClaim
+    );
+    require!(
+        pool.matching_sponsor.is_none(),
+        ErrorCode::MatchingRequiresDirectClaim
+    );
+    require!(
+        pool.receipt_expiry_secs == 0,
+        ErrorCode::ReceiptsRequireDirectClaim
+    );
+    require!(!pool.report_mint_stats, ErrorCode::MintStatsRequired);
+    require!(
+        pool.epoch_duration_secs == 0,
+        ErrorCode::EpochAccountRequired
+    );
+    require!(!pool.report_daily_stats, ErrorCode::DailyStatsRequired);
+    Ok(())
+}
+
+/// Load, check and size the signer's claim from one `[reward_pool, user_account, vault,
+/// destination]` group of `claim_all` accounts. An error only fails this pool's claim, and
+/// the caller drops the accounts without writing them back.
+fn prepare_pooled_claim<'info>(
+    group: &'info [AccountInfo<'info>],
+    authority: Pubkey,
+    slot_hashes: Option<&UncheckedAccount>,
+    clock: &Clock,
+) -> Result<PooledClaim<'info>> {
+    let (pool_info, user_info, vault_info, destination_info) =
+        (&group[0], &group[1], &group[2], &group[3]);
+    let mut pool: Account<RewardPool> = Account::try_from(pool_info)?;
+    let mut user_account: Account<UserAccount> = Account::try_from(user_info)?;
+    require_keys_eq!(
+        user_account.authority,
+        authority,
+        ErrorCode::InvalidClaimAllAccounts
+    );
+    require_keys_eq!(user_account.pool, pool.key(), ErrorCode::UserPoolMismatch);
+    ensure_claimable_together(&pool)?;
+    // A plain transfer takes no mint, which Token-2022 needs to check its extensions
+    let vault: InterfaceAccount<TokenAccount> = InterfaceAccount::try_from(vault_info)?;
+    require_keys_eq!(vault.key(), pool.vault, ErrorCode::InvalidClaimAllAccounts);
+    require_keys_eq!(
+        *vault_info.owner,
+        spl_token::ID,
+        ErrorCode::Token2022RequiresDirectClaim
+    );
+    require_keys_eq!(
+        destination_info.key(),
+        get_associated_token_address_with_program_id(&authority, &pool.mint, &spl_token::ID),
+        ErrorCode::InvalidClaimAllAccounts
+    );
+    // The destination must already exist, or the payout would fail every pool
+    InterfaceAccount::<TokenAccount>::try_from(destination_info)?;
+
+    // The wallet's denylist entry, region policy and boost NFT would take more accounts
+    require!(pool.denylist_len == 0, ErrorCode::DenylistRequiresDirectClaim);
+    require!(
+        pool.region_policy_count == 0,
+        ErrorCode::RegionPolicyRequiresDirectClaim
+    );
+    require!(
+        user_account.nft_boost_mint.is_none(),
+        ErrorCode::NftBoostRequiresDirectClaim
+    );
+
+    let now = clock.unix_timestamp;
+    require!(!pool.claims_paused, ErrorCode::ClaimsPaused);
+    require!(
+        user_account.referrer.is_none() || pool.referral_bps == 0,
+        ErrorCode::ReferrerAccountRequired
+    );
+    require!(user_account.banned_at == 0, ErrorCode::UserBanned);
+    require!(user_account.is_active, ErrorCode::UserNotActive);
+    require!(
+        user_account.terms_version >= pool.terms_version,
+        ErrorCode::TermsNotAccepted
+    );
+    require!(user_account.paused_at == 0, ErrorCode::AccrualPaused);
+    if pool.require_region_attestation {
+        require!(
+            user_account.region_attested_at != 0,
+            ErrorCode::RegionAttestationRequired
+        );
+    }
+
+    apply_due_config(&mut pool, now);
+    let breakdown = compute_reward(&pool, &user_account, now)?;
+    ensure_claim_interval(&pool, &user_account, &breakdown, now)?;
+    // Pricing scheduled before the pool was checked may have just taken effect
+    require!(
+        breakdown.usd_priced_reward == 0,
+        ErrorCode::UsdPricingRequiresDirectClaim
+    );
+    let reward_amount = reveal_lottery(
+        &pool,
+        &mut user_account,
+        slot_hashes,
+        clock.slot,
+        breakdown.reward_amount,
+    )?;
+
+    let throttle_bps = math::runway_throttle_bps(&pool, vault.amount, now)?;
+    let reward_amount = math::apply_throttle(reward_amount, throttle_bps)?
+        .min(lifetime_reward_remaining(&pool, &user_account)?)
+        .min(pool_budget_remaining(&pool)?);
+    require!(reward_amount > 0, ErrorCode::NoRewardsAvailable);
+    ensure_co_signed(&user_account, reward_amount, None)?;
+    require!(
+        pool.max_claim_per_tx == 0 || reward_amount <= pool.max_claim_per_tx,
+        ErrorCode::ClaimSessionRequired
+    );
+    if reward_amount > vault.amount && pool.yield_deployed > 0 {
+        return err!(ErrorCode::YieldWithdrawalRequired);
+    }
+    require!(
+        vault.amount >= reward_amount,
+        ErrorCode::InsufficientVaultFunds
+    );
+
+    Ok(PooledClaim {
+        pool,
+        user_account,
+        breakdown,
+        payout_amount: reward_amount,
+    })
+}
+
+/// Custom error code `error` would fail an instruction with, or 0 for a builtin program
+/// error
+fn error_code_number(error: &Error) -> u32 {
+    match error {
+        Error::AnchorError(error) => error.error_code_number,
+        Error::ProgramError(error) => match error.program_error {
+            ProgramError::Custom(code) => code,
+            _ => 0,
+        },
+    }
+}
+
+/// Record `amount` claimed in a new receipt when the pool keeps receipts, and reject a
+/// receipt account passed to a pool that does not
+fn write_claim_receipt(
+    pool: &Account<RewardPool>,
+    user_account: &UserAccount,
+    claim_receipt: Option<&mut Account<ClaimReceipt>>,
+    payer: Pubkey,
+    bump: u8,
+    amount: u64,
+    now: i64,
+) -> Result<()> {
+    if pool.receipt_expiry_secs == 0 {
+        require!(claim_receipt.is_none(), ErrorCode::ReceiptsNotEnabled);
+        return Ok(());
+    }
+    let receipt = claim_receipt.ok_or(error!(ErrorCode::ClaimReceiptRequired))?;
+    receipt.set_inner(ClaimReceipt {
+        pool: pool.key(),
+        user: user_account.authority,
+        claim_index: user_account.total_claims,
+        amount,
+        timestamp: now,
+        rate_per_hour: math::rate_per_hour_at(pool, now)?,
+        payer,
+        expires_at: now
+            .checked_add(pool.receipt_expiry_secs)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?,
+        bump,
+    });
+    Ok(())
+}
+
+/// Vesting positions and claim sessions are seeded by the user account's address, so a
+/// wallet rotation or recovery would leave them behind with the closed account
+fn ensure_no_open_claims(user_account: &UserAccount) -> Result<()> {
+    require!(
+        user_account.open_vesting_positions == 0 && user_account.open_claim_sessions == 0,
+        ErrorCode::OpenClaimsBlockRotation
+    );
+    Ok(())
+}
+
+/// Bookkeeping for `amount` of referral earnings paid out to `user_account`
+fn record_referral_payout(
+    pool: &mut RewardPool,
+    user_account: &mut UserAccount,
+    amount: u64,
+) -> Result<()> {
+    user_account.referral_paid = user_account
+        .referral_paid
+        .checked_add(amount)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    pool.total_referral_owed = pool.total_referral_owed.saturating_sub(amount);
+    pool.total_distributed = pool
+        .total_distributed
+        .checked_add(amount)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    deactivate_if_budget_spent(pool);
+    Ok(())
+}
+
+/// Shared accounts need their co-signer for claims above the solo limit
+fn ensure_co_signed(
+    user_account: &UserAccount,
+    amount: u64,
+    co_signer: Option<&Signer>,
+) -> Result<()> {
+    let required = match user_account.co_signer {
+        Some(co_signer) if amount > user_account.solo_claim_limit => co_signer,
+        _ => return Ok(()),
+    };
+
+    let signer = co_signer.ok_or(error!(ErrorCode::CoSignatureRequired))?;
+    require_keys_eq!(signer.key(), required, ErrorCode::CoSignatureRequired);
+    Ok(())
+}
+
+fn ensure_allowlisted(pool: &RewardPool, wallet: &Pubkey, proof: &[[u8; 32]]) -> Result<()> {
+    if pool.merkle_root == [0; 32] {
+        return Ok(());
+    }
+
+    require!(
+        merkle::verify(proof, pool.merkle_root, merkle::leaf(wallet)),
+        ErrorCode::NotAllowlisted
+    );
+    Ok(())
+}
+
+/// On safe-mode pools, require the guardian's co-signature for anything that lets funds
+/// leave the vault outside of claims
+fn ensure_guardian_approved(pool: &RewardPool, guardian: Option<&Signer>) -> Result<()> {
+    if let Some(safe_mode) = pool.safe_mode {
+        let guardian = guardian.ok_or(error!(ErrorCode::NotPoolGuardian))?;
+        require_keys_eq!(
+            guardian.key(),
+            safe_mode.guardian,
+            ErrorCode::NotPoolGuardian
+        );
+    }
+    Ok(())
+}
+
+/// CPI into the pool's yield venue. The pool never signs it: the venue is an outside
+/// program, and the pool PDA's signature would let it move the whole vault.
+fn invoke_yield_venue<'info>(
+    venue_program: &AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    data: Vec<u8>,
+) -> Result<()> {
+    let instruction = Instruction {
+        program_id: venue_program.key(),
+        accounts: accounts
+            .iter()
+            .map(|account| AccountMeta {
+                pubkey: account.key(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            })
+            .collect(),
+        data,
+    };
+
+    let mut infos = accounts.to_vec();
+    infos.push(venue_program.clone());
+    invoke(&instruction, &infos)?;
+    Ok(())
+}
+
+/// Who can move the vault's tokens besides the pool: its owner, delegate (and delegated
+/// amount) and close authority, compared around yield venue calls
+fn vault_authorities(vault: &TokenAccount) -> (Pubkey, COption<Pubkey>, u64, COption<Pubkey>) {
+    (
+        vault.owner,
+        vault.delegate,
+        vault.delegated_amount,
+        vault.close_authority,
+    )
+}
+
+/// Most recent activity for a user: last claim, or registration if they never claimed
+fn last_activity_timestamp(user_account: &UserAccount) -> i64 {
+    user_account
+        .last_claim_timestamp
+        .max(user_account.registration_timestamp)
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RewardPool::INIT_SPACE,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterUser<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + UserAccount::INIT_SPACE,
+        seeds = [b"user_account", authority.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    /// CHECK: denylist PDA for the signer; must not exist
+    #[account(
+        seeds = [b"denylist", reward_pool.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: the signer's `join_pool` membership in the pool; must not exist
+    #[account(
+        seeds = [b"user_account", reward_pool.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub membership: UncheckedAccount<'info>,
+
+    /// Pool's lamport reserve, required only when the pool charges a lamport registration fee
+    #[account(
+        mut,
+        seeds = [b"sol_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.sol_vault_bump
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Token accounts paying a token registration fee into the vault, required only when
+    /// the pool charges one
+    #[account(mut)]
+    pub fee_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, address = reward_pool.vault)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(address = reward_pool.mint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinPool<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + UserAccount::INIT_SPACE,
+        seeds = [b"user_account", reward_pool.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    /// CHECK: denylist PDA for the signer; must not exist
+    #[account(
+        seeds = [b"denylist", reward_pool.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: the signer's registration account; must not be in this pool
+    #[account(seeds = [b"user_account", authority.key().as_ref()], bump)]
+    pub registration: UncheckedAccount<'info>,
+
+    /// Pool's lamport reserve, required only when the pool charges a lamport registration fee
+    #[account(
+        mut,
+        seeds = [b"sol_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.sol_vault_bump
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Token accounts paying a token registration fee into the vault, required only when
+    /// the pool charges one
+    #[account(mut)]
+    pub fee_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, address = reward_pool.vault)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(address = reward_pool.mint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterUserWithAttestation<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + UserAccount::INIT_SPACE,
+        seeds = [b"user_account", authority.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    /// CHECK: denylist PDA for the signer; must not exist
+    #[account(
+        seeds = [b"denylist", reward_pool.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: the signer's `join_pool` membership in the pool; must not exist
+    #[account(
+        seeds = [b"user_account", reward_pool.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub membership: UncheckedAccount<'info>,
+
+    /// CHECK: instructions sysvar, used to find the attestor's Ed25519 signature check
+    #[account(address = instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// Pool's lamport reserve, required only when the pool charges a lamport registration fee
+    #[account(
+        mut,
+        seeds = [b"sol_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.sol_vault_bump
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Token accounts paying a token registration fee into the vault, required only when
+    /// the pool charges one
+    #[account(mut)]
+    pub fee_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, address = reward_pool.vault)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(address = reward_pool.mint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(referrer: Pubkey)]
+pub struct RegisterUserWithReferrer<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + UserAccount::INIT_SPACE,
+        seeds = [b"user_account", authority.key().as_ref()],
+        bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        constraint = referrer_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+        seeds = [b"user_account", referrer.as_ref()],
+        bump = referrer_account.bump
+    )]
+    pub referrer_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    /// CHECK: denylist PDA for the signer; must not exist
+    #[account(
+        seeds = [b"denylist", reward_pool.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: the signer's `join_pool` membership in the pool; must not exist
+    #[account(
+        seeds = [b"user_account", reward_pool.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub membership: UncheckedAccount<'info>,
+
+    /// Pool's lamport reserve, required only when the pool charges a lamport registration fee
+    #[account(
+        mut,
+        seeds = [b"sol_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.sol_vault_bump
+    )]
+    pub sol_vault: Option<SystemAccount<'info>>,
+
+    /// Token accounts paying a token registration fee into the vault, required only when
+    /// the pool charges one
+    #[account(mut)]
+    pub fee_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut, address = reward_pool.vault)]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(address = reward_pool.mint)]
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralEarnings<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: denylist PDA for the signer; must not exist
+    #[account(
+        seeds = [b"denylist", reward_pool.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimNativeReferralEarnings<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"sol_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.sol_vault_bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// CHECK: denylist PDA for the signer; must not exist
+    #[account(
+        seeds = [b"denylist", reward_pool.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(airdrop_id: u64)]
+pub struct CreateAirdrop<'info> {
+    #[account(
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Airdrop::INIT_SPACE,
+        seeds = [b"airdrop", reward_pool.key().as_ref(), &airdrop_id.to_le_bytes()],
+        bump
+    )]
+    pub airdrop: Account<'info, Airdrop>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitAccrualSnapshot<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = reward_pool.snapshot_cranker == Some(cranker.key())
+            @ ErrorCode::NotSnapshotCranker,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = cranker,
+        space = 8 + AccrualSnapshot::INIT_SPACE,
+        seeds = [
+            b"accrual_snapshot",
+            reward_pool.key().as_ref(),
+            &reward_pool.snapshot_count.to_le_bytes()
+        ],
+        bump
+    )]
+    pub snapshot: Account<'info, AccrualSnapshot>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u64)]
+pub struct VerifySnapshotEntry<'info> {
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"accrual_snapshot", reward_pool.key().as_ref(), &index.to_le_bytes()],
+        bump = snapshot.bump
+    )]
+    pub snapshot: Account<'info, AccrualSnapshot>,
+}
+
+#[derive(Accounts)]
+#[instruction(airdrop_id: u64)]
+pub struct ClaimAirdrop<'info> {
+    #[account(mut)]
+    pub claimant: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"airdrop", reward_pool.key().as_ref(), &airdrop_id.to_le_bytes()],
+        bump = airdrop.bump
+    )]
+    pub airdrop: Account<'info, Airdrop>,
+
+    /// Exists once the claimant has claimed, so a second claim fails on `init`
+    #[account(
+        init,
+        payer = claimant,
+        space = 8 + AirdropReceipt::INIT_SPACE,
+        seeds = [b"airdrop_receipt", airdrop.key().as_ref(), claimant.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, AirdropReceipt>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = claimant,
+        associated_token::mint = mint,
+        associated_token::authority = claimant,
+        associated_token::token_program = token_program,
+    )]
+    pub claimant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: denylist PDA for the signer; must not exist
+    #[account(
+        seeds = [b"denylist", reward_pool.key().as_ref(), claimant.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CalculateRewards<'info> {
+    /// CHECK: only used to derive the user account PDA; no signature needed to read it
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+}
+
+#[derive(Accounts)]
+pub struct SetAccrualPause<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+}
+
+#[derive(Accounts)]
+pub struct AcknowledgeTerms<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+}
+
+#[derive(Accounts)]
+pub struct CommitLotteryDraw<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+}
+
+#[derive(Accounts)]
+pub struct RefreshClaimSchedule<'info> {
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), user_account.authority.as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+}
+
+#[derive(Accounts)]
+pub struct PreviewClaim<'info> {
+    #[account(
+        seeds = [b"user_account", user_account.pool_seed(), user_account.authority.as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(address = reward_pool.vault)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct GetClaimQuote<'info> {
+    #[account(
+        seeds = [b"user_account", user_account.pool_seed(), user_account.authority.as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+}
+
+#[derive(Accounts)]
+pub struct StartEpochs<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Epoch::INIT_SPACE,
+        seeds = [b"epoch", reward_pool.key().as_ref(), &0u64.to_le_bytes()],
+        bump
+    )]
+    pub epoch: Account<'info, Epoch>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitLeaderboard<'info> {
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<Leaderboard>(),
+        seeds = [b"leaderboard", reward_pool.key().as_ref()],
+        bump
+    )]
+    pub leaderboard: AccountLoader<'info, Leaderboard>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(next_index: u64)]
+pub struct RollEpoch<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = reward_pool.epoch_duration_secs > 0 @ ErrorCode::EpochsNotStarted,
+        constraint = reward_pool.current_epoch.checked_add(1) == Some(next_index)
+            @ ErrorCode::EpochNotCurrent,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [
+            b"epoch",
+            reward_pool.key().as_ref(),
+            &reward_pool.current_epoch.to_le_bytes()
+        ],
+        bump = current_epoch.bump
+    )]
+    pub current_epoch: Account<'info, Epoch>,
+
+    #[account(
+        init,
+        payer = cranker,
+        space = 8 + Epoch::INIT_SPACE,
+        seeds = [b"epoch", reward_pool.key().as_ref(), &next_index.to_le_bytes()],
+        bump
+    )]
+    pub next_epoch: Account<'info, Epoch>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct GetThrottleFactor<'info> {
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(address = reward_pool.vault)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+}
+
+#[derive(Accounts)]
+pub struct ComparePools {}
+
+#[derive(Accounts)]
+pub struct BuyStreakInsurance<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = authority,
+        token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct SetUserMultiplier<'info> {
+    #[account(
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+        seeds = [b"user_account", user_account.pool_seed(), user_account.authority.as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct BanUser<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+        seeds = [b"user_account", user_account.pool_seed(), user_account.authority.as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetCoSigner<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    /// Existing co-signer, required when one is set
+    pub current_co_signer: Option<Signer<'info>>,
+
+    /// Incoming co-signer, required when setting one
+    pub new_co_signer: Option<Signer<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct CreateMatchingSponsor<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = sponsor,
+        space = 8 + MatchingSponsor::INIT_SPACE,
+        seeds = [b"matching_sponsor", reward_pool.key().as_ref()],
+        bump
+    )]
+    pub matching_sponsor: Account<'info, MatchingSponsor>,
+
+    #[account(
+        init,
+        payer = sponsor,
+        associated_token::mint = mint,
+        associated_token::authority = matching_sponsor,
+        associated_token::token_program = token_program,
+    )]
+    pub matching_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Where matched tokens go, e.g. a community treasury
+    #[account(token::mint = mint, token::token_program = token_program)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMatchingSponsor<'info> {
+    #[account(
+        mut,
+        has_one = sponsor,
+        seeds = [b"matching_sponsor", matching_sponsor.pool.as_ref()],
+        bump = matching_sponsor.bump
+    )]
+    pub matching_sponsor: Account<'info, MatchingSponsor>,
+
+    pub sponsor: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawMatchingFunds<'info> {
+    #[account(
+        has_one = sponsor,
+        has_one = vault,
+        seeds = [b"matching_sponsor", matching_sponsor.pool.as_ref()],
+        bump = matching_sponsor.bump
+    )]
+    pub matching_sponsor: Account<'info, MatchingSponsor>,
+
+    #[account(mut)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = sponsor,
+        token::token_program = token_program,
+    )]
+    pub sponsor_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = vault.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub sponsor: Signer<'info>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct CreateBoostAuction<'info> {
+    #[account(
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BoostAuction::INIT_SPACE,
+        seeds = [b"boost_auction", reward_pool.key().as_ref(), &epoch.to_le_bytes()],
+        bump
+    )]
+    pub auction: Account<'info, BoostAuction>,
+
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = auction,
+        associated_token::token_program = token_program,
+    )]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct PlaceBoostBid<'info> {
+    #[account(
+        mut,
+        has_one = escrow,
+        seeds = [b"boost_auction", auction.pool.as_ref(), &auction.epoch.to_le_bytes()],
+        bump = auction.bump
+    )]
+    pub auction: Account<'info, BoostAuction>,
+
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + BoostBid::INIT_SPACE,
+        seeds = [b"boost_bid", auction.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub bid: Account<'info, BoostBid>,
+
+    /// Only registered users of the pool can bid
+    #[account(
+        seeds = [b"user_account", user_account.pool_seed(), bidder.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == auction.pool @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(mut)]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bidder,
+        token::token_program = token_program,
+    )]
+    pub bidder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SettleBoostAuction<'info> {
+    #[account(
+        mut,
+        has_one = escrow,
+        seeds = [b"boost_auction", auction.pool.as_ref(), &auction.epoch.to_le_bytes()],
+        bump = auction.bump
+    )]
+    pub auction: Account<'info, BoostAuction>,
+
+    #[account(mut)]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+
+    /// Proceeds destination, required only when the auction does not burn
+    #[account(mut)]
+    pub treasury: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, address = escrow.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct FinalizeBoostBid<'info> {
+    #[account(
+        has_one = escrow,
+        seeds = [b"boost_auction", auction.pool.as_ref(), &auction.epoch.to_le_bytes()],
+        bump = auction.bump
+    )]
+    pub auction: Account<'info, BoostAuction>,
+
+    #[account(
+        mut,
+        close = bidder,
+        has_one = auction,
+        has_one = bidder,
+        seeds = [b"boost_bid", auction.key().as_ref(), bidder.key().as_ref()],
+        bump = bid.bump
+    )]
+    pub bid: Account<'info, BoostBid>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), bidder.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == auction.pool @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(mut)]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bidder,
+        token::token_program = token_program,
+    )]
+    pub bidder_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(address = escrow.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterNftBoost<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(token::mint = nft_mint, token::authority = authority)]
+    pub nft_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"metadata", Metadata::id().as_ref(), nft_mint.key().as_ref()],
+        seeds::program = Metadata::id(),
+        bump
+    )]
+    pub nft_metadata: Account<'info, MetadataAccount>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimRewards<'info> {
+    /// The account owner or their claim delegate
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: the account owner, whose associated token account receives the reward
+    #[account(address = user_account.authority)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), user_account.authority.as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.authority == authority.key()
+            || user_account.claim_delegate == Some(authority.key())
+            @ ErrorCode::NotClaimDelegate,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    /// CHECK: denylist PDA for the owner; must not exist
+    #[account(
+        seeds = [b"denylist", reward_pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: region policy PDA for the user's attested region; may not exist
+    #[account(
+        seeds = [
+            b"region_policy",
+            reward_pool.key().as_ref(),
+            &user_account.region_code.to_le_bytes()
+        ],
+        bump
+    )]
+    pub region_policy: UncheckedAccount<'info>,
+
+    /// The pool's vault, by its stored address rather than re-deriving the associated token
+    /// address on every claim
+    #[account(mut, address = reward_pool.vault)]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Alternative token account of the pool's mint to receive the reward (owner only),
+    /// e.g. a multisig or exchange deposit address; defaults to `user_token_account`
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub destination: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Captcha service co-signature, required only for faucet pools
+    pub captcha_verifier: Option<Signer<'info>>,
+
+    /// Shared-account co-signature, required only above the user's solo claim limit
+    pub co_signer: Option<Signer<'info>>,
+
+    /// New vesting position for this claim, required only for vesting pools
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + VestingPosition::INIT_SPACE,
+        seeds = [
+            b"vesting",
+            user_account.key().as_ref(),
+            &user_account.total_claims.to_le_bytes()
+        ],
+        bump
+    )]
+    pub vesting_position: Option<Account<'info, VestingPosition>>,
+
+    /// New claim session, required only when the claim exceeds the per-transaction cap
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ClaimSession::INIT_SPACE,
+        seeds = [b"claim_session", user_account.key().as_ref()],
+        bump
+    )]
+    pub claim_session: Option<Account<'info, ClaimSession>>,
+
+    /// New receipt for this claim, required only when the pool keeps receipts
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ClaimReceipt::INIT_SPACE,
+        seeds = [
+            b"claim_receipt",
+            reward_pool.key().as_ref(),
+            user_account.authority.as_ref(),
+            &user_account.total_claims.to_le_bytes()
+        ],
+        bump
+    )]
+    pub claim_receipt: Option<Account<'info, ClaimReceipt>>,
+
+    /// Matching sponsor and its token accounts, required only when the pool has one
+    #[account(mut)]
+    pub matching_sponsor: Option<Account<'info, MatchingSponsor>>,
+    #[account(mut)]
+    pub matching_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+    #[account(mut)]
+    pub matching_destination: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Token account holding the user's boost NFT, required only to keep an NFT boost
+    pub nft_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Mint-wide statistics, required only when the pool reports them
+    #[account(
+        mut,
+        seeds = [b"mint_stats", reward_pool.mint.as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Option<Account<'info, MintStats>>,
+
+    /// Current epoch, required only when the pool runs epochs
+    #[account(
+        mut,
+        seeds = [b"epoch", reward_pool.key().as_ref(), &epoch.index.to_le_bytes()],
+        bump = epoch.bump
+    )]
+    pub epoch: Option<Account<'info, Epoch>>,
+
+    /// Today's statistics, required only when the pool reports daily stats
+    #[account(
+        mut,
+        seeds = [
+            b"daily_stats",
+            reward_pool.key().as_ref(),
+            &daily_stats.day_index.to_le_bytes()
+        ],
+        bump = daily_stats.bump
+    )]
+    pub daily_stats: Option<Account<'info, DailyStats>>,
+
+    /// Referrer's user account, required only when the user was referred
+    #[account(
+        mut,
+        seeds = [b"user_account", referrer_account.authority.as_ref()],
+        bump = referrer_account.bump,
+        constraint = referrer_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub referrer_account: Option<Account<'info, UserAccount>>,
+
+    /// CHECK: Pyth price update, required only for USD-priced pools; validated in
+    /// `oracle::load_price`
+    pub price_feed: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: SlotHashes sysvar, required only to reveal a committed lottery draw
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+
+    /// Pool leaderboard, updated with the claimer's new total when passed
+    #[account(mut, constraint = leaderboard.load()?.pool == reward_pool.key())]
+    pub leaderboard: Option<AccountLoader<'info, Leaderboard>>,
+
+    /// SPL Memo program, required only when the pool tags transfers with memos
+    pub memo_program: Option<Program<'info, Memo>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePoolConfig<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawExcess<'info> {
+    #[account(
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+    #[account(address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct AnnounceEmergencyWithdraw<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    /// CHECK: only the address is recorded; the withdrawal itself validates the account
+    pub destination: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyWithdraw<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// SPL Memo program, required only when the pool tags transfers with memos
+    pub memo_program: Option<Program<'info, Memo>>,
+}
+
+#[derive(Accounts)]
+pub struct TimelockedWithdraw<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+    #[account(address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct VetoEmergencyWithdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct YieldVenueCall<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: must be the pool's whitelisted yield venue
+    #[account(
+        executable,
+        constraint = reward_pool.yield_venue == Some(venue_program.key())
+            @ ErrorCode::YieldVenueNotWhitelisted
+    )]
+    pub venue_program: UncheckedAccount<'info>,
+
+    /// Venue-owned account deployed funds are transferred into; required by
+    /// `deploy_to_yield`
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+        constraint = reward_pool.yield_deposit_account == Some(venue_token_account.key())
+            @ ErrorCode::YieldVenueNotWhitelisted
+    )]
+    pub venue_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub authority: Signer<'info>,
+    #[account(address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(delegate_program: Pubkey)]
+pub struct CreateBudgetDelegation<'info> {
+    #[account(
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + BudgetDelegation::INIT_SPACE,
+        seeds = [b"budget_delegation", reward_pool.key().as_ref(), delegate_program.as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, BudgetDelegation>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    /// Required on safe-mode pools
+    pub guardian: Option<Signer<'info>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateBudgetDelegation<'info> {
+    #[account(
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"budget_delegation",
+            reward_pool.key().as_ref(),
+            delegation.delegate_program.as_ref()
+        ],
+        bump = delegation.bump
+    )]
+    pub delegation: Account<'info, BudgetDelegation>,
+
+    pub authority: Signer<'info>,
+    /// Required to raise the allowance on safe-mode pools
+    pub guardian: Option<Signer<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct DrawDelegatedBudget<'info> {
+    pub delegate_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        has_one = delegate_authority @ ErrorCode::NotBudgetDelegate,
+        seeds = [
+            b"budget_delegation",
+            reward_pool.key().as_ref(),
+            delegation.delegate_program.as_ref()
+        ],
+        bump = delegation.bump
+    )]
+    pub delegation: Account<'info, BudgetDelegation>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub destination: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimNativeRewards<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    /// CHECK: denylist PDA for the signer; must not exist
+    #[account(
+        seeds = [b"denylist", reward_pool.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: region policy PDA for the user's attested region; may not exist
+    #[account(
+        seeds = [
+            b"region_policy",
+            reward_pool.key().as_ref(),
+            &user_account.region_code.to_le_bytes()
+        ],
+        bump
+    )]
+    pub region_policy: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"sol_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.sol_vault_bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    /// Token account holding the user's boost NFT, required only to keep an NFT boost
+    pub nft_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Mint-wide statistics, required only when the pool reports them
+    #[account(
+        mut,
+        seeds = [b"mint_stats", reward_pool.mint.as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Option<Account<'info, MintStats>>,
+
+    /// Current epoch, required only when the pool runs epochs
+    #[account(
+        mut,
+        seeds = [b"epoch", reward_pool.key().as_ref(), &epoch.index.to_le_bytes()],
+        bump = epoch.bump
+    )]
+    pub epoch: Option<Account<'info, Epoch>>,
+
+    /// Today's statistics, required only when the pool reports daily stats
+    #[account(
+        mut,
+        seeds = [
+            b"daily_stats",
+            reward_pool.key().as_ref(),
+            &daily_stats.day_index.to_le_bytes()
+        ],
+        bump = daily_stats.bump
+    )]
+    pub daily_stats: Option<Account<'info, DailyStats>>,
+
+    /// New receipt for this claim, required only when the pool keeps receipts
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ClaimReceipt::INIT_SPACE,
+        seeds = [
+            b"claim_receipt",
+            reward_pool.key().as_ref(),
+            user_account.authority.as_ref(),
+            &user_account.total_claims.to_le_bytes()
+        ],
+        bump
+    )]
+    pub claim_receipt: Option<Account<'info, ClaimReceipt>>,
+
+    /// Referrer's user account, required only when the user was referred
+    #[account(
+        mut,
+        seeds = [b"user_account", referrer_account.authority.as_ref()],
+        bump = referrer_account.bump,
+        constraint = referrer_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub referrer_account: Option<Account<'info, UserAccount>>,
+
+    /// Shared-account co-signature, required only above the user's solo claim limit
+    pub co_signer: Option<Signer<'info>>,
+
+    /// CHECK: SlotHashes sysvar, required only to reveal a committed lottery draw
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyWithdrawNative<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"sol_vault", reward_pool.key().as_ref()],
+        bump = reward_pool.sol_vault_bump
+    )]
+    pub sol_vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseVested<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        constraint = vesting_position.owner == authority.key() @ ErrorCode::VestingOwnerMismatch,
+        constraint = vesting_position.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+        seeds = [
+            b"vesting",
+            user_account.key().as_ref(),
+            &vesting_position.claim_index.to_le_bytes()
+        ],
+        bump = vesting_position.bump
+    )]
+    pub vesting_position: Account<'info, VestingPosition>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimSessionPart<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        constraint = claim_session.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+        seeds = [b"claim_session", user_account.key().as_ref()],
+        bump = claim_session.bump
+    )]
+    pub claim_session: Account<'info, ClaimSession>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetClaimManager<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+}
+
+#[derive(Accounts)]
+pub struct SetClaimDelegate<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+}
+
+#[derive(Accounts)]
+#[instruction(nickname: String, metadata_uri: String)]
+pub struct SetUserMetadata<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+        realloc = UserAccount::space_with_metadata(&nickname, &metadata_uri),
+        realloc::payer = authority,
+        realloc::zero = false
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimForMembers<'info> {
+    pub manager: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Mint-wide statistics, required only when the pool reports them
+    #[account(
+        mut,
+        seeds = [b"mint_stats", reward_pool.mint.as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Option<Account<'info, MintStats>>,
+
+    /// Current epoch, required only when the pool runs epochs
+    #[account(
+        mut,
+        seeds = [b"epoch", reward_pool.key().as_ref(), &epoch.index.to_le_bytes()],
+        bump = epoch.bump
+    )]
+    pub epoch: Option<Account<'info, Epoch>>,
+
+    /// Today's statistics, required only when the pool reports daily stats
+    #[account(
+        mut,
+        seeds = [
+            b"daily_stats",
+            reward_pool.key().as_ref(),
+            &daily_stats.day_index.to_le_bytes()
+        ],
+        bump = daily_stats.bump
+    )]
+    pub daily_stats: Option<Account<'info, DailyStats>>,
+
+    /// CHECK: SlotHashes sysvar, required only to reveal members' committed lottery draws
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAll<'info> {
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// CHECK: SlotHashes sysvar, required only to reveal pending lottery draws
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct SettleUsers<'info> {
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives the crank tips
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub cranker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Mint-wide statistics, required only when the pool reports them
+    #[account(
+        mut,
+        seeds = [b"mint_stats", reward_pool.mint.as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Option<Account<'info, MintStats>>,
+
+    /// Current epoch, required only when the pool runs epochs
+    #[account(
+        mut,
+        seeds = [b"epoch", reward_pool.key().as_ref(), &epoch.index.to_le_bytes()],
+        bump = epoch.bump
+    )]
+    pub epoch: Option<Account<'info, Epoch>>,
+
+    /// Today's statistics, required only when the pool reports daily stats
+    #[account(
+        mut,
+        seeds = [
+            b"daily_stats",
+            reward_pool.key().as_ref(),
+            &daily_stats.day_index.to_le_bytes()
+        ],
+        bump = daily_stats.bump
+    )]
+    pub daily_stats: Option<Account<'info, DailyStats>>,
+
+    /// CHECK: SlotHashes sysvar, required only to reveal users' committed lottery draws
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct CrankClaim<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+        seeds = [b"user_account", user_account.pool_seed(), user_account.authority.as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    /// CHECK: the account owner, whose associated token account receives the reward
+    #[account(address = user_account.authority)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    /// CHECK: denylist PDA for the owner; must not exist
+    #[account(
+        seeds = [b"denylist", reward_pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: region policy PDA for the user's attested region; may not exist
+    #[account(
+        seeds = [
+            b"region_policy",
+            reward_pool.key().as_ref(),
+            &user_account.region_code.to_le_bytes()
+        ],
+        bump
+    )]
+    pub region_policy: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives the crank tip
+    #[account(mut, token::mint = mint, token::token_program = token_program)]
+    pub cranker_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Mint-wide statistics, required only when the pool reports them
+    #[account(
+        mut,
+        seeds = [b"mint_stats", reward_pool.mint.as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Option<Account<'info, MintStats>>,
+
+    /// Current epoch, required only when the pool runs epochs
+    #[account(
+        mut,
+        seeds = [b"epoch", reward_pool.key().as_ref(), &epoch.index.to_le_bytes()],
+        bump = epoch.bump
+    )]
+    pub epoch: Option<Account<'info, Epoch>>,
+
+    /// Today's statistics, required only when the pool reports daily stats
+    #[account(
+        mut,
+        seeds = [
+            b"daily_stats",
+            reward_pool.key().as_ref(),
+            &daily_stats.day_index.to_le_bytes()
+        ],
+        bump = daily_stats.bump
+    )]
+    pub daily_stats: Option<Account<'info, DailyStats>>,
+
+    /// Referrer's user account, required only when the user was referred
+    #[account(
+        mut,
+        seeds = [b"user_account", referrer_account.authority.as_ref()],
+        bump = referrer_account.bump,
+        constraint = referrer_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub referrer_account: Option<Account<'info, UserAccount>>,
+
+    /// CHECK: SlotHashes sysvar, required only to reveal a committed lottery draw
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetNormalizedAccrual<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct WarnDormantUser<'info> {
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+        seeds = [b"user_account", user_account.pool_seed(), user_account.authority.as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+}
+
+#[derive(Accounts)]
+pub struct SweepDormantUser<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        close = rent_recovery_fund,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+        seeds = [b"user_account", user_account.pool_seed(), user_account.authority.as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    /// CHECK: only receives lamports; must match the fund configured on the pool
+    #[account(mut, address = reward_pool.rent_recovery_fund)]
+    pub rent_recovery_fund: UncheckedAccount<'info>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: u64)]
+pub struct CreateRentSponsor<'info> {
+    #[account(
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + RentSponsor::INIT_SPACE,
+        seeds = [b"rent_sponsor", reward_pool.key().as_ref(), &campaign_id.to_le_bytes()],
+        bump
+    )]
+    pub rent_sponsor: Account<'info, RentSponsor>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRentSponsor<'info> {
+    #[account(
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"rent_sponsor",
+            reward_pool.key().as_ref(),
+            &rent_sponsor.campaign_id.to_le_bytes()
+        ],
+        bump = rent_sponsor.bump
+    )]
+    pub rent_sponsor: Account<'info, RentSponsor>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: u64)]
+pub struct RegisterUserSponsored<'info> {
+    pub authority: Signer<'info>,
+
+    /// CHECK: created and initialized by the instruction with sponsor-provided rent
+    #[account(
+        mut,
+        seeds = [b"user_account", authority.key().as_ref()],
+        bump
+    )]
+    pub user_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"rent_sponsor", reward_pool.key().as_ref(), &campaign_id.to_le_bytes()],
+        bump = rent_sponsor.bump
+    )]
+    pub rent_sponsor: Account<'info, RentSponsor>,
+
+    /// CHECK: denylist PDA for the signer; must not exist
+    #[account(
+        seeds = [b"denylist", reward_pool.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: the signer's `join_pool` membership in the pool; must not exist
+    #[account(
+        seeds = [b"user_account", reward_pool.key().as_ref(), authority.key().as_ref()],
+        bump
+    )]
+    pub membership: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(campaign_id: u64)]
+pub struct CreateTokenAccountSponsored<'info> {
+    /// The registered user, who owns the new token account
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"rent_sponsor", reward_pool.key().as_ref(), &campaign_id.to_le_bytes()],
+        bump = rent_sponsor.bump
+    )]
+    pub rent_sponsor: Account<'info, RentSponsor>,
+
+    /// CHECK: the user's associated token account for the pool mint, created here if missing
+    #[account(
+        mut,
+        address = get_associated_token_address_with_program_id(
+            &authority.key(),
+            &mint.key(),
+            &token_program.key()
+        )
+    )]
+    pub user_token_account: UncheckedAccount<'info>,
+
+    #[account(address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct AddToDenylist<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + DenylistEntry::INIT_SPACE,
+        seeds = [b"denylist", reward_pool.key().as_ref(), address.as_ref()],
+        bump
+    )]
+    pub denylist_entry: Account<'info, DenylistEntry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    /// CHECK: may predate the current layout, so it is decoded after being resized; the
+    /// seeds tie it to `authority`
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump
+    )]
+    pub reward_pool: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateUser<'info> {
+    /// CHECK: may predate the current layout, so it is decoded after being resized
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"user_account", user.key().as_ref()],
+        bump
+    )]
+    pub user_account: UncheckedAccount<'info>,
+
+    /// CHECK: only used to derive the user account address
+    pub user: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyPendingConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+}
+
+#[derive(Accounts)]
+#[instruction(granter_program: Pubkey)]
+pub struct GrantBoost<'info> {
+    /// The granter program's `[b"boost_granter"]` PDA, which only that program can sign for
+    #[account(seeds = [b"boost_granter"], bump, seeds::program = granter_program)]
+    pub granter: Signer<'info>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), user_account.authority.as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+}
+
+#[derive(Accounts)]
+pub struct PurchaseBoost<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    /// Pays for the boost
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = authority,
+        token::token_program = token_program,
+    )]
+    pub user_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Receives the payment, required only when the pool doesn't burn boost proceeds
+    #[account(mut)]
+    pub proceeds_destination: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+#[instruction(verifier: Pubkey)]
+pub struct AddActivityVerifier<'info> {
+    #[account(
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ActivityVerifier::INIT_SPACE,
+        seeds = [b"activity_verifier", reward_pool.key().as_ref(), verifier.as_ref()],
+        bump
+    )]
+    pub activity_verifier: Account<'info, ActivityVerifier>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveActivityVerifier<'info> {
+    #[account(
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            b"activity_verifier",
+            reward_pool.key().as_ref(),
+            activity_verifier.verifier.as_ref()
+        ],
+        bump = activity_verifier.bump
+    )]
+    pub activity_verifier: Account<'info, ActivityVerifier>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RecordActivity<'info> {
+    pub verifier: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"activity_verifier",
+            user_account.pool.as_ref(),
+            verifier.key().as_ref()
+        ],
+        bump = activity_verifier.bump
+    )]
+    pub activity_verifier: Account<'info, ActivityVerifier>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), user_account.authority.as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(index: u8)]
+pub struct SetBadgeMilestone<'info> {
+    #[account(
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + BadgeMilestone::INIT_SPACE,
+        seeds = [b"badge_milestone", reward_pool.key().as_ref(), &[index]],
+        bump
+    )]
+    pub milestone: Account<'info, BadgeMilestone>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MintMilestoneBadge<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        seeds = [b"badge_milestone", reward_pool.key().as_ref(), &[milestone.index]],
+        bump = milestone.bump
+    )]
+    pub milestone: Account<'info, BadgeMilestone>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"badge", user_account.key().as_ref(), &[milestone.index]],
+        bump,
+        mint::decimals = 0,
+        mint::authority = reward_pool,
+        mint::freeze_authority = reward_pool,
+        mint::token_program = token_program,
+    )]
+    pub badge_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = badge_mint,
+        associated_token::authority = authority,
+        associated_token::token_program = token_program,
+    )]
+    pub badge_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: metadata PDA of the badge mint, created by the metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", Metadata::id().as_ref(), badge_mint.key().as_ref()],
+        seeds::program = Metadata::id(),
+        bump
+    )]
+    pub badge_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: master edition PDA of the badge mint, created by the metadata program
+    #[account(
+        mut,
+        seeds = [
+            b"metadata",
+            Metadata::id().as_ref(),
+            badge_mint.key().as_ref(),
+            b"edition"
+        ],
+        seeds::program = Metadata::id(),
+        bump
+    )]
+    pub badge_edition: UncheckedAccount<'info>,
+
+    /// Token Metadata only supports the original token program
+    #[account(address = spl_token::ID)]
+    pub token_program: Interface<'info, TokenInterface>,
+    pub metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CloseReceipt<'info> {
+    #[account(
+        mut,
+        close = payer,
+        has_one = payer,
+        seeds = [
+            b"claim_receipt",
+            claim_receipt.pool.as_ref(),
+            claim_receipt.user.as_ref(),
+            &claim_receipt.claim_index.to_le_bytes()
+        ],
+        bump = claim_receipt.bump
+    )]
+    pub claim_receipt: Account<'info, ClaimReceipt>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveFromDenylist<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            b"denylist",
+            reward_pool.key().as_ref(),
+            denylist_entry.address.as_ref()
+        ],
+        bump = denylist_entry.bump
+    )]
+    pub denylist_entry: Account<'info, DenylistEntry>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(region_code: u16)]
+pub struct SetRegionPolicy<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + RegionPolicy::INIT_SPACE,
+        seeds = [b"region_policy", reward_pool.key().as_ref(), &region_code.to_le_bytes()],
+        bump
+    )]
+    pub region_policy: Account<'info, RegionPolicy>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveRegionPolicy<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [
+            b"region_policy",
+            reward_pool.key().as_ref(),
+            &region_policy.region_code.to_le_bytes()
+        ],
+        bump = region_policy.bump
+    )]
+    pub region_policy: Account<'info, RegionPolicy>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AttestRegion<'info> {
+    pub authority: Signer<'info>,
+
+    pub verifier: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+}
+
+#[derive(Accounts)]
+pub struct SetRecoveryGuardians<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + RecoveryConfig::INIT_SPACE,
+        seeds = [b"recovery", user_account.key().as_ref()],
+        bump
+    )]
+    pub recovery: Account<'info, RecoveryConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveRecovery<'info> {
+    pub guardian: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"recovery", recovery.user_account.as_ref()],
+        bump = recovery.bump
+    )]
+    pub recovery: Account<'info, RecoveryConfig>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRecovery<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        has_one = user_account,
+        seeds = [b"recovery", user_account.key().as_ref()],
+        bump = recovery.bump
+    )]
+    pub recovery: Account<'info, RecoveryConfig>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteRecovery<'info> {
+    #[account(mut)]
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = new_authority,
+        seeds = [
+            b"user_account",
+            old_user_account.pool_seed(),
+            old_user_account.authority.as_ref()
+        ],
+        bump = old_user_account.bump
+    )]
+    pub old_user_account: Account<'info, UserAccount>,
+
+    #[account(
+        init,
+        payer = new_authority,
+        space = UserAccount::space_with_metadata(
+            &old_user_account.nickname,
+            &old_user_account.metadata_uri
+        ),
+        seeds = [b"user_account", old_user_account.pool_seed(), new_authority.key().as_ref()],
+        bump
+    )]
+    pub new_user_account: Account<'info, UserAccount>,
+
+    /// CHECK: the new key's other account seeding (its registration account when moving a
+    /// membership, and the other way round); must not be in this pool
+    #[account(
+        seeds = [
+            b"user_account",
+            old_user_account.sibling_pool_seed(),
+            new_authority.key().as_ref()
+        ],
+        bump
+    )]
+    pub new_sibling_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = new_authority,
+        constraint = recovery.user_account == old_user_account.key() @ ErrorCode::NoPendingRecovery,
+        seeds = [b"recovery", old_user_account.key().as_ref()],
+        bump = recovery.bump
+    )]
+    pub recovery: Account<'info, RecoveryConfig>,
+
+    /// CHECK: denylist PDA for the new key; must not exist
+    #[account(
+        seeds = [
+            b"denylist",
+            old_user_account.pool.as_ref(),
+            new_authority.key().as_ref()
+        ],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitiateWalletRotation<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    /// Fails to initialize while another rotation is pending
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + WalletRotation::INIT_SPACE,
+        seeds = [b"wallet_rotation", user_account.key().as_ref()],
+        bump
+    )]
+    pub rotation: Account<'info, WalletRotation>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelWalletRotation<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"user_account", user_account.pool_seed(), authority.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        close = authority,
+        has_one = user_account,
+        seeds = [b"wallet_rotation", user_account.key().as_ref()],
+        bump = rotation.bump
+    )]
+    pub rotation: Account<'info, WalletRotation>,
+}
+
+#[derive(Accounts)]
+pub struct CompleteWalletRotation<'info> {
+    #[account(mut)]
+    pub new_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        close = new_authority,
+        seeds = [
+            b"user_account",
+            old_user_account.pool_seed(),
+            old_user_account.authority.as_ref()
+        ],
+        bump = old_user_account.bump
+    )]
+    pub old_user_account: Account<'info, UserAccount>,
+
+    #[account(
+        init,
+        payer = new_authority,
+        space = UserAccount::space_with_metadata(
+            &old_user_account.nickname,
+            &old_user_account.metadata_uri
+        ),
+        seeds = [b"user_account", old_user_account.pool_seed(), new_authority.key().as_ref()],
+        bump
+    )]
+    pub new_user_account: Account<'info, UserAccount>,
+
+    /// CHECK: the new key's other account seeding (its registration account when moving a
+    /// membership, and the other way round); must not be in this pool
+    #[account(
+        seeds = [
+            b"user_account",
+            old_user_account.sibling_pool_seed(),
+            new_authority.key().as_ref()
+        ],
+        bump
+    )]
+    pub new_sibling_account: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = new_authority,
+        constraint = rotation.user_account == old_user_account.key()
+            && rotation.new_authority == new_authority.key() @ ErrorCode::InvalidWalletRotation,
+        seeds = [b"wallet_rotation", old_user_account.key().as_ref()],
+        bump = rotation.bump
+    )]
+    pub rotation: Account<'info, WalletRotation>,
+
+    /// CHECK: denylist PDA for the new key; must not exist
+    #[account(
+        seeds = [
+            b"denylist",
+            old_user_account.pool.as_ref(),
+            new_authority.key().as_ref()
+        ],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EnableStaking<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = stake_mint,
+        token::authority = reward_pool,
+        token::token_program = token_program,
+        seeds = [b"stake_vault", reward_pool.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StakeTokens<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump,
+        constraint = reward_pool.stake_mint == Some(stake_mint.key())
+            @ ErrorCode::StakingNotEnabled,
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [b"stake_position", reward_pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_vault", reward_pool.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = stake_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_stake_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: denylist PDA for the signer; must not exist
+    #[account(
+        seeds = [b"denylist", reward_pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimAndCompound<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = user_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+        seeds = [b"user_account", user_account.pool_seed(), owner.key().as_ref()],
+        bump = user_account.bump
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump,
+        constraint = reward_pool.stake_mint == Some(reward_pool.mint)
+            @ ErrorCode::StakingNotEnabled,
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + StakePosition::INIT_SPACE,
+        seeds = [b"stake_position", reward_pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_vault", reward_pool.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: denylist PDA for the signer; must not exist
+    #[account(
+        seeds = [b"denylist", reward_pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    /// CHECK: region policy PDA for the user's attested region; may not exist
+    #[account(
+        seeds = [
+            b"region_policy",
+            reward_pool.key().as_ref(),
+            &user_account.region_code.to_le_bytes()
+        ],
+        bump
+    )]
+    pub region_policy: UncheckedAccount<'info>,
+
+    /// Token account holding the user's boost NFT, required only to keep an NFT boost
+    pub nft_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Shared-account co-signature, required only above the user's solo claim limit
+    pub co_signer: Option<Signer<'info>>,
+
+    /// Mint-wide statistics, required only when the pool reports them
+    #[account(
+        mut,
+        seeds = [b"mint_stats", reward_pool.mint.as_ref()],
+        bump = mint_stats.bump
+    )]
+    pub mint_stats: Option<Account<'info, MintStats>>,
+
+    /// Current epoch, required only when the pool runs epochs
+    #[account(
+        mut,
+        seeds = [b"epoch", reward_pool.key().as_ref(), &epoch.index.to_le_bytes()],
+        bump = epoch.bump
+    )]
+    pub epoch: Option<Account<'info, Epoch>>,
+
+    /// Today's statistics, required only when the pool reports daily stats
+    #[account(
+        mut,
+        seeds = [
+            b"daily_stats",
+            reward_pool.key().as_ref(),
+            &daily_stats.day_index.to_le_bytes()
+        ],
+        bump = daily_stats.bump
+    )]
+    pub daily_stats: Option<Account<'info, DailyStats>>,
+
+    /// Referrer's user account, required only when the user was referred
+    #[account(
+        mut,
+        seeds = [b"user_account", referrer_account.authority.as_ref()],
+        bump = referrer_account.bump,
+        constraint = referrer_account.pool == reward_pool.key() @ ErrorCode::UserPoolMismatch,
+    )]
+    pub referrer_account: Option<Account<'info, UserAccount>>,
+
+    /// CHECK: SlotHashes sysvar, required only to reveal a committed lottery draw
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeTokens<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump,
+        constraint = reward_pool.stake_mint == Some(stake_mint.key())
+            @ ErrorCode::StakingNotEnabled,
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        seeds = [b"stake_position", reward_pool.key().as_ref(), owner.key().as_ref()],
+        bump = stake_position.bump
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(
+        mut,
+        seeds = [b"stake_vault", reward_pool.key().as_ref()],
+        bump
+    )]
+    pub stake_vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = stake_mint,
+        token::authority = owner,
+        token::token_program = token_program,
+    )]
+    pub owner_stake_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub stake_mint: InterfaceAccount<'info, Mint>,
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimStakeRewards<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        seeds = [b"stake_position", reward_pool.key().as_ref(), owner.key().as_ref()],
+        bump = stake_position.bump
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_reward_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: denylist PDA for the signer; must not exist
+    #[account(
+        seeds = [b"denylist", reward_pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct StakeNft<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + StakedNft::INIT_SPACE,
+        seeds = [b"staked_nft", reward_pool.key().as_ref(), nft_mint.key().as_ref()],
+        bump
+    )]
+    pub staked_nft: Account<'info, StakedNft>,
+
+    #[account(
+        init,
+        payer = owner,
+        associated_token::mint = nft_mint,
+        associated_token::authority = staked_nft,
+        associated_token::token_program = nft_token_program,
+    )]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = owner,
+        token::token_program = nft_token_program,
+    )]
+    pub owner_nft_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        seeds = [b"metadata", Metadata::id().as_ref(), nft_mint.key().as_ref()],
+        seeds::program = Metadata::id(),
+        bump
+    )]
+    pub nft_metadata: Account<'info, MetadataAccount>,
+
+    /// CHECK: denylist PDA for the signer; must not exist
+    #[account(
+        seeds = [b"denylist", reward_pool.key().as_ref(), owner.key().as_ref()],
+        bump
+    )]
+    pub denylist_entry: UncheckedAccount<'info>,
+
+    pub nft_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimNftRewards<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        has_one = owner,
+        seeds = [b"staked_nft", reward_pool.key().as_ref(), staked_nft.nft_mint.as_ref()],
+        bump = staked_nft.bump
+    )]
+    pub staked_nft: Account<'info, StakedNft>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_reward_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnstakeNft<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
 
     #[account(
-        init,
-        payer = authority,
+        mut,
+        close = owner,
+        has_one = owner,
+        has_one = nft_mint,
+        seeds = [b"staked_nft", reward_pool.key().as_ref(), nft_mint.key().as_ref()],
+        bump = staked_nft.bump
+    )]
+    pub staked_nft: Account<'info, StakedNft>,
+
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = staked_nft,
+        associated_token::token_program = nft_token_program,
+    )]
+    pub escrow: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = owner,
+        token::token_program = nft_token_program,
+    )]
+    pub owner_nft_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub nft_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
         associated_token::mint = mint,
         associated_token::authority = reward_pool,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program,
+    )]
+    pub owner_reward_account: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(mut, address = reward_pool.mint)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub nft_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitMintStats<'info> {
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + MintStats::INIT_SPACE,
+        seeds = [b"mint_stats", mint.key().as_ref()],
+        bump
+    )]
+    pub mint_stats: Account<'info, MintStats>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(day_index: u64)]
+pub struct InitDailyStats<'info> {
+    #[account(
+        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + DailyStats::INIT_SPACE,
+        seeds = [b"daily_stats", reward_pool.key().as_ref(), &day_index.to_le_bytes()],
+        bump
+    )]
+    pub daily_stats: Account<'info, DailyStats>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetMintStatsReporting<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"reward_pool", authority.key().as_ref()],
+        bump = reward_pool.bump
+    )]
+    pub reward_pool: Account<'info, RewardPool>,
+
+    #[account(
+        mut,
+        seeds = [b"mint_stats", reward_pool.mint.as_ref()],
+        bump = mint_stats.bump
     )]
-    pub vault: Account<'info, TokenAccount>,
+    pub mint_stats: Account<'info, MintStats>,
+
+    pub authority: Signer<'info>,
+}
+
+#[account]
+#[derive(InitSpace, Default)]
+pub struct RewardPool {
+    pub authority: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub reward_rate_per_hour: u64,
+    /// Claim interval in hours before version 5, when `migrate` moved it to
+    /// `min_claim_interval_secs`; zero since
+    pub _legacy_min_claim_interval_hours: u64,
+    pub max_daily_reward: u64,
+    /// Accrual window in hours; elapsed time beyond it stops accruing (0 = unbounded)
+    pub max_accrual_hours: u64,
+    pub distribution_mode: DistributionMode,
+    pub sol_vault_bump: u8,
+    pub total_distributed: u64,
+    pub total_transfer_fees: u64,
+    pub participant_count: u64,
+    pub claims_paused: bool,
+    pub registrations_paused: bool,
+    /// When pool-wide accrual was paused (0 if accruing)
+    pub accrual_paused_at: i64,
+    /// Completed pool-wide accrual pauses; with an ongoing pause this forms the pause clock
+    pub total_accrual_paused_secs: i64,
+    pub allow_partial_payout: bool,
+    pub gross_up_transfer_fees: bool,
+    pub dormancy_period_secs: i64,
+    pub dormancy_grace_secs: i64,
+    pub rent_recovery_fund: Pubkey,
+    pub region_verifier: Option<Pubkey>,
+    pub require_region_attestation: bool,
+    pub total_withheld: u64,
+    pub faucet: Option<FaucetConfig>,
+    pub vesting: Option<VestingSchedule>,
+    /// Claimed rewards still locked in vesting positions
+    pub total_vesting_locked: u64,
+    /// Hours after becoming eligible within which a claim extends a streak (0 = streaks off)
+    pub streak_window_hours: u64,
+    /// Bonus per consecutive claim after the first
+    pub streak_bonus_bps: u16,
+    pub max_streak_bonus_bps: u16,
+    /// Price of streak insurance in reward tokens, paid into the vault (0 = not sold)
+    pub streak_insurance_fee: u64,
+    pub total_streak_insurance_fees: u64,
+    /// Target hours between claims for the cadence modifier (0 = off)
+    pub cadence_target_hours: u64,
+    pub cadence_tolerance_hours: u64,
+    pub cadence_bonus_bps: u16,
+    pub cadence_penalty_bps: u16,
+    pub matching_sponsor: Option<Pubkey>,
+    pub nft_boost: Option<NftBoostConfig>,
+    /// Token staked for the staking reward stream (None = staking disabled)
+    pub stake_mint: Option<Pubkey>,
+    /// Reward tokens emitted per hour, shared pro rata among stakers
+    pub stake_reward_rate_per_hour: u64,
+    pub total_staked: u64,
+    /// Rewards per staked token, scaled by `math::REWARD_PER_TOKEN_SCALE`
+    pub reward_per_token_stored: u128,
+    pub stake_rewards_updated_at: i64,
+    pub nft_staking: Option<NftStakingConfig>,
+    /// Whether claims also update the mint's `MintStats`
+    pub report_mint_stats: bool,
+    /// Share of each referee's token claims credited to their referrer
+    pub referral_bps: u16,
+    /// Root of the registration allowlist tree; all zeroes leaves registration open
+    pub merkle_root: [u8; 32],
+    /// Hash of the current terms of service document
+    pub terms_hash: [u8; 32],
+    /// Incremented on every terms change; 0 means the pool has no terms
+    pub terms_version: u32,
+    /// Declared outage window during which expiries and streak resets are suspended
+    pub outage_start: i64,
+    pub outage_end: i64,
+    /// Largest single claim transfer (0 = uncapped); larger claims open a claim session
+    pub max_claim_per_tx: u64,
+    /// Claimed into sessions but not yet paid out
+    pub total_session_locked: u64,
+    /// Share of each cranked claim paid to the cranker
+    pub crank_tip_bps: u16,
+    pub total_crank_tips: u64,
+    /// When set, the rate and daily cap are in `math::NORMALIZED_DECIMALS` units and
+    /// rewards are converted to the mint's `mint_decimals` at claim time
+    pub normalized_accrual: bool,
+    pub mint_decimals: u8,
+    /// Set for pools created with `initialize_pool_safe`
+    pub safe_mode: Option<SafeModeConfig>,
+    pub pending_withdrawal: Option<PendingWithdrawal>,
+    /// Whitelisted program idle vault funds may be deployed to
+    pub yield_venue: Option<Pubkey>,
+    /// Principal currently deployed to the yield venue (not in the vault)
+    pub yield_deployed: u64,
+    pub yield_earned: u64,
+    /// Liquid balance kept in the vault on top of its reserved liabilities
+    pub yield_min_liquid: u64,
+    /// Signer allowed to commit accrual snapshots
+    pub snapshot_cranker: Option<Pubkey>,
+    /// Minimum spacing between snapshot `as_of` times
+    pub snapshot_interval_secs: i64,
+    pub snapshot_count: u64,
+    pub last_snapshot_as_of: i64,
+    /// Decaying emission replacing `reward_rate_per_hour` when set
+    pub emission: Option<EmissionSchedule>,
+    /// Hourly budget split across participants at claim time (0 = flat rate)
+    pub budget_per_hour: u64,
+    /// Lowest per-user rate the participant budget can fall to
+    pub floor_rate_per_hour: u64,
+    /// Scale claims down pro-rata when the vault can't cover a full round of claims
+    pub runway_throttle: bool,
+    /// Epoch length (0 = epochs not started)
+    pub epoch_duration_secs: i64,
+    /// Budget given to each new epoch
+    pub epoch_budget: u64,
+    pub current_epoch: u64,
+    /// Campaign start; no accrual before it (0 = unbounded)
+    pub accrual_start: i64,
+    /// Campaign end; accrual clamps here but claims stay open (0 = unbounded)
+    pub accrual_end: i64,
+    /// Most a single wallet can ever earn from the pool (0 = uncapped)
+    pub max_lifetime_reward_per_user: u64,
+    /// Most the pool can ever distribute, regardless of vault funding (0 = uncapped)
+    pub max_total_distribution: u64,
+    /// Pause claims and registrations once `max_total_distribution` is reached
+    pub deactivate_when_budget_spent: bool,
+    /// When set, the rate and caps are USD micro-units converted at claim time
+    pub usd_pricing: Option<UsdPricing>,
+    /// Chance that a committed lottery draw wins (0 = no lottery)
+    pub lottery_chance_bps: u16,
+    /// Multiplier applied to a winning claim (20_000 = 2x)
+    pub lottery_multiplier_bps: u16,
+    /// Realms governance whose native treasury is the pool authority, if DAO-controlled
+    pub governance: Option<Pubkey>,
+    /// Claims must update the pool's `DailyStats` for the current day
+    pub report_daily_stats: bool,
+    /// How long claim receipts are kept before they may be closed (0 = no receipts)
+    pub receipt_expiry_secs: i64,
+    /// Reward per verified activity point, paid on top of time accrual (0 = points unpaid)
+    pub reward_per_activity_point: u64,
+    /// Programs allowed to call `grant_boost` via CPI
+    #[max_len(MAX_BOOST_GRANTERS)]
+    pub boost_granter_programs: Vec<Pubkey>,
+    /// Largest multiplier a granter program can award
+    pub max_granted_boost_bps: u16,
+    /// Key whose Ed25519 attestation registration requires, if any
+    pub attestor: Option<Pubkey>,
+    /// Most claims a user may make per UTC day (0 = unlimited)
+    pub max_claims_per_day: u16,
+    /// Delay before rate, cap and accrual model changes take effect (0 = immediately)
+    pub config_delay_secs: i64,
+    pub pending_config: Option<PendingConfig>,
+    /// Rate in effect before `rate_changed_at`, when the last scheduled change applied
+    pub previous_rate_per_hour: u64,
+    pub rate_changed_at: i64,
+    /// Delay between `announce_emergency_withdraw` and `emergency_withdraw`
+    pub emergency_withdraw_delay_secs: i64,
+    /// Fractional part of `reward_rate_per_hour` in units of 2^-64, set by `set_reward_rate`
+    pub reward_rate_fraction: u64,
+    pub previous_rate_fraction: u64,
+    /// Rounding of reward amounts, multipliers and fee splits (runway throttling and
+    /// emission decay always round down)
+    pub rounding: RoundingMode,
+    pub created_at: i64,
+    pub bump: u8,
+    /// Layout version. Fixed-size fields are carved out of the reserved words, which needs
+    /// no realloc; anything else is appended after them, so older accounts stay a prefix
+    /// that `migrate_pool` can extend.
+    pub version: u8,
+    /// Claims and emergency withdrawals write an SPL Memo describing the transfer
+    pub transfer_memos: bool,
+    /// Most accounts sponsorship campaigns may fund per UTC day (0 = unlimited)
+    pub max_sponsored_per_day: u32,
+    /// UTC day `sponsored_today` counts
+    pub sponsored_day: u64,
+    pub sponsored_today: u32,
+    /// Lifetime tokens paid for boosts
+    pub total_boost_sales: u64,
+    /// Share of every claim burned from the vault instead of paid out, in basis points
+    pub burn_bps: u16,
+    /// Lifetime tokens burned by `burn_bps`
+    pub total_burned: u64,
+    /// Hourly growth of unclaimed rewards in basis points; 0 accrues linearly
+    pub compound_growth_bps: u16,
+    /// Minimum time between a user's claims. Intervals under an hour also make accrual
+    /// count whole intervals instead of whole hours.
+    pub min_claim_interval_secs: u64,
+    /// Referral bonuses credited to referrers and not yet claimed
+    pub total_referral_owed: u64,
+    /// Rewards users left accrued with partial claims
+    pub total_carried_over: u64,
+    /// Pads the fields above to the `ACCOUNT_RESERVED_WORDS` they replaced
+    pub _reserved_padding: [u8; 3],
+    /// Fee charged by unsponsored registrations, if any
+    pub registration_fee: Option<RegistrationFee>,
+    /// Boosts users can buy with `purchase_boost`, indexed by tier
+    pub boost_tiers: [BoostTier; MAX_BOOST_TIERS],
+    /// Token account receiving boost purchases (None = burn them)
+    pub boost_proceeds_destination: Option<Pubkey>,
+    /// Venue-owned token account `deploy_to_yield` transfers into
+    pub yield_deposit_account: Option<Pubkey>,
+    pub pending_yield_venue: Option<PendingYieldVenue>,
+    /// Denylist entries and region policies the pool has, which `claim_all` can't look up
+    /// per wallet; `UNCOUNTED` on pools migrated from before they were counted
+    pub denylist_len: u32,
+    pub region_policy_count: u32,
+    /// Spare space for fixed-size fields added without changing the layout; see
+    /// `POOL_RESERVED_WORDS`
+    pub _reserved: [u64; POOL_RESERVED_WORDS],
+    /// Accrual model change taking effect with `pending_config`
+    pub pending_model: Option<AccrualModel>,
+    /// Accrual model in effect before `rate_changed_at`
+    pub previous_model: AccrualModel,
+}
+
+impl RewardPool {
+    /// Decode a pool account written under an older layout. That version only serialized
+    /// up to its own last field, and the bytes after it are zeros or leftovers from a `Vec`
+    /// or `Option` in the pool that has since shrunk, so they are read as zeros: every
+    /// field appended since decodes as its default. Where the old fields end depends on the
+    /// values decoded, so each pass reads the account up to the end the previous pass
+    /// found, until that end stops moving.
+    pub fn decode_older(data: &[u8]) -> Result<Self> {
+        require!(
+            data.len() >= 8 && data[..8] == RewardPool::DISCRIMINATOR,
+            anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+        );
+        let mut buf = vec![0; data.len()];
+        let mut end = 8;
+        loop {
+            buf[..end].copy_from_slice(&data[..end]);
+            let mut fields = &buf[8..];
+            let pool = RewardPool::deserialize(&mut fields)
+                .map_err(|_| error!(anchor_lang::error::ErrorCode::AccountDidNotDeserialize))?;
+            let old_end =
+                (buf.len() - fields.len()).saturating_sub(Self::appended_since(pool.version));
+            if old_end <= end {
+                return Ok(pool);
+            }
+            end = old_end;
+        }
+    }
+
+    /// Serialized length, at their defaults, of the fields appended to the layout after
+    /// `version`
+    fn appended_since(version: u8) -> usize {
+        let mut len = 0;
+        if version < 2 {
+            // The four reserved words version 2 added, since carved into fields
+            len += 4 * 8;
+        }
+        if version < 3 {
+            // registration_fee
+            len += 1;
+        }
+        if version < 4 {
+            // boost_tiers and boost_proceeds_destination
+            len += MAX_BOOST_TIERS * BoostTier::INIT_SPACE + 1;
+        }
+        if version < 6 {
+            // yield_deposit_account and pending_yield_venue
+            len += 1 + 1;
+        }
+        if version < 7 {
+            len += 8 * ACCOUNT_RESERVED_WORDS;
+        }
+        if version < 8 {
+            // pending_model and previous_model, both options of which are `None`
+            len += 1 + (1 + 8 + 8 + 2 + 1);
+        }
+        len
+    }
+
+    /// Bring a pool decoded from an older layout up to `POOL_VERSION`, giving fields added
+    /// since then their `init_pool` defaults where zero is not a safe value
+    pub fn migrate(&mut self) -> Result<()> {
+        require!(
+            self.version <= POOL_VERSION,
+            ErrorCode::UnsupportedAccountVersion
+        );
+        if self.version < 1 {
+            self.emergency_withdraw_delay_secs = self
+                .emergency_withdraw_delay_secs
+                .max(MIN_EMERGENCY_WITHDRAW_DELAY_SECS);
+        }
+        // Version 2 only widened the reserved space; the fields since carved out of it are
+        // off, or start counting, at zero. Versions 3 and 4 appended the registration fee
+        // and boost tiers, both off when zero-extended
+        // Version 5 moved the claim interval from hours to seconds
+        if self.version < 5 {
+            self.min_claim_interval_secs = self
+                ._legacy_min_claim_interval_hours
+                .saturating_mul(math::SECONDS_PER_HOUR as u64);
+            self._legacy_min_claim_interval_hours = 0;
+        }
+        // Version 6 added the yield deposit account; a venue set before it can still be
+        // withdrawn from, but deploying needs the venue scheduled again with its account
+        // Version 7 restored the reserved words at the end, which `decode_older` reads as
+        // zeros
+        // Version 8 scheduled accrual model changes; the last rate change left the model as
+        // it is now
+        if self.version < 8 {
+            self.previous_model = self.accrual_model();
+        }
+        // Version 9 counted denylist entries and region policies, which older pools may
+        // already have
+        if self.version < 9 {
+            self.denylist_len = UNCOUNTED;
+            self.region_policy_count = UNCOUNTED;
+        }
+        self.version = POOL_VERSION;
+        Ok(())
+    }
+
+    /// Replace a user's carried-over rewards released by a claim (or a ban) with what the
+    /// claim carried over
+    pub fn track_carryover(&mut self, released: u64, carried: u64) {
+        self.total_carried_over = self
+            .total_carried_over
+            .saturating_sub(released)
+            .saturating_add(carried);
+    }
+
+    /// Flat per-hour rate including its fractional part
+    pub fn reward_rate(&self) -> Q64 {
+        Q64::from_parts(self.reward_rate_per_hour, self.reward_rate_fraction)
+    }
+
+    /// Rate before the last applied config change, including its fractional part
+    pub fn previous_rate(&self) -> Q64 {
+        Q64::from_parts(self.previous_rate_per_hour, self.previous_rate_fraction)
+    }
+
+    /// Config a new scheduled change builds on: the pending one, or the current values
+    pub fn next_config(&self) -> PendingConfig {
+        self.pending_config.unwrap_or(PendingConfig {
+            reward_rate_per_hour: self.reward_rate_per_hour,
+            reward_rate_fraction: self.reward_rate_fraction,
+            max_daily_reward: self.max_daily_reward,
+            max_accrual_hours: self.max_accrual_hours,
+            effective_at: 0,
+        })
+    }
+
+    /// Move a due scheduled config change into effect, remembering the rate and accrual
+    /// model it replaces so accrual before `effective_at` keeps them
+    pub fn apply_pending_config(&mut self, now: i64) -> Option<PendingConfig> {
+        let pending = self
+            .pending_config
+            .filter(|pending| now >= pending.effective_at)?;
+        self.previous_rate_per_hour = self.reward_rate_per_hour;
+        self.previous_rate_fraction = self.reward_rate_fraction;
+        self.previous_model = self.accrual_model();
+        self.rate_changed_at = pending.effective_at;
+        self.reward_rate_per_hour = pending.reward_rate_per_hour;
+        self.reward_rate_fraction = pending.reward_rate_fraction;
+        self.max_daily_reward = pending.max_daily_reward;
+        self.max_accrual_hours = pending.max_accrual_hours;
+        if let Some(model) = self.pending_model.take() {
+            self.set_accrual_model(model);
+        }
+        self.pending_config = None;
+        Some(pending)
+    }
+
+    /// Accrual model currently in effect
+    pub fn accrual_model(&self) -> AccrualModel {
+        AccrualModel {
+            emission: self.emission,
+            budget_per_hour: self.budget_per_hour,
+            floor_rate_per_hour: self.floor_rate_per_hour,
+            compound_growth_bps: self.compound_growth_bps,
+            usd_pricing: self.usd_pricing,
+        }
+    }
+
+    fn set_accrual_model(&mut self, model: AccrualModel) {
+        self.emission = model.emission;
+        self.budget_per_hour = model.budget_per_hour;
+        self.floor_rate_per_hour = model.floor_rate_per_hour;
+        self.compound_growth_bps = model.compound_growth_bps;
+        self.usd_pricing = model.usd_pricing;
+    }
+
+    /// Accrual model a new scheduled change builds on: the pending one, or the current one
+    pub fn next_accrual_model(&self) -> AccrualModel {
+        self.pending_model.unwrap_or(self.accrual_model())
+    }
+
+    /// Accrual model in effect at `at`: the pending one once it takes effect, and the
+    /// replaced one before the last applied change
+    pub fn accrual_model_at(&self, at: i64) -> AccrualModel {
+        match self.pending_config {
+            Some(pending) if at >= pending.effective_at => self.next_accrual_model(),
+            _ if at < self.rate_changed_at => self.previous_model,
+            _ => self.accrual_model(),
+        }
+    }
+
+    /// Start or end a pool-wide accrual pause; ending one adds it to the pause clock
+    pub fn set_accrual_paused(&mut self, paused: bool, now: i64) -> Result<()> {
+        if paused && self.accrual_paused_at == 0 {
+            self.accrual_paused_at = now;
+        } else if !paused && self.accrual_paused_at != 0 {
+            let paused_for = now
+                .checked_sub(self.accrual_paused_at)
+                .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+            self.total_accrual_paused_secs = self
+                .total_accrual_paused_secs
+                .checked_add(paused_for)
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+            self.accrual_paused_at = 0;
+        }
+        Ok(())
+    }
+
+    /// Bring the staking accumulator up to `now`
+    pub fn update_stake_rewards(&mut self, now: i64) -> Result<()> {
+        self.reward_per_token_stored = math::reward_per_token(
+            self.reward_per_token_stored,
+            self.total_staked,
+            self.stake_reward_rate_per_hour,
+            self.stake_rewards_updated_at,
+            now,
+        )?;
+        self.stake_rewards_updated_at = now;
+        Ok(())
+    }
+}
+
+/// How a pool pays out claimed rewards
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub enum DistributionMode {
+    /// Transfer from a pre-funded vault
+    #[default]
+    Vault,
+    /// Mint on demand; the pool PDA must hold the mint authority
+    MintOnClaim,
+    /// Pay lamports from the pool's `sol_vault` PDA
+    NativeSol,
+}
+
+/// Boost tier sold by `purchase_boost`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct BoostTier {
+    /// Bonus on accrual while the boost runs, like an auction boost (10_000 = +100%)
+    pub bonus_bps: u16,
+    /// Price in pool tokens per hour of boost (0 = not for sale)
+    pub price_per_hour: u64,
+}
+
+/// Boost tiers a pool can sell
+pub const MAX_BOOST_TIERS: usize = 4;
+/// Longest boost a single purchase can buy
+pub const MAX_BOOST_PURCHASE_HOURS: u64 = 30 * 24;
+
+/// Fee a pool charges users to register, to discourage mass sybil registration and offset
+/// the rent of sponsored accounts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum RegistrationFee {
+    /// Lamports paid into the pool's `sol_vault`
+    Lamports(u64),
+    /// Tokens of the pool mint paid into the pool vault
+    Tokens(u64),
+}
+
+/// Claim interval (and accrual window) used by faucet pools
+pub const FAUCET_CLAIM_INTERVAL_HOURS: u64 = 24;
+
+/// Shortest emergency-withdrawal timelock a safe-mode pool accepts
+pub const MIN_SAFE_TIMELOCK_SECS: i64 = 24 * 60 * 60;
+
+/// Shortest delay between announcing and executing an emergency withdrawal
+pub const MIN_EMERGENCY_WITHDRAW_DELAY_SECS: i64 = 24 * 60 * 60;
+
+/// Delay between `initiate_wallet_rotation` and `complete_wallet_rotation`, leaving the
+/// current key time to cancel a rotation started by someone who stole it
+pub const WALLET_ROTATION_DELAY_SECS: i64 = 48 * 60 * 60;
+
+/// Layout version written to new pools; `migrate_pool` upgrades older ones
+pub const POOL_VERSION: u8 = 9;
+/// Layout version written to new user accounts; `migrate_user` upgrades older ones
+pub const USER_ACCOUNT_VERSION: u8 = 5;
+/// Spare space at the end of each versioned account, in 8-byte words (64 bytes), for
+/// fields added without a realloc. Zeroed bytes decode as each new field's default.
+pub const ACCOUNT_RESERVED_WORDS: usize = 8;
+/// `ACCOUNT_RESERVED_WORDS` left at the end of `RewardPool` after the denylist and region
+/// policy counts were carved out
+pub const POOL_RESERVED_WORDS: usize = 7;
+/// `RewardPool::denylist_len` or `region_policy_count` of a pool that can't tell
+pub const UNCOUNTED: u32 = u32::MAX;
+/// `ACCOUNT_RESERVED_WORDS` left in `UserAccount` after the purchased and granted boosts,
+/// the open claim counts and the membership flag were carved out
+pub const USER_RESERVED_WORDS: usize = 1;
+
+/// Vetted parameter sets for `initialize_pool_safe`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PoolPreset {
+    Conservative,
+    Standard,
+    Generous,
+}
+
+impl PoolPreset {
+    /// `(tokens_per_hour, min_claim_interval_hours, max_daily_tokens, max_accrual_hours)`,
+    /// in whole tokens; the daily cap never exceeds one claim interval's accrual
+    pub fn params(self) -> (u64, u64, u64, u64) {
+        match self {
+            PoolPreset::Conservative => (1, 24, 24, 72),
+            PoolPreset::Standard => (10, 24, 240, 168),
+            PoolPreset::Generous => (100, 12, 1_200, 336),
+        }
+    }
+}
+
+/// Oracle settings for pools whose rewards are denominated in USD
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct UsdPricing {
+    /// Pyth price feed id of the reward token in USD
+    pub feed_id: [u8; 32],
+    pub max_price_age_secs: i64,
+    /// Widest accepted confidence interval, relative to the price
+    pub max_confidence_bps: u16,
+}
+
+/// Shortest decay interval an emission schedule may use, bounding the per-claim
+/// piecewise integration
+pub const MIN_EMISSION_DECAY_INTERVAL_SECS: i64 = 86_400;
+
+/// Per-hour emission that decays by `decay_bps` every `decay_interval_secs` from
+/// `start_time`; 5_000 bps halves the rate each interval
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct EmissionSchedule {
+    pub start_time: i64,
+    pub start_rate: u64,
+    pub decay_interval_secs: i64,
+    pub decay_bps: u16,
+}
+
+/// Changes for `update_pool_config`; fields left `None` keep their current value
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PoolConfigUpdate {
+    pub reward_rate_per_hour: Option<u64>,
+    pub min_claim_interval_secs: Option<u64>,
+    pub max_daily_reward: Option<u64>,
+    pub max_accrual_hours: Option<u64>,
+    pub claims_paused: Option<bool>,
+    pub registrations_paused: Option<bool>,
+    pub accrual_paused: Option<bool>,
+    pub allow_partial_payout: Option<bool>,
+    pub gross_up_transfer_fees: Option<bool>,
+    pub max_lifetime_reward_per_user: Option<u64>,
+    pub max_total_distribution: Option<u64>,
+    pub deactivate_when_budget_spent: Option<bool>,
+    pub max_claims_per_day: Option<u16>,
+}
+
+/// Rate and cap change scheduled by `update_pool_config` on pools with a config delay
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct PendingConfig {
+    pub reward_rate_per_hour: u64,
+    pub reward_rate_fraction: u64,
+    pub max_daily_reward: u64,
+    pub max_accrual_hours: u64,
+    pub effective_at: i64,
+}
+
+impl PendingConfig {
+    pub fn reward_rate(&self) -> Q64 {
+        Q64::from_parts(self.reward_rate_per_hour, self.reward_rate_fraction)
+    }
+}
+
+/// Accrual settings beyond the flat rate and caps. Changes are scheduled with
+/// `pending_config` and, like rate changes, only shape accrual after they take effect.
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace,
+)]
+pub struct AccrualModel {
+    pub emission: Option<EmissionSchedule>,
+    pub budget_per_hour: u64,
+    pub floor_rate_per_hour: u64,
+    pub compound_growth_bps: u16,
+    pub usd_pricing: Option<UsdPricing>,
+}
+
+/// Yield venue change scheduled by `set_yield_venue`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct PendingYieldVenue {
+    pub venue_program: Option<Pubkey>,
+    pub deposit_account: Option<Pubkey>,
+    pub min_liquid_balance: u64,
+    pub effective_at: i64,
+}
+
+/// Guardrails applied to pools created with `initialize_pool_safe`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct SafeModeConfig {
+    pub guardian: Pubkey,
+    pub timelock_secs: i64,
+}
+
+/// Emergency withdrawal waiting out its timelock or announcement delay
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct PendingWithdrawal {
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub executable_at: i64,
+}
+
+/// Extra restrictions applied to pools created with `initialize_faucet_pool`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct FaucetConfig {
+    pub lifetime_cap_per_wallet: u64,
+    pub captcha_verifier: Pubkey,
+}
+
+/// Boost for holders of NFTs from a verified Metaplex collection
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct NftBoostConfig {
+    pub collection: Pubkey,
+    pub boost_bps: u16,
+}
+
+/// Hourly reward for each staked NFT from a verified Metaplex collection
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct NftStakingConfig {
+    pub collection: Pubkey,
+    pub reward_rate_per_hour: u64,
+}
+
+/// Vesting applied to every claim from a pool
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct VestingSchedule {
+    pub cliff_secs: i64,
+    pub duration_secs: i64,
+}
+
+/// Claim intervals kept per user for the cadence modifier
+pub const CADENCE_HISTORY_LEN: usize = 4;
+
+/// Longest `UserAccount::nickname`, in bytes
+pub const MAX_NICKNAME_LEN: usize = 32;
+/// Longest `UserAccount::metadata_uri`, in bytes
+pub const MAX_USER_METADATA_URI_LEN: usize = 128;
+
+#[account]
+#[derive(InitSpace, Default)]
+pub struct UserAccount {
+    pub authority: Pubkey,
+    pub pool: Pubkey,
+    pub total_earned: u64,
+    pub total_fees_paid: u64,
+    pub total_claims: u64,
+    pub last_claim_timestamp: i64,
+    pub registration_timestamp: i64,
+    pub is_active: bool,
+    pub claim_manager: Option<Pubkey>,
+    pub dormancy_warned_at: i64,
+    /// Region attested by the pool's verifier (0 = unattested)
+    pub region_code: u16,
+    pub region_attested_at: i64,
+    pub total_withheld: u64,
+    pub current_streak: u32,
+    pub longest_streak: u32,
+    /// Protects the streak across one missed window; consumed automatically
+    pub streak_insured: bool,
+    /// Most recent claim intervals in seconds, newest first
+    pub recent_claim_intervals: [i64; CADENCE_HISTORY_LEN],
+    /// Number of valid entries in `recent_claim_intervals`
+    pub cadence_samples: u8,
+    /// Promotional reward multiplier set by the pool authority (0 = none, 10_000 = 1x)
+    pub multiplier_bps: u16,
+    /// When the multiplier lapses (0 = never)
+    pub multiplier_expires_at: i64,
+    /// Boost won in an auction and the window of accrual it applies to
+    pub auction_boost_bps: u16,
+    pub auction_boost_starts_at: i64,
+    pub auction_boost_ends_at: i64,
+    /// NFT registered for the pool's NFT boost; ownership is re-checked at claim time
+    pub nft_boost_mint: Option<Pubkey>,
+    pub nft_boost_bps: u16,
+    /// `referral_key` of the account that referred this user
+    pub referrer: Option<Pubkey>,
+    pub referral_count: u32,
+    /// Lifetime referral bonuses credited; `referral_earned - referral_paid` is claimable
+    pub referral_earned: u64,
+    pub referral_paid: u64,
+    /// Earliest timestamp the next claim passes the pool's minimum interval
+    pub next_eligible_at: i64,
+    /// When the pool authority banned this user (0 if not banned)
+    pub banned_at: i64,
+    /// Second signer for shared accounts; claims above `solo_claim_limit` need both
+    pub co_signer: Option<Pubkey>,
+    pub solo_claim_limit: u64,
+    /// Latest terms version the user accepted, and when
+    pub terms_version: u32,
+    pub terms_accepted_at: i64,
+    /// When the user paused accrual (0 if accruing)
+    pub paused_at: i64,
+    /// Paused time within the current accrual period, excluded from accrual
+    pub paused_seconds: i64,
+    pub total_paused_seconds: i64,
+    /// Wallet allowed to sign `claim_rewards` on the owner's behalf
+    pub claim_delegate: Option<Pubkey>,
+    /// Normalized accrual below one mint base unit, paid once it adds up
+    pub accrual_dust: u64,
+    /// Reward left accrued by a partial claim, paid with the next claim
+    pub accrued_unclaimed: u64,
+    /// Pool pause clock when the current accrual period began
+    pub pool_pause_checkpoint: i64,
+    /// Slot of a lottery draw the next claim reveals (0 = none pending)
+    pub lottery_commit_slot: u64,
+    pub lottery_wins: u32,
+    pub lottery_bonus_earned: u64,
+    /// Bitmap of badge milestones the user has minted
+    pub badges_minted: u8,
+    /// Verified activity points not yet paid out by a claim
+    pub activity_points: u64,
+    pub total_activity_points: u64,
+    /// Day `claims_today` counts claims for
+    pub claims_today_day: u64,
+    pub claims_today: u16,
+    pub bump: u8,
+    /// Layout version; see `RewardPool::version`
+    pub version: u8,
+    /// Boost bought with `purchase_boost` and the window of accrual it applies to
+    pub purchased_boost_bps: u16,
+    pub purchased_boost_starts_at: i64,
+    pub purchased_boost_ends_at: i64,
+    /// Pads the purchased boost to the three reserved words it replaced
+    pub _boost_padding: [u8; 6],
+    /// Multiplier granted by a whitelisted program with `grant_boost`, and when it lapses
+    pub granted_boost_bps: u16,
+    pub granted_boost_expires_at: i64,
+    /// Pads the granted boost to the two reserved words it replaced
+    pub _granted_boost_padding: [u8; 6],
+    /// Vesting positions and claim sessions not yet closed; wallet rotation waits for both
+    /// to reach zero
+    pub open_vesting_positions: u32,
+    pub open_claim_sessions: u32,
+    /// Created by `join_pool`, so its address is also seeded by the pool
+    pub is_membership: bool,
+    /// Pads `is_membership` to the reserved word it replaced
+    pub _membership_padding: [u8; 7],
+    pub _reserved: [u64; USER_RESERVED_WORDS],
+    /// Leaderboard label, set with `set_user_metadata`. Both strings start empty and the
+    /// account grows to fit them, so `INIT_SPACE` only counts their length prefixes.
+    #[max_len(0)]
+    pub nickname: String,
+    /// Off-chain profile JSON (avatar, links)
+    #[max_len(0)]
+    pub metadata_uri: String,
+    /// Key referees record this account by. It is fixed at registration and kept when the
+    /// account moves to a new wallet, so referrals keep crediting it after a rotation or
+    /// recovery, and a wallet registering again later gets a different one.
+    pub referral_key: Pubkey,
+}
+
+impl UserAccount {
+    /// Account size (with discriminator) holding `nickname` and `metadata_uri`
+    pub fn space_with_metadata(nickname: &str, metadata_uri: &str) -> usize {
+        8 + UserAccount::INIT_SPACE + nickname.len() + metadata_uri.len()
+    }
+
+    pub fn new(authority: Pubkey, pool: Pubkey, registration_timestamp: i64, bump: u8) -> Self {
+        Self {
+            authority,
+            pool,
+            total_earned: 0,
+            total_fees_paid: 0,
+            total_claims: 0,
+            last_claim_timestamp: 0,
+            registration_timestamp,
+            is_active: true,
+            claim_manager: None,
+            dormancy_warned_at: 0,
+            region_code: 0,
+            region_attested_at: 0,
+            total_withheld: 0,
+            current_streak: 0,
+            longest_streak: 0,
+            streak_insured: false,
+            recent_claim_intervals: [0; CADENCE_HISTORY_LEN],
+            cadence_samples: 0,
+            multiplier_bps: 0,
+            multiplier_expires_at: 0,
+            auction_boost_bps: 0,
+            auction_boost_starts_at: 0,
+            auction_boost_ends_at: 0,
+            nft_boost_mint: None,
+            nft_boost_bps: 0,
+            referrer: None,
+            referral_count: 0,
+            referral_earned: 0,
+            referral_paid: 0,
+            next_eligible_at: 0,
+            banned_at: 0,
+            co_signer: None,
+            solo_claim_limit: 0,
+            terms_version: 0,
+            terms_accepted_at: 0,
+            paused_at: 0,
+            paused_seconds: 0,
+            total_paused_seconds: 0,
+            claim_delegate: None,
+            accrual_dust: 0,
+            accrued_unclaimed: 0,
+            pool_pause_checkpoint: 0,
+            lottery_commit_slot: 0,
+            lottery_wins: 0,
+            lottery_bonus_earned: 0,
+            badges_minted: 0,
+            activity_points: 0,
+            total_activity_points: 0,
+            claims_today_day: 0,
+            claims_today: 0,
+            bump,
+            version: USER_ACCOUNT_VERSION,
+            purchased_boost_bps: 0,
+            purchased_boost_starts_at: 0,
+            purchased_boost_ends_at: 0,
+            _boost_padding: [0; 6],
+            granted_boost_bps: 0,
+            granted_boost_expires_at: 0,
+            _granted_boost_padding: [0; 6],
+            open_vesting_positions: 0,
+            open_claim_sessions: 0,
+            is_membership: false,
+            _membership_padding: [0; 7],
+            _reserved: [0; USER_RESERVED_WORDS],
+            nickname: String::new(),
+            metadata_uri: String::new(),
+            referral_key: Pubkey::new_from_array(
+                keccak::hashv(&[
+                    b"referral",
+                    authority.as_ref(),
+                    pool.as_ref(),
+                    &registration_timestamp.to_le_bytes(),
+                ])
+                .to_bytes(),
+            ),
+        }
+    }
+
+    /// Bring an account decoded from an older layout up to `USER_ACCOUNT_VERSION`
+    pub fn migrate(&mut self) -> Result<()> {
+        require!(
+            self.version <= USER_ACCOUNT_VERSION,
+            ErrorCode::UnsupportedAccountVersion
+        );
+        // Version 0 predates versioning, version 2 only widened the reserved space,
+        // version 3 appended the empty profile strings and version 4 carved the purchased
+        // boost out of zeroed reserved words; resizing covers all of them. The membership
+        // flag was carved out of them too, and accounts from before it are all registrations
+        if self.version < 5 {
+            // Their referees recorded the referrer's wallet
+            self.referral_key = self.authority;
+        }
+        self.version = USER_ACCOUNT_VERSION;
+        Ok(())
+    }
+
+    /// Bookkeeping shared by every claim path once the reward has been paid (or locked).
+    /// Returns the carried-over rewards the claim released, for the pool's total.
+    pub fn record_claim(
+        &mut self,
+        pool: &RewardPool,
+        breakdown: &RewardBreakdown,
+        now: i64,
+    ) -> Result<u64> {
+        let released = self.accrued_unclaimed;
+        if self.last_claim_timestamp != 0 {
+            math::ensure_clock_after(self.last_claim_timestamp, now)?;
+            let interval = now
+                .checked_sub(self.last_claim_timestamp)
+                .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+            self.recent_claim_intervals.rotate_right(1);
+            self.recent_claim_intervals[0] = interval;
+            self.cadence_samples = (self.cadence_samples + 1).min(CADENCE_HISTORY_LEN as u8);
+        }
+        self.total_claims = self
+            .total_claims
+            .checked_add(1)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        self.claims_today = math::claims_today(self, now).saturating_add(1);
+        self.claims_today_day = math::day_index(now);
+        self.last_claim_timestamp = now;
+        self.checkpoint_pool_pause(pool, now);
+        self.paused_seconds = 0;
+        self.accrual_dust = breakdown.accrual_dust;
+        self.accrued_unclaimed = breakdown.carryover;
+        self.activity_points = 0;
+        self.current_streak = breakdown.streak;
+        self.longest_streak = self.longest_streak.max(breakdown.streak);
+        if breakdown.streak_insurance_used {
+            self.streak_insured = false;
+        }
+        self.refresh_next_eligible_at(pool)?;
+        Ok(released)
+    }
+
+    /// Pool seed of this account's address: memberships are seeded by their pool as well as
+    /// their wallet, registration accounts by the wallet alone
+    pub fn pool_seed(&self) -> &[u8] {
+        if self.is_membership {
+            self.pool.as_ref()
+        } else {
+            &[]
+        }
+    }
+
+    /// Pool seed of the wallet's other account in this pool: its registration account for
+    /// a membership, its membership for a registration account
+    pub fn sibling_pool_seed(&self) -> &[u8] {
+        if self.is_membership {
+            &[]
+        } else {
+            self.pool.as_ref()
+        }
+    }
+
+    /// Copy of this account for `new_authority`'s address, used when a recovery or wallet
+    /// rotation moves it to a new key
+    pub fn succeeded_by(&self, new_authority: Pubkey, bump: u8) -> Self {
+        UserAccount {
+            authority: new_authority,
+            bump,
+            ..self.clone()
+        }
+    }
+
+    /// Record acceptance of the pool's current terms; `terms_version` must be exactly the
+    /// current version so a signature can't accept terms the user never saw
+    pub fn accept_terms(&mut self, pool: &RewardPool, terms_version: u32, now: i64) -> Result<()> {
+        require!(
+            terms_version == pool.terms_version,
+            ErrorCode::TermsVersionMismatch
+        );
+        self.terms_version = terms_version;
+        self.terms_accepted_at = now;
+        Ok(())
+    }
+
+    /// Start excluding pool-wide accrual pauses from `now`, when a new accrual period begins
+    pub fn checkpoint_pool_pause(&mut self, pool: &RewardPool, now: i64) {
+        self.pool_pause_checkpoint = math::pool_pause_clock(pool, now);
+    }
+
+    /// Recompute `next_eligible_at` from the last claim and the pool's current interval
+    pub fn refresh_next_eligible_at(&mut self, pool: &RewardPool) -> Result<()> {
+        self.next_eligible_at = math::eligible_at(pool, self)?;
+        Ok(())
+    }
+}
+
+/// Return data of `preview_claim`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ClaimPreview {
+    pub pool_active: bool,
+    pub user_active: bool,
+    pub reward_rate_per_hour: u64,
+    pub min_claim_interval_secs: u64,
+    pub max_daily_reward: u64,
+    pub max_accrual_hours: u64,
+    pub breakdown: RewardBreakdown,
+    pub daily_cap_remaining: u64,
+    /// Hours left before the accrual window stops accrual (u64::MAX when unbounded)
+    pub accrual_hours_remaining: u64,
+    pub seconds_until_claimable: i64,
+    pub vault_balance: u64,
+    pub vault_can_cover: bool,
+    pub timestamp: i64,
+}
+
+/// Return data of `get_claim_quote`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct ClaimQuote {
+    /// Reward the user would receive by claiming now
+    pub pending_amount: u64,
+    pub hours_accrued: u64,
+    /// When the minimum claim interval is met
+    pub eligible_at: i64,
+    pub daily_cap_remaining: u64,
+    /// User multiplier in bps (10_000 = 1x)
+    pub applied_multiplier: u16,
+    /// Accrued time in seconds, for pools accruing in steps under an hour
+    pub secs_accrued: u64,
+}
+
+/// Maximum pools per `compare_pools` call, keeping the result within the return data limit
+pub const MAX_COMPARED_POOLS: usize = 10;
+
+/// Comparable per-pool metrics returned by `compare_pools`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PoolMetrics {
+    pub pool: Pubkey,
+    pub is_active: bool,
+    /// Reward for a full day of accrual after the daily cap
+    pub effective_daily_reward: u64,
+    /// What the pool can still pay out: the remaining distribution budget, further
+    /// capped by the spendable vault balance for vault-funded and native pools
+    pub remaining_budget: u64,
+    /// When accrual stops (0 = unbounded)
+    pub accrual_end: i64,
+    pub participant_count: u64,
+    pub total_distributed: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct VestingPosition {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub claim_index: u64,
+    pub total_amount: u64,
+    pub released_amount: u64,
+    pub start_timestamp: i64,
+    pub cliff_secs: i64,
+    pub duration_secs: i64,
+    pub bump: u8,
+}
+
+/// Highest multiplier the pool authority can set on a user (5x)
+pub const MAX_USER_MULTIPLIER_BPS: u64 = 50_000;
+
+/// Most programs a pool can whitelist to grant boosts
+pub const MAX_BOOST_GRANTERS: usize = 4;
+
+/// Badge milestones per pool, limited by the `UserAccount::badges_minted` bitmap
+pub const MAX_BADGE_MILESTONES: usize = 8;
+pub const MAX_BADGE_NAME_LEN: usize = 32;
+pub const MAX_BADGE_SYMBOL_LEN: usize = 10;
+pub const MAX_BADGE_URI_LEN: usize = 200;
+
+/// What a badge milestone counts
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum MilestoneKind {
+    /// Lifetime claims (`UserAccount::total_claims`)
+    ClaimCount,
+    /// Lifetime earnings in base units (`UserAccount::total_earned`)
+    TotalEarned,
+}
+
+/// Milestone whose users can mint a badge NFT via `mint_milestone_badge`
+#[account]
+#[derive(InitSpace)]
+pub struct BadgeMilestone {
+    pub pool: Pubkey,
+    pub index: u8,
+    pub kind: MilestoneKind,
+    pub threshold: u64,
+    #[max_len(MAX_BADGE_NAME_LEN)]
+    pub name: String,
+    #[max_len(MAX_BADGE_SYMBOL_LEN)]
+    pub symbol: String,
+    #[max_len(MAX_BADGE_URI_LEN)]
+    pub uri: String,
+    pub bump: u8,
+}
 
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
+/// Durable record of one `claim_rewards` or `claim_native_rewards` payout
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimReceipt {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    /// The user's claim count before this claim
+    pub claim_index: u64,
+    /// Reward claimed, before transfer fees
+    pub amount: u64,
+    pub timestamp: i64,
+    /// Pool reward rate in effect at the claim
+    pub rate_per_hour: u64,
+    /// Signer who paid the rent and gets it back on close
+    pub payer: Pubkey,
+    /// When the receipt may be closed
+    pub expires_at: i64,
+    pub bump: u8,
 }
 
-#[derive(Accounts)]
-pub struct RegisterUser<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+/// Claim too large for one transfer, paid out in parts capped at `max_claim_per_tx`
+#[account]
+#[derive(InitSpace)]
+pub struct ClaimSession {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub total_amount: u64,
+    pub paid_amount: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
 
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + UserAccount::INIT_SPACE,
-        seeds = [b"user_account", authority.key().as_ref()],
-        bump
-    )]
-    pub user_account: Account<'info, UserAccount>,
+#[account]
+#[derive(InitSpace)]
+pub struct RentSponsor {
+    pub pool: Pubkey,
+    pub campaign_id: u64,
+    pub budget_lamports: u64,
+    pub spent_lamports: u64,
+    pub sponsored_count: u64,
+    pub is_active: bool,
+    pub bump: u8,
+}
 
-    #[account(
-        mut,
-        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
-        bump = reward_pool.bump
-    )]
-    pub reward_pool: Account<'info, RewardPool>,
+/// Schedule and terms of a boost auction, as passed to `create_boost_auction`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BoostAuctionConfig {
+    pub bidding_ends_at: i64,
+    pub boost_starts_at: i64,
+    pub boost_ends_at: i64,
+    pub slots: u8,
+    pub boost_bps: u16,
+    pub min_bid: u64,
+    /// Token account receiving winning bids (None = burn them)
+    pub proceeds_destination: Option<Pubkey>,
+}
 
-    pub system_program: Program<'info, System>,
+/// Most bids one auction accepts, keeping settlement within a single transaction
+pub const MAX_AUCTION_BIDS: u32 = 24;
+
+/// Per-epoch auction for a limited number of accrual boosts
+#[account]
+#[derive(InitSpace)]
+pub struct BoostAuction {
+    pub pool: Pubkey,
+    pub epoch: u64,
+    /// Token account owned by this PDA holding escrowed bids
+    pub escrow: Pubkey,
+    pub bidding_ends_at: i64,
+    pub boost_starts_at: i64,
+    pub boost_ends_at: i64,
+    pub slots: u8,
+    pub boost_bps: u16,
+    pub min_bid: u64,
+    /// Token account receiving winning bids (None = burn them)
+    pub proceeds_destination: Option<Pubkey>,
+    pub bid_count: u32,
+    pub total_proceeds: u64,
+    pub settled: bool,
+    pub bump: u8,
 }
 
-#[derive(Accounts)]
-pub struct CalculateRewards<'info> {
-    pub authority: Signer<'info>,
+#[account]
+#[derive(InitSpace)]
+pub struct BoostBid {
+    pub auction: Pubkey,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub placed_at: i64,
+    pub won: bool,
+    pub bump: u8,
+}
 
-    #[account(
-        seeds = [b"user_account", authority.key().as_ref()],
-        bump = user_account.bump
-    )]
-    pub user_account: Account<'info, UserAccount>,
+/// A staker's deposit in a pool and their share of the staking accumulator
+#[account]
+#[derive(InitSpace)]
+pub struct StakePosition {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub amount: u64,
+    /// Pool `reward_per_token_stored` at this position's last settlement
+    pub reward_per_token_paid: u128,
+    pub pending_rewards: u64,
+    pub total_claimed: u64,
+    pub bump: u8,
+}
 
-    #[account(
-        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
-        bump = reward_pool.bump
-    )]
-    pub reward_pool: Account<'info, RewardPool>,
+impl StakePosition {
+    /// Move rewards earned since the last settlement into `pending_rewards`
+    pub fn settle(&mut self, reward_per_token: u128) -> Result<()> {
+        self.pending_rewards = math::stake_earned(
+            self.amount,
+            reward_per_token,
+            self.reward_per_token_paid,
+            self.pending_rewards,
+        )?;
+        self.reward_per_token_paid = reward_per_token;
+        Ok(())
+    }
 }
 
-#[derive(Accounts)]
-pub struct ClaimRewards<'info> {
-    #[account(mut)]
-    pub authority: Signer<'info>,
+/// An NFT escrowed in a pool's NFT staking program
+#[account]
+#[derive(InitSpace)]
+pub struct StakedNft {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub nft_mint: Pubkey,
+    pub staked_at: i64,
+    /// Rewards are paid for whole hours elapsed since this timestamp
+    pub last_accrual_timestamp: i64,
+    pub total_earned: u64,
+    pub bump: u8,
+}
 
-    #[account(
-        mut,
-        seeds = [b"user_account", authority.key().as_ref()],
-        bump = user_account.bump
-    )]
-    pub user_account: Account<'info, UserAccount>,
+/// Distribution across every reporting pool of one mint
+#[account]
+#[derive(InitSpace)]
+pub struct MintStats {
+    pub mint: Pubkey,
+    pub reporting_pools: u32,
+    pub total_distributed: u64,
+    pub total_claims: u64,
+    pub last_claim_at: i64,
+    pub bump: u8,
+}
 
-    #[account(
-        mut,
-        seeds = [b"reward_pool", reward_pool.authority.as_ref()],
-        bump = reward_pool.bump
-    )]
-    pub reward_pool: Account<'info, RewardPool>,
+/// Claim activity of one pool during one UTC day
+#[account]
+#[derive(InitSpace)]
+pub struct DailyStats {
+    pub pool: Pubkey,
+    /// Days since the unix epoch
+    pub day_index: u64,
+    pub claims_count: u64,
+    pub amount_distributed: u64,
+    /// Wallets whose first claim of the day this was
+    pub unique_claimers: u64,
+    pub bump: u8,
+}
 
-    #[account(
-        mut,
-        associated_token::mint = mint,
-        associated_token::authority = reward_pool,
-    )]
-    pub vault: Account<'info, TokenAccount>,
+/// Most guardians a user can designate for social recovery
+pub const MAX_GUARDIANS: usize = 8;
 
-    #[account(
-        init_if_needed,
-        payer = authority,
-        associated_token::mint = mint,
-        associated_token::authority = authority,
-    )]
-    pub user_token_account: Account<'info, TokenAccount>,
+/// Guardian set that can jointly move a user account to a new key
+#[account]
+#[derive(InitSpace)]
+pub struct RecoveryConfig {
+    pub user_account: Pubkey,
+    #[max_len(MAX_GUARDIANS)]
+    pub guardians: Vec<Pubkey>,
+    pub threshold: u8,
+    /// Delay between the first approval and execution, during which the user can cancel
+    pub timelock_secs: i64,
+    pub pending_authority: Option<Pubkey>,
+    /// Bitmap of approving guardians, indexed like `guardians`
+    pub approvals: u8,
+    pub initiated_at: i64,
+    pub bump: u8,
+}
 
-    pub mint: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
+/// Self-service move of a user account to a new key, pending its delay
+#[account]
+#[derive(InitSpace)]
+pub struct WalletRotation {
+    pub user_account: Pubkey,
+    pub new_authority: Pubkey,
+    pub initiated_at: i64,
+    pub bump: u8,
 }
 
-#[derive(Accounts)]
-pub struct UpdatePoolConfig<'info> {
-    #[account(
-        mut,
-        has_one = authority,
-        seeds = [b"reward_pool", authority.key().as_ref()],
-        bump = reward_pool.bump
-    )]
-    pub reward_pool: Account<'info, RewardPool>,
+/// One-shot airdrop of `(wallet, amount)` allocations committed to by a Merkle root
+#[account]
+#[derive(InitSpace)]
+pub struct Airdrop {
+    pub pool: Pubkey,
+    pub airdrop_id: u64,
+    pub merkle_root: [u8; 32],
+    pub total_amount: u64,
+    pub claimed_amount: u64,
+    pub claimed_count: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
 
-    pub authority: Signer<'info>,
+/// Proof that a wallet already claimed its allocation of an airdrop
+#[account]
+#[derive(InitSpace)]
+pub struct AirdropReceipt {
+    pub airdrop: Pubkey,
+    pub claimant: Pubkey,
+    pub amount: u64,
+    pub claimed_at: i64,
+    pub bump: u8,
 }
 
-#[derive(Accounts)]
-pub struct EmergencyWithdraw<'info> {
-    #[account(
-        has_one = authority,
-        seeds = [b"reward_pool", authority.key().as_ref()],
-        bump = reward_pool.bump
-    )]
-    pub reward_pool: Account<'info, RewardPool>,
+/// Budget and statistics for one epoch of a pool that runs epochs
+#[account]
+#[derive(InitSpace)]
+pub struct Epoch {
+    pub pool: Pubkey,
+    pub index: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    /// Most that may be distributed during this epoch
+    pub budget: u64,
+    pub distributed_this_epoch: u64,
+    pub claim_count: u64,
+    pub bump: u8,
+}
 
-    #[account(
-        mut,
-        associated_token::mint = mint,
-        associated_token::authority = reward_pool,
-    )]
-    pub vault: Account<'info, TokenAccount>,
+/// Entries kept on a pool's leaderboard
+pub const LEADERBOARD_SIZE: usize = 100;
 
-    #[account(mut)]
-    pub destination: Account<'info, TokenAccount>,
+#[zero_copy]
+pub struct LeaderboardEntry {
+    pub user: Pubkey,
+    pub total_earned: u64,
+}
 
-    pub authority: Signer<'info>,
-    pub mint: Account<'info, Mint>,
-    pub token_program: Program<'info, Token>,
+/// A pool's top earners by `total_earned`, highest first
+#[account(zero_copy)]
+pub struct Leaderboard {
+    pub pool: Pubkey,
+    /// Occupied prefix of `entries`
+    pub count: u32,
+    pub bump: u8,
+    pub _padding: [u8; 3],
+    pub entries: [LeaderboardEntry; LEADERBOARD_SIZE],
+}
+
+impl Leaderboard {
+    /// Update or insert `user` with its new `total_earned`, keeping entries sorted. A
+    /// full board only admits users who beat its lowest entry.
+    pub fn record(&mut self, user: Pubkey, total_earned: u64) {
+        let count = self.count as usize;
+        let mut index = match self.entries[..count]
+            .iter()
+            .position(|entry| entry.user == user)
+        {
+            Some(index) => index,
+            None if count < LEADERBOARD_SIZE => {
+                self.count += 1;
+                count
+            }
+            None if total_earned > self.entries[count - 1].total_earned => count - 1,
+            None => return,
+        };
+
+        self.entries[index] = LeaderboardEntry { user, total_earned };
+        while index > 0 && self.entries[index - 1].total_earned < total_earned {
+            self.entries.swap(index - 1, index);
+            index -= 1;
+        }
+    }
 }
 
+/// Merkle root of every user's accrued reward at a point in time
 #[account]
 #[derive(InitSpace)]
-pub struct RewardPool {
-    pub authority: Pubkey,
-    pub mint: Pubkey,
-    pub vault: Pubkey,
-    pub reward_rate_per_hour: u64,
-    pub min_claim_interval_hours: u64,
-    pub max_daily_reward: u64,
-    pub total_distributed: u64,
-    pub participant_count: u64,
+pub struct AccrualSnapshot {
+    pub pool: Pubkey,
+    pub index: u64,
+    pub merkle_root: [u8; 32],
+    /// Time the committed balances were computed at
+    pub as_of: i64,
+    pub committed_at: i64,
+    pub user_count: u64,
+    pub total_accrued: u64,
+    pub bump: u8,
+}
+
+/// Slice of a pool's budget that a downstream distributor program may draw down
+#[account]
+#[derive(InitSpace)]
+pub struct BudgetDelegation {
+    pub pool: Pubkey,
+    pub delegate_program: Pubkey,
+    /// Signer allowed to draw, typically a PDA of `delegate_program`
+    pub delegate_authority: Pubkey,
+    /// Total that may ever be drawn
+    pub allowance: u64,
+    pub drawn: u64,
     pub is_active: bool,
-    pub created_at: i64,
     pub bump: u8,
+    /// Raised allowance that replaces `allowance` at `pending_allowance_at` (0 if none)
+    pub pending_allowance: u64,
+    pub pending_allowance_at: i64,
+}
+
+impl BudgetDelegation {
+    /// Move a raised allowance into effect once its delay has passed
+    pub fn apply_due_allowance(&mut self, now: i64) {
+        if self.pending_allowance_at != 0 && now >= self.pending_allowance_at {
+            self.allowance = self.pending_allowance;
+            self.pending_allowance = 0;
+            self.pending_allowance_at = 0;
+        }
+    }
 }
 
+/// Sponsor matching user claims into a secondary destination, up to a budget
 #[account]
 #[derive(InitSpace)]
-pub struct UserAccount {
-    pub authority: Pubkey,
-    pub total_earned: u64,
-    pub total_claims: u64,
-    pub last_claim_timestamp: i64,
-    pub registration_timestamp: i64,
+pub struct MatchingSponsor {
+    pub pool: Pubkey,
+    pub sponsor: Pubkey,
+    /// Token account owned by this PDA that the sponsor funds
+    pub vault: Pubkey,
+    pub destination: Pubkey,
+    /// Matched amount per unit claimed (10_000 = 1:1)
+    pub ratio_bps: u16,
+    pub budget: u64,
+    pub matched_total: u64,
+    pub match_count: u64,
     pub is_active: bool,
     pub bump: u8,
 }
 
+/// Signer allowed to credit activity points to a pool's users
+#[account]
+#[derive(InitSpace)]
+pub struct ActivityVerifier {
+    pub pool: Pubkey,
+    pub verifier: Pubkey,
+    pub points_recorded: u64,
+    pub added_at: i64,
+    pub bump: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct DenylistEntry {
+    pub pool: Pubkey,
+    pub address: Pubkey,
+    pub reason_code: u16,
+    pub added_at: i64,
+    pub bump: u8,
+}
+
+/// What happens to claims from users attested to a region
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum RegionAction {
+    /// Claims are rejected
+    Blocked,
+    /// Rewards are scaled to `bps` of the accrued amount
+    ReducedRate { bps: u16 },
+    /// `bps` of the accrued amount is withheld in the vault
+    Withholding { bps: u16 },
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct RegionPolicy {
+    pub pool: Pubkey,
+    pub region_code: u16,
+    pub action: RegionAction,
+    pub bump: u8,
+}
+
+#[event]
+pub struct MatchingContribution {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub sponsor: Pubkey,
+    pub destination: Pubkey,
+    pub claim_amount: u64,
+    pub matched_amount: u64,
+    pub remaining_budget: u64,
+}
+
+#[event]
+pub struct ReferralBonus {
+    pub pool: Pubkey,
+    pub referrer: Pubkey,
+    pub referee: Pubkey,
+    pub claim_amount: u64,
+    pub bonus: u64,
+}
+
+#[event]
+pub struct LotteryWon {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub multiplier_bps: u16,
+    pub bonus: u64,
+}
+
+#[event]
+pub struct BadgeMinted {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub index: u8,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct ActivityRecorded {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub verifier: Pubkey,
+    pub points: u64,
+}
+
+#[event]
+pub struct BoostGranted {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub granter_program: Pubkey,
+    pub multiplier_bps: u16,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct BoostPurchased {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub tier: u8,
+    pub bonus_bps: u16,
+    pub price: u64,
+    /// Whether the payment was burned rather than sent to the proceeds destination
+    pub burned: bool,
+    pub expires_at: i64,
+}
+
+#[event]
+pub struct ExcessWithdrawn {
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub reserve: u64,
+    pub destination: Pubkey,
+}
+
+#[event]
+pub struct EmergencyWithdrawAnnounced {
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub destination: Pubkey,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct EmergencyWithdrawCancelled {
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub destination: Pubkey,
+}
+
+#[event]
+pub struct PendingConfigApplied {
+    pub pool: Pubkey,
+    pub reward_rate_per_hour: u64,
+    pub reward_rate_fraction: u64,
+    pub max_daily_reward: u64,
+    pub max_accrual_hours: u64,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct EpochRolled {
+    pub pool: Pubkey,
+    pub index: u64,
+    pub previous_distributed: u64,
+    pub previous_claims: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub budget: u64,
+}
+
+#[event]
+pub struct AccrualSnapshotCommitted {
+    pub pool: Pubkey,
+    pub index: u64,
+    pub merkle_root: [u8; 32],
+    pub as_of: i64,
+    pub user_count: u64,
+    pub total_accrued: u64,
+}
+
+#[event]
+pub struct YieldRebalanced {
+    pub pool: Pubkey,
+    pub venue_program: Pubkey,
+    pub deposited: u64,
+    pub withdrawn: u64,
+    pub yield_deployed: u64,
+    pub liquid_balance: u64,
+}
+
+#[event]
+pub struct BudgetDrawn {
+    pub pool: Pubkey,
+    pub delegate_program: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub remaining: u64,
+}
+
+#[event]
+pub struct PoolClaimed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PoolClaimFailed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    /// Error a direct claim from the pool would have failed with
+    pub error_code: u32,
+}
+
+#[event]
+pub struct CrankClaimed {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub cranker: Pubkey,
+    pub amount: u64,
+    pub tip: u64,
+}
+
+#[event]
+pub struct RewardsBurned {
+    pub pool: Pubkey,
+    pub amount: u64,
+    pub claims: u64,
+    pub total_burned: u64,
+}
+
+#[event]
+pub struct ClaimRetryHint {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub retry_after_secs: i64,
+    pub eligible_at: i64,
+}
+
+#[event]
+pub struct DenylistBlocked {
+    pub pool: Pubkey,
+    pub address: Pubkey,
+}
+
+#[event]
+pub struct DormancyWarning {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub last_activity: i64,
+    pub sweepable_at: i64,
+}
+
 #[error_code]
 pub enum ErrorCode {
     #[msg("Pool is not active")]
@@ -397,8 +13018,352 @@ pub enum ErrorCode {
     UserNotActive,
     #[msg("Claim too soon - minimum interval not met")]
     ClaimTooSoon,
-    #[msg("Amount mismatch between expected and calculated")]
-    AmountMismatch,
+    #[msg("Claimable amount is below the minimum expected amount")]
+    BelowMinimumExpected,
     #[msg("No rewards available to claim")]
     NoRewardsAvailable,
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow,
+    #[msg("Arithmetic underflow")]
+    ArithmeticUnderflow,
+    #[msg("Insufficient funds in reward vault")]
+    InsufficientVaultFunds,
+    #[msg("Member accounts must be passed as user_account/token_account pairs")]
+    InvalidMemberAccounts,
+    #[msg("Signer is not the approved claim manager for this member")]
+    NotClaimManager,
+    #[msg("Dormancy periods must not be negative")]
+    InvalidDormancyPolicy,
+    #[msg("Dormancy sweeping is disabled for this pool")]
+    DormancySweepDisabled,
+    #[msg("User account is not dormant")]
+    UserNotDormant,
+    #[msg("User account must be warned before it can be swept")]
+    DormancyWarningRequired,
+    #[msg("Dormancy grace period has not elapsed")]
+    DormancyGracePeriodActive,
+    #[msg("User account does not belong to this pool")]
+    UserPoolMismatch,
+    #[msg("Rent sponsorship campaign is not active")]
+    RentSponsorNotActive,
+    #[msg("Rent sponsorship campaign budget exceeded")]
+    RentSponsorBudgetExceeded,
+    #[msg("Rent sponsor account has insufficient lamports")]
+    RentSponsorInsufficientFunds,
+    #[msg("User account already exists")]
+    UserAlreadyRegistered,
+    #[msg("Pool accounts must be passed as reward_pool/vault pairs")]
+    InvalidPoolAccounts,
+    #[msg("Address is denylisted")]
+    AddressDenylisted,
+    #[msg("Mint-on-claim pools require the pool PDA to be the mint authority")]
+    PoolNotMintAuthority,
+    #[msg("Claims are blocked for the user's region")]
+    RegionBlocked,
+    #[msg("A region attestation is required to claim from this pool")]
+    RegionAttestationRequired,
+    #[msg("Signer is not the pool's region verifier")]
+    InvalidRegionVerifier,
+    #[msg("Basis points must not exceed 10000")]
+    InvalidBasisPoints,
+    #[msg("Instruction does not match the pool's distribution mode")]
+    WrongDistributionMode,
+    #[msg("Native SOL pools must use the native mint")]
+    NativePoolRequiresNativeMint,
+    #[msg("Faucet lifetime cap must be at least one non-zero claim")]
+    InvalidFaucetConfig,
+    #[msg("Faucet claims require a captcha attestation from the configured verifier")]
+    CaptchaAttestationRequired,
+    #[msg("Faucet lifetime cap reached for this wallet")]
+    FaucetLifetimeCapReached,
+    #[msg("Faucet pools only accept direct claims")]
+    FaucetRequiresDirectClaim,
+    #[msg("Vesting schedule durations must not be negative")]
+    InvalidVestingSchedule,
+    #[msg("Vesting pools require a vesting position account on claim")]
+    VestingPositionRequired,
+    #[msg("Vesting is not enabled for this pool")]
+    VestingNotEnabled,
+    #[msg("Vesting pools only accept direct claims")]
+    VestingRequiresDirectClaim,
+    #[msg("No vested tokens available to release")]
+    NothingToRelease,
+    #[msg("Vesting position belongs to a different user")]
+    VestingOwnerMismatch,
+    #[msg("Streak insurance is not offered by this pool")]
+    StreakInsuranceUnavailable,
+    #[msg("Streak is already insured")]
+    StreakAlreadyInsured,
+    #[msg("Matching sponsor accounts are missing or do not match the pool")]
+    MatchingAccountsRequired,
+    #[msg("Pools with a matching sponsor only accept direct claims")]
+    MatchingRequiresDirectClaim,
+    #[msg("Invalid boost auction configuration")]
+    InvalidAuctionConfig,
+    #[msg("Bidding for this auction has closed")]
+    AuctionClosed,
+    #[msg("Bid is below the auction minimum")]
+    BidTooLow,
+    #[msg("Auction has reached its bid limit")]
+    AuctionFull,
+    #[msg("Bidding has not ended yet")]
+    AuctionNotEnded,
+    #[msg("Auction is already settled")]
+    AuctionAlreadySettled,
+    #[msg("Auction has not been settled")]
+    AuctionNotSettled,
+    #[msg("Remaining accounts must be every bid of the auction")]
+    InvalidBidAccounts,
+    #[msg("NFT boost is not enabled for this pool")]
+    NftBoostNotEnabled,
+    #[msg("NFT is not a verified member of the boost collection")]
+    NftNotInCollection,
+    #[msg("NFT must be a held, zero-decimal, single-supply mint")]
+    InvalidBoostNft,
+    #[msg("Guardians must be 1-8 distinct keys other than the user, with a valid threshold")]
+    InvalidGuardianSet,
+    #[msg("Signer is not a guardian of this account")]
+    NotGuardian,
+    #[msg("A recovery to a different key is already pending")]
+    RecoveryAlreadyPending,
+    #[msg("No matching recovery is pending")]
+    NoPendingRecovery,
+    #[msg("Not enough guardians have approved the recovery")]
+    RecoveryThresholdNotMet,
+    #[msg("Recovery timelock has not elapsed")]
+    RecoveryTimelockActive,
+    #[msg("Staking is already enabled for this pool")]
+    StakingAlreadyEnabled,
+    #[msg("Staking is not enabled for this pool or the stake mint does not match")]
+    StakingNotEnabled,
+    #[msg("Stake amount must be positive and no more than the staked balance")]
+    InvalidStakeAmount,
+    #[msg("NFT staking is not enabled for this pool")]
+    NftStakingNotEnabled,
+    #[msg("This pool reports mint stats; the mint stats account is required")]
+    MintStatsRequired,
+    #[msg("Users cannot refer themselves")]
+    SelfReferral,
+    #[msg("This user was referred; the referrer's account is required")]
+    ReferrerAccountRequired,
+    #[msg("Signer is not on the pool's registration allowlist")]
+    NotAllowlisted,
+    #[msg("Airdrop needs a non-zero root and amount")]
+    InvalidAirdropConfig,
+    #[msg("Airdrop proof does not match the published root")]
+    InvalidAirdropProof,
+    #[msg("Airdrop has paid out its full amount")]
+    AirdropExhausted,
+    #[msg("User has been banned from this pool")]
+    UserBanned,
+    #[msg("User is not banned")]
+    UserNotBanned,
+    #[msg("Claim exceeds the solo limit and needs the co-signer's signature")]
+    CoSignatureRequired,
+    #[msg("Co-signer must differ from the account authority")]
+    InvalidCoSigner,
+    #[msg("Accept the pool's current terms before claiming")]
+    TermsNotAccepted,
+    #[msg("Acknowledged terms version does not match the pool's current terms")]
+    TermsVersionMismatch,
+    #[msg("Accrual is paused")]
+    AccrualPaused,
+    #[msg("Accrual is not paused")]
+    AccrualNotPaused,
+    #[msg("Outage window must start before it ends and lie in the past")]
+    InvalidOutageWindow,
+    #[msg("Signer is neither the account owner nor its claim delegate")]
+    NotClaimDelegate,
+    #[msg("Claim exceeds the per-transaction cap; pass a claim session account")]
+    ClaimSessionRequired,
+    #[msg("Claim session account passed for a claim within the per-transaction cap")]
+    ClaimSessionNotNeeded,
+    #[msg("Only the account owner can route rewards to a custom destination")]
+    DestinationRequiresOwner,
+    #[msg("Mint has more decimals than normalized accrual supports")]
+    UnsupportedMintDecimals,
+    #[msg("Safe mode needs a guardian other than the authority and at least a 24h timelock")]
+    InvalidSafeModeConfig,
+    #[msg("Safe-mode pools withdraw through the timelocked proposal flow")]
+    SafeModeTimelocked,
+    #[msg("Pool was not created in safe mode")]
+    SafeModeNotEnabled,
+    #[msg("An emergency withdrawal is already pending")]
+    WithdrawalAlreadyPending,
+    #[msg("No matching emergency withdrawal is pending")]
+    NoPendingWithdrawal,
+    #[msg("Emergency withdrawal timelock has not passed")]
+    WithdrawalTimelockActive,
+    #[msg("Signer is not the pool's guardian")]
+    NotPoolGuardian,
+    #[msg("Budget delegation is paused")]
+    DelegationNotActive,
+    #[msg("Draw exceeds the delegated allowance")]
+    DelegationAllowanceExceeded,
+    #[msg("Allowance cannot drop below the amount already drawn")]
+    InvalidDelegationAllowance,
+    #[msg("Signer is not the delegation's authority")]
+    NotBudgetDelegate,
+    #[msg("Claim amount must be positive and at most the accrued reward")]
+    InvalidClaimAmount,
+    #[msg("Program is not the pool's whitelisted yield venue")]
+    YieldVenueNotWhitelisted,
+    #[msg("Yield venue cannot change while funds are deployed")]
+    YieldFundsDeployed,
+    #[msg("Yield deposit amount must be positive")]
+    InvalidYieldAmount,
+    #[msg("Deployment would leave the vault below its liquid reserve")]
+    YieldReserveBreached,
+    #[msg("Vault balance change does not match the yield venue call")]
+    YieldVenueMismatch,
+    #[msg("Vault is short; withdraw deployed funds from the yield venue first")]
+    YieldWithdrawalRequired,
+    #[msg("Signer is not the pool's snapshot cranker")]
+    NotSnapshotCranker,
+    #[msg("Invalid snapshot root, time or interval")]
+    InvalidSnapshot,
+    #[msg("Snapshot interval has not elapsed")]
+    SnapshotTooSoon,
+    #[msg("Proof does not match the snapshot root")]
+    InvalidSnapshotProof,
+    #[msg("Invalid emission schedule: rate, decay interval (min one day) or decay bps")]
+    InvalidEmissionSchedule,
+    #[msg("Epoch duration and budget must be positive")]
+    InvalidEpochConfig,
+    #[msg("Epochs have already been started")]
+    EpochsAlreadyStarted,
+    #[msg("Pool does not run epochs")]
+    EpochsNotStarted,
+    #[msg("Current epoch account is required")]
+    EpochAccountRequired,
+    #[msg("Epoch is not the pool's current epoch or has ended")]
+    EpochNotCurrent,
+    #[msg("Current epoch has not ended yet")]
+    EpochStillRunning,
+    #[msg("Epoch budget exhausted")]
+    EpochBudgetExhausted,
+    #[msg("Accrual end must be after accrual start")]
+    InvalidAccrualWindow,
+    #[msg("Lifetime reward cap reached for this wallet")]
+    LifetimeCapReached,
+    #[msg("Pool distribution budget exhausted")]
+    PoolBudgetExhausted,
+    #[msg("Claims are paused for this pool")]
+    ClaimsPaused,
+    #[msg("Registrations are paused for this pool")]
+    RegistrationsPaused,
+    #[msg("Accrual is paused for the whole pool")]
+    PoolAccrualPaused,
+    #[msg("USD pricing needs a feed id, a positive max price age and confidence <= 100%")]
+    InvalidUsdPricing,
+    #[msg("Price feed account is required for USD-priced pools")]
+    PriceFeedRequired,
+    #[msg("Price feed is not a fully verified update for the pool's feed")]
+    InvalidPriceFeed,
+    #[msg("Oracle price is stale")]
+    StalePrice,
+    #[msg("Oracle price confidence interval is too wide")]
+    PriceConfidenceTooWide,
+    #[msg("USD-priced pools only support direct claims")]
+    UsdPricingRequiresDirectClaim,
+    #[msg("Lottery chance must be at most 100% with a multiplier above 1x")]
+    InvalidLotteryConfig,
+    #[msg("Lottery is not enabled for this pool")]
+    LotteryNotEnabled,
+    #[msg("A lottery draw is already pending")]
+    LotteryDrawPending,
+    #[msg("Lottery draw cannot be revealed in its commit slot")]
+    LotteryNotRevealable,
+    #[msg("SlotHashes sysvar is required to reveal the lottery draw")]
+    SlotHashesRequired,
+    #[msg("Pool authority is not the native treasury of this governance")]
+    InvalidGovernance,
+    #[msg("Daily stats account is required when the pool reports daily stats")]
+    DailyStatsRequired,
+    #[msg("Daily stats account is not for the current day")]
+    DailyStatsNotCurrent,
+    #[msg("Claim receipt account is required when the pool keeps receipts")]
+    ClaimReceiptRequired,
+    #[msg("Pool does not keep claim receipts")]
+    ReceiptsNotEnabled,
+    #[msg("Receipt expiry must not be negative")]
+    InvalidReceiptExpiry,
+    #[msg("Claim receipt has not expired yet")]
+    ReceiptNotExpired,
+    #[msg("Invalid badge milestone configuration")]
+    InvalidBadgeMilestone,
+    #[msg("Badge for this milestone was already minted")]
+    BadgeAlreadyMinted,
+    #[msg("Milestone has not been reached")]
+    MilestoneNotReached,
+    #[msg("Activity points must be positive")]
+    InvalidActivityPoints,
+    #[msg("Invalid boost granter configuration")]
+    InvalidBoostGranters,
+    #[msg("Boosts can only be granted via CPI from a whitelisted program")]
+    UnauthorizedBoostGranter,
+    #[msg("Boost exceeds the pool's granted boost cap or has no duration")]
+    InvalidBoost,
+    #[msg("Pool requires registration through register_user_with_attestation")]
+    AttestationRequired,
+    #[msg("Pool has no registration attestor")]
+    AttestationNotEnabled,
+    #[msg("Attestation has expired")]
+    AttestationExpired,
+    #[msg("Attestor signature check must directly precede registration")]
+    AttestationMissing,
+    #[msg("Attestation is not signed by the pool attestor for this user")]
+    InvalidAttestation,
+    #[msg("Daily claim limit reached")]
+    TooManyClaimsToday,
+    #[msg("Config delay must not be negative")]
+    InvalidConfigDelay,
+    #[msg("No scheduled config change is due")]
+    PendingConfigNotDue,
+    #[msg("Emergency withdrawal delay is below the minimum")]
+    InvalidWithdrawDelay,
+    #[msg("Withdrawal does not match the announced amount and destination")]
+    WithdrawalNotAnnounced,
+    #[msg("Withdrawal would dip into funds reserved for participants")]
+    ExceedsExcessFunds,
+    #[msg("Account was written by a newer program version")]
+    UnsupportedAccountVersion,
+    #[msg("Recorded timestamp is ahead of the cluster clock")]
+    InvalidTimestamp,
+    #[msg("Pool writes transfer memos; pass the SPL Memo program")]
+    MemoProgramRequired,
+    #[msg("Pool charges a registration fee; pass the accounts to pay it from and into")]
+    RegistrationFeeAccountsRequired,
+    #[msg("Pool's daily sponsorship limit reached; try again tomorrow")]
+    SponsorshipDailyLimitReached,
+    #[msg("Nickname or metadata URI is too long")]
+    UserMetadataTooLong,
+    #[msg("Wallet rotation must name a different key, signed by that key")]
+    InvalidWalletRotation,
+    #[msg("Wallet rotation delay has not elapsed")]
+    WalletRotationTimelockActive,
+    #[msg("Boost tier is not for sale, or its proceeds account is missing")]
+    BoostTierUnavailable,
+    #[msg("Another purchased boost is still running or unclaimed")]
+    PurchasedBoostActive,
+    #[msg("Pools that keep claim receipts only accept direct claims")]
+    ReceiptsRequireDirectClaim,
+    #[msg("Release vesting positions and finish claim sessions before moving the account")]
+    OpenClaimsBlockRotation,
+    #[msg("Claim-all accounts must be passed in groups of four per pool")]
+    InvalidClaimAllAccounts,
+    #[msg("Multiplier must be 0 to clear, or 1x to 5x with a future expiry if any")]
+    InvalidUserMultiplier,
+    #[msg("Wallet already has an account in this pool")]
+    AlreadyInPool,
+    #[msg("Pools that burn part of each claim only accept direct claims")]
+    BurnRequiresDirectClaim,
+    #[msg("Token-2022 pools only accept direct claims")]
+    Token2022RequiresDirectClaim,
+    #[msg("Pools with a denylist only accept direct claims")]
+    DenylistRequiresDirectClaim,
+    #[msg("Pools with region policies only accept direct claims")]
+    RegionPolicyRequiresDirectClaim,
+    #[msg("NFT boosts are only re-checked by direct claims")]
+    NftBoostRequiresDirectClaim,
 }
\ No newline at end of file
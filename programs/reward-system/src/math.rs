@@ -0,0 +1,1917 @@
+use anchor_lang::prelude::*;
+
+use reward_math::accrual;
+
+use crate::fixed_point::{div_to_int, mul_div, RoundingMode, Q64};
+use crate::{
+    AccrualModel, DistributionMode, EmissionSchedule, ErrorCode, RegionAction, RewardPool,
+    UserAccount, CADENCE_HISTORY_LEN,
+};
+
+pub use reward_math::{
+    accrual_step_secs, day_index, whole_hours, whole_steps, BPS_DENOMINATOR, SECONDS_PER_DAY,
+    SECONDS_PER_HOUR,
+};
+
+/// Fixed-point scale of the staking reward-per-token accumulator
+pub const REWARD_PER_TOKEN_SCALE: u128 = 1_000_000_000_000;
+/// Decimals of the fixed-point unit accrual is computed in for normalized pools
+pub const NORMALIZED_DECIMALS: u8 = 9;
+
+/// Everything the claim paths need to know about a user's accrual at a point in time
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RewardBreakdown {
+    /// Whole hours since the last claim (or registration, if never claimed)
+    pub hours_since_last_claim: u64,
+    /// Hours that actually accrue after applying the pool's accrual window
+    pub accrual_hours: u64,
+    /// Reward before the daily cap is applied
+    pub uncapped_reward: u64,
+    /// Reward owed after the daily cap, user multiplier and bonuses are applied
+    pub reward_amount: u64,
+    /// Whether the minimum claim interval has been met
+    pub interval_met: bool,
+    /// User multiplier applied to the capped reward (10_000 = 1x)
+    pub multiplier_bps: u16,
+    /// Streak the user would be on if they claimed now (0 when streaks are disabled)
+    pub streak: u32,
+    /// Bonus rate earned by that streak
+    pub streak_bonus_bps: u16,
+    /// Bonus added on top of the capped reward
+    pub streak_bonus: u64,
+    /// Whether the user's streak insurance is spent to keep the streak alive
+    pub streak_insurance_used: bool,
+    /// Bonus for claiming on the pool's target cadence
+    pub cadence_bonus: u64,
+    /// Deduction for burst-claiming well ahead of the target cadence
+    pub cadence_penalty: u64,
+    /// Bonus from a won boost auction, for the part of the accrual inside the boost window
+    pub auction_boost_bonus: u64,
+    /// Bonus from a boost bought with `purchase_boost`, for the part inside its window
+    pub purchased_boost_bonus: u64,
+    /// Bonus for holding an NFT from the pool's boost collection
+    pub nft_boost_bonus: u64,
+    /// Normalized remainder below one mint base unit, carried into the next claim.
+    /// For normalized pools every amount above except `reward_amount` is in
+    /// `NORMALIZED_DECIMALS` units; `reward_amount` is always in mint base units.
+    pub accrual_dust: u64,
+    /// Reward for the user's unpaid activity points, on top of time accrual
+    pub activity_bonus: u64,
+    /// Reward left unclaimed by an earlier partial claim, included in `reward_amount`
+    pub accrued_unclaimed: u64,
+    /// Part of the accrued reward a partial claim leaves for later; set by the claim path,
+    /// never by `compute_reward`
+    pub carryover: u64,
+    /// Seconds that accrue: `accrual_hours` in seconds, or whole claim intervals on pools
+    /// with intervals under an hour
+    pub accrual_secs: u64,
+    /// Part of `reward_amount` in USD micro-units, converted to tokens at claim time; the
+    /// rest is in tokens
+    pub usd_priced_reward: u64,
+}
+
+/// Compute the reward owed to `user` from `pool` at unix time `now`.
+///
+/// This is the single source of truth for accrual; every instruction that
+/// reports or pays out rewards must go through it.
+pub fn compute_reward(pool: &RewardPool, user: &UserAccount, now: i64) -> Result<RewardBreakdown> {
+    ensure_clock_after(user.registration_timestamp, now)?;
+    ensure_clock_after(user.last_claim_timestamp, now)?;
+
+    // Accrual is frozen while the user has paused it
+    let now = if user.paused_at != 0 {
+        now.min(user.paused_at)
+    } else {
+        now
+    };
+    // Pool-wide accrual pauses since the user's accrual period began don't count either
+    let now = now.saturating_sub(
+        pool_pause_clock(pool, now)
+            .saturating_sub(user.pool_pause_checkpoint)
+            .max(0),
+    );
+
+    // Pauses can push the accrual start past the adjusted clock; that is no time, not an error
+    let secs_since_last_claim = now
+        .checked_sub(accrual_start(user))
+        .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+    let hours_since_last_claim = whole_hours(secs_since_last_claim);
+
+    // Only time inside the pool's campaign counts, in whole hours or, on pools claiming
+    // more often than hourly, whole claim intervals; claims after the end still pay out
+    // what accrued before it
+    let (campaign_from, campaign_until) = campaign_window(pool, user, now);
+    let campaign_secs = whole_steps(
+        campaign_until.saturating_sub(campaign_from),
+        accrual_step_secs(pool.min_claim_interval_secs),
+    );
+
+    // Hours beyond the pool's accrual window (if any) do not accrue; a declared outage
+    // extends the window by its length, rounded up to whole hours
+    let accrual_secs = if pool.max_accrual_hours > 0 {
+        let outage_hours = whole_hours(outage_secs(pool, user, now) + SECONDS_PER_HOUR - 1);
+        let window_hours = pool.max_accrual_hours.saturating_add(outage_hours);
+        campaign_secs.min(window_hours.saturating_mul(SECONDS_PER_HOUR as u64))
+    } else {
+        campaign_secs
+    };
+    let accrual_hours = accrual_secs / SECONDS_PER_HOUR as u64;
+
+    // Compounding pools also grow what has accrued so far, hour by hour
+    let uncapped_reward = compounded_reward(pool, campaign_from, accrual_secs)?;
+    let multiplier_bps = user_multiplier_bps(pool, user, now);
+    let rounding = pool.rounding;
+    let base_reward = accrual::cap_reward(
+        uncapped_reward,
+        pool.max_daily_reward,
+        multiplier_bps,
+        rounding.into(),
+    )
+    .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+    let (streak, streak_insurance_used) = next_streak(pool, user, now)?;
+    let streak_bonus_bps = streak_bonus_bps(pool, streak);
+    let streak_bonus = apply_bps(base_reward, streak_bonus_bps, rounding)?;
+    let (cadence_bonus_bps, cadence_penalty_bps) = cadence_modifier_bps(pool, user, now)?;
+    let cadence_bonus = apply_bps(base_reward, cadence_bonus_bps, rounding)?;
+    let cadence_penalty = apply_bps(base_reward, cadence_penalty_bps, rounding)?;
+    let auction_boost_bonus = auction_boost_bonus(user, base_reward, now, rounding)?;
+    let purchased_boost_bonus = purchased_boost_bonus(user, base_reward, now, rounding)?;
+    let nft_boost_bonus = apply_bps(base_reward, user.nft_boost_bps, rounding)?;
+    let activity_bonus = user
+        .activity_points
+        .checked_mul(pool.reward_per_activity_point)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+
+    let total_reward = base_reward
+        .checked_add(streak_bonus)
+        .and_then(|amount| amount.checked_add(cadence_bonus))
+        .and_then(|amount| amount.checked_add(auction_boost_bonus))
+        .and_then(|amount| amount.checked_add(purchased_boost_bonus))
+        .and_then(|amount| amount.checked_add(nft_boost_bonus))
+        .and_then(|amount| amount.checked_add(activity_bonus))
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?
+        .checked_sub(cadence_penalty)
+        .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+
+    // Normalized pools convert to mint units only here, carrying the sub-unit remainder
+    let (reward_amount, accrual_dust) = if pool.normalized_accrual {
+        let with_dust = total_reward
+            .checked_add(user.accrual_dust)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        denormalize(with_dust, pool.mint_decimals)?
+    } else {
+        (total_reward, 0)
+    };
+    let reward_amount = reward_amount
+        .checked_add(user.accrued_unclaimed)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    let usd_priced_reward = usd_priced_share(pool, campaign_from, accrual_secs, reward_amount)?;
+
+    Ok(RewardBreakdown {
+        hours_since_last_claim,
+        accrual_hours,
+        uncapped_reward,
+        reward_amount,
+        interval_met: u64::try_from(secs_since_last_claim).unwrap_or(0)
+            >= pool.min_claim_interval_secs,
+        multiplier_bps,
+        streak,
+        streak_bonus_bps,
+        streak_bonus,
+        streak_insurance_used,
+        cadence_bonus,
+        cadence_penalty,
+        auction_boost_bonus,
+        purchased_boost_bonus,
+        nft_boost_bonus,
+        activity_bonus,
+        accrual_dust,
+        accrued_unclaimed: user.accrued_unclaimed,
+        carryover: 0,
+        accrual_secs,
+        usd_priced_reward,
+    })
+}
+
+/// Per-hour rate in effect at `at`: the emission schedule's decayed rate if the pool has
+/// one, otherwise the flat rate
+pub fn rate_per_hour_at(pool: &RewardPool, at: i64) -> Result<u64> {
+    match pool.accrual_model_at(at).emission {
+        Some(schedule) => reward_math::EmissionSchedule::from(&schedule)
+            .rate_at(at)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow)),
+        None => Ok(flat_rate_at(pool, at).whole()),
+    }
+}
+
+/// Flat per-user rate at `at`: the share of the participant budget in effect then, if any,
+/// and otherwise the pool's scheduled rate: the pending rate once it takes effect, and the
+/// replaced rate before the last applied change.
+fn flat_rate_at(pool: &RewardPool, at: i64) -> Q64 {
+    let model = pool.accrual_model_at(at);
+    if model.budget_per_hour > 0 {
+        return Q64::from_int(budget_share(pool, &model));
+    }
+    match pool.pending_config {
+        Some(pending) if at >= pending.effective_at => pending.reward_rate(),
+        _ if at < pool.rate_changed_at => pool.previous_rate(),
+        _ => pool.reward_rate(),
+    }
+}
+
+/// Times the pool's config changes (or changed) at, earliest first: the last applied
+/// change and the pending one
+fn config_changes(pool: &RewardPool) -> [i64; 2] {
+    let mut changes = [
+        pool.rate_changed_at,
+        pool.pending_config
+            .map_or(0, |pending| pending.effective_at),
+    ];
+    changes.sort_unstable();
+    changes
+}
+
+fn span_end(from: i64, secs: u64) -> Result<i64> {
+    i64::try_from(secs)
+        .ok()
+        .and_then(|secs| from.checked_add(secs))
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))
+}
+
+/// Flat-rate reward over `accrual_secs` from `from`, split at scheduled rate changes
+fn flat_reward(pool: &RewardPool, from: i64, accrual_secs: u64) -> Result<u64> {
+    let end = span_end(from, accrual_secs)?;
+    let mut cursor = from;
+    let segments = config_changes(pool)
+        .into_iter()
+        .filter(|&change| change > from && change < end)
+        .chain([end])
+        .map(|boundary| {
+            let segment = (
+                flat_rate_at(pool, cursor).into(),
+                (boundary - cursor) as u64,
+            );
+            cursor = boundary;
+            segment
+        });
+    accrual::integrate_rate(segments, pool.rounding.into())
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))
+}
+
+/// Reward emitted over `accrual_secs` from `from`, compounded hour by hour at the growth
+/// in effect. Hours before a scheduled growth change compound at the old growth, and what
+/// accrued by then keeps growing at the new one.
+fn compounded_reward(pool: &RewardPool, from: i64, accrual_secs: u64) -> Result<u64> {
+    let end = span_end(from, accrual_secs)?;
+    let growth_at = |at: i64| pool.accrual_model_at(at).compound_growth_bps;
+    let rounding = pool.rounding.into();
+
+    // Linear parts are differences of the reward emitted since `from`, so splitting the
+    // span doesn't round the total any differently
+    let (mut reward, mut emitted, mut cursor) = (0u64, 0u64, from);
+    for boundary in config_changes(pool)
+        .into_iter()
+        .filter(|&change| {
+            change > from && change < end && growth_at(change) != growth_at(change - 1)
+        })
+        .chain([end])
+    {
+        let emitted_by_boundary = emitted_reward_over(pool, from, (boundary - from) as u64)?;
+        let hours = whole_hours(boundary - from) - whole_hours(cursor - from);
+        let growth_bps = growth_at(cursor);
+        reward = accrual::grow_reward(reward, hours, growth_bps, rounding).saturating_add(
+            accrual::compound_reward(
+                emitted_by_boundary.saturating_sub(emitted),
+                hours,
+                growth_bps,
+                rounding,
+            ),
+        );
+        emitted = emitted_by_boundary;
+        cursor = boundary;
+    }
+    Ok(reward)
+}
+
+/// Part of `reward` accrued while the pool was priced in USD. A span crossing a scheduled
+/// change of denomination splits the reward pro rata by what was emitted on each side.
+fn usd_priced_share(pool: &RewardPool, from: i64, accrual_secs: u64, reward: u64) -> Result<u64> {
+    let end = span_end(from, accrual_secs)?;
+    let priced_at = |at: i64| pool.accrual_model_at(at).usd_pricing.is_some();
+
+    let (mut usd_emitted, mut emitted, mut cursor) = (0u64, 0u64, from);
+    for boundary in config_changes(pool)
+        .into_iter()
+        .filter(|&change| {
+            change > from && change < end && priced_at(change) != priced_at(change - 1)
+        })
+        .chain([end])
+    {
+        let emitted_by_boundary = emitted_reward_over(pool, from, (boundary - from) as u64)?;
+        if priced_at(cursor) {
+            usd_emitted += emitted_by_boundary.saturating_sub(emitted);
+        }
+        emitted = emitted_by_boundary;
+        cursor = boundary;
+    }
+
+    if emitted == 0 {
+        return Ok(if priced_at(from) { reward } else { 0 });
+    }
+    mul_div(reward, usd_emitted, emitted, RoundingMode::Floor)
+}
+
+/// Per-user rate without an emission schedule: the participant budget split across the
+/// current participants (floored) when one is set, otherwise `reward_rate_per_hour`
+pub fn flat_rate_per_hour(pool: &RewardPool) -> u64 {
+    if pool.budget_per_hour == 0 {
+        return pool.reward_rate_per_hour;
+    }
+    budget_share(pool, &pool.accrual_model())
+}
+
+/// `model`'s participant budget split across the pool's current participants, floored
+fn budget_share(pool: &RewardPool, model: &AccrualModel) -> u64 {
+    (model.budget_per_hour / pool.participant_count.max(1)).max(model.floor_rate_per_hour)
+}
+
+/// Reward emitted over `accrual_hours` starting at `from`, integrating the emission
+/// schedule piecewise so accrual spanning a decay boundary gets the blended amount
+pub fn emitted_reward(pool: &RewardPool, from: i64, accrual_hours: u64) -> Result<u64> {
+    emitted_reward_over(pool, from, hours_to_secs(accrual_hours)? as u64)
+}
+
+/// [`emitted_reward`] over `accrual_secs`, for pools accruing in sub-hour steps. A span
+/// crossing a scheduled emission change uses the schedule (or flat rate) in effect on
+/// each side of it.
+pub fn emitted_reward_over(pool: &RewardPool, from: i64, accrual_secs: u64) -> Result<u64> {
+    let end = span_end(from, accrual_secs)?;
+    let emission_at = |at: i64| pool.accrual_model_at(at).emission;
+
+    let (mut reward, mut cursor) = (0u64, from);
+    for boundary in config_changes(pool)
+        .into_iter()
+        .filter(|&change| {
+            change > from && change < end && emission_at(change) != emission_at(change - 1)
+        })
+        .chain([end])
+    {
+        let emitted = match emission_at(cursor) {
+            Some(schedule) => reward_math::EmissionSchedule::from(&schedule)
+                .emitted(cursor, boundary, pool.rounding.into())
+                .ok_or(error!(ErrorCode::ArithmeticOverflow))?,
+            None => flat_reward(pool, cursor, (boundary - cursor) as u64)?,
+        };
+        reward = reward
+            .checked_add(emitted)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        cursor = boundary;
+    }
+    Ok(reward)
+}
+
+impl From<&EmissionSchedule> for reward_math::EmissionSchedule {
+    fn from(schedule: &EmissionSchedule) -> Self {
+        Self {
+            start_time: schedule.start_time,
+            start_rate: schedule.start_rate,
+            decay_interval_secs: schedule.decay_interval_secs,
+            decay_bps: schedule.decay_bps,
+        }
+    }
+}
+
+/// Pro-rata factor applied to claims on throttled vault pools, in bps.
+///
+/// Projected liabilities are every participant claiming one full interval at the current
+/// rate, compounded on compounding pools; when they exceed the liquid vault balance
+/// (excluding vesting and session payouts already locked) each claim is scaled by
+/// `liquid / liabilities`.
+pub fn runway_throttle_bps(pool: &RewardPool, vault_balance: u64, now: i64) -> Result<u16> {
+    if !pool.runway_throttle || pool.distribution_mode != DistributionMode::Vault {
+        return Ok(BPS_DENOMINATOR as u16);
+    }
+
+    let locked = pool
+        .total_vesting_locked
+        .saturating_add(pool.total_session_locked);
+    let liquid = vault_balance.saturating_sub(locked) as u128;
+    let interval_secs = pool.min_claim_interval_secs.max(SECONDS_PER_HOUR as u64);
+    let per_user = accrual::compound_reward(
+        mul_div(
+            rate_per_hour_at(pool, now)?,
+            interval_secs,
+            SECONDS_PER_HOUR as u64,
+            RoundingMode::Ceil,
+        )
+        .unwrap_or(u64::MAX),
+        interval_secs / SECONDS_PER_HOUR as u64,
+        pool.accrual_model_at(now).compound_growth_bps,
+        pool.rounding.into(),
+    );
+    let liabilities = (pool.participant_count as u128)
+        .checked_mul(per_user as u128)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    if liabilities <= liquid {
+        return Ok(BPS_DENOMINATOR as u16);
+    }
+    Ok((liquid * BPS_DENOMINATOR as u128 / liabilities) as u16)
+}
+
+/// Vault balance the pool must keep to cover what it owes: locked vesting and session
+/// payouts, unclaimed referral bonuses and carried-over partial claims, plus every
+/// participant claiming a full accrual window at the current rate (compounded on
+/// compounding pools), capped per claim by `max_daily_reward`. Saturates when the pool has
+/// no cap at all.
+pub fn reserved_liabilities(pool: &RewardPool, now: i64) -> Result<u64> {
+    let window_reward = if pool.max_accrual_hours > 0 {
+        accrual::compound_reward(
+            pool.max_accrual_hours
+                .saturating_mul(rate_per_hour_at(pool, now)?),
+            pool.max_accrual_hours,
+            pool.accrual_model_at(now).compound_growth_bps,
+            pool.rounding.into(),
+        )
+    } else {
+        u64::MAX
+    };
+    let mut per_user = window_reward.min(pool.max_daily_reward);
+    if pool.normalized_accrual && per_user != u64::MAX {
+        let (amount, remainder) = denormalize(per_user, pool.mint_decimals)?;
+        per_user = amount + u64::from(remainder > 0);
+    }
+
+    Ok(pool
+        .participant_count
+        .saturating_mul(per_user)
+        .saturating_add(pool.total_vesting_locked)
+        .saturating_add(pool.total_session_locked)
+        .saturating_add(pool.total_referral_owed)
+        .saturating_add(pool.total_carried_over))
+}
+
+/// Amount a payout of `amount` can take from a vault holding `vault_balance` (the spendable
+/// balance, for SOL vaults): all of it, or what the vault holds when the pool allows
+/// partial payouts
+pub fn vault_payout(pool: &RewardPool, vault_balance: u64, amount: u64) -> Result<u64> {
+    if vault_balance >= amount {
+        Ok(amount)
+    } else if pool.allow_partial_payout && vault_balance > 0 {
+        verbose_msg!("Vault short: paying out {} of {}", vault_balance, amount);
+        Ok(vault_balance)
+    } else {
+        msg!(
+            "Insufficient vault funds: {} available, {} required",
+            vault_balance,
+            amount
+        );
+        err!(ErrorCode::InsufficientVaultFunds)
+    }
+}
+
+/// Multiplier set on the user by the pool authority or granted by a whitelisted program,
+/// the higher of the two while both are active, or 1x if neither is. A declared outage
+/// during the accrual period pushes each expiry back by its length.
+pub fn user_multiplier_bps(pool: &RewardPool, user: &UserAccount, now: i64) -> u16 {
+    let active = |multiplier_bps: u16, expires_at: i64| {
+        let extended = expires_at.saturating_add(outage_secs(pool, user, expires_at));
+        let expired = expires_at != 0 && now >= extended;
+        (multiplier_bps != 0 && !expired).then_some(multiplier_bps)
+    };
+    match (
+        active(user.multiplier_bps, user.multiplier_expires_at),
+        active(user.granted_boost_bps, user.granted_boost_expires_at),
+    ) {
+        (Some(set), Some(granted)) => set.max(granted),
+        (Some(multiplier_bps), None) | (None, Some(multiplier_bps)) => multiplier_bps,
+        (None, None) => BPS_DENOMINATOR as u16,
+    }
+}
+
+/// Auction boost on `base_reward`, prorated by how much of the time since the last claim
+/// falls inside the user's boost window
+pub fn auction_boost_bonus(
+    user: &UserAccount,
+    base_reward: u64,
+    now: i64,
+    rounding: RoundingMode,
+) -> Result<u64> {
+    window_boost_bonus(
+        accrual_start(user),
+        user.auction_boost_bps,
+        user.auction_boost_starts_at,
+        user.auction_boost_ends_at,
+        base_reward,
+        now,
+        rounding,
+    )
+}
+
+/// Purchased boost on `base_reward`, prorated like the auction boost so time accrued
+/// before the purchase is never boosted
+pub fn purchased_boost_bonus(
+    user: &UserAccount,
+    base_reward: u64,
+    now: i64,
+    rounding: RoundingMode,
+) -> Result<u64> {
+    window_boost_bonus(
+        accrual_start(user),
+        user.purchased_boost_bps,
+        user.purchased_boost_starts_at,
+        user.purchased_boost_ends_at,
+        base_reward,
+        now,
+        rounding,
+    )
+}
+
+/// `bps` of `base_reward` for the share of the accrual period since `start` that falls
+/// inside `[starts_at, ends_at)`
+fn window_boost_bonus(
+    start: i64,
+    bps: u16,
+    starts_at: i64,
+    ends_at: i64,
+    base_reward: u64,
+    now: i64,
+    rounding: RoundingMode,
+) -> Result<u64> {
+    if bps == 0 {
+        return Ok(0);
+    }
+
+    let elapsed = now.saturating_sub(start);
+    let overlap = now.min(ends_at).saturating_sub(start.max(starts_at));
+    if elapsed <= 0 || overlap <= 0 {
+        return Ok(0);
+    }
+
+    let full_bonus = apply_bps(base_reward, bps, rounding)?;
+    div_to_int(
+        full_bonus as u128 * overlap as u128,
+        0,
+        elapsed as u64,
+        rounding,
+    )
+}
+
+/// Streak a claim at `now` would put the user on, and whether streak insurance is spent
+/// to get there.
+///
+/// The streak continues if the user claims within the pool's streak window of becoming
+/// eligible and restarts at 1 otherwise. Insured users may miss one window: a claim in the
+/// following window still continues the streak and consumes the insurance.
+pub fn next_streak(pool: &RewardPool, user: &UserAccount, now: i64) -> Result<(u32, bool)> {
+    if pool.streak_window_hours == 0 {
+        return Ok((0, false));
+    }
+    if user.last_claim_timestamp == 0 {
+        return Ok((1, false));
+    }
+
+    // A declared outage since the last claim keeps the window open for its length
+    let window_secs = hours_to_secs(pool.streak_window_hours)?;
+    let window_closes_at = eligible_at(pool, user)?
+        .checked_add(window_secs)
+        .and_then(|closes_at| closes_at.checked_add(outage_secs(pool, user, now)))
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    let continued = user.current_streak.saturating_add(1);
+
+    if now <= window_closes_at {
+        return Ok((continued, false));
+    }
+    if user.streak_insured {
+        let insured_until = i64::try_from(pool.min_claim_interval_secs)
+            .ok()
+            .and_then(|interval_secs| interval_secs.checked_add(window_secs))
+            .and_then(|cycle| window_closes_at.checked_add(cycle))
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+        if now <= insured_until {
+            return Ok((continued, true));
+        }
+    }
+    Ok((1, false))
+}
+
+/// Bonus rate for a streak: `streak_bonus_bps` per consecutive claim after the first,
+/// capped at the pool's `max_streak_bonus_bps`
+pub fn streak_bonus_bps(pool: &RewardPool, streak: u32) -> u16 {
+    let bonus =
+        u64::from(streak.saturating_sub(1)).saturating_mul(u64::from(pool.streak_bonus_bps));
+    bonus.min(u64::from(pool.max_streak_bonus_bps)) as u16
+}
+
+/// Reject a recorded timestamp that is ahead of the clock (cluster clock drift, migrated
+/// accounts) instead of letting the elapsed time go negative
+pub fn ensure_clock_after(recorded: i64, now: i64) -> Result<()> {
+    require!(now >= recorded, ErrorCode::InvalidTimestamp);
+    Ok(())
+}
+
+/// Timestamp accrual is measured from: the last claim, or registration if never claimed,
+/// pushed back by any time the user has spent paused since then
+pub fn accrual_start(user: &UserAccount) -> i64 {
+    let start = if user.last_claim_timestamp == 0 {
+        // First time claiming - calculate from registration
+        user.registration_timestamp
+    } else {
+        user.last_claim_timestamp
+    };
+    start.saturating_add(user.paused_seconds)
+}
+
+/// Monotonic clock of pool-wide accrual pause time: completed pauses plus the ongoing one.
+/// The difference between two readings is the pause time between them.
+pub fn pool_pause_clock(pool: &RewardPool, now: i64) -> i64 {
+    let ongoing = if pool.accrual_paused_at != 0 {
+        now.saturating_sub(pool.accrual_paused_at).max(0)
+    } else {
+        0
+    };
+    pool.total_accrual_paused_secs.saturating_add(ongoing)
+}
+
+/// Part of the user's accrual period up to `now` inside the pool's campaign, as
+/// `(from, until)`; `until` may precede `from` when the campaign hasn't started yet
+pub fn campaign_window(pool: &RewardPool, user: &UserAccount, now: i64) -> (i64, i64) {
+    let from = accrual_start(user).max(pool.accrual_start);
+    let until = if pool.accrual_end > 0 {
+        now.min(pool.accrual_end)
+    } else {
+        now
+    };
+    (from, until)
+}
+
+/// Seconds of the pool's declared outage that fall between the user's accrual start and
+/// `until`; expiries, streak windows and the accrual window are extended by this much
+pub fn outage_secs(pool: &RewardPool, user: &UserAccount, until: i64) -> i64 {
+    if pool.outage_end <= pool.outage_start {
+        return 0;
+    }
+    until
+        .min(pool.outage_end)
+        .saturating_sub(accrual_start(user).max(pool.outage_start))
+        .max(0)
+}
+
+/// Cadence modifier for a claim at `now`, as `(bonus_bps, penalty_bps)`.
+///
+/// Only applies once the user has a full history of `CADENCE_HISTORY_LEN` claim intervals.
+/// If the pending interval and every recorded one land within the pool's tolerance of its
+/// target cadence the bonus applies; if most of them fall short of it (burst claiming) the
+/// penalty applies instead.
+pub fn cadence_modifier_bps(pool: &RewardPool, user: &UserAccount, now: i64) -> Result<(u16, u16)> {
+    if pool.cadence_target_hours == 0
+        || user.last_claim_timestamp == 0
+        || usize::from(user.cadence_samples) < CADENCE_HISTORY_LEN
+    {
+        return Ok((0, 0));
+    }
+
+    let target = hours_to_secs(pool.cadence_target_hours)?;
+    let tolerance = hours_to_secs(pool.cadence_tolerance_hours)?;
+    let pending = now
+        .checked_sub(user.last_claim_timestamp)
+        .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+
+    let (mut on_target, mut bursts) = (0, 0);
+    for interval in std::iter::once(pending).chain(user.recent_claim_intervals) {
+        if interval.abs_diff(target) <= tolerance.unsigned_abs() {
+            on_target += 1;
+        } else if interval < target {
+            bursts += 1;
+        }
+    }
+
+    let sampled = CADENCE_HISTORY_LEN + 1;
+    if on_target == sampled {
+        Ok((pool.cadence_bonus_bps, 0))
+    } else if bursts * 2 > sampled {
+        Ok((0, pool.cadence_penalty_bps))
+    } else {
+        Ok((0, 0))
+    }
+}
+
+/// Timestamp at which the user satisfies the pool's minimum claim interval
+pub fn eligible_at(pool: &RewardPool, user: &UserAccount) -> Result<i64> {
+    i64::try_from(pool.min_claim_interval_secs)
+        .ok()
+        .and_then(|interval_secs| accrual_start(user).checked_add(interval_secs))
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))
+}
+
+fn hours_to_secs(hours: u64) -> Result<i64> {
+    i64::try_from(hours)
+        .ok()
+        .and_then(|hours| hours.checked_mul(SECONDS_PER_HOUR))
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))
+}
+
+/// Room left under the pool's daily cap: the cap less the accrual it limits, before the
+/// user multiplier and bonuses are applied
+pub fn daily_cap_remaining(pool: &RewardPool, breakdown: &RewardBreakdown) -> u64 {
+    pool.max_daily_reward.saturating_sub(breakdown.uncapped_reward)
+}
+
+/// Seconds until the user satisfies the pool's minimum claim interval (0 if already eligible)
+pub fn seconds_until_claimable(pool: &RewardPool, user: &UserAccount, now: i64) -> Result<i64> {
+    Ok(eligible_at(pool, user)?.saturating_sub(now).max(0))
+}
+
+/// Whether a claim by `user` at `now` is their first of the UTC day
+pub fn is_first_claim_of_day(user: &UserAccount, now: i64) -> bool {
+    user.total_claims == 0 || day_index(user.last_claim_timestamp) != day_index(now)
+}
+
+/// Claims `user` has made during the UTC day containing `now`
+pub fn claims_today(user: &UserAccount, now: i64) -> u16 {
+    if user.claims_today_day == day_index(now) {
+        user.claims_today
+    } else {
+        0
+    }
+}
+
+/// `amount * bps / 10_000`, rounded as `rounding` says
+pub fn apply_bps(amount: u64, bps: u16, rounding: RoundingMode) -> Result<u64> {
+    reward_math::apply_bps(amount, bps, rounding.into())
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))
+}
+
+/// Scale a claim by a runway throttle factor. Always rounds down, whatever the pool's
+/// rounding mode, so throttled claims never outrun the vault.
+pub fn apply_throttle(amount: u64, throttle_bps: u16) -> Result<u64> {
+    apply_bps(amount, throttle_bps, RoundingMode::Floor)
+}
+
+/// Convert a `NORMALIZED_DECIMALS` amount to base units of a mint with `mint_decimals`,
+/// as `(amount, remainder)` with the remainder still in normalized units
+pub fn denormalize(amount: u64, mint_decimals: u8) -> Result<(u64, u64)> {
+    let shift = NORMALIZED_DECIMALS
+        .checked_sub(mint_decimals)
+        .ok_or(error!(ErrorCode::UnsupportedMintDecimals))?;
+    let scale = 10u64.pow(u32::from(shift));
+    Ok((amount / scale, amount % scale))
+}
+
+/// Split a reward into `(payable, withheld)` under a region policy action
+pub fn apply_region_policy(
+    reward_amount: u64,
+    action: &RegionAction,
+    rounding: RoundingMode,
+) -> Result<(u64, u64)> {
+    match *action {
+        RegionAction::Blocked => err!(ErrorCode::RegionBlocked),
+        RegionAction::ReducedRate { bps } => Ok((apply_bps(reward_amount, bps, rounding)?, 0)),
+        RegionAction::Withholding { bps } => {
+            let withheld = apply_bps(reward_amount, bps, rounding)?;
+            let payable = reward_amount
+                .checked_sub(withheld)
+                .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+            Ok((payable, withheld))
+        }
+    }
+}
+
+/// Amount of a vesting position unlocked at `now`: nothing before the cliff, then linear
+/// from `start_timestamp` over `duration_secs` (a zero duration unlocks everything at the cliff)
+pub fn vested_amount(
+    total_amount: u64,
+    start_timestamp: i64,
+    cliff_secs: i64,
+    duration_secs: i64,
+    now: i64,
+) -> Result<u64> {
+    ensure_clock_after(start_timestamp, now)?;
+    let elapsed = now
+        .checked_sub(start_timestamp)
+        .ok_or(error!(ErrorCode::ArithmeticUnderflow))?;
+    if elapsed < cliff_secs {
+        return Ok(0);
+    }
+    if duration_secs <= 0 || elapsed >= duration_secs {
+        return Ok(total_amount);
+    }
+
+    let vested = (total_amount as u128)
+        .checked_mul(elapsed as u128)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?
+        / duration_secs as u128;
+    u64::try_from(vested).map_err(|_| error!(ErrorCode::ArithmeticOverflow))
+}
+
+/// Whole hours a staked NFT has accrued since `last_accrual` and the reward they earn
+pub fn nft_stake_reward(rate_per_hour: u64, last_accrual: i64, now: i64) -> Result<(u64, u64)> {
+    ensure_clock_after(last_accrual, now)?;
+    let hours = whole_hours(
+        now.checked_sub(last_accrual)
+            .ok_or(error!(ErrorCode::ArithmeticUnderflow))?,
+    );
+    let reward = hours
+        .checked_mul(rate_per_hour)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    Ok((hours, reward))
+}
+
+/// Staking accumulator advanced from `updated_at` to `now`: every staked token earns an equal
+/// share of `rate_per_hour` while anything is staked
+pub fn reward_per_token(
+    stored: u128,
+    total_staked: u64,
+    rate_per_hour: u64,
+    updated_at: i64,
+    now: i64,
+) -> Result<u128> {
+    if total_staked == 0 || now <= updated_at {
+        return Ok(stored);
+    }
+
+    let elapsed = (now - updated_at) as u128;
+    let increment = (rate_per_hour as u128)
+        .checked_mul(elapsed)
+        .and_then(|emitted| emitted.checked_mul(REWARD_PER_TOKEN_SCALE))
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?
+        / (SECONDS_PER_HOUR as u128 * total_staked as u128);
+    stored
+        .checked_add(increment)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))
+}
+
+/// Rewards owed to a stake of `amount` given the accumulator now and at its last settlement
+pub fn stake_earned(
+    amount: u64,
+    reward_per_token: u128,
+    reward_per_token_paid: u128,
+    pending_rewards: u64,
+) -> Result<u64> {
+    let accrued = reward_per_token
+        .checked_sub(reward_per_token_paid)
+        .ok_or(error!(ErrorCode::ArithmeticUnderflow))?
+        .checked_mul(amount as u128)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?
+        / REWARD_PER_TOKEN_SCALE;
+    u64::try_from(accrued)
+        .ok()
+        .and_then(|accrued| accrued.checked_add(pending_rewards))
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PendingConfig, UsdPricing};
+
+    const HOUR: i64 = SECONDS_PER_HOUR;
+    const REGISTERED_AT: i64 = 1_700_000_000;
+
+    fn pool(rate: u64, min_interval_hours: u64, max_daily: u64, max_accrual: u64) -> RewardPool {
+        RewardPool {
+            reward_rate_per_hour: rate,
+            min_claim_interval_secs: min_interval_hours * HOUR as u64,
+            max_daily_reward: max_daily,
+            max_accrual_hours: max_accrual,
+            ..Default::default()
+        }
+    }
+
+    fn user(last_claim: i64) -> UserAccount {
+        UserAccount {
+            registration_timestamp: REGISTERED_AT,
+            last_claim_timestamp: last_claim,
+            is_active: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn accrues_from_registration_when_never_claimed() {
+        let breakdown =
+            compute_reward(&pool(10, 1, 1_000, 0), &user(0), REGISTERED_AT + 5 * HOUR).unwrap();
+
+        assert_eq!(breakdown.hours_since_last_claim, 5);
+        assert_eq!(breakdown.accrual_hours, 5);
+        assert_eq!(breakdown.reward_amount, 50);
+        assert!(breakdown.interval_met);
+    }
+
+    #[test]
+    fn accrues_from_last_claim_after_first_claim() {
+        let last_claim = REGISTERED_AT + 100 * HOUR;
+        let breakdown = compute_reward(
+            &pool(10, 1, 1_000, 0),
+            &user(last_claim),
+            last_claim + 3 * HOUR,
+        )
+        .unwrap();
+
+        assert_eq!(breakdown.hours_since_last_claim, 3);
+        assert_eq!(breakdown.reward_amount, 30);
+    }
+
+    #[test]
+    fn paused_time_does_not_accrue() {
+        let mut paused = user(0);
+        paused.paused_at = REGISTERED_AT + 4 * HOUR;
+        let breakdown =
+            compute_reward(&pool(10, 1, 1_000, 0), &paused, REGISTERED_AT + 9 * HOUR).unwrap();
+        assert_eq!(breakdown.hours_since_last_claim, 4);
+
+        let mut resumed = user(0);
+        resumed.paused_seconds = 5 * HOUR;
+        let breakdown =
+            compute_reward(&pool(10, 1, 1_000, 0), &resumed, REGISTERED_AT + 12 * HOUR).unwrap();
+        assert_eq!(breakdown.hours_since_last_claim, 7);
+        assert_eq!(breakdown.reward_amount, 70);
+    }
+
+    #[test]
+    fn normalized_pools_carry_sub_unit_dust() {
+        // Half a token per hour on a 0-decimal mint
+        let pool = RewardPool {
+            normalized_accrual: true,
+            mint_decimals: 0,
+            ..pool(500_000_000, 1, 100_000_000_000, 0)
+        };
+
+        let breakdown = compute_reward(&pool, &user(0), REGISTERED_AT + 3 * HOUR).unwrap();
+        assert_eq!(breakdown.reward_amount, 1);
+        assert_eq!(breakdown.accrual_dust, 500_000_000);
+
+        let carried = UserAccount {
+            accrual_dust: breakdown.accrual_dust,
+            ..user(REGISTERED_AT + 3 * HOUR)
+        };
+        let breakdown = compute_reward(&pool, &carried, REGISTERED_AT + 6 * HOUR).unwrap();
+        assert_eq!(breakdown.reward_amount, 2);
+        assert_eq!(breakdown.accrual_dust, 0);
+    }
+
+    fn halving_pool(start_time: i64) -> RewardPool {
+        RewardPool {
+            emission: Some(EmissionSchedule {
+                start_time,
+                start_rate: 100,
+                decay_interval_secs: 24 * HOUR,
+                decay_bps: 5_000,
+            }),
+            ..pool(0, 1, u64::MAX, 0)
+        }
+    }
+
+    #[test]
+    fn emission_schedule_halves_the_rate_each_interval() {
+        let pool = halving_pool(REGISTERED_AT);
+        assert_eq!(rate_per_hour_at(&pool, REGISTERED_AT - HOUR).unwrap(), 100);
+        assert_eq!(
+            rate_per_hour_at(&pool, REGISTERED_AT + 23 * HOUR).unwrap(),
+            100
+        );
+        assert_eq!(
+            rate_per_hour_at(&pool, REGISTERED_AT + 24 * HOUR).unwrap(),
+            50
+        );
+        assert_eq!(
+            rate_per_hour_at(&pool, REGISTERED_AT + 72 * HOUR).unwrap(),
+            12
+        );
+    }
+
+    #[test]
+    fn accrual_spanning_a_halving_is_blended() {
+        // 4 hours at 100 before the halving, then 6 hours at 50
+        let pool = halving_pool(REGISTERED_AT - 20 * HOUR);
+        let breakdown = compute_reward(&pool, &user(0), REGISTERED_AT + 10 * HOUR).unwrap();
+        assert_eq!(breakdown.uncapped_reward, 4 * 100 + 6 * 50);
+
+        // Across two boundaries: 4h at 100, 24h at 50, 2h at 25
+        let breakdown = compute_reward(&pool, &user(0), REGISTERED_AT + 30 * HOUR).unwrap();
+        assert_eq!(breakdown.uncapped_reward, 400 + 1_200 + 50);
+    }
+
+    #[test]
+    fn participant_budget_is_split_down_to_the_floor() {
+        let budget_pool = |participants| RewardPool {
+            budget_per_hour: 1_000,
+            floor_rate_per_hour: 40,
+            participant_count: participants,
+            ..pool(7, 1, u64::MAX, 0)
+        };
+        assert_eq!(flat_rate_per_hour(&budget_pool(0)), 1_000);
+        assert_eq!(flat_rate_per_hour(&budget_pool(4)), 250);
+        assert_eq!(flat_rate_per_hour(&budget_pool(100)), 40);
+
+        let breakdown =
+            compute_reward(&budget_pool(10), &user(0), REGISTERED_AT + 3 * HOUR).unwrap();
+        assert_eq!(breakdown.reward_amount, 300);
+        assert_eq!(flat_rate_per_hour(&pool(7, 1, 100, 0)), 7);
+    }
+
+    #[test]
+    fn runway_throttle_scales_claims_to_the_liquid_vault() {
+        // 10 participants at 10/hour over a 5 hour interval owe 500
+        let throttled = RewardPool {
+            runway_throttle: true,
+            participant_count: 10,
+            total_vesting_locked: 100,
+            ..pool(10, 5, u64::MAX, 0)
+        };
+        assert_eq!(
+            runway_throttle_bps(&throttled, 600, REGISTERED_AT).unwrap(),
+            10_000
+        );
+        assert_eq!(
+            runway_throttle_bps(&throttled, 350, REGISTERED_AT).unwrap(),
+            5_000
+        );
+        assert_eq!(
+            runway_throttle_bps(&throttled, 50, REGISTERED_AT).unwrap(),
+            0
+        );
+
+        let disabled = RewardPool {
+            runway_throttle: false,
+            ..throttled
+        };
+        assert_eq!(
+            runway_throttle_bps(&disabled, 0, REGISTERED_AT).unwrap(),
+            10_000
+        );
+    }
+
+    #[test]
+    fn reserve_covers_every_participant_claiming_a_full_window() {
+        // 10 participants at 10/hour over a 24 hour window, 100 already vesting
+        let capped = RewardPool {
+            participant_count: 10,
+            total_vesting_locked: 100,
+            ..pool(10, 1, u64::MAX, 24)
+        };
+        assert_eq!(reserved_liabilities(&capped, REGISTERED_AT).unwrap(), 2_500);
+
+        // Referral bonuses and carried-over rewards are owed on top of the window
+        let owing = RewardPool {
+            total_referral_owed: 30,
+            total_carried_over: 70,
+            ..capped.clone()
+        };
+        assert_eq!(reserved_liabilities(&owing, REGISTERED_AT).unwrap(), 2_600);
+
+        let daily_capped = RewardPool {
+            max_daily_reward: 50,
+            ..capped.clone()
+        };
+        assert_eq!(
+            reserved_liabilities(&daily_capped, REGISTERED_AT).unwrap(),
+            600
+        );
+
+        let uncapped = RewardPool {
+            max_accrual_hours: 0,
+            ..capped
+        };
+        assert_eq!(
+            reserved_liabilities(&uncapped, REGISTERED_AT).unwrap(),
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn campaign_window_bounds_accrual() {
+        let campaign = RewardPool {
+            accrual_start: REGISTERED_AT + 2 * HOUR,
+            accrual_end: REGISTERED_AT + 10 * HOUR,
+            ..pool(10, 1, 1_000, 0)
+        };
+
+        let breakdown = compute_reward(&campaign, &user(0), REGISTERED_AT + HOUR).unwrap();
+        assert_eq!(breakdown.reward_amount, 0);
+
+        let breakdown = compute_reward(&campaign, &user(0), REGISTERED_AT + 5 * HOUR).unwrap();
+        assert_eq!(breakdown.reward_amount, 30);
+
+        // After the end, claims still pay what accrued inside the window
+        let breakdown = compute_reward(&campaign, &user(0), REGISTERED_AT + 30 * HOUR).unwrap();
+        assert_eq!(breakdown.reward_amount, 80);
+        assert!(breakdown.interval_met);
+
+        let claimed_after_end = user(REGISTERED_AT + 30 * HOUR);
+        let breakdown =
+            compute_reward(&campaign, &claimed_after_end, REGISTERED_AT + 40 * HOUR).unwrap();
+        assert_eq!(breakdown.reward_amount, 0);
+    }
+
+    #[test]
+    fn pool_accrual_pauses_are_excluded() {
+        let mut paused = pool(10, 1, 1_000, 0);
+        paused
+            .set_accrual_paused(true, REGISTERED_AT + 2 * HOUR)
+            .unwrap();
+
+        let breakdown = compute_reward(&paused, &user(0), REGISTERED_AT + 6 * HOUR).unwrap();
+        assert_eq!(breakdown.reward_amount, 20);
+
+        paused
+            .set_accrual_paused(false, REGISTERED_AT + 5 * HOUR)
+            .unwrap();
+        let breakdown = compute_reward(&paused, &user(0), REGISTERED_AT + 6 * HOUR).unwrap();
+        assert_eq!(breakdown.reward_amount, 30);
+
+        // Pauses before the user's accrual period began are not excluded twice
+        let checkpointed = UserAccount {
+            pool_pause_checkpoint: pool_pause_clock(&paused, REGISTERED_AT + 6 * HOUR),
+            ..user(REGISTERED_AT + 6 * HOUR)
+        };
+        let breakdown = compute_reward(&paused, &checkpointed, REGISTERED_AT + 8 * HOUR).unwrap();
+        assert_eq!(breakdown.reward_amount, 20);
+    }
+
+    #[test]
+    fn partial_claim_carryover_is_added_on_top_of_the_cap() {
+        let carried = UserAccount {
+            accrued_unclaimed: 250,
+            ..user(REGISTERED_AT)
+        };
+        let breakdown =
+            compute_reward(&pool(10, 1, 100, 0), &carried, REGISTERED_AT + 24 * HOUR).unwrap();
+        assert_eq!(breakdown.reward_amount, 350);
+        assert_eq!(breakdown.accrued_unclaimed, 250);
+        assert_eq!(breakdown.carryover, 0);
+    }
+
+    #[test]
+    fn denormalize_rejects_mints_finer_than_the_normalized_unit() {
+        assert_eq!(denormalize(1_234_567_890, 6).unwrap(), (1_234_567, 890));
+        assert_eq!(denormalize(42, 9).unwrap(), (42, 0));
+        assert!(denormalize(1, 12).is_err());
+    }
+
+    #[test]
+    fn partial_hours_are_truncated() {
+        let breakdown = compute_reward(
+            &pool(10, 0, 1_000, 0),
+            &user(0),
+            REGISTERED_AT + 2 * HOUR - 1,
+        )
+        .unwrap();
+
+        assert_eq!(breakdown.hours_since_last_claim, 1);
+        assert_eq!(breakdown.reward_amount, 10);
+    }
+
+    #[test]
+    fn interval_not_met_is_reported() {
+        let breakdown =
+            compute_reward(&pool(10, 24, 1_000, 0), &user(0), REGISTERED_AT + 23 * HOUR).unwrap();
+
+        assert!(!breakdown.interval_met);
+        assert_eq!(breakdown.reward_amount, 230);
+
+        let breakdown =
+            compute_reward(&pool(10, 24, 1_000, 0), &user(0), REGISTERED_AT + 24 * HOUR).unwrap();
+        assert!(breakdown.interval_met);
+    }
+
+    #[test]
+    fn sub_hour_intervals_accrue_whole_intervals() {
+        let quarter_hourly = RewardPool {
+            min_claim_interval_secs: 15 * 60,
+            ..pool(60, 0, 1_000, 0)
+        };
+
+        let breakdown = compute_reward(&quarter_hourly, &user(0), REGISTERED_AT + 14 * 60).unwrap();
+        assert!(!breakdown.interval_met);
+        assert_eq!(breakdown.reward_amount, 0);
+
+        let breakdown = compute_reward(&quarter_hourly, &user(0), REGISTERED_AT + 40 * 60).unwrap();
+        assert!(breakdown.interval_met);
+        assert_eq!(breakdown.accrual_secs, 30 * 60);
+        assert_eq!(breakdown.accrual_hours, 0);
+        assert_eq!(breakdown.reward_amount, 30);
+        assert_eq!(
+            eligible_at(&quarter_hourly, &user(0)).unwrap(),
+            REGISTERED_AT + 15 * 60
+        );
+    }
+
+    #[test]
+    fn countdown_reaches_zero_at_interval() {
+        let pool = pool(10, 24, 1_000, 0);
+
+        assert_eq!(
+            seconds_until_claimable(&pool, &user(0), REGISTERED_AT + 23 * HOUR).unwrap(),
+            HOUR
+        );
+        assert_eq!(
+            seconds_until_claimable(&pool, &user(0), REGISTERED_AT + 30 * HOUR).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn daily_cap_limits_reward() {
+        let breakdown =
+            compute_reward(&pool(100, 1, 500, 0), &user(0), REGISTERED_AT + 10 * HOUR).unwrap();
+
+        assert_eq!(breakdown.uncapped_reward, 1_000);
+        assert_eq!(breakdown.reward_amount, 500);
+    }
+
+    #[test]
+    fn daily_cap_remaining_ignores_multiplier_and_bonuses() {
+        let pool = pool(100, 1, 500, 0);
+        let boosted = UserAccount {
+            multiplier_bps: 20_000,
+            ..user(0)
+        };
+        let breakdown = compute_reward(&pool, &boosted, REGISTERED_AT + 3 * HOUR).unwrap();
+
+        assert_eq!(breakdown.reward_amount, 600);
+        assert_eq!(daily_cap_remaining(&pool, &breakdown), 200);
+
+        let breakdown = compute_reward(&pool, &boosted, REGISTERED_AT + 10 * HOUR).unwrap();
+        assert_eq!(daily_cap_remaining(&pool, &breakdown), 0);
+    }
+
+    #[test]
+    fn accrual_window_stops_accrual() {
+        let breakdown = compute_reward(
+            &pool(10, 1, u64::MAX, 48),
+            &user(0),
+            REGISTERED_AT + 365 * 24 * HOUR,
+        )
+        .unwrap();
+
+        assert_eq!(breakdown.hours_since_last_claim, 365 * 24);
+        assert_eq!(breakdown.accrual_hours, 48);
+        assert_eq!(breakdown.reward_amount, 480);
+    }
+
+    #[test]
+    fn zero_accrual_window_is_unbounded() {
+        let breakdown = compute_reward(
+            &pool(1, 1, u64::MAX, 0),
+            &user(0),
+            REGISTERED_AT + 1_000 * HOUR,
+        )
+        .unwrap();
+
+        assert_eq!(breakdown.accrual_hours, 1_000);
+    }
+
+    #[test]
+    fn no_time_elapsed_yields_nothing() {
+        let breakdown = compute_reward(&pool(10, 0, 1_000, 0), &user(0), REGISTERED_AT).unwrap();
+
+        assert_eq!(
+            breakdown,
+            RewardBreakdown {
+                interval_met: true,
+                multiplier_bps: 10_000,
+                ..Default::default()
+            }
+        );
+    }
+
+    fn streak_pool(window_hours: u64, bonus_bps: u16, max_bonus_bps: u16) -> RewardPool {
+        RewardPool {
+            streak_window_hours: window_hours,
+            streak_bonus_bps: bonus_bps,
+            max_streak_bonus_bps: max_bonus_bps,
+            ..pool(10, 24, 1_000, 0)
+        }
+    }
+
+    #[test]
+    fn streaks_are_disabled_without_a_window() {
+        let mut claimed = user(REGISTERED_AT);
+        claimed.current_streak = 5;
+
+        let breakdown =
+            compute_reward(&pool(10, 24, 1_000, 0), &claimed, REGISTERED_AT + 24 * HOUR).unwrap();
+
+        assert_eq!(breakdown.streak, 0);
+        assert_eq!(breakdown.streak_bonus, 0);
+        assert_eq!(breakdown.reward_amount, 240);
+    }
+
+    #[test]
+    fn first_claim_starts_a_streak_without_bonus() {
+        let breakdown = compute_reward(
+            &streak_pool(12, 500, 2_000),
+            &user(0),
+            REGISTERED_AT + 24 * HOUR,
+        )
+        .unwrap();
+
+        assert_eq!(breakdown.streak, 1);
+        assert_eq!(breakdown.streak_bonus_bps, 0);
+        assert_eq!(breakdown.reward_amount, 240);
+    }
+
+    #[test]
+    fn claim_within_window_extends_streak() {
+        let mut claimed = user(REGISTERED_AT);
+        claimed.current_streak = 2;
+
+        // Eligible at +24h; the 12h streak window closes at +36h
+        let breakdown = compute_reward(
+            &streak_pool(12, 500, 2_000),
+            &claimed,
+            REGISTERED_AT + 36 * HOUR,
+        )
+        .unwrap();
+
+        assert_eq!(breakdown.streak, 3);
+        assert_eq!(breakdown.streak_bonus_bps, 1_000);
+        assert_eq!(breakdown.streak_bonus, 36);
+        assert_eq!(breakdown.reward_amount, 396);
+    }
+
+    #[test]
+    fn claim_after_window_resets_streak() {
+        let mut claimed = user(REGISTERED_AT);
+        claimed.current_streak = 7;
+
+        let breakdown = compute_reward(
+            &streak_pool(12, 500, 2_000),
+            &claimed,
+            REGISTERED_AT + 36 * HOUR + 1,
+        )
+        .unwrap();
+
+        assert_eq!(breakdown.streak, 1);
+        assert_eq!(breakdown.streak_bonus, 0);
+    }
+
+    #[test]
+    fn outage_keeps_the_streak_window_open() {
+        let mut claimed = user(REGISTERED_AT);
+        claimed.current_streak = 7;
+        let pool = RewardPool {
+            outage_start: REGISTERED_AT + 30 * HOUR,
+            outage_end: REGISTERED_AT + 34 * HOUR,
+            ..streak_pool(12, 500, 2_000)
+        };
+
+        // The 12h window closing at +36h is extended by the 4h outage
+        let breakdown = compute_reward(&pool, &claimed, REGISTERED_AT + 40 * HOUR).unwrap();
+        assert_eq!(breakdown.streak, 8);
+
+        let breakdown = compute_reward(&pool, &claimed, REGISTERED_AT + 40 * HOUR + 1).unwrap();
+        assert_eq!(breakdown.streak, 1);
+    }
+
+    #[test]
+    fn outage_extends_the_accrual_window() {
+        let pool = RewardPool {
+            outage_start: REGISTERED_AT + 2 * HOUR,
+            outage_end: REGISTERED_AT + 4 * HOUR + 1,
+            ..pool(10, 1, 1_000, 5)
+        };
+
+        // A 2h+1s outage rounds up to 3 extra accrual hours
+        let breakdown = compute_reward(&pool, &user(0), REGISTERED_AT + 20 * HOUR).unwrap();
+        assert_eq!(breakdown.accrual_hours, 8);
+    }
+
+    #[test]
+    fn insurance_bridges_one_missed_window() {
+        let mut claimed = user(REGISTERED_AT);
+        claimed.current_streak = 4;
+        claimed.streak_insured = true;
+        let pool = streak_pool(12, 500, 2_000);
+
+        // First window closes at +36h; the next one at +36h + 24h + 12h
+        let breakdown = compute_reward(&pool, &claimed, REGISTERED_AT + 72 * HOUR).unwrap();
+        assert_eq!(breakdown.streak, 5);
+        assert!(breakdown.streak_insurance_used);
+
+        let breakdown = compute_reward(&pool, &claimed, REGISTERED_AT + 72 * HOUR + 1).unwrap();
+        assert_eq!(breakdown.streak, 1);
+        assert!(!breakdown.streak_insurance_used);
+    }
+
+    #[test]
+    fn insurance_is_kept_when_window_is_met() {
+        let mut claimed = user(REGISTERED_AT);
+        claimed.current_streak = 4;
+        claimed.streak_insured = true;
+
+        let breakdown = compute_reward(
+            &streak_pool(12, 500, 2_000),
+            &claimed,
+            REGISTERED_AT + 30 * HOUR,
+        )
+        .unwrap();
+
+        assert_eq!(breakdown.streak, 5);
+        assert!(!breakdown.streak_insurance_used);
+    }
+
+    #[test]
+    fn streak_bonus_is_capped_and_applied_after_daily_cap() {
+        let mut claimed = user(REGISTERED_AT);
+        claimed.current_streak = 100;
+
+        let breakdown = compute_reward(
+            &RewardPool {
+                max_daily_reward: 200,
+                ..streak_pool(12, 500, 2_000)
+            },
+            &claimed,
+            REGISTERED_AT + 24 * HOUR,
+        )
+        .unwrap();
+
+        assert_eq!(breakdown.streak, 101);
+        assert_eq!(breakdown.streak_bonus_bps, 2_000);
+        assert_eq!(breakdown.streak_bonus, 40);
+        assert_eq!(breakdown.reward_amount, 240);
+    }
+
+    #[test]
+    fn user_multiplier_applies_until_expiry() {
+        let promoted = UserAccount {
+            multiplier_bps: 20_000,
+            multiplier_expires_at: REGISTERED_AT + 48 * HOUR,
+            ..user(0)
+        };
+        let pool = pool(10, 1, 100, 0);
+
+        let breakdown = compute_reward(&pool, &promoted, REGISTERED_AT + 24 * HOUR).unwrap();
+        assert_eq!(breakdown.multiplier_bps, 20_000);
+        assert_eq!(breakdown.reward_amount, 200);
+
+        let breakdown = compute_reward(&pool, &promoted, REGISTERED_AT + 48 * HOUR).unwrap();
+        assert_eq!(breakdown.multiplier_bps, 10_000);
+        assert_eq!(breakdown.reward_amount, 100);
+    }
+
+    #[test]
+    fn granted_boost_and_authority_multiplier_take_the_higher() {
+        let boosted = UserAccount {
+            multiplier_bps: 15_000,
+            granted_boost_bps: 20_000,
+            granted_boost_expires_at: REGISTERED_AT + 48 * HOUR,
+            ..user(0)
+        };
+        let pool = pool(10, 1, 100, 0);
+
+        let breakdown = compute_reward(&pool, &boosted, REGISTERED_AT + 24 * HOUR).unwrap();
+        assert_eq!(breakdown.multiplier_bps, 20_000);
+
+        // The authority's multiplier is still in place once the grant lapses
+        let breakdown = compute_reward(&pool, &boosted, REGISTERED_AT + 48 * HOUR).unwrap();
+        assert_eq!(breakdown.multiplier_bps, 15_000);
+    }
+
+    #[test]
+    fn outage_suspends_multiplier_expiry() {
+        let promoted = UserAccount {
+            multiplier_bps: 20_000,
+            multiplier_expires_at: REGISTERED_AT + 48 * HOUR,
+            ..user(0)
+        };
+        let pool = RewardPool {
+            outage_start: REGISTERED_AT + 10 * HOUR,
+            outage_end: REGISTERED_AT + 16 * HOUR,
+            ..pool(10, 1, 100, 0)
+        };
+
+        let breakdown = compute_reward(&pool, &promoted, REGISTERED_AT + 50 * HOUR).unwrap();
+        assert_eq!(breakdown.multiplier_bps, 20_000);
+
+        let breakdown = compute_reward(&pool, &promoted, REGISTERED_AT + 54 * HOUR).unwrap();
+        assert_eq!(breakdown.multiplier_bps, 10_000);
+    }
+
+    #[test]
+    fn auction_boost_is_prorated_over_the_boost_window() {
+        let boosted = UserAccount {
+            auction_boost_bps: 5_000,
+            auction_boost_starts_at: REGISTERED_AT + 6 * HOUR,
+            auction_boost_ends_at: REGISTERED_AT + 12 * HOUR,
+            ..user(0)
+        };
+
+        // Half of the 12 hours accrued fall inside the window: 50% boost on half of 120
+        let breakdown =
+            compute_reward(&pool(10, 1, 1_000, 0), &boosted, REGISTERED_AT + 12 * HOUR).unwrap();
+        assert_eq!(breakdown.auction_boost_bonus, 30);
+        assert_eq!(breakdown.reward_amount, 150);
+
+        let breakdown =
+            compute_reward(&pool(10, 1, 1_000, 0), &boosted, REGISTERED_AT + 6 * HOUR).unwrap();
+        assert_eq!(breakdown.auction_boost_bonus, 0);
+    }
+
+    #[test]
+    fn purchased_boost_never_covers_time_before_the_purchase() {
+        let boosted = UserAccount {
+            purchased_boost_bps: 10_000,
+            purchased_boost_starts_at: REGISTERED_AT + 9 * HOUR,
+            purchased_boost_ends_at: REGISTERED_AT + 33 * HOUR,
+            ..user(0)
+        };
+
+        // Bought 3 hours before claiming: only a quarter of the 12 hours is doubled
+        let breakdown =
+            compute_reward(&pool(10, 1, 1_000, 0), &boosted, REGISTERED_AT + 12 * HOUR).unwrap();
+        assert_eq!(breakdown.purchased_boost_bonus, 30);
+        assert_eq!(breakdown.auction_boost_bonus, 0);
+        assert_eq!(breakdown.reward_amount, 150);
+    }
+
+    fn cadence_pool() -> RewardPool {
+        RewardPool {
+            cadence_target_hours: 24,
+            cadence_tolerance_hours: 2,
+            cadence_bonus_bps: 500,
+            cadence_penalty_bps: 2_000,
+            ..pool(10, 1, 1_000, 0)
+        }
+    }
+
+    fn user_with_intervals(
+        last_claim: i64,
+        intervals_hours: [i64; CADENCE_HISTORY_LEN],
+    ) -> UserAccount {
+        UserAccount {
+            recent_claim_intervals: intervals_hours.map(|hours| hours * HOUR),
+            cadence_samples: CADENCE_HISTORY_LEN as u8,
+            ..user(last_claim)
+        }
+    }
+
+    #[test]
+    fn steady_cadence_earns_bonus() {
+        let claimed = user_with_intervals(REGISTERED_AT, [23, 25, 24, 24]);
+
+        let breakdown =
+            compute_reward(&cadence_pool(), &claimed, REGISTERED_AT + 24 * HOUR).unwrap();
+
+        assert_eq!(breakdown.cadence_bonus, 12);
+        assert_eq!(breakdown.cadence_penalty, 0);
+        assert_eq!(breakdown.reward_amount, 252);
+    }
+
+    #[test]
+    fn burst_claiming_is_penalized() {
+        let claimed = user_with_intervals(REGISTERED_AT, [1, 2, 24, 1]);
+
+        let breakdown =
+            compute_reward(&cadence_pool(), &claimed, REGISTERED_AT + 10 * HOUR).unwrap();
+
+        assert_eq!(breakdown.cadence_bonus, 0);
+        assert_eq!(breakdown.cadence_penalty, 20);
+        assert_eq!(breakdown.reward_amount, 80);
+    }
+
+    #[test]
+    fn cadence_needs_full_history() {
+        let mut claimed = user_with_intervals(REGISTERED_AT, [24, 24, 24, 24]);
+        claimed.cadence_samples -= 1;
+
+        let breakdown =
+            compute_reward(&cadence_pool(), &claimed, REGISTERED_AT + 24 * HOUR).unwrap();
+
+        assert_eq!(breakdown.cadence_bonus, 0);
+        assert_eq!(breakdown.reward_amount, 240);
+    }
+
+    #[test]
+    fn region_policy_reduces_or_withholds() {
+        assert!(apply_region_policy(1_000, &RegionAction::Blocked, RoundingMode::Floor).is_err());
+        assert_eq!(
+            apply_region_policy(
+                1_000,
+                &RegionAction::ReducedRate { bps: 2_500 },
+                RoundingMode::Floor
+            )
+            .unwrap(),
+            (250, 0)
+        );
+        assert_eq!(
+            apply_region_policy(
+                1_000,
+                &RegionAction::Withholding { bps: 3_000 },
+                RoundingMode::Floor
+            )
+            .unwrap(),
+            (700, 300)
+        );
+    }
+
+    #[test]
+    fn vesting_respects_cliff_and_linear_unlock() {
+        let start = REGISTERED_AT;
+
+        assert_eq!(
+            vested_amount(1_000, start, HOUR, 10 * HOUR, start + HOUR - 1).unwrap(),
+            0
+        );
+        assert_eq!(
+            vested_amount(1_000, start, HOUR, 10 * HOUR, start + HOUR).unwrap(),
+            100
+        );
+        assert_eq!(
+            vested_amount(1_000, start, HOUR, 10 * HOUR, start + 5 * HOUR).unwrap(),
+            500
+        );
+        assert_eq!(
+            vested_amount(1_000, start, HOUR, 10 * HOUR, start + 11 * HOUR).unwrap(),
+            1_000
+        );
+    }
+
+    #[test]
+    fn zero_duration_vests_fully_at_cliff() {
+        let start = REGISTERED_AT;
+
+        assert_eq!(
+            vested_amount(1_000, start, HOUR, 0, start + HOUR - 1).unwrap(),
+            0
+        );
+        assert_eq!(
+            vested_amount(1_000, start, HOUR, 0, start + HOUR).unwrap(),
+            1_000
+        );
+    }
+
+    #[test]
+    fn stake_rewards_split_pro_rata() {
+        // 100 tokens/hour over 1_000 staked: 0.1 per staked token per hour
+        let rpt = reward_per_token(0, 1_000, 100, REGISTERED_AT, REGISTERED_AT + 2 * HOUR).unwrap();
+
+        assert_eq!(stake_earned(750, rpt, 0, 0).unwrap(), 150);
+        assert_eq!(stake_earned(250, rpt, 0, 0).unwrap(), 50);
+    }
+
+    #[test]
+    fn stake_rewards_only_count_since_last_settlement() {
+        let first = reward_per_token(0, 500, 100, REGISTERED_AT, REGISTERED_AT + HOUR).unwrap();
+        let second = reward_per_token(
+            first,
+            1_000,
+            100,
+            REGISTERED_AT + HOUR,
+            REGISTERED_AT + 2 * HOUR,
+        )
+        .unwrap();
+
+        // Held 500 of 500 for the first hour, then 500 of 1_000 for the second
+        assert_eq!(stake_earned(500, first, 0, 0).unwrap(), 100);
+        assert_eq!(stake_earned(500, second, first, 100).unwrap(), 150);
+    }
+
+    #[test]
+    fn nothing_accrues_without_stake() {
+        assert_eq!(
+            reward_per_token(42, 0, 100, REGISTERED_AT, REGISTERED_AT + HOUR).unwrap(),
+            42
+        );
+    }
+
+    #[test]
+    fn scheduled_rate_change_only_applies_after_it_takes_effect() {
+        let mut pool = pool(10, 0, u64::MAX, 0);
+        pool.pending_config = Some(PendingConfig {
+            reward_rate_per_hour: 40,
+            reward_rate_fraction: 0,
+            max_daily_reward: u64::MAX,
+            max_accrual_hours: 0,
+            effective_at: REGISTERED_AT + 3 * HOUR,
+        });
+
+        let before = compute_reward(&pool, &user(0), REGISTERED_AT + 5 * HOUR).unwrap();
+        assert_eq!(before.reward_amount, 3 * 10 + 2 * 40);
+
+        // Applying the change keeps the old rate for time before it took effect
+        pool.apply_pending_config(REGISTERED_AT + 4 * HOUR).unwrap();
+        let after = compute_reward(&pool, &user(0), REGISTERED_AT + 5 * HOUR).unwrap();
+        assert_eq!(after.reward_amount, before.reward_amount);
+    }
+
+    #[test]
+    fn scheduled_growth_change_only_compounds_hours_after_it() {
+        let mut pool = pool(100, 0, u64::MAX, 0);
+        pool.pending_config = Some(PendingConfig {
+            effective_at: REGISTERED_AT + 2 * HOUR,
+            ..pool.next_config()
+        });
+        pool.pending_model = Some(AccrualModel {
+            compound_growth_bps: 5_000,
+            ..pool.accrual_model()
+        });
+
+        // 200 accrued linearly grows 50% an hour for two hours, and the next 200 compound:
+        // 200 * 1.5^2 + 200 * (1.5^2 - 1) / 0.5 / 2, where compounding all four hours
+        // would pay 812
+        let before = compute_reward(&pool, &user(0), REGISTERED_AT + 4 * HOUR).unwrap();
+        assert_eq!(before.reward_amount, 450 + 250);
+
+        // Applying the change keeps the linear hours before it took effect
+        pool.apply_pending_config(REGISTERED_AT + 3 * HOUR).unwrap();
+        assert_eq!(pool.compound_growth_bps, 5_000);
+        let after = compute_reward(&pool, &user(0), REGISTERED_AT + 4 * HOUR).unwrap();
+        assert_eq!(after.reward_amount, before.reward_amount);
+    }
+
+    #[test]
+    fn scheduled_emission_only_replaces_the_rate_after_it_takes_effect() {
+        let mut pool = pool(10, 0, u64::MAX, 0);
+        pool.pending_config = Some(PendingConfig {
+            effective_at: REGISTERED_AT + 3 * HOUR,
+            ..pool.next_config()
+        });
+        pool.pending_model = Some(AccrualModel {
+            emission: Some(EmissionSchedule {
+                start_time: REGISTERED_AT,
+                start_rate: 40,
+                decay_interval_secs: 86_400,
+                decay_bps: 5_000,
+            }),
+            ..pool.accrual_model()
+        });
+
+        let before = compute_reward(&pool, &user(0), REGISTERED_AT + 5 * HOUR).unwrap();
+        assert_eq!(before.reward_amount, 3 * 10 + 2 * 40);
+        assert_eq!(rate_per_hour_at(&pool, REGISTERED_AT + HOUR).unwrap(), 10);
+        assert_eq!(rate_per_hour_at(&pool, REGISTERED_AT + 4 * HOUR).unwrap(), 40);
+
+        pool.apply_pending_config(REGISTERED_AT + 4 * HOUR).unwrap();
+        let after = compute_reward(&pool, &user(0), REGISTERED_AT + 5 * HOUR).unwrap();
+        assert_eq!(after.reward_amount, before.reward_amount);
+    }
+
+    #[test]
+    fn scheduled_participant_budget_only_applies_after_it_takes_effect() {
+        let mut pool = pool(10, 0, u64::MAX, 0);
+        pool.participant_count = 4;
+        pool.pending_config = Some(PendingConfig {
+            effective_at: REGISTERED_AT + 3 * HOUR,
+            ..pool.next_config()
+        });
+        pool.pending_model = Some(AccrualModel {
+            budget_per_hour: 1_000,
+            floor_rate_per_hour: 40,
+            ..pool.accrual_model()
+        });
+
+        let before = compute_reward(&pool, &user(0), REGISTERED_AT + 5 * HOUR).unwrap();
+        assert_eq!(before.reward_amount, 3 * 10 + 2 * 250);
+
+        pool.apply_pending_config(REGISTERED_AT + 4 * HOUR).unwrap();
+        assert_eq!(flat_rate_per_hour(&pool), 250);
+        let after = compute_reward(&pool, &user(0), REGISTERED_AT + 5 * HOUR).unwrap();
+        assert_eq!(after.reward_amount, before.reward_amount);
+    }
+
+    #[test]
+    fn accrual_before_a_scheduled_usd_switch_stays_in_tokens() {
+        let mut pool = pool(10, 0, u64::MAX, 0);
+        pool.pending_config = Some(PendingConfig {
+            effective_at: REGISTERED_AT + 3 * HOUR,
+            ..pool.next_config()
+        });
+        pool.pending_model = Some(AccrualModel {
+            usd_pricing: Some(UsdPricing {
+                feed_id: [1; 32],
+                max_price_age_secs: 60,
+                max_confidence_bps: 100,
+            }),
+            ..pool.accrual_model()
+        });
+
+        let before = compute_reward(&pool, &user(0), REGISTERED_AT + 5 * HOUR).unwrap();
+        assert_eq!(before.reward_amount, 50);
+        assert_eq!(before.usd_priced_reward, 20);
+
+        pool.apply_pending_config(REGISTERED_AT + 4 * HOUR).unwrap();
+        let after = compute_reward(&pool, &user(0), REGISTERED_AT + 5 * HOUR).unwrap();
+        assert_eq!(after, before);
+
+        // Accrual entirely after the switch is all USD-priced
+        let later = compute_reward(
+            &pool,
+            &user(REGISTERED_AT + 5 * HOUR),
+            REGISTERED_AT + 7 * HOUR,
+        )
+        .unwrap();
+        assert_eq!(later.usd_priced_reward, later.reward_amount);
+    }
+
+    #[test]
+    fn fractional_rate_accrues_without_losing_the_fraction() {
+        let mut pool = pool(0, 0, u64::MAX, 0);
+        pool.reward_rate_fraction = 1 << 63;
+
+        // Half a unit per hour pays nothing after an hour, but one unit after two
+        assert_eq!(
+            compute_reward(&pool, &user(0), REGISTERED_AT + HOUR)
+                .unwrap()
+                .reward_amount,
+            0
+        );
+        assert_eq!(
+            compute_reward(&pool, &user(0), REGISTERED_AT + 2 * HOUR)
+                .unwrap()
+                .reward_amount,
+            1
+        );
+        assert_eq!(rate_per_hour_at(&pool, REGISTERED_AT).unwrap(), 0);
+    }
+
+    #[test]
+    fn pool_rounding_mode_resolves_fractional_rewards() {
+        let mut pool = pool(0, 0, u64::MAX, 0);
+        pool.reward_rate_fraction = 1 << 63;
+        let reward = |pool: &RewardPool, hours: i64| {
+            compute_reward(pool, &user(0), REGISTERED_AT + hours * HOUR)
+                .unwrap()
+                .reward_amount
+        };
+
+        pool.rounding = RoundingMode::Ceil;
+        assert_eq!(reward(&pool, 1), 1);
+        assert_eq!(reward(&pool, 3), 2);
+        pool.rounding = RoundingMode::HalfEven;
+        assert_eq!(reward(&pool, 1), 0);
+        assert_eq!(reward(&pool, 3), 2);
+        assert_eq!(apply_bps(15, 5_000, RoundingMode::HalfEven).unwrap(), 8);
+    }
+
+    #[test]
+    fn daily_claim_count_resets_on_a_new_day() {
+        let day = 20_000 * SECONDS_PER_DAY;
+        let mut claimed = user(day);
+        claimed.claims_today_day = day_index(day);
+        claimed.claims_today = 3;
+
+        assert_eq!(claims_today(&claimed, day + SECONDS_PER_DAY - 1), 3);
+        assert_eq!(claims_today(&claimed, day + SECONDS_PER_DAY), 0);
+    }
+
+    #[test]
+    fn activity_points_pay_on_top_of_time_accrual() {
+        let mut pool = pool(10, 0, 1_000, 0);
+        pool.reward_per_activity_point = 3;
+        let mut active = user(0);
+        active.activity_points = 7;
+
+        let breakdown = compute_reward(&pool, &active, REGISTERED_AT + 5 * HOUR).unwrap();
+
+        assert_eq!(breakdown.activity_bonus, 21);
+        assert_eq!(breakdown.reward_amount, 50 + 21);
+    }
+
+    #[test]
+    fn first_claim_of_day_resets_at_utc_midnight() {
+        let midnight = 20_000 * SECONDS_PER_DAY;
+        let mut claimed = user(midnight - 1);
+        claimed.total_claims = 1;
+
+        assert!(is_first_claim_of_day(&claimed, midnight));
+        claimed.last_claim_timestamp = midnight;
+        assert!(!is_first_claim_of_day(
+            &claimed,
+            midnight + SECONDS_PER_DAY - 1
+        ));
+        assert!(is_first_claim_of_day(&user(0), 0));
+    }
+
+    #[test]
+    fn rate_overflow_is_an_error() {
+        let result = compute_reward(
+            &pool(u64::MAX, 0, u64::MAX, 0),
+            &user(0),
+            REGISTERED_AT + 2 * HOUR,
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn clock_behind_the_last_claim_is_rejected() {
+        let drifted = user(REGISTERED_AT + 2 * HOUR);
+
+        assert_eq!(
+            compute_reward(&pool(10, 0, 1_000, 0), &drifted, REGISTERED_AT + HOUR).unwrap_err(),
+            error!(ErrorCode::InvalidTimestamp)
+        );
+        assert!(compute_reward(&pool(10, 0, 1_000, 0), &drifted, REGISTERED_AT + 2 * HOUR).is_ok());
+        assert_eq!(whole_hours(-HOUR), 0);
+    }
+
+    #[test]
+    fn timestamp_underflow_is_an_error() {
+        let mut stale = user(0);
+        stale.registration_timestamp = i64::MAX;
+
+        assert!(compute_reward(&pool(10, 0, 1_000, 0), &stale, i64::MIN).is_err());
+    }
+}
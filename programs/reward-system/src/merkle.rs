@@ -0,0 +1,78 @@
+use anchor_lang::{prelude::*, solana_program::keccak};
+
+/// Leaf committed to the allowlist tree for `wallet`: `keccak256(wallet)`
+pub fn leaf(wallet: &Pubkey) -> [u8; 32] {
+    keccak::hashv(&[wallet.as_ref()]).to_bytes()
+}
+
+/// Leaf committed to an airdrop tree: `keccak256(wallet || amount_le)`
+pub fn airdrop_leaf(wallet: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[wallet.as_ref(), &amount.to_le_bytes()]).to_bytes()
+}
+
+/// Verify `leaf` against `root` using sorted-pair hashing, so proofs need no
+/// left/right position flags
+pub fn verify(proof: &[[u8; 32]], root: [u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| {
+        if node <= *sibling {
+            keccak::hashv(&[&node, sibling]).to_bytes()
+        } else {
+            keccak::hashv(&[sibling, &node]).to_bytes()
+        }
+    });
+    computed == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parent(a: [u8; 32], b: [u8; 32]) -> [u8; 32] {
+        if a <= b {
+            keccak::hashv(&[&a, &b]).to_bytes()
+        } else {
+            keccak::hashv(&[&b, &a]).to_bytes()
+        }
+    }
+
+    #[test]
+    fn verifies_members_of_a_four_leaf_tree() {
+        let wallets: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = wallets.iter().map(leaf).collect();
+        let left = parent(leaves[0], leaves[1]);
+        let right = parent(leaves[2], leaves[3]);
+        let root = parent(left, right);
+
+        assert!(verify(&[leaves[1], right], root, leaves[0]));
+        assert!(verify(&[leaves[2], left], root, leaves[3]));
+    }
+
+    #[test]
+    fn rejects_outsiders_and_wrong_proofs() {
+        let wallets: Vec<Pubkey> = (0..2).map(|_| Pubkey::new_unique()).collect();
+        let root = parent(leaf(&wallets[0]), leaf(&wallets[1]));
+
+        assert!(!verify(
+            &[leaf(&wallets[1])],
+            root,
+            leaf(&Pubkey::new_unique())
+        ));
+        assert!(!verify(&[], root, leaf(&wallets[0])));
+    }
+
+    #[test]
+    fn airdrop_leaves_bind_the_amount() {
+        let wallet = Pubkey::new_unique();
+        let other = airdrop_leaf(&Pubkey::new_unique(), 5);
+        let root = parent(airdrop_leaf(&wallet, 100), other);
+
+        assert!(verify(&[other], root, airdrop_leaf(&wallet, 100)));
+        assert!(!verify(&[other], root, airdrop_leaf(&wallet, 101)));
+    }
+
+    #[test]
+    fn single_leaf_tree_needs_no_proof() {
+        let wallet = Pubkey::new_unique();
+        assert!(verify(&[], leaf(&wallet), leaf(&wallet)));
+    }
+}
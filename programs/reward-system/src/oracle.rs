@@ -0,0 +1,144 @@
+use anchor_lang::{prelude::*, solana_program::pubkey};
+
+use crate::{ErrorCode, UsdPricing};
+
+/// Pyth pull-oracle receiver program that owns `PriceUpdateV2` accounts
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+/// First 8 bytes of `sha256("account:PriceUpdateV2")`
+pub const PRICE_UPDATE_V2_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+/// USD amounts are expressed in micro-units
+pub const USD_MICROS: u128 = 1_000_000;
+
+#[derive(AnchorDeserialize)]
+enum VerificationLevel {
+    Partial {
+        #[allow(dead_code)]
+        num_signatures: u8,
+    },
+    Full,
+}
+
+#[derive(AnchorDeserialize)]
+struct PriceFeedMessage {
+    feed_id: [u8; 32],
+    price: i64,
+    conf: u64,
+    exponent: i32,
+    publish_time: i64,
+}
+
+/// Leading fields of Pyth's `PriceUpdateV2` account; the rest is not needed
+#[derive(AnchorDeserialize)]
+struct PriceUpdateV2 {
+    _write_authority: Pubkey,
+    verification_level: VerificationLevel,
+    price_message: PriceFeedMessage,
+}
+
+/// Validated USD price of one whole token: `price * 10^exponent`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UsdPrice {
+    pub price: u64,
+    pub exponent: i32,
+}
+
+/// Read the USD price from a Pyth `PriceUpdateV2` account, rejecting foreign or partially
+/// verified accounts, other feeds, stale prices and too-wide confidence intervals
+pub fn load_price(info: &AccountInfo, pricing: &UsdPricing, now: i64) -> Result<UsdPrice> {
+    require_keys_eq!(
+        *info.owner,
+        PYTH_RECEIVER_PROGRAM_ID,
+        ErrorCode::InvalidPriceFeed
+    );
+    let data = info.try_borrow_data()?;
+    require!(
+        data.len() > 8 && data[..8] == PRICE_UPDATE_V2_DISCRIMINATOR,
+        ErrorCode::InvalidPriceFeed
+    );
+    let update = PriceUpdateV2::deserialize(&mut &data[8..])
+        .map_err(|_| error!(ErrorCode::InvalidPriceFeed))?;
+    let message = update.price_message;
+
+    require!(
+        matches!(update.verification_level, VerificationLevel::Full)
+            && message.feed_id == pricing.feed_id
+            && message.price > 0,
+        ErrorCode::InvalidPriceFeed
+    );
+    require!(
+        now.saturating_sub(message.publish_time) <= pricing.max_price_age_secs,
+        ErrorCode::StalePrice
+    );
+
+    let price = message.price as u64;
+    require!(
+        (message.conf as u128) * crate::math::BPS_DENOMINATOR as u128
+            <= (price as u128) * pricing.max_confidence_bps as u128,
+        ErrorCode::PriceConfidenceTooWide
+    );
+
+    Ok(UsdPrice {
+        price,
+        exponent: message.exponent,
+    })
+}
+
+/// Base units of a mint with `mint_decimals` worth `usd_micros` at `price`, rounded down
+pub fn usd_to_tokens(usd_micros: u64, price: &UsdPrice, mint_decimals: u8) -> Result<u64> {
+    let pow10 = |exponent: u32| {
+        10u128
+            .checked_pow(exponent)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))
+    };
+
+    let mut numerator = (usd_micros as u128)
+        .checked_mul(pow10(mint_decimals as u32)?)
+        .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    let mut denominator = USD_MICROS * price.price as u128;
+    if price.exponent < 0 {
+        numerator = numerator
+            .checked_mul(pow10(price.exponent.unsigned_abs())?)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    } else {
+        denominator = denominator
+            .checked_mul(pow10(price.exponent as u32)?)
+            .ok_or(error!(ErrorCode::ArithmeticOverflow))?;
+    }
+
+    u64::try_from(numerator / denominator).map_err(|_| error!(ErrorCode::ArithmeticOverflow))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::hash::hash;
+
+    #[test]
+    fn discriminator_matches_the_anchor_account_name() {
+        assert_eq!(
+            hash(b"account:PriceUpdateV2").to_bytes()[..8],
+            PRICE_UPDATE_V2_DISCRIMINATOR
+        );
+    }
+
+    #[test]
+    fn converts_usd_micros_at_the_oracle_price() {
+        // $2.50 per token, Pyth-style exponent
+        let price = UsdPrice {
+            price: 250_000_000,
+            exponent: -8,
+        };
+        // $0.10 buys 0.04 tokens of a 6-decimal mint
+        assert_eq!(usd_to_tokens(100_000, &price, 6).unwrap(), 40_000);
+        assert_eq!(usd_to_tokens(100_000, &price, 0).unwrap(), 0);
+
+        let whole_dollars = UsdPrice {
+            price: 4,
+            exponent: 0,
+        };
+        assert_eq!(
+            usd_to_tokens(1_000_000, &whole_dollars, 9).unwrap(),
+            250_000_000
+        );
+    }
+}
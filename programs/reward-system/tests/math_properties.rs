@@ -0,0 +1,257 @@
+//! Property tests for the reward math in `reward_system::math`
+
+use proptest::prelude::*;
+use reward_system::{
+    fixed_point::RoundingMode,
+    math::{self, SECONDS_PER_HOUR as HOUR},
+    EmissionSchedule, RewardPool, UserAccount,
+};
+
+const REGISTERED_AT: i64 = 1_700_000_000;
+/// Elapsed times up to a year
+const MAX_SECS: i64 = 365 * 24 * HOUR;
+
+fn rounding() -> impl Strategy<Value = RoundingMode> {
+    prop_oneof![
+        Just(RoundingMode::Floor),
+        Just(RoundingMode::Ceil),
+        Just(RoundingMode::HalfEven),
+    ]
+}
+
+/// Settings of a flat-rate pool with no bonuses, optionally fractional. Strategies
+/// generate these rather than the pool itself, which isn't `Debug`.
+#[derive(Clone, Debug)]
+struct FlatPool {
+    rate: u64,
+    fraction: u64,
+    min_interval_hours: u64,
+    max_daily: u64,
+    max_accrual: u64,
+    rounding: RoundingMode,
+}
+
+impl FlatPool {
+    fn build(&self) -> RewardPool {
+        RewardPool {
+            reward_rate_per_hour: self.rate,
+            reward_rate_fraction: self.fraction,
+            min_claim_interval_secs: self.min_interval_hours * HOUR as u64,
+            max_daily_reward: self.max_daily,
+            max_accrual_hours: self.max_accrual,
+            rounding: self.rounding,
+            ..Default::default()
+        }
+    }
+}
+
+fn flat_pool() -> impl Strategy<Value = FlatPool> {
+    (
+        0..1_000_000_000u64,
+        any::<u64>(),
+        0..72u64,
+        any::<u64>(),
+        0..500u64,
+        rounding(),
+    )
+        .prop_map(
+            |(rate, fraction, min_interval_hours, max_daily, max_accrual, rounding)| FlatPool {
+                rate,
+                fraction,
+                min_interval_hours,
+                max_daily,
+                max_accrual,
+                rounding,
+            },
+        )
+}
+
+fn new_user() -> UserAccount {
+    UserAccount {
+        registration_timestamp: REGISTERED_AT,
+        is_active: true,
+        ..Default::default()
+    }
+}
+
+/// Claim `user`'s full reward at `now` as `claim_rewards` would, returning the amount
+fn claim(pool: &RewardPool, user: &mut UserAccount, now: i64) -> u64 {
+    let breakdown = math::compute_reward(pool, user, now).unwrap();
+    user.record_claim(pool, &breakdown, now).unwrap();
+    breakdown.reward_amount
+}
+
+proptest! {
+    #[test]
+    fn reward_never_exceeds_max_daily_reward(params in flat_pool(), elapsed in 0..MAX_SECS) {
+        let pool = params.build();
+        let breakdown = math::compute_reward(&pool, &new_user(), REGISTERED_AT + elapsed)
+            .unwrap();
+
+        prop_assert!(breakdown.reward_amount <= pool.max_daily_reward);
+    }
+
+    #[test]
+    fn accrual_never_exceeds_the_window(params in flat_pool(), elapsed in 0..MAX_SECS) {
+        let pool = params.build();
+        let breakdown = math::compute_reward(&pool, &new_user(), REGISTERED_AT + elapsed)
+            .unwrap();
+
+        prop_assert!(breakdown.accrual_hours <= breakdown.hours_since_last_claim);
+        if pool.max_accrual_hours > 0 {
+            prop_assert!(breakdown.accrual_hours <= pool.max_accrual_hours);
+        }
+    }
+
+    #[test]
+    fn payout_never_exceeds_vault(
+        allow_partial_payout in any::<bool>(),
+        vault_balance in any::<u64>(),
+        amount in any::<u64>(),
+    ) {
+        let pool = RewardPool { allow_partial_payout, ..Default::default() };
+
+        match math::vault_payout(&pool, vault_balance, amount) {
+            Ok(payout) => {
+                prop_assert!(payout <= vault_balance && payout <= amount);
+                if vault_balance >= amount {
+                    prop_assert_eq!(payout, amount);
+                }
+            }
+            Err(_) => prop_assert!(
+                vault_balance < amount && (!allow_partial_payout || vault_balance == 0)
+            ),
+        }
+    }
+
+    /// Claims on hour boundaries lose nothing to splitting; claims mid-hour forfeit the
+    /// partial hour by design, so they are not covered here
+    #[test]
+    fn split_claims_equal_one_full_claim(
+        rate in 0..1_000_000_000u64,
+        hours in prop::collection::vec(0..200i64, 1..6),
+    ) {
+        let pool = RewardPool {
+            reward_rate_per_hour: rate,
+            max_daily_reward: u64::MAX,
+            ..Default::default()
+        };
+        let end = REGISTERED_AT + hours.iter().sum::<i64>() * HOUR;
+        let full = math::compute_reward(&pool, &new_user(), end).unwrap().reward_amount;
+
+        let mut user = new_user();
+        let mut now = REGISTERED_AT;
+        let mut split = 0u64;
+        for step in &hours {
+            now += step * HOUR;
+            split += claim(&pool, &mut user, now);
+        }
+
+        prop_assert_eq!(split, full);
+    }
+
+    /// With a fractional rate every claim rounds once, so splitting can cost at most one
+    /// base unit per extra claim
+    #[test]
+    fn split_fractional_claims_round_once_each(
+        rate in 0..1_000_000u64,
+        fraction in any::<u64>(),
+        hours in prop::collection::vec(1..200i64, 1..6),
+    ) {
+        let pool = RewardPool {
+            reward_rate_per_hour: rate,
+            reward_rate_fraction: fraction,
+            max_daily_reward: u64::MAX,
+            ..Default::default()
+        };
+        let end = REGISTERED_AT + hours.iter().sum::<i64>() * HOUR;
+        let full = math::compute_reward(&pool, &new_user(), end).unwrap().reward_amount;
+
+        let mut user = new_user();
+        let mut now = REGISTERED_AT;
+        let mut split = 0u64;
+        for step in &hours {
+            now += step * HOUR;
+            split += claim(&pool, &mut user, now);
+        }
+
+        prop_assert!(split <= full);
+        prop_assert!(full - split < hours.len() as u64);
+    }
+
+    #[test]
+    fn partial_amount_claims_sum_to_the_full_claim(
+        rate in 1..1_000_000u64,
+        elapsed_hours in 1..200i64,
+        share_bps in 1..=10_000u64,
+    ) {
+        let pool = RewardPool {
+            reward_rate_per_hour: rate,
+            max_daily_reward: u64::MAX,
+            ..Default::default()
+        };
+        let now = REGISTERED_AT + elapsed_hours * HOUR;
+        let mut user = new_user();
+        let mut breakdown = math::compute_reward(&pool, &user, now).unwrap();
+        let full = breakdown.reward_amount;
+
+        // Take part now, as claim_rewards does with `amount`, and the rest later
+        let taken = (full * share_bps / 10_000).max(1);
+        breakdown.carryover = full - taken;
+        breakdown.reward_amount = taken;
+        user.record_claim(&pool, &breakdown, now).unwrap();
+        let rest = math::compute_reward(&pool, &user, now).unwrap().reward_amount;
+
+        prop_assert_eq!(taken + rest, full);
+    }
+
+    /// Compound accrual against a floating-point reference of `rate * ((1 + g)^h - 1) / g`,
+    /// and never below what the same pool pays linearly
+    #[test]
+    fn compound_reward_tracks_the_reference(
+        rate in 1..1_000_000u64,
+        elapsed_hours in 1..2_000i64,
+        growth_bps in 1..=100u16,
+    ) {
+        let pool = RewardPool {
+            reward_rate_per_hour: rate,
+            max_daily_reward: u64::MAX,
+            compound_growth_bps: growth_bps,
+            ..Default::default()
+        };
+        let reward = math::compute_reward(&pool, &new_user(), REGISTERED_AT + elapsed_hours * HOUR)
+            .unwrap()
+            .reward_amount;
+
+        let growth = f64::from(growth_bps) / 10_000.0;
+        let reference = rate as f64 * (elapsed_hours as f64 * growth.ln_1p()).exp_m1() / growth;
+        prop_assert!((reward as f64 - reference).abs() <= reference * 1e-9 + 1.0);
+        prop_assert!(reward >= rate * elapsed_hours as u64);
+    }
+
+    #[test]
+    fn emission_is_monotonic_in_elapsed_time(
+        start_rate in 0..1_000_000_000u64,
+        decay_interval_hours in 1..200i64,
+        decay_bps in 0..=10_000u16,
+        started_hours_ago in 0..1_000i64,
+        hours in 0..2_000u64,
+        more_hours in 0..2_000u64,
+    ) {
+        let pool = RewardPool {
+            emission: Some(EmissionSchedule {
+                start_time: REGISTERED_AT - started_hours_ago * HOUR,
+                start_rate,
+                decay_interval_secs: decay_interval_hours * HOUR,
+                decay_bps,
+            }),
+            ..Default::default()
+        };
+
+        let shorter = math::emitted_reward(&pool, REGISTERED_AT, hours).unwrap();
+        let longer = math::emitted_reward(&pool, REGISTERED_AT, hours + more_hours).unwrap();
+        prop_assert!(shorter <= longer);
+        // Rates only decay, so no window out-earns the starting rate
+        prop_assert!(longer as u128 <= start_rate as u128 * (hours + more_hours) as u128);
+    }
+}